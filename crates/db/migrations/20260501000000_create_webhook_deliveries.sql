@@ -0,0 +1,26 @@
+-- Durable outbound webhook deliveries. Every event fanned out to a webhook
+-- is persisted here first so that retries survive server restarts and
+-- operators can inspect (and redeliver) what was sent.
+-- status: 'pending' (awaiting first attempt or retry), 'delivered',
+--         'dead' (gave up after the maximum number of attempts)
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id               TEXT PRIMARY KEY NOT NULL,
+    webhook_id       TEXT NOT NULL,
+    event_type       TEXT NOT NULL,
+    payload          TEXT NOT NULL,
+    status           TEXT NOT NULL DEFAULT 'pending'
+                     CHECK (status IN ('pending', 'delivered', 'dead')),
+    attempts         INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at  DATETIME NOT NULL DEFAULT (datetime('now', 'subsec')),
+    last_attempt_at  DATETIME,
+    response_status  INTEGER,
+    last_error       TEXT,
+    created_at       DATETIME NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       DATETIME NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+  ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_created
+  ON webhook_deliveries (webhook_id, created_at DESC);
@@ -0,0 +1,6 @@
+-- Optional subscription filters. NULL means "no restriction".
+-- event_types and repo_ids are JSON arrays of strings.
+ALTER TABLE webhooks ADD COLUMN event_types TEXT;
+ALTER TABLE webhooks ADD COLUMN repo_ids TEXT;
+ALTER TABLE webhooks ADD COLUMN workspace_glob TEXT;
+ALTER TABLE webhooks ADD COLUMN branch_glob TEXT;
@@ -0,0 +1,14 @@
+-- Follow-up messages queued for a session while an execution is running.
+-- Drained one per finished turn in ascending position order.
+CREATE TABLE IF NOT EXISTS queued_messages (
+    id          BLOB PRIMARY KEY,
+    session_id  BLOB NOT NULL,
+    position    INTEGER NOT NULL,
+    data        TEXT NOT NULL,
+    queued_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_queued_messages_session_position
+    ON queued_messages (session_id, position);
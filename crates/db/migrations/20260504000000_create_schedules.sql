@@ -0,0 +1,41 @@
+-- Recurring agent runs. On each tick of `cron_expression` the scheduler
+-- creates a workspace on `repo_id`/`target_branch` and starts the executor
+-- with the rendered `prompt_template`.
+CREATE TABLE IF NOT EXISTS schedules (
+    id                   BLOB PRIMARY KEY,
+    name                 TEXT NOT NULL,
+    cron_expression      TEXT NOT NULL,
+    repo_id              BLOB NOT NULL,
+    target_branch        TEXT NOT NULL,
+    prompt_template      TEXT NOT NULL,
+    executor_profile_id  TEXT NOT NULL,
+    paused               INTEGER NOT NULL DEFAULT 0,
+    next_run_at          TEXT,
+    last_run_at          TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedules_due
+    ON schedules (paused, next_run_at);
+
+-- One row per firing of a schedule, pointing at what it created.
+-- status: 'started' (workspace created and executor launched),
+--         'failed' (see `error`; `workspace_id` is set if it got that far)
+CREATE TABLE IF NOT EXISTS schedule_runs (
+    id             BLOB PRIMARY KEY,
+    schedule_id    BLOB NOT NULL,
+    scheduled_for  TEXT NOT NULL,
+    status         TEXT NOT NULL CHECK (status IN ('started', 'failed')),
+    workspace_id   BLOB,
+    session_id     BLOB,
+    error          TEXT,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (schedule_id) REFERENCES schedules(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_schedule_runs_schedule_created
+    ON schedule_runs (schedule_id, created_at DESC);
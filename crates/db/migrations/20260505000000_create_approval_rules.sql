@@ -0,0 +1,18 @@
+-- Per-repository rules that settle executor tool approvals without asking the
+-- user. Rules are evaluated in ascending position order and the first rule
+-- whose criteria all match decides. A NULL criterion matches anything.
+-- decision: 'allow' (approve), 'deny' (reject), 'ask' (forward to the user)
+CREATE TABLE IF NOT EXISTS approval_rules (
+    id               BLOB PRIMARY KEY,
+    repo_id          BLOB NOT NULL,
+    position         INTEGER NOT NULL,
+    decision         TEXT NOT NULL CHECK (decision IN ('allow', 'deny', 'ask')),
+    tool_name        TEXT,
+    command_pattern  TEXT,
+    path_glob        TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_approval_rules_repo_position
+    ON approval_rules (repo_id, position);
@@ -0,0 +1,23 @@
+-- History of executor tool approvals and agent questions. A row is written
+-- when the request is created (or settled by an approval rule) and updated
+-- once it resolves. outcome is the JSON-encoded ApprovalOutcome and is NULL
+-- while the request is pending.
+-- responder: 'local' (the UI of this machine), 'relay' (a paired relay client,
+-- see relay_client_id), 'mcp' (an MCP client) or 'policy' (an approval rule);
+-- NULL for requests that timed out or were cancelled.
+CREATE TABLE IF NOT EXISTS approvals (
+    id                    TEXT PRIMARY KEY,
+    execution_process_id  BLOB NOT NULL,
+    tool_name             TEXT NOT NULL,
+    is_question           INTEGER NOT NULL DEFAULT 0,
+    questions             TEXT,
+    outcome               TEXT,
+    responder             TEXT CHECK (responder IN ('local', 'relay', 'mcp', 'policy')),
+    relay_client_id       BLOB,
+    requested_at          TEXT NOT NULL,
+    resolved_at           TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_approvals_execution_process_id
+    ON approvals (execution_process_id, requested_at);
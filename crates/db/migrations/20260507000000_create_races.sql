@@ -0,0 +1,62 @@
+-- A race runs one prompt on several executors, each in its own workspace, so
+-- the results can be compared and a winner picked.
+CREATE TABLE IF NOT EXISTS races (
+    id                   BLOB PRIMARY KEY,
+    name                 TEXT NOT NULL,
+    prompt               TEXT NOT NULL,
+    test_script          TEXT,
+    winner_workspace_id  BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (winner_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+-- One workspace per executor taking part in a race.
+-- executor_profile_id: JSON-encoded ExecutorProfileId
+-- test_execution_process_id: the latest run of the race's test script
+CREATE TABLE IF NOT EXISTS race_attempts (
+    workspace_id               BLOB PRIMARY KEY,
+    race_id                    BLOB NOT NULL,
+    position                   INTEGER NOT NULL,
+    executor_profile_id        TEXT NOT NULL,
+    test_execution_process_id  BLOB,
+    created_at                 TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (race_id) REFERENCES races(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (test_execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_race_attempts_race_id
+    ON race_attempts (race_id, position);
+
+-- Add 'testscript' to the run_reason CHECK constraint, the same way
+-- 'archivescript' was added in 20260203000000_add_archive_script_to_repos.
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                               'cleanupscript',
+                               'archivescript',
+                               'codingagent',
+                               'devserver',
+                               'testscript'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_status_run_reason;
+DROP INDEX IF EXISTS idx_execution_processes_session_run_reason_created;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_run_reason
+        ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+        ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+        ON execution_processes (session_id, run_reason, created_at DESC);
@@ -0,0 +1,38 @@
+-- Tokens used by each coding agent execution, updated while it runs.
+-- cost_usd: priced with model_prices when the usage was recorded; NULL when
+-- no price matches the model
+CREATE TABLE IF NOT EXISTS execution_process_token_usage (
+    execution_process_id  BLOB PRIMARY KEY,
+    model                 TEXT,
+    input_tokens          INTEGER NOT NULL DEFAULT 0,
+    output_tokens         INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens     INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens    INTEGER NOT NULL DEFAULT 0,
+    cost_usd              REAL,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+-- Prices in USD per million tokens. A row applies to every model id it is a
+-- prefix of; the longest matching prefix wins.
+CREATE TABLE IF NOT EXISTS model_prices (
+    model                    TEXT PRIMARY KEY,
+    input_per_mtok           REAL NOT NULL,
+    output_per_mtok          REAL NOT NULL,
+    cache_read_per_mtok      REAL NOT NULL DEFAULT 0,
+    cache_write_per_mtok     REAL NOT NULL DEFAULT 0,
+    created_at               TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at               TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Spending limits of a workspace; running agents are stopped once the
+-- workspace's executions exceed either limit.
+CREATE TABLE IF NOT EXISTS workspace_budgets (
+    workspace_id  BLOB PRIMARY KEY,
+    max_cost_usd  REAL,
+    max_tokens    INTEGER,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- How PRs of a GitHub repository are reached: 'auto' uses the REST API when a
+-- token is configured and the gh CLI otherwise; 'api' and 'cli' force one.
+ALTER TABLE repos ADD COLUMN github_access TEXT NOT NULL DEFAULT 'auto';
@@ -0,0 +1,14 @@
+-- A stacked workspace branches off another workspace's branch instead of a
+-- repository target branch. Its workspace_repos.target_branch is the parent's
+-- branch until the parent's pull request merges, when it is moved to the
+-- branch the parent merged into.
+CREATE TABLE IF NOT EXISTS workspace_stacks (
+    workspace_id         BLOB PRIMARY KEY,
+    parent_workspace_id  BLOB NOT NULL,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_stacks_parent_workspace_id
+    ON workspace_stacks (parent_workspace_id);
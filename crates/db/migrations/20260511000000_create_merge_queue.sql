@@ -0,0 +1,31 @@
+-- Script run in a workspace before the merge queue merges it, such as `npm test`.
+ALTER TABLE repos ADD COLUMN verification_script TEXT;
+
+-- Workspaces waiting to be merged. Entries with the same repo and target
+-- branch form one queue and are merged one at a time, oldest first. A
+-- 'conflict' or 'failed' entry pauses its queue until it is retried or
+-- cancelled.
+-- strategy: MergeStrategy used for the merge ('squash', 'rebase' or 'merge')
+CREATE TABLE IF NOT EXISTS merge_queue_entries (
+    id                                 BLOB PRIMARY KEY,
+    workspace_id                       BLOB NOT NULL,
+    repo_id                            BLOB NOT NULL,
+    target_branch                      TEXT NOT NULL,
+    strategy                           TEXT NOT NULL CHECK (strategy IN ('squash', 'rebase', 'merge')),
+    status                             TEXT NOT NULL DEFAULT 'queued'
+                                           CHECK (status IN ('queued', 'running', 'merged', 'conflict', 'failed', 'cancelled')),
+    error                              TEXT,
+    verification_execution_process_id  BLOB,
+    merge_commit                       TEXT,
+    created_at                         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (verification_execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_merge_queue_entries_queue
+    ON merge_queue_entries (repo_id, target_branch, status);
+
+CREATE INDEX IF NOT EXISTS idx_merge_queue_entries_workspace_id
+    ON merge_queue_entries (workspace_id);
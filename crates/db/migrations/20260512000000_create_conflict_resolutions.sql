@@ -0,0 +1,27 @@
+-- Coding agent runs started to resolve a stuck rebase, merge, cherry-pick or
+-- revert in one repo of a workspace. When the run finishes without leaving
+-- conflict markers behind, the operation is continued automatically.
+-- op: ConflictOp of the stuck operation ('rebase', 'merge', 'cherry_pick' or 'revert')
+-- conflicted_files: JSON array of the paths that were conflicted when the run started
+CREATE TABLE IF NOT EXISTS conflict_resolutions (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    op                    TEXT NOT NULL CHECK (op IN ('rebase', 'merge', 'cherry_pick', 'revert')),
+    conflicted_files      TEXT NOT NULL DEFAULT '[]',
+    status                TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'continued', 'unresolved', 'failed')),
+    error                 TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_conflict_resolutions_execution_process_id
+    ON conflict_resolutions (execution_process_id);
+
+CREATE INDEX IF NOT EXISTS idx_conflict_resolutions_workspace_id
+    ON conflict_resolutions (workspace_id);
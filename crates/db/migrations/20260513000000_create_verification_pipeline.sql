@@ -0,0 +1,76 @@
+-- Named checks of a repo, such as lint, test and typecheck, run in order.
+-- They replace the single verification script.
+CREATE TABLE IF NOT EXISTS verification_steps (
+    id          BLOB PRIMARY KEY,
+    repo_id     BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    script      TEXT NOT NULL,
+    position    INTEGER NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_steps_repo_id
+    ON verification_steps (repo_id, position);
+
+INSERT INTO verification_steps (id, repo_id, name, script, position)
+SELECT randomblob(16), id, 'verify', verification_script, 0
+FROM repos
+WHERE verification_script IS NOT NULL AND TRIM(verification_script) != '';
+
+ALTER TABLE repos DROP COLUMN verification_script;
+
+ALTER TABLE repos ADD COLUMN verify_after_agent INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE repos ADD COLUMN require_verification INTEGER NOT NULL DEFAULT 0;
+
+-- One pass over the verification steps of a workspace's repos. The steps run
+-- as a chain of script execution processes in the session and the chain stops
+-- at the first failure.
+-- trigger: what started the run ('agent', 'manual' or 'merge_queue')
+CREATE TABLE IF NOT EXISTS verification_runs (
+    id          BLOB PRIMARY KEY,
+    workspace_id BLOB NOT NULL,
+    session_id  BLOB NOT NULL,
+    trigger     TEXT NOT NULL CHECK (trigger IN ('agent', 'manual', 'merge_queue')),
+    status      TEXT NOT NULL DEFAULT 'running'
+                    CHECK (status IN ('running', 'passed', 'failed', 'cancelled')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_runs_workspace_id
+    ON verification_runs (workspace_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_verification_runs_session_id
+    ON verification_runs (session_id, status);
+
+-- The outcome of one step in a run. The step's name and script are copied so
+-- the result survives edits to the step.
+-- execution_process_id: the script run, set when the step starts
+CREATE TABLE IF NOT EXISTS verification_results (
+    id                    BLOB PRIMARY KEY,
+    run_id                BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    step_id               BLOB,
+    name                  TEXT NOT NULL,
+    position              INTEGER NOT NULL,
+    execution_process_id  BLOB,
+    status                TEXT NOT NULL DEFAULT 'pending'
+                              CHECK (status IN ('pending', 'running', 'passed', 'failed', 'cancelled', 'skipped')),
+    exit_code             INTEGER,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (run_id) REFERENCES verification_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (step_id) REFERENCES verification_steps(id) ON DELETE SET NULL,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_results_run_id
+    ON verification_results (run_id, position);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_verification_results_execution_process_id
+    ON verification_results (execution_process_id);
@@ -0,0 +1,31 @@
+-- Opt-in loops that send failing verification output back to a session's
+-- coding agent as a follow-up, then verify again, until the steps pass or
+-- max_iterations follow-ups have been sent.
+-- iterations: number of follow-ups sent so far
+-- execution_process_id: coding agent run of the latest follow-up
+-- verification_run_id: verification run chained after the latest follow-up
+CREATE TABLE IF NOT EXISTS fix_up_loops (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    session_id            BLOB NOT NULL,
+    max_iterations        INTEGER NOT NULL,
+    iterations            INTEGER NOT NULL DEFAULT 0,
+    execution_process_id  BLOB,
+    verification_run_id   BLOB,
+    status                TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'passed', 'exhausted', 'failed', 'cancelled')),
+    error                 TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL,
+    FOREIGN KEY (verification_run_id) REFERENCES verification_runs(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_fix_up_loops_session_id
+    ON fix_up_loops (session_id, created_at);
+
+-- At most one loop runs per session
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fix_up_loops_running_session
+    ON fix_up_loops (session_id) WHERE status = 'running';
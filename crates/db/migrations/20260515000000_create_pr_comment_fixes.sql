@@ -0,0 +1,33 @@
+-- Coding agent follow-ups addressing inline review comments on a workspace's
+-- pull request. Once the follow-up and its cleanup scripts finish, the branch
+-- is pushed and every comment gets a reply on the git host.
+-- comment_ids: JSON array of the host's ids of the review comments addressed
+-- resolve_threads: also mark each replied thread resolved
+-- replied: how many of the comments were replied to
+CREATE TABLE IF NOT EXISTS pr_comment_fixes (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    session_id            BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    pr_number             INTEGER NOT NULL,
+    comment_ids           TEXT NOT NULL DEFAULT '[]',
+    resolve_threads       INTEGER NOT NULL DEFAULT 0,
+    replied               INTEGER NOT NULL DEFAULT 0,
+    status                TEXT NOT NULL DEFAULT 'running'
+                              CHECK (status IN ('running', 'addressed', 'failed', 'cancelled')),
+    error                 TEXT,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pr_comment_fixes_workspace_id
+    ON pr_comment_fixes (workspace_id, created_at);
+
+-- At most one fix runs per session
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pr_comment_fixes_running_session
+    ON pr_comment_fixes (session_id) WHERE status = 'running';
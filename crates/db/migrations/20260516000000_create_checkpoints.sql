@@ -0,0 +1,33 @@
+-- Snapshots of each repo's worktree taken after a coding agent turn that used
+-- tools, uncommitted and untracked changes included. The snapshot commit is
+-- kept reachable from a hidden ref so it survives garbage collection.
+-- ref_name: refs/vibe/checkpoints/<workspace_id>/<execution_process_id>
+-- commit_sha: the snapshot commit, whose parent is head_commit
+-- head_commit: the worktree's HEAD when the snapshot was taken
+-- files_changed, lines_added, lines_removed: the turn's changes, counted from
+--   the previous checkpoint of the repo in the session or from the HEAD the
+--   turn started on
+CREATE TABLE IF NOT EXISTS checkpoints (
+    id                    BLOB PRIMARY KEY,
+    workspace_id          BLOB NOT NULL,
+    session_id            BLOB NOT NULL,
+    execution_process_id  BLOB NOT NULL,
+    repo_id               BLOB NOT NULL,
+    ref_name              TEXT NOT NULL,
+    commit_sha            TEXT NOT NULL,
+    head_commit           TEXT NOT NULL,
+    files_changed         INTEGER NOT NULL DEFAULT 0,
+    lines_added           INTEGER NOT NULL DEFAULT 0,
+    lines_removed         INTEGER NOT NULL DEFAULT 0,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_checkpoints_execution_process_repo
+    ON checkpoints (execution_process_id, repo_id);
+
+CREATE INDEX IF NOT EXISTS idx_checkpoints_session_id
+    ON checkpoints (session_id, created_at);
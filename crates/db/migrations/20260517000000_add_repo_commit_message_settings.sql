@@ -0,0 +1,5 @@
+-- How commits made on behalf of coding agents are worded. A NULL template
+-- keeps the agent's summary as the message.
+ALTER TABLE repos ADD COLUMN commit_message_template TEXT;
+ALTER TABLE repos ADD COLUMN conventional_commits INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE repos ADD COLUMN agent_commit_trailers INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+-- How commits made on behalf of a repo are signed: 'off', 'git_config' to use
+-- the repo's gpg.format and user.signingkey, or 'gpg'/'ssh' to force a format.
+-- signing_key overrides user.signingkey with a key id or key path.
+ALTER TABLE repos ADD COLUMN commit_signing TEXT NOT NULL DEFAULT 'off';
+ALTER TABLE repos ADD COLUMN signing_key TEXT;
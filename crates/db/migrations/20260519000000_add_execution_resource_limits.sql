@@ -0,0 +1,12 @@
+-- Limits for the processes of a workspace's executions, enforced through a
+-- cgroup on Linux. cpu_limit is in CPUs and may be fractional.
+ALTER TABLE repos ADD COLUMN cpu_limit REAL;
+ALTER TABLE repos ADD COLUMN memory_limit_mb INTEGER;
+ALTER TABLE repos ADD COLUMN pids_limit INTEGER;
+
+-- Usage of limited executions, read from their cgroup when they finish.
+-- failure_reason explains a failed execution beyond its exit code, e.g.
+-- 'oom_killed'.
+ALTER TABLE execution_processes ADD COLUMN peak_memory_bytes INTEGER;
+ALTER TABLE execution_processes ADD COLUMN cpu_time_ms INTEGER;
+ALTER TABLE execution_processes ADD COLUMN failure_reason TEXT;
@@ -0,0 +1,39 @@
+-- Add 'queued' to the status CHECK constraint for coding agent executions
+-- waiting for a free concurrency slot, the same way 'testscript' was added
+-- to run_reason in 20260507000000_create_races.
+ALTER TABLE execution_processes
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'running'
+    CHECK (status_new IN ('queued',
+                          'running',
+                          'completed',
+                          'failed',
+                          'killed'));
+
+UPDATE execution_processes
+  SET status_new = status;
+
+DROP INDEX IF EXISTS idx_execution_processes_status;
+DROP INDEX IF EXISTS idx_execution_processes_session_status_run_reason;
+
+ALTER TABLE execution_processes DROP COLUMN status;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN status_new TO status;
+
+CREATE INDEX idx_execution_processes_status
+        ON execution_processes(status);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+        ON execution_processes (session_id, status, run_reason);
+
+-- Queued coding agent starts, started in ascending position order as slots
+-- free up. Entries are removed when the execution starts or is cancelled.
+CREATE TABLE IF NOT EXISTS execution_start_queue (
+    execution_process_id  BLOB PRIMARY KEY,
+    position              INTEGER NOT NULL,
+    queued_at             TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_start_queue_position
+    ON execution_start_queue (position);
@@ -10,6 +10,8 @@ use utils::assets::asset_dir;
 pub mod database_stats;
 pub mod metrics;
 pub mod models;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod wal_monitor;
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use executors::logs::AskUserQuestionItem;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::approvals::ApprovalOutcome;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_responder", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalResponder {
+    /// The UI of the machine running Vibe Kanban
+    Local,
+    /// A paired relay client; see `relay_client_id`
+    Relay,
+    /// An MCP client
+    Mcp,
+    /// Settled by a repository approval rule without asking
+    Policy,
+}
+
+/// A tool approval or agent question and how it was resolved
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Approval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub is_question: bool,
+    #[ts(type = "Array<AskUserQuestionItem> | null")]
+    pub questions: Option<Json<Vec<AskUserQuestionItem>>>,
+    /// `None` while the request is pending
+    #[ts(type = "ApprovalOutcome | null")]
+    pub outcome: Option<Json<ApprovalOutcome>>,
+    /// Who resolved the request; `None` when it timed out or was cancelled
+    pub responder: Option<ApprovalResponder>,
+    pub relay_client_id: Option<Uuid>,
+    pub requested_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct CreateApproval {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub is_question: bool,
+    pub questions: Option<Vec<AskUserQuestionItem>>,
+    pub requested_at: DateTime<Utc>,
+}
+
+impl Approval {
+    pub async fn create(pool: &SqlitePool, data: &CreateApproval) -> Result<(), sqlx::Error> {
+        let questions = data.questions.as_ref().map(Json);
+        sqlx::query!(
+            r#"INSERT INTO approvals
+                   (id, execution_process_id, tool_name, is_question, questions, requested_at)
+               VALUES ($1, $2, $3, $4, $5, $6)"#,
+            data.id,
+            data.execution_process_id,
+            data.tool_name,
+            data.is_question,
+            questions,
+            data.requested_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the outcome of a pending request. Returns `false` if the request
+    /// is unknown or was already resolved, so the first resolution wins.
+    pub async fn resolve(
+        pool: &SqlitePool,
+        id: &str,
+        outcome: &ApprovalOutcome,
+        responder: Option<ApprovalResponder>,
+        relay_client_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let outcome = Json(outcome);
+        let resolved_at = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET outcome = $2, responder = $3, relay_client_id = $4, resolved_at = $5
+               WHERE id = $1 AND outcome IS NULL"#,
+            id,
+            outcome,
+            responder,
+            relay_client_id,
+            resolved_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolve every request still pending with `outcome`. Pending requests
+    /// only live in memory, so at startup any unresolved row is orphaned.
+    pub async fn resolve_all_pending(
+        pool: &SqlitePool,
+        outcome: &ApprovalOutcome,
+    ) -> Result<u64, sqlx::Error> {
+        let outcome = Json(outcome);
+        let resolved_at = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE approvals
+               SET outcome = $1, resolved_at = $2
+               WHERE outcome IS NULL"#,
+            outcome,
+            resolved_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT id AS "id!",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      tool_name,
+                      is_question AS "is_question!: bool",
+                      questions AS "questions?: Json<Vec<AskUserQuestionItem>>",
+                      outcome AS "outcome?: Json<ApprovalOutcome>",
+                      responder AS "responder?: ApprovalResponder",
+                      relay_client_id AS "relay_client_id?: Uuid",
+                      requested_at AS "requested_at!: DateTime<Utc>",
+                      resolved_at AS "resolved_at?: DateTime<Utc>"
+               FROM approvals
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// All requests made by executions of the workspace, oldest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id AS "id!",
+                      a.execution_process_id AS "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.is_question AS "is_question!: bool",
+                      a.questions AS "questions?: Json<Vec<AskUserQuestionItem>>",
+                      a.outcome AS "outcome?: Json<ApprovalOutcome>",
+                      a.responder AS "responder?: ApprovalResponder",
+                      a.relay_client_id AS "relay_client_id?: Uuid",
+                      a.requested_at AS "requested_at!: DateTime<Utc>",
+                      a.resolved_at AS "resolved_at?: DateTime<Utc>"
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1
+               ORDER BY a.requested_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// All requests made by executions of the session, oldest first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Approval,
+            r#"SELECT a.id AS "id!",
+                      a.execution_process_id AS "execution_process_id!: Uuid",
+                      a.tool_name,
+                      a.is_question AS "is_question!: bool",
+                      a.questions AS "questions?: Json<Vec<AskUserQuestionItem>>",
+                      a.outcome AS "outcome?: Json<ApprovalOutcome>",
+                      a.responder AS "responder?: ApprovalResponder",
+                      a.relay_client_id AS "relay_client_id?: Uuid",
+                      a.requested_at AS "requested_at!: DateTime<Utc>",
+                      a.resolved_at AS "resolved_at?: DateTime<Utc>"
+               FROM approvals a
+               JOIN execution_processes ep ON ep.id = a.execution_process_id
+               WHERE ep.session_id = $1
+               ORDER BY a.requested_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ApprovalRuleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid approval rule: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_decision", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalDecision {
+    /// Approve the tool call without asking
+    Allow,
+    /// Reject the tool call without asking
+    Deny,
+    /// Forward the request to the user, skipping any later rule
+    Ask,
+}
+
+/// A repository rule that settles executor tool approvals. The first rule of
+/// a repository whose criteria all match decides; unset criteria match any
+/// tool call.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    /// Evaluation order within the repository; lowest first
+    pub position: i64,
+    pub decision: ApprovalDecision,
+    /// Tool name as reported by the executor, compared case-insensitively
+    pub tool_name: Option<String>,
+    /// Regex matched against the command of command-running tools
+    pub command_pattern: Option<String>,
+    /// Glob matched against the repository-relative path of file edits
+    pub path_glob: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRuleInput {
+    pub decision: ApprovalDecision,
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    #[serde(default)]
+    pub command_pattern: Option<String>,
+    #[serde(default)]
+    pub path_glob: Option<String>,
+}
+
+impl ApprovalRule {
+    /// The repository's rules in evaluation order
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id AS "id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      position AS "position!: i64",
+                      decision AS "decision!: ApprovalDecision",
+                      tool_name,
+                      command_pattern,
+                      path_glob,
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE repo_id = $1
+               ORDER BY position ASC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace all rules of a repository with `rules`, keeping their order.
+    pub async fn replace_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        rules: &[ApprovalRuleInput],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("DELETE FROM approval_rules WHERE repo_id = $1", repo_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, rule) in rules.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            sqlx::query!(
+                r#"INSERT INTO approval_rules
+                       (id, repo_id, position, decision, tool_name, command_pattern, path_glob)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                id,
+                repo_id,
+                position,
+                rule.decision,
+                rule.tool_name,
+                rule.command_pattern,
+                rule.path_glob
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Self::find_by_repo_id(pool, repo_id).await
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A snapshot of one repo's worktree taken after a coding agent turn,
+/// uncommitted changes included
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Checkpoint {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    /// The coding agent turn the snapshot was taken after
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    /// Hidden ref keeping the snapshot commit reachable
+    pub ref_name: String,
+    /// The snapshot commit, whose parent is `head_commit`
+    pub commit_sha: String,
+    /// HEAD when the snapshot was taken
+    pub head_commit: String,
+    /// Changes of the turn, counted from the previous checkpoint
+    pub files_changed: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateCheckpoint {
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    pub ref_name: String,
+    pub commit_sha: String,
+    pub head_commit: String,
+    pub files_changed: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+}
+
+impl Checkpoint {
+    /// The hidden ref holding the checkpoint of a turn in every repo of a
+    /// workspace
+    pub fn ref_name(workspace_id: Uuid, execution_process_id: Uuid) -> String {
+        format!("refs/vibe/checkpoints/{workspace_id}/{execution_process_id}")
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateCheckpoint) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Checkpoint,
+            r#"INSERT INTO checkpoints
+                   (id, workspace_id, session_id, execution_process_id, repo_id, ref_name,
+                    commit_sha, head_commit, files_changed, lines_added, lines_removed)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         execution_process_id AS "execution_process_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         ref_name,
+                         commit_sha,
+                         head_commit,
+                         files_changed AS "files_changed!: i64",
+                         lines_added AS "lines_added!: i64",
+                         lines_removed AS "lines_removed!: i64",
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.session_id,
+            data.execution_process_id,
+            data.repo_id,
+            data.ref_name,
+            data.commit_sha,
+            data.head_commit,
+            data.files_changed,
+            data.lines_added,
+            data.lines_removed
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Checkpoints of the turns a session still has, oldest first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Checkpoint,
+            r#"SELECT c.id AS "id!: Uuid",
+                      c.workspace_id AS "workspace_id!: Uuid",
+                      c.session_id AS "session_id!: Uuid",
+                      c.execution_process_id AS "execution_process_id!: Uuid",
+                      c.repo_id AS "repo_id!: Uuid",
+                      c.ref_name,
+                      c.commit_sha,
+                      c.head_commit,
+                      c.files_changed AS "files_changed!: i64",
+                      c.lines_added AS "lines_added!: i64",
+                      c.lines_removed AS "lines_removed!: i64",
+                      c.created_at AS "created_at!: DateTime<Utc>"
+               FROM checkpoints c
+               JOIN execution_processes ep ON ep.id = c.execution_process_id
+               WHERE c.session_id = $1 AND ep.dropped = FALSE
+               ORDER BY c.created_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Checkpoints of turns that a reset or restore dropped from a session
+    pub async fn find_dropped_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Checkpoint,
+            r#"SELECT c.id AS "id!: Uuid",
+                      c.workspace_id AS "workspace_id!: Uuid",
+                      c.session_id AS "session_id!: Uuid",
+                      c.execution_process_id AS "execution_process_id!: Uuid",
+                      c.repo_id AS "repo_id!: Uuid",
+                      c.ref_name,
+                      c.commit_sha,
+                      c.head_commit,
+                      c.files_changed AS "files_changed!: i64",
+                      c.lines_added AS "lines_added!: i64",
+                      c.lines_removed AS "lines_removed!: i64",
+                      c.created_at AS "created_at!: DateTime<Utc>"
+               FROM checkpoints c
+               JOIN execution_processes ep ON ep.id = c.execution_process_id
+               WHERE c.session_id = $1 AND ep.dropped = TRUE"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Checkpoint,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      ref_name,
+                      commit_sha,
+                      head_commit,
+                      files_changed AS "files_changed!: i64",
+                      lines_added AS "lines_added!: i64",
+                      lines_removed AS "lines_removed!: i64",
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM checkpoints
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The snapshot commit of the latest checkpoint of a repo among the turns
+    /// a session still has
+    pub async fn find_latest_commit(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT c.commit_sha
+               FROM checkpoints c
+               JOIN execution_processes ep ON ep.id = c.execution_process_id
+               WHERE c.session_id = $1 AND c.repo_id = $2 AND ep.dropped = FALSE
+               ORDER BY c.created_at DESC
+               LIMIT 1"#,
+            session_id,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM checkpoints WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -28,6 +28,21 @@ pub struct CreateCodingAgentTurn {
 pub struct CodingAgentResumeInfo {
     pub session_id: String,
     pub message_id: Option<String>,
+    /// Later coding agent turns were dropped by restoring a checkpoint or
+    /// retrying, so the agent's own history is ahead of the session
+    pub rewound: bool,
+}
+
+impl CodingAgentResumeInfo {
+    /// The message a follow-up trims the agent's history back to: the resumed
+    /// turn's last message when the session was rewound past later turns
+    pub fn reset_to_message_id(&self) -> Option<String> {
+        if self.rewound {
+            self.message_id.clone()
+        } else {
+            None
+        }
+    }
 }
 
 impl CodingAgentTurn {
@@ -41,7 +56,14 @@ impl CodingAgentTurn {
             CodingAgentResumeInfo,
             r#"SELECT
                 cat.agent_session_id as "session_id!",
-                cat.agent_message_id as "message_id"
+                cat.agent_message_id as "message_id",
+                EXISTS(
+                    SELECT 1 FROM execution_processes later
+                     WHERE later.session_id = ep.session_id
+                       AND later.run_reason = 'codingagent'
+                       AND later.dropped = TRUE
+                       AND later.created_at > ep.created_at
+                ) as "rewound!: bool"
                FROM execution_processes ep
                JOIN coding_agent_turns cat ON ep.id = cat.execution_process_id
                WHERE ep.session_id = $1
@@ -333,3 +355,81 @@ impl CodingAgentTurn {
         Ok(result.into_iter().collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::execution_process::ExecutionProcess,
+        test_support::{insert_session, insert_workspace, memory_pool},
+    };
+
+    /// A coding agent run `minute` minutes into the session, with a turn
+    /// whose last agent message is `message_id`
+    async fn insert_turn(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        minute: u32,
+        message_id: &str,
+    ) -> Uuid {
+        let execution_process_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, session_id, run_reason, created_at)
+             VALUES ($1, $2, 'codingagent', $3)",
+        )
+        .bind(execution_process_id)
+        .bind(session_id)
+        .bind(format!("2026-01-01 00:{minute:02}:00.000"))
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO coding_agent_turns
+                 (id, execution_process_id, agent_session_id, agent_message_id)
+             VALUES ($1, $2, 'agent-session', $3)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(execution_process_id)
+        .bind(message_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        execution_process_id
+    }
+
+    #[tokio::test]
+    async fn follow_ups_after_a_rewind_reset_to_the_resumed_turn() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        insert_turn(&pool, session_id, 1, "m1").await;
+        let checkpoint_turn = insert_turn(&pool, session_id, 2, "m2").await;
+        insert_turn(&pool, session_id, 3, "m3").await;
+
+        let info = CodingAgentTurn::find_latest_session_info(&pool, session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.message_id.as_deref(), Some("m3"));
+        assert_eq!(info.reset_to_message_id(), None);
+
+        // Restoring the checkpoint of the second turn drops the third
+        ExecutionProcess::drop_after(&pool, session_id, checkpoint_turn)
+            .await
+            .unwrap();
+        let info = CodingAgentTurn::find_latest_session_info(&pool, session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.reset_to_message_id().as_deref(), Some("m2"));
+
+        // The next turn continues from the trimmed history
+        insert_turn(&pool, session_id, 4, "m4").await;
+        let info = CodingAgentTurn::find_latest_session_info(&pool, session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.message_id.as_deref(), Some("m4"));
+        assert_eq!(info.reset_to_message_id(), None);
+    }
+}
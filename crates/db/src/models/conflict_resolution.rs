@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "conflict_resolution_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolutionStatus {
+    /// The coding agent is still working on the conflicts
+    Running,
+    /// The conflicts were staged and the operation continued
+    Continued,
+    /// The agent finished but conflict markers were left behind
+    Unresolved,
+    /// The agent run failed or the operation could not be continued
+    Failed,
+}
+
+/// A coding agent run resolving the conflicts of one repo in a workspace
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ConflictResolution {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub execution_process_id: Uuid,
+    /// `ConflictOp` of the operation being resolved
+    pub op: String,
+    #[ts(type = "Array<string>")]
+    pub conflicted_files: sqlx::types::Json<Vec<String>>,
+    pub status: ConflictResolutionStatus,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConflictResolution {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        execution_process_id: Uuid,
+        op: &str,
+        conflicted_files: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let conflicted_files = sqlx::types::Json(conflicted_files);
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"INSERT INTO conflict_resolutions
+                   (id, workspace_id, repo_id, execution_process_id, op, conflicted_files)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         execution_process_id AS "execution_process_id!: Uuid",
+                         op,
+                         conflicted_files AS "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                         status AS "status!: ConflictResolutionStatus",
+                         error,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            execution_process_id,
+            op,
+            conflicted_files
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      op,
+                      conflicted_files AS "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                      status AS "status!: ConflictResolutionStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Every resolution of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ConflictResolution,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      op,
+                      conflicted_files AS "conflicted_files!: sqlx::types::Json<Vec<String>>",
+                      status AS "status!: ConflictResolutionStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ConflictResolutionStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET status = $2, error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
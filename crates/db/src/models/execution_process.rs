@@ -41,6 +41,8 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    /// A coding agent waiting for a free concurrency slot
+    Queued,
     Running,
     Completed,
     Failed,
@@ -56,6 +58,18 @@ pub enum ExecutionProcessRunReason {
     ArchiveScript,
     CodingAgent,
     DevServer,
+    /// A race's test script; unlike cleanup scripts its changes aren't committed
+    TestScript,
+}
+
+/// Why an execution failed, beyond its exit code
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "execution_failure_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ExecutionFailureReason {
+    /// The kernel killed a process for exceeding the memory limit
+    OomKilled,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -67,6 +81,14 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    /// Set when the exit code alone doesn't explain a failure
+    pub failure_reason: Option<ExecutionFailureReason>,
+    /// Peak memory of the process and its children, recorded when resource
+    /// limits applied to it
+    pub peak_memory_bytes: Option<i64>,
+    /// CPU time of the process and its children, recorded when resource
+    /// limits applied to it
+    pub cpu_time_ms: Option<i64>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -82,6 +104,8 @@ pub struct CreateExecutionProcess {
     pub session_id: Uuid,
     pub executor_action: ExecutorAction,
     pub run_reason: ExecutionProcessRunReason,
+    /// `Running`, or `Queued` for a start that waits for a concurrency slot
+    pub status: ExecutionProcessStatus,
 }
 
 #[derive(Debug)]
@@ -136,6 +160,9 @@ impl ExecutionProcess {
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.failure_reason as "failure_reason?: ExecutionFailureReason",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep WHERE ep.id = ?"#,
@@ -210,6 +237,9 @@ impl ExecutionProcess {
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.failure_reason as "failure_reason?: ExecutionFailureReason",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep WHERE ep.rowid = ?"#,
@@ -237,6 +267,9 @@ impl ExecutionProcess {
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
+                      ep.peak_memory_bytes,
+                      ep.cpu_time_ms,
+                      ep.failure_reason    as "failure_reason?: ExecutionFailureReason",
                       ep.created_at      as "created_at!: DateTime<Utc>",
                       ep.updated_at      as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
@@ -264,6 +297,9 @@ impl ExecutionProcess {
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.failure_reason as "failure_reason?: ExecutionFailureReason",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep WHERE ep.status = 'running' ORDER BY ep.created_at ASC"#,
@@ -272,7 +308,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Check if there's a running coding agent process for a session
+    /// Check if there's a running or queued coding agent process for a session
     pub async fn has_running_coding_agent_for_session(
         pool: &SqlitePool,
         session_id: Uuid,
@@ -281,7 +317,7 @@ impl ExecutionProcess {
             r#"SELECT COUNT(*) as "count!: i64"
                FROM execution_processes ep
                WHERE ep.session_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('running', 'queued')
                  AND ep.run_reason = 'codingagent'"#,
             session_id
         )
@@ -290,7 +326,7 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
-    /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// Check if there are running or queued processes (excluding dev servers) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -300,7 +336,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('running', 'queued')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -327,6 +363,9 @@ impl ExecutionProcess {
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
+            ep.peak_memory_bytes,
+            ep.cpu_time_ms,
+            ep.failure_reason as "failure_reason?: ExecutionFailureReason",
             ep.created_at as "created_at!: DateTime<Utc>",
             ep.updated_at as "updated_at!: DateTime<Utc>"
         FROM execution_processes ep
@@ -361,6 +400,9 @@ impl ExecutionProcess {
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.failure_reason as "failure_reason?: ExecutionFailureReason",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
@@ -399,7 +441,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            data.status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -416,6 +458,38 @@ impl ExecutionProcess {
             .ok_or(sqlx::Error::RowNotFound)
     }
 
+    /// Mark a queued execution as running from now. Returns false when it
+    /// is no longer queued, e.g. because it was cancelled.
+    pub async fn start_queued(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a queued execution as killed before it started. Returns false
+    /// when it is no longer queued.
+    pub async fn cancel_queued(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'killed', completed_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn was_stopped(pool: &SqlitePool, id: Uuid) -> bool {
         if let Ok(exp_process) = Self::find_by_id(pool, id).await
             && exp_process.is_some_and(|ep| {
@@ -456,6 +530,30 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Record what a limited execution used, and why it failed if the limits
+    /// were the cause
+    pub async fn update_resource_usage(
+        pool: &SqlitePool,
+        id: Uuid,
+        peak_memory_bytes: Option<i64>,
+        cpu_time_ms: Option<i64>,
+        failure_reason: Option<ExecutionFailureReason>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET peak_memory_bytes = $1, cpu_time_ms = $2, failure_reason = $3
+               WHERE id = $4"#,
+            peak_memory_bytes,
+            cpu_time_ms,
+            failure_reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -485,6 +583,26 @@ impl ExecutionProcess {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Soft-drop processes strictly after the specified boundary (exclusive)
+    pub async fn drop_after(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        boundary_process_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET dropped = TRUE
+             WHERE session_id = $1
+               AND created_at > (SELECT created_at FROM execution_processes WHERE id = $2)
+               AND dropped = FALSE"#,
+            session_id,
+            boundary_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() as i64)
+    }
+
     /// Find the previous process's after_head_commit before the given boundary process
     /// for a specific repository
     pub async fn find_prev_after_head_commit(
@@ -573,6 +691,9 @@ impl ExecutionProcess {
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.peak_memory_bytes,
+                    ep.cpu_time_ms,
+                    ep.failure_reason as "failure_reason?: ExecutionFailureReason",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ExecutionStartQueueError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Reorder must list every queued execution exactly once")]
+    InvalidOrder,
+}
+
+/// A coding agent execution waiting for a free concurrency slot
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedExecution {
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    pub workspace_branch: String,
+    /// The coding agent of the session, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    /// Ordering of the queue; lowest starts first
+    pub position: i64,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QueuedExecution {
+    /// Every queued execution in start order
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedExecution,
+            r#"SELECT q.execution_process_id AS "execution_process_id!: Uuid",
+                      ep.session_id AS "session_id!: Uuid",
+                      s.workspace_id AS "workspace_id!: Uuid",
+                      w.name AS workspace_name,
+                      w.branch AS workspace_branch,
+                      s.executor,
+                      q.position AS "position!: i64",
+                      q.queued_at AS "queued_at!: DateTime<Utc>"
+               FROM execution_start_queue q
+               JOIN execution_processes ep ON ep.id = q.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               ORDER BY q.position ASC, q.queued_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Append an execution to the end of the queue
+    pub async fn create(pool: &SqlitePool, execution_process_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO execution_start_queue (execution_process_id, position)
+               VALUES (
+                   $1,
+                   (SELECT COALESCE(MAX(position) + 1, 0) FROM execution_start_queue)
+               )"#,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, execution_process_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM execution_start_queue WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Rewrite positions so the queue starts executions in the order of
+    /// `ordered_ids`, which must contain every queued execution exactly once.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        ordered_ids: &[Uuid],
+    ) -> Result<Vec<Self>, ExecutionStartQueueError> {
+        let mut tx = pool.begin().await?;
+
+        let existing: HashSet<Uuid> = sqlx::query_scalar!(
+            r#"SELECT execution_process_id AS "execution_process_id!: Uuid"
+               FROM execution_start_queue"#
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != existing {
+            return Err(ExecutionStartQueueError::InvalidOrder);
+        }
+
+        for (position, id) in ordered_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "UPDATE execution_start_queue SET position = $1 WHERE execution_process_id = $2",
+                position,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Self::find_all(pool).await?)
+    }
+}
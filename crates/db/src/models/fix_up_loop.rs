@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::verification::VerificationRunStatus;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "fix_up_loop_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FixUpLoopStatus {
+    /// Verifying, or waiting for the coding agent to fix a failure
+    Running,
+    /// The verification steps passed
+    Passed,
+    /// The steps still failed after `max_iterations` follow-ups
+    Exhausted,
+    /// A follow-up could not be started or did not complete
+    Failed,
+    /// Stopped by the user, or a step was stopped before it finished
+    Cancelled,
+}
+
+/// A loop sending failing verification output back to a session's coding
+/// agent until the verification steps pass
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct FixUpLoop {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    /// Most follow-ups the loop sends before giving up
+    pub max_iterations: i64,
+    /// Follow-ups sent so far
+    pub iterations: i64,
+    /// Coding agent run of the latest follow-up
+    pub execution_process_id: Option<Uuid>,
+    /// Verification run chained after the latest follow-up
+    pub verification_run_id: Option<Uuid>,
+    pub status: FixUpLoopStatus,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What a running loop does once one of its verification runs settled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixUpTransition {
+    /// The run is still going
+    Wait,
+    /// Send the failure to the coding agent as another follow-up
+    Iterate,
+    /// End the loop and send the user's queued follow-up message instead of
+    /// another fix-up
+    SendQueuedMessage,
+    /// End the loop, notifying the user with `notification`
+    Finish {
+        status: FixUpLoopStatus,
+        error: Option<String>,
+        notification: String,
+    },
+}
+
+impl FixUpLoop {
+    /// The next step after a verification run of the loop ended with
+    /// `run_status`. `has_queued_message` is whether the user queued a
+    /// follow-up, which goes before another fix-up and ends the loop.
+    pub fn transition(
+        &self,
+        run_status: VerificationRunStatus,
+        has_queued_message: bool,
+    ) -> FixUpTransition {
+        let finish =
+            |status, error: Option<String>, notification: String| FixUpTransition::Finish {
+                status,
+                error,
+                notification,
+            };
+        match run_status {
+            VerificationRunStatus::Running => FixUpTransition::Wait,
+            VerificationRunStatus::Passed => finish(
+                FixUpLoopStatus::Passed,
+                None,
+                match self.iterations {
+                    0 => "Verification passed".to_string(),
+                    1 => "Verification passed after 1 fix-up follow-up".to_string(),
+                    n => format!("Verification passed after {n} fix-up follow-ups"),
+                },
+            ),
+            VerificationRunStatus::Cancelled => {
+                let error = "A verification step was stopped".to_string();
+                finish(FixUpLoopStatus::Cancelled, Some(error.clone()), error)
+            }
+            VerificationRunStatus::Failed if self.iterations >= self.max_iterations => {
+                let error = format!(
+                    "Verification still fails after {} of {} fix-up follow-ups",
+                    self.iterations, self.max_iterations
+                );
+                finish(FixUpLoopStatus::Exhausted, Some(error.clone()), error)
+            }
+            VerificationRunStatus::Failed if has_queued_message => {
+                FixUpTransition::SendQueuedMessage
+            }
+            VerificationRunStatus::Failed => FixUpTransition::Iterate,
+        }
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        session_id: Uuid,
+        max_iterations: i64,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            FixUpLoop,
+            r#"INSERT INTO fix_up_loops (id, workspace_id, session_id, max_iterations)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         max_iterations AS "max_iterations!: i64",
+                         iterations AS "iterations!: i64",
+                         execution_process_id AS "execution_process_id?: Uuid",
+                         verification_run_id AS "verification_run_id?: Uuid",
+                         status AS "status!: FixUpLoopStatus",
+                         error,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            session_id,
+            max_iterations
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The loop still running in a session, if any
+    pub async fn find_running_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FixUpLoop,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      max_iterations AS "max_iterations!: i64",
+                      iterations AS "iterations!: i64",
+                      execution_process_id AS "execution_process_id?: Uuid",
+                      verification_run_id AS "verification_run_id?: Uuid",
+                      status AS "status!: FixUpLoopStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM fix_up_loops
+               WHERE session_id = $1 AND status = 'running'"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Every loop of a session, newest first
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FixUpLoop,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      max_iterations AS "max_iterations!: i64",
+                      iterations AS "iterations!: i64",
+                      execution_process_id AS "execution_process_id?: Uuid",
+                      verification_run_id AS "verification_run_id?: Uuid",
+                      status AS "status!: FixUpLoopStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM fix_up_loops
+               WHERE session_id = $1
+               ORDER BY created_at DESC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Count a follow-up sent by the loop, with the coding agent run and the
+    /// verification run it started
+    pub async fn record_iteration(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+        verification_run_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE fix_up_loops
+               SET iterations = iterations + 1,
+                   execution_process_id = $2,
+                   verification_run_id = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id,
+            verification_run_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: FixUpLoopStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE fix_up_loops
+               SET status = $2, error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::verification::{VerificationRun, VerificationTrigger},
+        test_support::{insert_execution_process, insert_session, insert_workspace, memory_pool},
+    };
+
+    fn finished_status(transition: FixUpTransition) -> Option<FixUpLoopStatus> {
+        match transition {
+            FixUpTransition::Finish { status, .. } => Some(status),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn iterations_are_counted_until_exhausted() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        let fix_up = FixUpLoop::create(&pool, workspace_id, session_id, 2)
+            .await
+            .unwrap();
+        assert_eq!(fix_up.iterations, 0);
+        assert_eq!(
+            fix_up.transition(VerificationRunStatus::Failed, false),
+            FixUpTransition::Iterate
+        );
+
+        for expected in 1..=2 {
+            let execution_process_id = insert_execution_process(&pool, session_id).await;
+            let run = VerificationRun::create(
+                &pool,
+                workspace_id,
+                session_id,
+                VerificationTrigger::Agent,
+                &[],
+            )
+            .await
+            .unwrap();
+            FixUpLoop::record_iteration(&pool, fix_up.id, execution_process_id, run.run.id)
+                .await
+                .unwrap();
+            let fix_up = FixUpLoop::find_running_by_session_id(&pool, session_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(fix_up.iterations, expected);
+            assert_eq!(fix_up.execution_process_id, Some(execution_process_id));
+            assert_eq!(fix_up.verification_run_id, Some(run.run.id));
+        }
+
+        let fix_up = FixUpLoop::find_running_by_session_id(&pool, session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let FixUpTransition::Finish {
+            status,
+            error: Some(error),
+            ..
+        } = fix_up.transition(VerificationRunStatus::Failed, false)
+        else {
+            panic!("a loop out of iterations should finish");
+        };
+        assert_eq!(status, FixUpLoopStatus::Exhausted);
+        assert!(error.contains("after 2 of 2"));
+    }
+
+    #[tokio::test]
+    async fn settled_runs_end_the_loop() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        let fix_up = FixUpLoop::create(&pool, workspace_id, session_id, 3)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fix_up.transition(VerificationRunStatus::Running, false),
+            FixUpTransition::Wait
+        );
+        assert_eq!(
+            finished_status(fix_up.transition(VerificationRunStatus::Passed, false)),
+            Some(FixUpLoopStatus::Passed)
+        );
+        assert_eq!(
+            finished_status(fix_up.transition(VerificationRunStatus::Cancelled, false)),
+            Some(FixUpLoopStatus::Cancelled)
+        );
+        // A queued message goes before another fix-up
+        assert_eq!(
+            fix_up.transition(VerificationRunStatus::Failed, true),
+            FixUpTransition::SendQueuedMessage
+        );
+
+        FixUpLoop::update_status(&pool, fix_up.id, FixUpLoopStatus::Passed, None)
+            .await
+            .unwrap();
+        assert!(
+            FixUpLoop::find_running_by_session_id(&pool, session_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn one_running_loop_per_session() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        let other_session_id = insert_session(&pool, workspace_id).await;
+        let first = FixUpLoop::create(&pool, workspace_id, session_id, 3)
+            .await
+            .unwrap();
+
+        assert!(
+            FixUpLoop::create(&pool, workspace_id, session_id, 3)
+                .await
+                .is_err()
+        );
+        FixUpLoop::create(&pool, workspace_id, other_session_id, 3)
+            .await
+            .unwrap();
+
+        // A finished loop no longer blocks a new one
+        FixUpLoop::update_status(&pool, first.id, FixUpLoopStatus::Exhausted, Some("done"))
+            .await
+            .unwrap();
+        let second = FixUpLoop::create(&pool, workspace_id, session_id, 3)
+            .await
+            .unwrap();
+        let loops = FixUpLoop::find_by_session_id(&pool, session_id)
+            .await
+            .unwrap();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(
+            FixUpLoop::find_running_by_session_id(&pool, session_id)
+                .await
+                .unwrap()
+                .map(|fix_up| fix_up.id),
+            Some(second.id)
+        );
+    }
+}
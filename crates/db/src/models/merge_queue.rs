@@ -0,0 +1,430 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum MergeQueueError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Merge queue entry not found")]
+    NotFound,
+    #[error("Cannot queue merge: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "merge_queue_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MergeQueueStatus {
+    /// Waiting for the entries ahead of it
+    Queued,
+    /// Being rebased, verified and merged
+    Running,
+    Merged,
+    /// Rebasing onto the target branch hit conflicts; pauses the queue
+    Conflict,
+    /// Verification or the merge failed; pauses the queue
+    Failed,
+    Cancelled,
+}
+
+/// A workspace waiting in the merge queue of one repo and target branch
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeQueueEntry {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// `MergeStrategy` the workspace is merged with
+    pub strategy: String,
+    pub status: MergeQueueStatus,
+    pub error: Option<String>,
+    /// The latest verification step run for this entry
+    pub verification_execution_process_id: Option<Uuid>,
+    pub merge_commit: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MergeQueueEntry {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+        strategy: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"INSERT INTO merge_queue_entries (id, workspace_id, repo_id, target_branch, strategy)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         target_branch,
+                         strategy,
+                         status AS "status!: MergeQueueStatus",
+                         error,
+                         verification_execution_process_id AS "verification_execution_process_id?: Uuid",
+                         merge_commit,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            target_branch,
+            strategy
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      strategy,
+                      status AS "status!: MergeQueueStatus",
+                      error,
+                      verification_execution_process_id AS "verification_execution_process_id?: Uuid",
+                      merge_commit,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Entries that are waiting, running or pausing a queue, in queue order
+    pub async fn find_pending(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      strategy,
+                      status AS "status!: MergeQueueStatus",
+                      error,
+                      verification_execution_process_id AS "verification_execution_process_id?: Uuid",
+                      merge_commit,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE status IN ('queued', 'running', 'conflict', 'failed')
+               ORDER BY repo_id, target_branch, rowid"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Every entry of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      strategy,
+                      status AS "status!: MergeQueueStatus",
+                      error,
+                      verification_execution_process_id AS "verification_execution_process_id?: Uuid",
+                      merge_commit,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries
+               WHERE workspace_id = $1
+               ORDER BY rowid DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The oldest queued entry of every queue that is neither running nor
+    /// paused, leaving out workspaces that already have an entry running in
+    /// another queue
+    pub async fn find_ready(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeQueueEntry,
+            r#"SELECT q.id AS "id!: Uuid",
+                      q.workspace_id AS "workspace_id!: Uuid",
+                      q.repo_id AS "repo_id!: Uuid",
+                      q.target_branch,
+                      q.strategy,
+                      q.status AS "status!: MergeQueueStatus",
+                      q.error,
+                      q.verification_execution_process_id AS "verification_execution_process_id?: Uuid",
+                      q.merge_commit,
+                      q.created_at AS "created_at!: DateTime<Utc>",
+                      q.updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_queue_entries q
+               WHERE q.status = 'queued'
+                 AND NOT EXISTS (
+                     SELECT 1 FROM merge_queue_entries other
+                     WHERE other.repo_id = q.repo_id
+                       AND other.target_branch = q.target_branch
+                       AND (other.status IN ('running', 'conflict', 'failed')
+                            OR (other.status = 'queued' AND other.rowid < q.rowid))
+                 )
+                 AND NOT EXISTS (
+                     SELECT 1 FROM merge_queue_entries busy
+                     WHERE busy.workspace_id = q.workspace_id
+                       AND busy.status = 'running'
+                 )
+               ORDER BY q.rowid"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Move a queued entry to `running`. Returns false if it was no longer
+    /// queued.
+    pub async fn claim(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'running', error = NULL, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'queued'"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: MergeQueueStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = $2, error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_verification_execution_process(
+        pool: &SqlitePool,
+        id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET verification_execution_process_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_merged(
+        pool: &SqlitePool,
+        id: Uuid,
+        merge_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'merged', error = NULL, merge_commit = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            merge_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Put a conflicted or failed entry back at the head of its queue, which
+    /// resumes the queue. Returns false if the entry was not paused.
+    pub async fn retry(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'queued', error = NULL, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status IN ('conflict', 'failed')"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take an entry out of its queue. Running entries cannot be cancelled.
+    /// Returns false if the entry was not waiting or paused.
+    pub async fn cancel(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'cancelled', updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status IN ('queued', 'conflict', 'failed')"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fail entries left running by a previous server process, so their
+    /// queues pause instead of merging a half-processed workspace
+    pub async fn fail_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE merge_queue_entries
+               SET status = 'failed', error = 'Interrupted by a server restart',
+                   updated_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_repo, insert_workspace, memory_pool};
+
+    fn ids(entries: &[MergeQueueEntry]) -> Vec<Uuid> {
+        entries.iter().map(|entry| entry.id).collect()
+    }
+
+    async fn enqueue(pool: &SqlitePool, repo_id: Uuid, target_branch: &str) -> MergeQueueEntry {
+        let workspace_id = insert_workspace(pool).await;
+        MergeQueueEntry::create(pool, workspace_id, repo_id, target_branch, "squash")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_the_head_of_each_queue_is_ready() {
+        let pool = memory_pool().await;
+        let repo_id = insert_repo(&pool, "app").await;
+        let main_first = enqueue(&pool, repo_id, "main").await;
+        let main_second = enqueue(&pool, repo_id, "main").await;
+        let release = enqueue(&pool, repo_id, "release").await;
+
+        let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+        assert_eq!(ids(&ready), [main_first.id, release.id]);
+
+        // The running head still blocks its queue, not the other one
+        assert!(MergeQueueEntry::claim(&pool, main_first.id).await.unwrap());
+        let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+        assert_eq!(ids(&ready), [release.id]);
+
+        MergeQueueEntry::mark_merged(&pool, main_first.id, "abc123")
+            .await
+            .unwrap();
+        let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+        assert_eq!(ids(&ready), [main_second.id, release.id]);
+    }
+
+    #[tokio::test]
+    async fn conflict_or_failure_pauses_the_queue_until_retried() {
+        let pool = memory_pool().await;
+        let repo_id = insert_repo(&pool, "app").await;
+
+        for status in [MergeQueueStatus::Conflict, MergeQueueStatus::Failed] {
+            let branch = format!("target-{status:?}");
+            let head = enqueue(&pool, repo_id, &branch).await;
+            let next = enqueue(&pool, repo_id, &branch).await;
+            assert!(MergeQueueEntry::claim(&pool, head.id).await.unwrap());
+            MergeQueueEntry::update_status(&pool, head.id, status, Some("boom"))
+                .await
+                .unwrap();
+            let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+            assert!(!ids(&ready).contains(&next.id), "{status:?}");
+
+            assert!(MergeQueueEntry::retry(&pool, head.id).await.unwrap());
+            let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+            assert!(ids(&ready).contains(&head.id), "{status:?}");
+            assert!(!ids(&ready).contains(&next.id), "{status:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn an_entry_is_claimed_once() {
+        let pool = memory_pool().await;
+        let repo_id = insert_repo(&pool, "app").await;
+        let entry = enqueue(&pool, repo_id, "main").await;
+
+        // Whoever claims second sees the entry is no longer queued
+        assert!(MergeQueueEntry::claim(&pool, entry.id).await.unwrap());
+        assert!(!MergeQueueEntry::claim(&pool, entry.id).await.unwrap());
+        let entry = MergeQueueEntry::find_by_id(&pool, entry.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.status, MergeQueueStatus::Running);
+    }
+
+    #[tokio::test]
+    async fn a_workspace_runs_one_entry_at_a_time() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let app = insert_repo(&pool, "app").await;
+        let lib = insert_repo(&pool, "lib").await;
+        let app_entry = MergeQueueEntry::create(&pool, workspace_id, app, "main", "squash")
+            .await
+            .unwrap();
+        let lib_entry = MergeQueueEntry::create(&pool, workspace_id, lib, "main", "merge")
+            .await
+            .unwrap();
+
+        assert!(MergeQueueEntry::claim(&pool, app_entry.id).await.unwrap());
+        assert!(MergeQueueEntry::find_ready(&pool).await.unwrap().is_empty());
+
+        MergeQueueEntry::mark_merged(&pool, app_entry.id, "abc123")
+            .await
+            .unwrap();
+        let ready = MergeQueueEntry::find_ready(&pool).await.unwrap();
+        assert_eq!(ids(&ready), [lib_entry.id]);
+    }
+
+    #[tokio::test]
+    async fn interrupted_entries_fail_and_pause_their_queue() {
+        let pool = memory_pool().await;
+        let repo_id = insert_repo(&pool, "app").await;
+        let head = enqueue(&pool, repo_id, "main").await;
+        let next = enqueue(&pool, repo_id, "main").await;
+        assert!(MergeQueueEntry::claim(&pool, head.id).await.unwrap());
+
+        assert_eq!(MergeQueueEntry::fail_interrupted(&pool).await.unwrap(), 1);
+        let head = MergeQueueEntry::find_by_id(&pool, head.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(head.status, MergeQueueStatus::Failed);
+        assert!(head.error.is_some());
+        let next = MergeQueueEntry::find_by_id(&pool, next.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.status, MergeQueueStatus::Queued);
+        assert!(MergeQueueEntry::find_ready(&pool).await.unwrap().is_empty());
+        assert_eq!(MergeQueueEntry::fail_interrupted(&pool).await.unwrap(), 0);
+    }
+}
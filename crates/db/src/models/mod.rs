@@ -1,18 +1,35 @@
+pub mod approval;
+pub mod approval_rule;
+pub mod checkpoint;
 pub mod coding_agent_turn;
+pub mod conflict_resolution;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod execution_start_queue;
 pub mod external_session;
 pub mod file;
+pub mod fix_up_loop;
 pub mod merge;
+pub mod merge_queue;
+pub mod model_price;
+pub mod pr_comment_fix;
 pub mod project;
 pub mod pull_request;
+pub mod queued_message;
+pub mod race;
 pub mod repo;
 pub mod requests;
+pub mod schedule;
 pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod token_usage;
+pub mod verification;
 pub mod webhook;
+pub mod webhook_delivery;
 pub mod workspace;
+pub mod workspace_budget;
 pub mod workspace_repo;
+pub mod workspace_stack;
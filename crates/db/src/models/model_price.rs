@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use executors::logs::TokenUsage;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+
+#[derive(Debug, Error)]
+pub enum ModelPriceError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Model price not found")]
+    NotFound,
+    #[error("Invalid model price: {0}")]
+    Invalid(String),
+}
+
+/// Price of a model in USD per million tokens. Applies to every model id that
+/// starts with `model`; the longest matching prefix wins.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ModelPrice {
+    pub model: String,
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cache_read_per_mtok: f64,
+    pub cache_write_per_mtok: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    #[serde(default)]
+    pub cache_read_per_mtok: f64,
+    #[serde(default)]
+    pub cache_write_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_mtok
+            + usage.output_tokens as f64 * self.output_per_mtok
+            + usage.cache_read_tokens as f64 * self.cache_read_per_mtok
+            + usage.cache_write_tokens as f64 * self.cache_write_per_mtok)
+            / 1_000_000.0
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelPrice,
+            r#"SELECT model AS "model!",
+                      input_per_mtok AS "input_per_mtok!: f64",
+                      output_per_mtok AS "output_per_mtok!: f64",
+                      cache_read_per_mtok AS "cache_read_per_mtok!: f64",
+                      cache_write_per_mtok AS "cache_write_per_mtok!: f64",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM model_prices
+               ORDER BY model ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The price with the longest `model` that is a prefix of `model_id`
+    pub async fn find_for_model(
+        pool: &SqlitePool,
+        model_id: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ModelPrice,
+            r#"SELECT model AS "model!",
+                      input_per_mtok AS "input_per_mtok!: f64",
+                      output_per_mtok AS "output_per_mtok!: f64",
+                      cache_read_per_mtok AS "cache_read_per_mtok!: f64",
+                      cache_write_per_mtok AS "cache_write_per_mtok!: f64",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM model_prices
+               WHERE substr($1, 1, length(model)) = model
+               ORDER BY length(model) DESC
+               LIMIT 1"#,
+            model_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        model: &str,
+        data: &UpsertModelPrice,
+    ) -> Result<Self, ModelPriceError> {
+        if model.trim().is_empty() {
+            return Err(ModelPriceError::Invalid(
+                "model must not be empty".to_string(),
+            ));
+        }
+        let prices = [
+            data.input_per_mtok,
+            data.output_per_mtok,
+            data.cache_read_per_mtok,
+            data.cache_write_per_mtok,
+        ];
+        if prices
+            .iter()
+            .any(|price| !price.is_finite() || *price < 0.0)
+        {
+            return Err(ModelPriceError::Invalid(
+                "prices must be non-negative numbers".to_string(),
+            ));
+        }
+
+        Ok(sqlx::query_as!(
+            ModelPrice,
+            r#"INSERT INTO model_prices
+                   (model, input_per_mtok, output_per_mtok, cache_read_per_mtok, cache_write_per_mtok)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(model) DO UPDATE SET
+                   input_per_mtok = excluded.input_per_mtok,
+                   output_per_mtok = excluded.output_per_mtok,
+                   cache_read_per_mtok = excluded.cache_read_per_mtok,
+                   cache_write_per_mtok = excluded.cache_write_per_mtok,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING model AS "model!",
+                         input_per_mtok AS "input_per_mtok!: f64",
+                         output_per_mtok AS "output_per_mtok!: f64",
+                         cache_read_per_mtok AS "cache_read_per_mtok!: f64",
+                         cache_write_per_mtok AS "cache_write_per_mtok!: f64",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            model,
+            data.input_per_mtok,
+            data.output_per_mtok,
+            data.cache_read_per_mtok,
+            data.cache_write_per_mtok
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(pool: &SqlitePool, model: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM model_prices WHERE model = $1", model)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_is_priced_per_million_tokens() {
+        let price = ModelPrice {
+            model: "claude-sonnet-4".to_string(),
+            input_per_mtok: 3.0,
+            output_per_mtok: 15.0,
+            cache_read_per_mtok: 0.3,
+            cache_write_per_mtok: 3.75,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let usage = TokenUsage {
+            model: Some("claude-sonnet-4-20250514".to_string()),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 2_000_000,
+            cache_write_tokens: 0,
+        };
+        assert!((price.cost_usd(&usage) - 5.1).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,210 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "pr_comment_fix_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PrCommentFixStatus {
+    /// The coding agent or its cleanup scripts are still running
+    Running,
+    /// The branch was pushed and the comments were replied to
+    Addressed,
+    /// The follow-up did not complete, made no changes, or could not be pushed
+    Failed,
+    /// The coding agent was stopped
+    Cancelled,
+}
+
+/// A coding agent follow-up addressing inline review comments on the pull
+/// request of one repo in a workspace
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PrCommentFix {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub session_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub pr_number: i64,
+    /// The host's ids of the review comments being addressed
+    #[ts(type = "Array<bigint>")]
+    pub comment_ids: sqlx::types::Json<Vec<i64>>,
+    /// Mark each replied thread resolved
+    pub resolve_threads: bool,
+    /// How many of the comments were replied to
+    pub replied: i64,
+    pub status: PrCommentFixStatus,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PrCommentFix {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        session_id: Uuid,
+        execution_process_id: Uuid,
+        pr_number: i64,
+        comment_ids: &[i64],
+        resolve_threads: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let comment_ids = sqlx::types::Json(comment_ids);
+        sqlx::query_as!(
+            PrCommentFix,
+            r#"INSERT INTO pr_comment_fixes
+                   (id, workspace_id, repo_id, session_id, execution_process_id, pr_number,
+                    comment_ids, resolve_threads)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         execution_process_id AS "execution_process_id!: Uuid",
+                         pr_number AS "pr_number!: i64",
+                         comment_ids AS "comment_ids!: sqlx::types::Json<Vec<i64>>",
+                         resolve_threads AS "resolve_threads!: bool",
+                         replied AS "replied!: i64",
+                         status AS "status!: PrCommentFixStatus",
+                         error,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            session_id,
+            execution_process_id,
+            pr_number,
+            comment_ids,
+            resolve_threads
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The fix still running in a session, if any
+    pub async fn find_running_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrCommentFix,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      pr_number AS "pr_number!: i64",
+                      comment_ids AS "comment_ids!: sqlx::types::Json<Vec<i64>>",
+                      resolve_threads AS "resolve_threads!: bool",
+                      replied AS "replied!: i64",
+                      status AS "status!: PrCommentFixStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM pr_comment_fixes
+               WHERE session_id = $1 AND status = 'running'"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Every fix of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PrCommentFix,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      execution_process_id AS "execution_process_id!: Uuid",
+                      pr_number AS "pr_number!: i64",
+                      comment_ids AS "comment_ids!: sqlx::types::Json<Vec<i64>>",
+                      resolve_threads AS "resolve_threads!: bool",
+                      replied AS "replied!: i64",
+                      status AS "status!: PrCommentFixStatus",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM pr_comment_fixes
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Ids of the review comments on a pull request that a fix has addressed
+    /// or is addressing
+    pub async fn find_addressed_comment_ids(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        pr_number: i64,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query_scalar!(
+            r#"SELECT comment_ids AS "comment_ids!: sqlx::types::Json<Vec<i64>>"
+               FROM pr_comment_fixes
+               WHERE workspace_id = $1
+                 AND repo_id = $2
+                 AND pr_number = $3
+                 AND status IN ('running', 'addressed')"#,
+            workspace_id,
+            repo_id,
+            pr_number
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().flat_map(|ids| ids.0).collect())
+    }
+
+    /// Record that the branch was pushed and `replied` comments were replied to
+    pub async fn mark_addressed(
+        pool: &SqlitePool,
+        id: Uuid,
+        replied: i64,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_comment_fixes
+               SET status = 'addressed', replied = $2, error = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            replied,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PrCommentFixStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE pr_comment_fixes
+               SET status = $2, error = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
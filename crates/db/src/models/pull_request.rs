@@ -312,6 +312,24 @@ impl PullRequest {
         .await
     }
 
+    /// Record a new base branch after the pull request was retargeted
+    pub async fn update_target_branch(
+        pool: &SqlitePool,
+        pr_url: &str,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE pull_requests SET target_branch_name = ?, updated_at = ? WHERE pr_url = ?",
+            target_branch_name,
+            now,
+            pr_url,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn mark_synced(pool: &SqlitePool, id: &str) -> Result<(), sqlx::Error> {
         let now = Utc::now();
         sqlx::query!(
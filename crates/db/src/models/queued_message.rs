@@ -0,0 +1,357 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("Reorder must list every queued message of the session exactly once")]
+    InvalidOrder,
+}
+
+/// A follow-up message waiting for the session's current execution to finish
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    /// The session this message is queued for
+    pub session_id: Uuid,
+    /// Ordering within the session's queue; lowest runs first
+    pub position: i64,
+    /// The follow-up data (message + executor config)
+    #[ts(type = "DraftFollowUpData")]
+    pub data: sqlx::types::Json<DraftFollowUpData>,
+    /// Timestamp when the message was queued
+    pub queued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QueuedMessage {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id AS "id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      position AS "position!: i64",
+                      data AS "data!: sqlx::types::Json<DraftFollowUpData>",
+                      queued_at AS "queued_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// All queued messages for a session in execution order
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"SELECT id AS "id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      position AS "position!: i64",
+                      data AS "data!: sqlx::types::Json<DraftFollowUpData>",
+                      queued_at AS "queued_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC, queued_at ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn exists_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM queued_messages WHERE session_id = $1) AS "exists!: bool""#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn create(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"INSERT INTO queued_messages (id, session_id, position, data)
+               VALUES (
+                   $1,
+                   $2,
+                   (SELECT COALESCE(MAX(position) + 1, 0) FROM queued_messages WHERE session_id = $2),
+                   $3
+               )
+               RETURNING id AS "id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         position AS "position!: i64",
+                         data AS "data!: sqlx::types::Json<DraftFollowUpData>",
+                         queued_at AS "queued_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_data(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, QueuedMessageError> {
+        let data = sqlx::types::Json(data);
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"UPDATE queued_messages
+               SET data = $1, updated_at = datetime('now', 'subsec')
+               WHERE id = $2 AND session_id = $3
+               RETURNING id AS "id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         position AS "position!: i64",
+                         data AS "data!: sqlx::types::Json<DraftFollowUpData>",
+                         queued_at AS "queued_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            data,
+            id,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(QueuedMessageError::NotFound)
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        id: Uuid,
+    ) -> Result<(), QueuedMessageError> {
+        let rows = sqlx::query!(
+            "DELETE FROM queued_messages WHERE id = $1 AND session_id = $2",
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+        if rows == 0 {
+            Err(QueuedMessageError::NotFound)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove and return the message at the head of the session's queue.
+    /// Done in a single statement so two finishing executions can't both
+    /// consume the same message.
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            QueuedMessage,
+            r#"DELETE FROM queued_messages
+               WHERE id = (
+                   SELECT id FROM queued_messages
+                   WHERE session_id = $1
+                   ORDER BY position ASC, queued_at ASC
+                   LIMIT 1
+               )
+               RETURNING id AS "id!: Uuid",
+                         session_id AS "session_id!: Uuid",
+                         position AS "position!: i64",
+                         data AS "data!: sqlx::types::Json<DraftFollowUpData>",
+                         queued_at AS "queued_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Rewrite positions so the queue runs in the order of `ordered_ids`,
+    /// which must contain every message of the session exactly once.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<Vec<Self>, QueuedMessageError> {
+        let mut tx = pool.begin().await?;
+
+        let existing: HashSet<Uuid> = sqlx::query_scalar!(
+            r#"SELECT id AS "id!: Uuid" FROM queued_messages WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != existing {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+
+        for (position, id) in ordered_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                r#"UPDATE queued_messages
+                   SET position = $1, updated_at = datetime('now', 'subsec')
+                   WHERE id = $2"#,
+                position,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Self::find_by_session_id(pool, session_id).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{
+        executors::BaseCodingAgent,
+        profile::{ExecutorConfig, ExecutorProfileId},
+    };
+
+    use super::*;
+    use crate::test_support::{insert_session, insert_workspace, memory_pool};
+
+    fn message(text: &str) -> DraftFollowUpData {
+        DraftFollowUpData {
+            message: text.to_string(),
+            executor_config: ExecutorConfig::from(ExecutorProfileId::new(
+                BaseCodingAgent::ClaudeCode,
+            )),
+        }
+    }
+
+    fn texts(messages: &[QueuedMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.data.message.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn messages_run_in_the_order_they_were_queued() {
+        let pool = memory_pool().await;
+        let session_id = insert_session(&pool, insert_workspace(&pool).await).await;
+        for text in ["first", "second", "third"] {
+            QueuedMessage::create(&pool, session_id, &message(text))
+                .await
+                .unwrap();
+        }
+
+        let queue = QueuedMessage::find_by_session_id(&pool, session_id)
+            .await
+            .unwrap();
+        assert_eq!(texts(&queue), ["first", "second", "third"]);
+        assert_eq!(
+            queue.iter().map(|m| m.position).collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn reorder_requires_every_message_exactly_once() {
+        let pool = memory_pool().await;
+        let session_id = insert_session(&pool, insert_workspace(&pool).await).await;
+        let mut ids = Vec::new();
+        for text in ["a", "b", "c"] {
+            ids.push(
+                QueuedMessage::create(&pool, session_id, &message(text))
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+
+        let reordered = QueuedMessage::reorder(&pool, session_id, &[ids[2], ids[0], ids[1]])
+            .await
+            .unwrap();
+        assert_eq!(texts(&reordered), ["c", "a", "b"]);
+
+        for invalid in [vec![ids[0], ids[1]], vec![ids[0], ids[0], ids[1], ids[2]]] {
+            assert!(matches!(
+                QueuedMessage::reorder(&pool, session_id, &invalid).await,
+                Err(QueuedMessageError::InvalidOrder)
+            ));
+        }
+        // A rejected reorder leaves the queue as it was
+        let queue = QueuedMessage::find_by_session_id(&pool, session_id)
+            .await
+            .unwrap();
+        assert_eq!(texts(&queue), ["c", "a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn take_next_removes_the_head_of_one_session() {
+        let pool = memory_pool().await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        let other_session_id = insert_session(&pool, workspace_id).await;
+        QueuedMessage::create(&pool, session_id, &message("first"))
+            .await
+            .unwrap();
+        QueuedMessage::create(&pool, session_id, &message("second"))
+            .await
+            .unwrap();
+        QueuedMessage::create(&pool, other_session_id, &message("other"))
+            .await
+            .unwrap();
+
+        let next = QueuedMessage::take_next(&pool, session_id).await.unwrap();
+        assert_eq!(next.unwrap().data.message, "first");
+        let next = QueuedMessage::take_next(&pool, session_id).await.unwrap();
+        assert_eq!(next.unwrap().data.message, "second");
+        assert!(
+            QueuedMessage::take_next(&pool, session_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            QueuedMessage::exists_for_session(&pool, other_session_id)
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,299 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum RaceError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Race not found")]
+    NotFound,
+    #[error("Invalid race: {0}")]
+    Invalid(String),
+}
+
+/// One prompt run on several executors at once, each in its own workspace,
+/// so the attempts can be compared and a winner picked.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Race {
+    pub id: Uuid,
+    pub name: String,
+    pub prompt: String,
+    /// Script run in every attempt's worktree to compare the results
+    pub test_script: Option<String>,
+    /// Set once a winner is picked; the other attempts are archived
+    pub winner_workspace_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A workspace taking part in a race
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct RaceAttempt {
+    pub workspace_id: Uuid,
+    pub race_id: Uuid,
+    pub position: i64,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    /// The latest run of the race's test script in this workspace
+    pub test_execution_process_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct RaceWithAttempts {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub race: Race,
+    pub attempts: Vec<RaceAttempt>,
+}
+
+impl Race {
+    pub async fn create(
+        pool: &SqlitePool,
+        name: &str,
+        prompt: &str,
+        test_script: Option<&str>,
+    ) -> Result<Self, RaceError> {
+        let id = Uuid::new_v4();
+        Ok(sqlx::query_as!(
+            Race,
+            r#"INSERT INTO races (id, name, prompt, test_script)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id AS "id!: Uuid",
+                         name,
+                         prompt,
+                         test_script,
+                         winner_workspace_id AS "winner_workspace_id?: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            prompt,
+            test_script
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Race,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      prompt,
+                      test_script,
+                      winner_workspace_id AS "winner_workspace_id?: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM races
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set_winner(
+        pool: &SqlitePool,
+        id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Self, RaceError> {
+        sqlx::query_as!(
+            Race,
+            r#"UPDATE races
+               SET winner_workspace_id = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id AS "id!: Uuid",
+                         name,
+                         prompt,
+                         test_script,
+                         winner_workspace_id AS "winner_workspace_id?: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(RaceError::NotFound)
+    }
+
+    /// Delete a race and its attempts, leaving their workspaces alone
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM races WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl RaceAttempt {
+    pub async fn create(
+        pool: &SqlitePool,
+        race_id: Uuid,
+        workspace_id: Uuid,
+        position: i64,
+        executor_profile_id: &ExecutorProfileId,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = sqlx::types::Json(executor_profile_id);
+        sqlx::query_as!(
+            RaceAttempt,
+            r#"INSERT INTO race_attempts (workspace_id, race_id, position, executor_profile_id)
+               VALUES ($1, $2, $3, $4)
+               RETURNING workspace_id AS "workspace_id!: Uuid",
+                         race_id AS "race_id!: Uuid",
+                         position AS "position!: i64",
+                         executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         test_execution_process_id AS "test_execution_process_id?: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            workspace_id,
+            race_id,
+            position,
+            executor_profile_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Attempts of the race in the order they were started
+    pub async fn find_by_race_id(
+        pool: &SqlitePool,
+        race_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            RaceAttempt,
+            r#"SELECT workspace_id AS "workspace_id!: Uuid",
+                      race_id AS "race_id!: Uuid",
+                      position AS "position!: i64",
+                      executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      test_execution_process_id AS "test_execution_process_id?: Uuid",
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM race_attempts
+               WHERE race_id = $1
+               ORDER BY position ASC"#,
+            race_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_test_execution_process(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE race_attempts
+               SET test_execution_process_id = $2
+               WHERE workspace_id = $1"#,
+            workspace_id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+
+    use super::*;
+    use crate::test_support::{
+        insert_execution_process, insert_session, insert_workspace, memory_pool,
+    };
+
+    async fn race_with_attempts(pool: &SqlitePool, count: i64) -> (Race, Vec<Uuid>) {
+        let race = Race::create(pool, "Fix the bug", "Fix the bug", Some("cargo test"))
+            .await
+            .unwrap();
+        let mut workspace_ids = Vec::new();
+        for position in 0..count {
+            let workspace_id = insert_workspace(pool).await;
+            RaceAttempt::create(
+                pool,
+                race.id,
+                workspace_id,
+                position,
+                &ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            )
+            .await
+            .unwrap();
+            workspace_ids.push(workspace_id);
+        }
+        (race, workspace_ids)
+    }
+
+    #[tokio::test]
+    async fn set_winner_records_the_workspace() {
+        let pool = memory_pool().await;
+        let (race, workspace_ids) = race_with_attempts(&pool, 2).await;
+        assert!(race.winner_workspace_id.is_none());
+
+        let race = Race::set_winner(&pool, race.id, workspace_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(race.winner_workspace_id, Some(workspace_ids[1]));
+        let stored = Race::find_by_id(&pool, race.id).await.unwrap().unwrap();
+        assert_eq!(stored.winner_workspace_id, Some(workspace_ids[1]));
+
+        assert!(matches!(
+            Race::set_winner(&pool, Uuid::new_v4(), workspace_ids[0]).await,
+            Err(RaceError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn set_test_execution_process_keeps_the_latest_run_per_attempt() {
+        let pool = memory_pool().await;
+        let (race, workspace_ids) = race_with_attempts(&pool, 2).await;
+        let session_id = insert_session(&pool, workspace_ids[0]).await;
+        let first_run = insert_execution_process(&pool, session_id).await;
+        let second_run = insert_execution_process(&pool, session_id).await;
+
+        RaceAttempt::set_test_execution_process(&pool, workspace_ids[0], first_run)
+            .await
+            .unwrap();
+        RaceAttempt::set_test_execution_process(&pool, workspace_ids[0], second_run)
+            .await
+            .unwrap();
+
+        let attempts = RaceAttempt::find_by_race_id(&pool, race.id).await.unwrap();
+        assert_eq!(
+            attempts
+                .iter()
+                .map(|attempt| attempt.workspace_id)
+                .collect::<Vec<_>>(),
+            workspace_ids
+        );
+        assert_eq!(attempts[0].test_execution_process_id, Some(second_run));
+        assert_eq!(attempts[1].test_execution_process_id, None);
+    }
+
+    #[tokio::test]
+    async fn delete_removes_the_attempts_but_not_their_workspaces() {
+        let pool = memory_pool().await;
+        let (race, workspace_ids) = race_with_attempts(&pool, 2).await;
+
+        assert_eq!(Race::delete(&pool, race.id).await.unwrap(), 1);
+        assert!(Race::find_by_id(&pool, race.id).await.unwrap().is_none());
+        assert!(
+            RaceAttempt::find_by_race_id(&pool, race.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let workspaces: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workspaces")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(workspaces, workspace_ids.len() as i64);
+    }
+}
@@ -1,9 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use executors::resource_limits::ResourceLimits;
 use serde::{Deserialize, Serialize};
 use serde_with::rust::double_option;
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -33,6 +34,31 @@ pub enum RepoError {
     NotFound,
 }
 
+/// How pull requests of a GitHub repository are reached
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "github_access", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum GitHubAccess {
+    /// The REST API when a GitHub token is configured, the `gh` CLI otherwise
+    #[default]
+    Auto,
+    Api,
+    Cli,
+}
+
+/// How commits made on behalf of a repository are signed
+#[derive(Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "commit_signing", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SigningMode {
+    #[default]
+    Off,
+    /// Sign with the repository's `gpg.format` and `user.signingkey`
+    GitConfig,
+    Gpg,
+    Ssh,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Repo {
     pub id: Uuid,
@@ -45,8 +71,32 @@ pub struct Repo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
+    /// Run the verification steps after every coding agent turn
+    pub verify_after_agent: bool,
+    /// Refuse to merge or open a pull request until the verification steps
+    /// pass on the current commit
+    pub require_verification: bool,
+    /// Template for messages of commits made after a coding agent turn. The
+    /// agent's summary when unset.
+    pub commit_message_template: Option<String>,
+    /// Prefix commit messages with an inferred conventional-commit type
+    pub conventional_commits: bool,
+    /// Add `Co-authored-by` and `X-Agent` trailers naming the coding agent
+    pub agent_commit_trailers: bool,
+    /// Sign agent commits and merge commits
+    pub commit_signing: SigningMode,
+    /// GPG key id or SSH key path. The repository's `user.signingkey` when
+    /// unset.
+    pub signing_key: Option<String>,
+    /// CPUs an execution's processes may use together, e.g. `1.5`
+    pub cpu_limit: Option<f64>,
+    /// Memory an execution's processes may use together, in MiB
+    pub memory_limit_mb: Option<i64>,
+    /// Number of processes and threads an execution may run at once
+    pub pids_limit: Option<i64>,
     pub default_target_branch: Option<String>,
     pub default_working_dir: Option<String>,
+    pub github_access: GitHubAccess,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -111,6 +161,86 @@ pub struct UpdateRepo {
     #[ts(optional, type = "string | null")]
     pub dev_server_script: Option<Option<String>>,
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "boolean | null")]
+    pub verify_after_agent: Option<Option<bool>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "boolean | null")]
+    pub require_verification: Option<Option<bool>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub commit_message_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "boolean | null")]
+    pub conventional_commits: Option<Option<bool>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "boolean | null")]
+    pub agent_commit_trailers: Option<Option<bool>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "SigningMode | null")]
+    pub commit_signing: Option<Option<SigningMode>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub signing_key: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub cpu_limit: Option<Option<f64>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub memory_limit_mb: Option<Option<i64>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub pids_limit: Option<Option<i64>>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -126,9 +256,26 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_working_dir: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "GitHubAccess | null")]
+    pub github_access: Option<Option<GitHubAccess>>,
 }
 
 impl Repo {
+    /// Limits for the processes of executions in workspaces with this repo
+    pub fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            cpus: self.cpu_limit,
+            memory_mb: self.memory_limit_mb.and_then(|mb| u64::try_from(mb).ok()),
+            pids: self.pids_limit.and_then(|pids| u64::try_from(pids).ok()),
+        }
+    }
+
     /// Get repos that still have the migration sentinel as their name.
     /// Used by the startup backfill to fix repo names.
     pub async fn list_needing_name_fix(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
@@ -144,8 +291,19 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      verify_after_agent as "verify_after_agent!: bool",
+                      require_verification as "require_verification!: bool",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_trailers as "agent_commit_trailers!: bool",
+                      commit_signing as "commit_signing!: SigningMode",
+                      signing_key,
+                      cpu_limit,
+                      memory_limit_mb,
+                      pids_limit,
                       default_target_branch,
                       default_working_dir,
+                      github_access as "github_access!: GitHubAccess",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -185,8 +343,19 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      verify_after_agent as "verify_after_agent!: bool",
+                      require_verification as "require_verification!: bool",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_trailers as "agent_commit_trailers!: bool",
+                      commit_signing as "commit_signing!: SigningMode",
+                      signing_key,
+                      cpu_limit,
+                      memory_limit_mb,
+                      pids_limit,
                       default_target_branch,
                       default_working_dir,
+                      github_access as "github_access!: GitHubAccess",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -243,8 +412,19 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         verify_after_agent as "verify_after_agent!: bool",
+                         require_verification as "require_verification!: bool",
+                         commit_message_template,
+                         conventional_commits as "conventional_commits!: bool",
+                         agent_commit_trailers as "agent_commit_trailers!: bool",
+                         commit_signing as "commit_signing!: SigningMode",
+                         signing_key,
+                         cpu_limit,
+                         memory_limit_mb,
+                         pids_limit,
                          default_target_branch,
                          default_working_dir,
+                         github_access as "github_access!: GitHubAccess",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -269,8 +449,19 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      verify_after_agent as "verify_after_agent!: bool",
+                      require_verification as "require_verification!: bool",
+                      commit_message_template,
+                      conventional_commits as "conventional_commits!: bool",
+                      agent_commit_trailers as "agent_commit_trailers!: bool",
+                      commit_signing as "commit_signing!: SigningMode",
+                      signing_key,
+                      cpu_limit,
+                      memory_limit_mb,
+                      pids_limit,
                       default_target_branch,
                       default_working_dir,
+                      github_access as "github_access!: GitHubAccess",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -295,8 +486,19 @@ impl Repo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.verify_after_agent as "verify_after_agent!: bool",
+                      r.require_verification as "require_verification!: bool",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_trailers as "agent_commit_trailers!: bool",
+                      r.commit_signing as "commit_signing!: SigningMode",
+                      r.signing_key,
+                      r.cpu_limit,
+                      r.memory_limit_mb,
+                      r.pids_limit,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.github_access as "github_access!: GitHubAccess",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -381,6 +583,46 @@ impl Repo {
             None => existing.dev_server_script,
             Some(v) => v.clone(),
         };
+        let verify_after_agent = match &payload.verify_after_agent {
+            None => existing.verify_after_agent,
+            Some(v) => v.unwrap_or(false),
+        };
+        let require_verification = match &payload.require_verification {
+            None => existing.require_verification,
+            Some(v) => v.unwrap_or(false),
+        };
+        let commit_message_template = match &payload.commit_message_template {
+            None => existing.commit_message_template,
+            Some(v) => v.clone().filter(|template| !template.trim().is_empty()),
+        };
+        let conventional_commits = match &payload.conventional_commits {
+            None => existing.conventional_commits,
+            Some(v) => v.unwrap_or(false),
+        };
+        let agent_commit_trailers = match &payload.agent_commit_trailers {
+            None => existing.agent_commit_trailers,
+            Some(v) => v.unwrap_or(true),
+        };
+        let commit_signing = match &payload.commit_signing {
+            None => existing.commit_signing,
+            Some(v) => v.unwrap_or_default(),
+        };
+        let signing_key = match &payload.signing_key {
+            None => existing.signing_key,
+            Some(v) => v.clone().filter(|key| !key.trim().is_empty()),
+        };
+        let cpu_limit = match &payload.cpu_limit {
+            None => existing.cpu_limit,
+            Some(v) => *v,
+        };
+        let memory_limit_mb = match &payload.memory_limit_mb {
+            None => existing.memory_limit_mb,
+            Some(v) => *v,
+        };
+        let pids_limit = match &payload.pids_limit {
+            None => existing.pids_limit,
+            Some(v) => *v,
+        };
         let default_target_branch = match &payload.default_target_branch {
             None => existing.default_target_branch,
             Some(v) => v.clone(),
@@ -389,6 +631,10 @@ impl Repo {
             None => existing.default_working_dir,
             Some(v) => v.clone(),
         };
+        let github_access = match &payload.github_access {
+            None => existing.github_access,
+            Some(v) => v.unwrap_or_default(),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -400,10 +646,21 @@ impl Repo {
                    copy_files = $5,
                    parallel_setup_script = $6,
                    dev_server_script = $7,
-                   default_target_branch = $8,
-                   default_working_dir = $9,
+                   verify_after_agent = $8,
+                   require_verification = $9,
+                   commit_message_template = $10,
+                   conventional_commits = $11,
+                   agent_commit_trailers = $12,
+                   commit_signing = $13,
+                   signing_key = $14,
+                   cpu_limit = $15,
+                   memory_limit_mb = $16,
+                   pids_limit = $17,
+                   default_target_branch = $18,
+                   default_working_dir = $19,
+                   github_access = $20,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $10
+               WHERE id = $21
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -414,8 +671,19 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         verify_after_agent as "verify_after_agent!: bool",
+                         require_verification as "require_verification!: bool",
+                         commit_message_template,
+                         conventional_commits as "conventional_commits!: bool",
+                         agent_commit_trailers as "agent_commit_trailers!: bool",
+                         commit_signing as "commit_signing!: SigningMode",
+                         signing_key,
+                         cpu_limit,
+                         memory_limit_mb,
+                         pids_limit,
                          default_target_branch,
                          default_working_dir,
+                         github_access as "github_access!: GitHubAccess",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -425,8 +693,19 @@ impl Repo {
             copy_files,
             parallel_setup_script,
             dev_server_script,
+            verify_after_agent,
+            require_verification,
+            commit_message_template,
+            conventional_commits,
+            agent_commit_trailers,
+            commit_signing,
+            signing_key,
+            cpu_limit,
+            memory_limit_mb,
+            pids_limit,
             default_target_branch,
             default_working_dir,
+            github_access,
             id
         )
         .fetch_one(pool)
@@ -1,9 +1,13 @@
-use executors::profile::ExecutorConfig;
+use executors::profile::{ExecutorConfig, ExecutorProfileId};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{execution_process::ExecutionProcess, workspace::Workspace};
+use super::{
+    execution_process::ExecutionProcess,
+    race::{Race, RaceAttempt},
+    workspace::Workspace,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ContainerQuery {
@@ -45,6 +49,24 @@ pub struct CreateAndStartWorkspaceResponse {
     pub link_warning: Option<String>,
 }
 
+/// Start the same prompt on several executors, one workspace each
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateRaceRequest {
+    pub name: Option<String>,
+    pub repos: Vec<WorkspaceRepoInput>,
+    pub prompt: String,
+    pub executor_profile_ids: Vec<ExecutorProfileId>,
+    /// Script run in each attempt's worktree by the race's test action
+    pub test_script: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateRaceResponse {
+    pub race: Race,
+    pub attempts: Vec<RaceAttempt>,
+    pub workspaces: Vec<Workspace>,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct UpdateWorkspace {
     pub archived: Option<bool>,
@@ -0,0 +1,377 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ScheduleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Schedule not found")]
+    NotFound,
+    #[error("Invalid cron expression: {0}")]
+    InvalidCron(String),
+    #[error("Invalid schedule: {0}")]
+    Invalid(String),
+}
+
+/// A recurring agent run: on every tick of `cron_expression` a new workspace
+/// is created on `repo_id` and the executor is started with the rendered
+/// `prompt_template`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Schedule {
+    pub id: Uuid,
+    pub name: String,
+    /// Standard 5-field cron expression (an optional leading seconds field is
+    /// accepted), evaluated in the server's local timezone
+    pub cron_expression: String,
+    pub repo_id: Uuid,
+    /// Branch the scheduled workspaces are created from
+    pub target_branch: String,
+    /// Prompt sent to the agent; supports `{{date}}`, `{{datetime}}` and
+    /// `{{schedule_name}}` placeholders
+    pub prompt_template: String,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: sqlx::types::Json<ExecutorProfileId>,
+    pub paused: bool,
+    /// When the schedule fires next; `None` while paused
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateSchedule {
+    pub name: String,
+    pub cron_expression: String,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    pub prompt_template: String,
+    pub executor_profile_id: ExecutorProfileId,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct UpdateSchedule {
+    pub name: Option<String>,
+    pub cron_expression: Option<String>,
+    pub target_branch: Option<String>,
+    pub prompt_template: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub paused: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "schedule_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleRunStatus {
+    /// Workspace created and the executor launched
+    Started,
+    /// The run could not be started; see `error`
+    Failed,
+}
+
+/// One firing of a schedule and what it produced
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ScheduleRun {
+    pub id: Uuid,
+    pub schedule_id: Uuid,
+    /// The cron tick this run belongs to
+    pub scheduled_for: DateTime<Utc>,
+    pub status: ScheduleRunStatus,
+    pub workspace_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreateScheduleRun {
+    pub schedule_id: Uuid,
+    pub scheduled_for: DateTime<Utc>,
+    pub status: ScheduleRunStatus,
+    pub workspace_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub error: Option<String>,
+}
+
+impl Schedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Schedule,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      cron_expression,
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      prompt_template,
+                      executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      paused AS "paused!: bool",
+                      next_run_at AS "next_run_at?: DateTime<Utc>",
+                      last_run_at AS "last_run_at?: DateTime<Utc>",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM schedules
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Schedule,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      cron_expression,
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      prompt_template,
+                      executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      paused AS "paused!: bool",
+                      next_run_at AS "next_run_at?: DateTime<Utc>",
+                      last_run_at AS "last_run_at?: DateTime<Utc>",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Active schedules whose next run is at or before `now`, most overdue
+    /// first
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Schedule,
+            r#"SELECT id AS "id!: Uuid",
+                      name,
+                      cron_expression,
+                      repo_id AS "repo_id!: Uuid",
+                      target_branch,
+                      prompt_template,
+                      executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                      paused AS "paused!: bool",
+                      next_run_at AS "next_run_at?: DateTime<Utc>",
+                      last_run_at AS "last_run_at?: DateTime<Utc>",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM schedules
+               WHERE paused = 0
+                 AND next_run_at IS NOT NULL
+                 AND next_run_at <= $1
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateSchedule,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Self, ScheduleError> {
+        let id = Uuid::new_v4();
+        let executor_profile_id = sqlx::types::Json(&data.executor_profile_id);
+        Ok(sqlx::query_as!(
+            Schedule,
+            r#"INSERT INTO schedules (id, name, cron_expression, repo_id, target_branch, prompt_template, executor_profile_id, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id AS "id!: Uuid",
+                         name,
+                         cron_expression,
+                         repo_id AS "repo_id!: Uuid",
+                         target_branch,
+                         prompt_template,
+                         executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         paused AS "paused!: bool",
+                         next_run_at AS "next_run_at?: DateTime<Utc>",
+                         last_run_at AS "last_run_at?: DateTime<Utc>",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            data.cron_expression,
+            data.repo_id,
+            data.target_branch,
+            data.prompt_template,
+            executor_profile_id,
+            next_run_at,
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Apply `payload` on top of the stored schedule. `next_run_at` is
+    /// computed by the caller since it depends on the resulting cron
+    /// expression and paused state.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        payload: &UpdateSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, ScheduleError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(ScheduleError::NotFound)?;
+
+        let name = payload.name.clone().unwrap_or(existing.name);
+        let cron_expression = payload
+            .cron_expression
+            .clone()
+            .unwrap_or(existing.cron_expression);
+        let target_branch = payload
+            .target_branch
+            .clone()
+            .unwrap_or(existing.target_branch);
+        let prompt_template = payload
+            .prompt_template
+            .clone()
+            .unwrap_or(existing.prompt_template);
+        let executor_profile_id = payload
+            .executor_profile_id
+            .clone()
+            .map(sqlx::types::Json)
+            .unwrap_or(existing.executor_profile_id);
+        let paused = payload.paused.unwrap_or(existing.paused);
+
+        Ok(sqlx::query_as!(
+            Schedule,
+            r#"UPDATE schedules
+               SET name = $1,
+                   cron_expression = $2,
+                   target_branch = $3,
+                   prompt_template = $4,
+                   executor_profile_id = $5,
+                   paused = $6,
+                   next_run_at = $7,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $8
+               RETURNING id AS "id!: Uuid",
+                         name,
+                         cron_expression,
+                         repo_id AS "repo_id!: Uuid",
+                         target_branch,
+                         prompt_template,
+                         executor_profile_id AS "executor_profile_id!: sqlx::types::Json<ExecutorProfileId>",
+                         paused AS "paused!: bool",
+                         next_run_at AS "next_run_at?: DateTime<Utc>",
+                         last_run_at AS "last_run_at?: DateTime<Utc>",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            name,
+            cron_expression,
+            target_branch,
+            prompt_template,
+            executor_profile_id,
+            paused,
+            next_run_at,
+            id
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Claim the run due at `due_at` and move the schedule on to
+    /// `next_run_at`. Returns `false` if the schedule was paused, edited or
+    /// already claimed in the meantime, in which case the run must be
+    /// skipped.
+    pub async fn claim_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        due_at: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let rows = sqlx::query!(
+            r#"UPDATE schedules
+               SET next_run_at = $1,
+                   last_run_at = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $3
+                 AND paused = 0
+                 AND next_run_at = $4"#,
+            next_run_at,
+            now,
+            id,
+            due_at
+        )
+        .execute(pool)
+        .await?
+        .rows_affected();
+        Ok(rows > 0)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), ScheduleError> {
+        let rows = sqlx::query!("DELETE FROM schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        if rows == 0 {
+            Err(ScheduleError::NotFound)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ScheduleRun {
+    /// Most recent runs of a schedule, newest first
+    pub async fn find_by_schedule_id(
+        pool: &SqlitePool,
+        schedule_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ScheduleRun,
+            r#"SELECT id AS "id!: Uuid",
+                      schedule_id AS "schedule_id!: Uuid",
+                      scheduled_for AS "scheduled_for!: DateTime<Utc>",
+                      status AS "status!: ScheduleRunStatus",
+                      workspace_id AS "workspace_id?: Uuid",
+                      session_id AS "session_id?: Uuid",
+                      error,
+                      created_at AS "created_at!: DateTime<Utc>"
+               FROM schedule_runs
+               WHERE schedule_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            schedule_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(pool: &SqlitePool, data: &CreateScheduleRun) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ScheduleRun,
+            r#"INSERT INTO schedule_runs (id, schedule_id, scheduled_for, status, workspace_id, session_id, error)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               RETURNING id AS "id!: Uuid",
+                         schedule_id AS "schedule_id!: Uuid",
+                         scheduled_for AS "scheduled_for!: DateTime<Utc>",
+                         status AS "status!: ScheduleRunStatus",
+                         workspace_id AS "workspace_id?: Uuid",
+                         session_id AS "session_id?: Uuid",
+                         error,
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            id,
+            data.schedule_id,
+            data.scheduled_for,
+            data.status,
+            data.workspace_id,
+            data.session_id,
+            data.error,
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use executors::logs::TokenUsage;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tokens used by one coding agent execution, kept up to date while it runs
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionTokenUsage {
+    pub execution_process_id: Uuid,
+    pub model: Option<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// `None` when no model price matched when the usage was recorded
+    pub cost_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Token usage summed over a set of executions
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct UsageTotals {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Cost of the priced executions
+    pub cost_usd: f64,
+    /// Executions whose model had no price; their tokens are not in `cost_usd`
+    pub unpriced_executions: i64,
+}
+
+impl UsageTotals {
+    pub fn total_tokens(&self) -> i64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_write_tokens
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DailyUsage {
+    /// `YYYY-MM-DD` in UTC, by the day the execution started
+    pub day: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorUsage {
+    /// The session's executor; `None` for sessions that never ran an agent
+    pub executor: Option<String>,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Usage of the workspaces of a repository. A workspace with several
+/// repositories counts towards each of them.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RepoUsage {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub totals: UsageTotals,
+}
+
+impl ExecutionTokenUsage {
+    /// Record the latest usage of an execution, replacing the previous one
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        usage: &TokenUsage,
+        cost_usd: Option<f64>,
+    ) -> Result<Self, sqlx::Error> {
+        let input_tokens = usage.input_tokens as i64;
+        let output_tokens = usage.output_tokens as i64;
+        let cache_read_tokens = usage.cache_read_tokens as i64;
+        let cache_write_tokens = usage.cache_write_tokens as i64;
+        sqlx::query_as!(
+            ExecutionTokenUsage,
+            r#"INSERT INTO execution_process_token_usage
+                   (execution_process_id, model, input_tokens, output_tokens,
+                    cache_read_tokens, cache_write_tokens, cost_usd)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(execution_process_id) DO UPDATE SET
+                   model = excluded.model,
+                   input_tokens = excluded.input_tokens,
+                   output_tokens = excluded.output_tokens,
+                   cache_read_tokens = excluded.cache_read_tokens,
+                   cache_write_tokens = excluded.cache_write_tokens,
+                   cost_usd = excluded.cost_usd,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING execution_process_id AS "execution_process_id!: Uuid",
+                         model,
+                         input_tokens AS "input_tokens!: i64",
+                         output_tokens AS "output_tokens!: i64",
+                         cache_read_tokens AS "cache_read_tokens!: i64",
+                         cache_write_tokens AS "cache_write_tokens!: i64",
+                         cost_usd AS "cost_usd?: f64",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            execution_process_id,
+            usage.model,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            cost_usd
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionTokenUsage,
+            r#"SELECT execution_process_id AS "execution_process_id!: Uuid",
+                      model,
+                      input_tokens AS "input_tokens!: i64",
+                      output_tokens AS "output_tokens!: i64",
+                      cache_read_tokens AS "cache_read_tokens!: i64",
+                      cache_write_tokens AS "cache_write_tokens!: i64",
+                      cost_usd AS "cost_usd?: f64",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM execution_process_token_usage
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn totals_for_session(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT COALESCE(SUM(u.input_tokens), 0) AS "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) AS "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) AS "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) AS "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) AS "cost_usd!: f64",
+                      COUNT(u.execution_process_id) - COUNT(u.cost_usd) AS "unpriced_executions!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               WHERE ep.session_id = $1"#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn totals_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<UsageTotals, sqlx::Error> {
+        sqlx::query_as!(
+            UsageTotals,
+            r#"SELECT COALESCE(SUM(u.input_tokens), 0) AS "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) AS "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) AS "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) AS "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) AS "cost_usd!: f64",
+                      COUNT(u.execution_process_id) - COUNT(u.cost_usd) AS "unpriced_executions!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE s.workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Usage per day of the executions started since `since`, oldest first
+    pub async fn daily_totals(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<DailyUsage>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT date(ep.started_at) AS "day!: String",
+                      COALESCE(SUM(u.input_tokens), 0) AS "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) AS "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) AS "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) AS "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) AS "cost_usd!: f64",
+                      COUNT(u.execution_process_id) - COUNT(u.cost_usd) AS "unpriced_executions!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               WHERE ep.started_at >= $1
+               GROUP BY date(ep.started_at)
+               ORDER BY date(ep.started_at) ASC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DailyUsage {
+                day: row.day,
+                totals: UsageTotals {
+                    input_tokens: row.input_tokens,
+                    output_tokens: row.output_tokens,
+                    cache_read_tokens: row.cache_read_tokens,
+                    cache_write_tokens: row.cache_write_tokens,
+                    cost_usd: row.cost_usd,
+                    unpriced_executions: row.unpriced_executions,
+                },
+            })
+            .collect())
+    }
+
+    /// Usage per executor of the executions started since `since`, most
+    /// expensive first
+    pub async fn executor_totals(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ExecutorUsage>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT s.executor,
+                      COALESCE(SUM(u.input_tokens), 0) AS "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) AS "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) AS "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) AS "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) AS "cost_usd!: f64",
+                      COUNT(u.execution_process_id) - COUNT(u.cost_usd) AS "unpriced_executions!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE ep.started_at >= $1
+               GROUP BY s.executor
+               ORDER BY SUM(u.cost_usd) DESC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ExecutorUsage {
+                executor: row.executor,
+                totals: UsageTotals {
+                    input_tokens: row.input_tokens,
+                    output_tokens: row.output_tokens,
+                    cache_read_tokens: row.cache_read_tokens,
+                    cache_write_tokens: row.cache_write_tokens,
+                    cost_usd: row.cost_usd,
+                    unpriced_executions: row.unpriced_executions,
+                },
+            })
+            .collect())
+    }
+
+    /// Usage per repository of the executions started since `since`, most
+    /// expensive first
+    pub async fn repo_totals(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<RepoUsage>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT r.id AS "repo_id!: Uuid",
+                      r.name AS "repo_name!",
+                      COALESCE(SUM(u.input_tokens), 0) AS "input_tokens!: i64",
+                      COALESCE(SUM(u.output_tokens), 0) AS "output_tokens!: i64",
+                      COALESCE(SUM(u.cache_read_tokens), 0) AS "cache_read_tokens!: i64",
+                      COALESCE(SUM(u.cache_write_tokens), 0) AS "cache_write_tokens!: i64",
+                      COALESCE(SUM(u.cost_usd), 0.0) AS "cost_usd!: f64",
+                      COUNT(u.execution_process_id) - COUNT(u.cost_usd) AS "unpriced_executions!: i64"
+               FROM execution_process_token_usage u
+               JOIN execution_processes ep ON ep.id = u.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspace_repos wr ON wr.workspace_id = s.workspace_id
+               JOIN repos r ON r.id = wr.repo_id
+               WHERE ep.started_at >= $1
+               GROUP BY r.id, r.name
+               ORDER BY SUM(u.cost_usd) DESC"#,
+            since
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| RepoUsage {
+                repo_id: row.repo_id,
+                repo_name: row.repo_name,
+                totals: UsageTotals {
+                    input_tokens: row.input_tokens,
+                    output_tokens: row.output_tokens,
+                    cache_read_tokens: row.cache_read_tokens,
+                    cache_write_tokens: row.cache_write_tokens,
+                    cost_usd: row.cost_usd,
+                    unpriced_executions: row.unpriced_executions,
+                },
+            })
+            .collect())
+    }
+}
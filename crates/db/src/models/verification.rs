@@ -0,0 +1,691 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum VerificationError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Verification run not found")]
+    NotFound,
+    #[error("Invalid verification step: {0}")]
+    Invalid(String),
+    #[error("Verification required: {0}")]
+    Required(String),
+}
+
+/// A named check of a repository, such as lint, test or typecheck. The steps
+/// of a repository run in order and the run stops at the first failure.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct VerificationStep {
+    pub id: Uuid,
+    pub repo_id: Uuid,
+    pub name: String,
+    pub script: String,
+    /// Run order within the repository; lowest first
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct VerificationStepInput {
+    pub name: String,
+    pub script: String,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "verification_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationRunStatus {
+    Running,
+    /// Every step passed
+    Passed,
+    /// A step failed; the steps after it were skipped
+    Failed,
+    /// A step was stopped before it finished
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "verification_result_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationResultStatus {
+    Pending,
+    Running,
+    Passed,
+    Failed,
+    /// Stopped before it finished
+    Cancelled,
+    /// Not run because an earlier step failed or was stopped
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "verification_trigger", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationTrigger {
+    /// A coding agent turn finished in a repo with `verify_after_agent`
+    Agent,
+    Manual,
+    /// The merge queue checked the rebased workspace before merging
+    MergeQueue,
+}
+
+/// One pass over the verification steps of a workspace's repos
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct VerificationRun {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub session_id: Uuid,
+    pub trigger: VerificationTrigger,
+    pub status: VerificationRunStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The outcome of one step in a verification run
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct VerificationResult {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub repo_id: Uuid,
+    /// The step this result was created from, unless it has since been removed
+    pub step_id: Option<Uuid>,
+    pub name: String,
+    /// Run order within the run; lowest first
+    pub position: i64,
+    /// The script run of this step, set once the step starts
+    pub execution_process_id: Option<Uuid>,
+    pub status: VerificationResultStatus,
+    pub exit_code: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct VerificationRunWithResults {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub run: VerificationRun,
+    pub results: Vec<VerificationResult>,
+}
+
+impl VerificationStep {
+    /// The repository's steps in run order
+    pub async fn find_by_repo_id(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationStep,
+            r#"SELECT id AS "id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      name,
+                      script,
+                      position AS "position!: i64",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_steps
+               WHERE repo_id = $1
+               ORDER BY position ASC"#,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace all steps of a repository with `steps`, keeping their order.
+    pub async fn replace_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        steps: &[VerificationStepInput],
+    ) -> Result<Vec<Self>, VerificationError> {
+        for step in steps {
+            if step.name.trim().is_empty() {
+                return Err(VerificationError::Invalid(
+                    "name must not be empty".to_string(),
+                ));
+            }
+            if step.script.trim().is_empty() {
+                return Err(VerificationError::Invalid(format!(
+                    "script of '{}' must not be empty",
+                    step.name.trim()
+                )));
+            }
+        }
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!("DELETE FROM verification_steps WHERE repo_id = $1", repo_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, step) in steps.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let position = position as i64;
+            let name = step.name.trim();
+            sqlx::query!(
+                r#"INSERT INTO verification_steps (id, repo_id, name, script, position)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+                id,
+                repo_id,
+                name,
+                step.script,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Self::find_by_repo_id(pool, repo_id).await?)
+    }
+}
+
+impl VerificationRun {
+    /// Create a run with a pending result for each of `steps`, which are run
+    /// in the given order.
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        session_id: Uuid,
+        trigger: VerificationTrigger,
+        steps: &[VerificationStep],
+    ) -> Result<VerificationRunWithResults, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"INSERT INTO verification_runs (id, workspace_id, session_id, trigger)
+               VALUES ($1, $2, $3, $4)"#,
+            id,
+            workspace_id,
+            session_id,
+            trigger
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, step) in steps.iter().enumerate() {
+            let result_id = Uuid::new_v4();
+            let position = position as i64;
+            sqlx::query!(
+                r#"INSERT INTO verification_results (id, run_id, repo_id, step_id, name, position)
+                   VALUES ($1, $2, $3, $4, $5, $6)"#,
+                result_id,
+                id,
+                step.repo_id,
+                step.id,
+                step.name,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        let run = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let results = VerificationResult::find_by_run_id(pool, id).await?;
+        Ok(VerificationRunWithResults { run, results })
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      trigger AS "trigger!: VerificationTrigger",
+                      status AS "status!: VerificationRunStatus",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The run still in progress in a workspace, if any
+    pub async fn find_running_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      trigger AS "trigger!: VerificationTrigger",
+                      status AS "status!: VerificationRunStatus",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE workspace_id = $1 AND status = 'running'
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The latest runs of a workspace with their results, newest first
+    pub async fn find_recent_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<VerificationRunWithResults>, sqlx::Error> {
+        let runs = sqlx::query_as!(
+            VerificationRun,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      session_id AS "session_id!: Uuid",
+                      trigger AS "trigger!: VerificationTrigger",
+                      status AS "status!: VerificationRunStatus",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_runs
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            workspace_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut with_results = Vec::with_capacity(runs.len());
+        for run in runs {
+            let results = VerificationResult::find_by_run_id(pool, run.id).await?;
+            with_results.push(VerificationRunWithResults { run, results });
+        }
+        Ok(with_results)
+    }
+
+    /// Status of the latest run of each workspace with the given archived
+    /// status
+    pub async fn latest_status_for_workspaces(
+        pool: &SqlitePool,
+        archived: bool,
+    ) -> Result<HashMap<Uuid, VerificationRunStatus>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT r.workspace_id AS "workspace_id!: Uuid",
+                      r.status AS "status!: VerificationRunStatus"
+               FROM verification_runs r
+               INNER JOIN (
+                   SELECT workspace_id, MAX(created_at) AS max_created_at
+                   FROM verification_runs
+                   GROUP BY workspace_id
+               ) latest ON r.workspace_id = latest.workspace_id
+                       AND r.created_at = latest.max_created_at
+               INNER JOIN workspaces w ON r.workspace_id = w.id
+               WHERE w.archived = $1"#,
+            archived
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.workspace_id, row.status))
+            .collect())
+    }
+}
+
+impl VerificationResult {
+    pub async fn find_by_run_id(pool: &SqlitePool, run_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationResult,
+            r#"SELECT id AS "id!: Uuid",
+                      run_id AS "run_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      step_id AS "step_id?: Uuid",
+                      name,
+                      position AS "position!: i64",
+                      execution_process_id AS "execution_process_id?: Uuid",
+                      status AS "status!: VerificationResultStatus",
+                      exit_code,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_results
+               WHERE run_id = $1
+               ORDER BY position ASC"#,
+            run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationResult,
+            r#"SELECT id AS "id!: Uuid",
+                      run_id AS "run_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      step_id AS "step_id?: Uuid",
+                      name,
+                      position AS "position!: i64",
+                      execution_process_id AS "execution_process_id?: Uuid",
+                      status AS "status!: VerificationResultStatus",
+                      exit_code,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_results
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Attach a starting script run to the next pending step of the session's
+    /// running verification run. The steps are chained in position order, so
+    /// the next pending step is the one being started.
+    pub async fn claim_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationResult,
+            r#"UPDATE verification_results
+               SET status = 'running', execution_process_id = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = (
+                   SELECT res.id
+                   FROM verification_results res
+                   JOIN verification_runs run ON run.id = res.run_id
+                   WHERE run.session_id = $1
+                     AND run.status = 'running'
+                     AND res.status = 'pending'
+                   ORDER BY run.created_at ASC, res.position ASC
+                   LIMIT 1
+               )
+               RETURNING id AS "id!: Uuid",
+                         run_id AS "run_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         step_id AS "step_id?: Uuid",
+                         name,
+                         position AS "position!: i64",
+                         execution_process_id AS "execution_process_id?: Uuid",
+                         status AS "status!: VerificationResultStatus",
+                         exit_code,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            session_id,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record how the step run by `execution_process_id` ended and settle its
+    /// run: a failed or stopped step skips the remaining steps, and the run
+    /// passes once every step has passed. Returns the updated run, or `None`
+    /// if the process was not a verification step.
+    pub async fn finish(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        status: VerificationResultStatus,
+        exit_code: Option<i64>,
+    ) -> Result<Option<VerificationRun>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let Some(row) = sqlx::query!(
+            r#"UPDATE verification_results
+               SET status = $2, exit_code = $3, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1
+               RETURNING run_id AS "run_id!: Uuid""#,
+            execution_process_id,
+            status,
+            exit_code
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let run_id = row.run_id;
+
+        let run_status = if status == VerificationResultStatus::Passed {
+            let remaining = sqlx::query_scalar!(
+                r#"SELECT COUNT(*) AS "count!: i64"
+                   FROM verification_results
+                   WHERE run_id = $1 AND status IN ('pending', 'running')"#,
+                run_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            (remaining == 0).then_some(VerificationRunStatus::Passed)
+        } else {
+            sqlx::query!(
+                r#"UPDATE verification_results
+                   SET status = 'skipped', updated_at = datetime('now', 'subsec')
+                   WHERE run_id = $1 AND status = 'pending'"#,
+                run_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            Some(if status == VerificationResultStatus::Cancelled {
+                VerificationRunStatus::Cancelled
+            } else {
+                VerificationRunStatus::Failed
+            })
+        };
+
+        if let Some(run_status) = run_status {
+            sqlx::query!(
+                r#"UPDATE verification_runs
+                   SET status = $2, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1"#,
+                run_id,
+                run_status
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        VerificationRun::find_by_id(pool, run_id).await
+    }
+
+    /// Mark the remaining steps of a run skipped and end it with `status`,
+    /// for when its chain could not be started or continued.
+    pub async fn abandon_run(
+        pool: &SqlitePool,
+        run_id: Uuid,
+        status: VerificationRunStatus,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE verification_results
+               SET status = 'skipped', updated_at = datetime('now', 'subsec')
+               WHERE run_id = $1 AND status IN ('pending', 'running')"#,
+            run_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"UPDATE verification_runs
+               SET status = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            run_id,
+            status
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// The results of a repo in the latest run of the workspace that
+    /// verified it, in run order
+    pub async fn find_latest_for_repo(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            VerificationResult,
+            r#"SELECT id AS "id!: Uuid",
+                      run_id AS "run_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      step_id AS "step_id?: Uuid",
+                      name,
+                      position AS "position!: i64",
+                      execution_process_id AS "execution_process_id?: Uuid",
+                      status AS "status!: VerificationResultStatus",
+                      exit_code,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM verification_results
+               WHERE repo_id = $2
+                 AND run_id = (
+                     SELECT run.id
+                     FROM verification_runs run
+                     JOIN verification_results res ON res.run_id = run.id
+                     WHERE run.workspace_id = $1 AND res.repo_id = $2
+                     ORDER BY run.created_at DESC
+                     LIMIT 1
+                 )
+               ORDER BY position ASC"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_repo, insert_session, insert_workspace, memory_pool};
+
+    /// Version of the migration that moved `repos.verification_script` into
+    /// `verification_steps`
+    const STEPS_MIGRATION: i64 = 20260513000000;
+
+    /// Apply a migration the way sqlx does, inside a transaction, which the
+    /// migrations that rebuild tables rely on
+    async fn apply(pool: &SqlitePool, migration: &sqlx::migrate::Migration) {
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::raw_sql(&migration.sql)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    fn steps(names: &[&str]) -> Vec<VerificationStepInput> {
+        names
+            .iter()
+            .map(|name| VerificationStepInput {
+                name: name.to_string(),
+                script: format!("npm run {name}"),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn verification_script_becomes_a_step() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let migrator = sqlx::migrate!("./migrations");
+        for migration in migrator.iter().filter(|m| m.version < STEPS_MIGRATION) {
+            apply(&pool, migration).await;
+        }
+        let with_script = Uuid::new_v4();
+        let blank_script = Uuid::new_v4();
+        let without_script = Uuid::new_v4();
+        for (id, script) in [
+            (with_script, Some("npm test")),
+            (blank_script, Some("  ")),
+            (without_script, None),
+        ] {
+            sqlx::query(
+                "INSERT INTO repos (id, path, name, display_name, verification_script)
+                 VALUES ($1, $2, 'app', 'app', $3)",
+            )
+            .bind(id)
+            .bind(format!("/repos/{id}"))
+            .bind(script)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for migration in migrator.iter().filter(|m| m.version >= STEPS_MIGRATION) {
+            apply(&pool, migration).await;
+        }
+
+        let migrated = VerificationStep::find_by_repo_id(&pool, with_script)
+            .await
+            .unwrap();
+        assert_eq!(migrated.len(), 1);
+        assert_eq!(migrated[0].name, "verify");
+        assert_eq!(migrated[0].script, "npm test");
+        assert_eq!(migrated[0].position, 0);
+        for id in [blank_script, without_script] {
+            assert!(
+                VerificationStep::find_by_repo_id(&pool, id)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn latest_results_reflect_the_steps_they_ran() {
+        let pool = memory_pool().await;
+        let repo_id = insert_repo(&pool, "app").await;
+        let workspace_id = insert_workspace(&pool).await;
+        let session_id = insert_session(&pool, workspace_id).await;
+        let old_steps = VerificationStep::replace_for_repo(&pool, repo_id, &steps(&["lint"]))
+            .await
+            .unwrap();
+        VerificationRun::create(
+            &pool,
+            workspace_id,
+            session_id,
+            VerificationTrigger::Manual,
+            &old_steps,
+        )
+        .await
+        .unwrap();
+
+        let latest = VerificationResult::find_latest_for_repo(&pool, workspace_id, repo_id)
+            .await
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].step_id, Some(old_steps[0].id));
+
+        // Replacing the steps detaches the results of the old ones, so the
+        // new steps have no result until the next run
+        let new_steps =
+            VerificationStep::replace_for_repo(&pool, repo_id, &steps(&["lint", "test"]))
+                .await
+                .unwrap();
+        let latest = VerificationResult::find_latest_for_repo(&pool, workspace_id, repo_id)
+            .await
+            .unwrap();
+        assert_eq!(latest[0].step_id, None);
+        assert!(
+            new_steps
+                .iter()
+                .all(|step| latest.iter().all(|result| result.step_id != Some(step.id)))
+        );
+    }
+}
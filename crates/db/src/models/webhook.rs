@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use sqlx::{FromRow, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
@@ -11,6 +12,10 @@ pub enum WebhookError {
     Database(#[from] sqlx::Error),
     #[error("Webhook not found")]
     NotFound,
+    #[error("Webhook delivery not found")]
+    DeliveryNotFound,
+    #[error("Invalid webhook filter: {0}")]
+    InvalidFilter(String),
 }
 
 /// An outbound webhook registration — VK POSTs events here
@@ -22,6 +27,17 @@ pub struct Webhook {
     pub secret: Option<String>,
     pub description: Option<String>,
     pub enabled: bool,
+    /// Only deliver these event types (e.g. `workspace.updated`); `None`
+    /// delivers every event
+    #[ts(type = "string[] | null")]
+    pub event_types: Option<sqlx::types::Json<Vec<String>>>,
+    /// Only deliver events for workspaces that include one of these repos
+    #[ts(type = "string[] | null")]
+    pub repo_ids: Option<sqlx::types::Json<Vec<Uuid>>>,
+    /// Glob matched against the workspace name
+    pub workspace_glob: Option<String>,
+    /// Glob matched against the workspace branch
+    pub branch_glob: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -31,6 +47,65 @@ pub struct CreateWebhook {
     pub url: String,
     pub secret: Option<String>,
     pub description: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub repo_ids: Option<Vec<Uuid>>,
+    pub workspace_glob: Option<String>,
+    pub branch_glob: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub secret: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub description: Option<Option<String>>,
+
+    pub enabled: Option<bool>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string[] | null")]
+    pub event_types: Option<Option<Vec<String>>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string[] | null")]
+    pub repo_ids: Option<Option<Vec<Uuid>>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub workspace_glob: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub branch_glob: Option<Option<String>>,
 }
 
 impl Webhook {
@@ -42,6 +117,10 @@ impl Webhook {
                       secret,
                       description,
                       enabled AS "enabled!: bool",
+                      event_types AS "event_types: sqlx::types::Json<Vec<String>>",
+                      repo_ids AS "repo_ids: sqlx::types::Json<Vec<Uuid>>",
+                      workspace_glob,
+                      branch_glob,
                       created_at AS "created_at!: DateTime<Utc>",
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM webhooks
@@ -51,6 +130,28 @@ impl Webhook {
         .await
     }
 
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Webhook,
+            r#"SELECT id AS "id!: Uuid",
+                      url,
+                      secret,
+                      description,
+                      enabled AS "enabled!: bool",
+                      event_types AS "event_types: sqlx::types::Json<Vec<String>>",
+                      repo_ids AS "repo_ids: sqlx::types::Json<Vec<Uuid>>",
+                      workspace_glob,
+                      branch_glob,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_enabled(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Webhook,
@@ -59,6 +160,10 @@ impl Webhook {
                       secret,
                       description,
                       enabled AS "enabled!: bool",
+                      event_types AS "event_types: sqlx::types::Json<Vec<String>>",
+                      repo_ids AS "repo_ids: sqlx::types::Json<Vec<Uuid>>",
+                      workspace_glob,
+                      branch_glob,
                       created_at AS "created_at!: DateTime<Utc>",
                       updated_at AS "updated_at!: DateTime<Utc>"
                FROM webhooks
@@ -71,21 +176,108 @@ impl Webhook {
 
     pub async fn create(pool: &SqlitePool, data: &CreateWebhook) -> Result<Self, WebhookError> {
         let id = Uuid::new_v4();
+        let event_types = data.event_types.as_ref().map(sqlx::types::Json);
+        let repo_ids = data.repo_ids.as_ref().map(sqlx::types::Json);
         Ok(sqlx::query_as!(
             Webhook,
-            r#"INSERT INTO webhooks (id, url, secret, description)
-               VALUES ($1, $2, $3, $4)
+            r#"INSERT INTO webhooks (id, url, secret, description, event_types, repo_ids, workspace_glob, branch_glob)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
                RETURNING id AS "id!: Uuid",
                          url,
                          secret,
                          description,
                          enabled AS "enabled!: bool",
+                         event_types AS "event_types: sqlx::types::Json<Vec<String>>",
+                         repo_ids AS "repo_ids: sqlx::types::Json<Vec<Uuid>>",
+                         workspace_glob,
+                         branch_glob,
                          created_at AS "created_at!: DateTime<Utc>",
                          updated_at AS "updated_at!: DateTime<Utc>""#,
             id,
             data.url,
             data.secret,
             data.description,
+            event_types,
+            repo_ids,
+            data.workspace_glob,
+            data.branch_glob,
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        payload: &UpdateWebhook,
+    ) -> Result<Self, WebhookError> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(WebhookError::NotFound)?;
+
+        // None = don't update (use existing)
+        // Some(None) = set to NULL
+        // Some(Some(v)) = set to v
+        let url = payload.url.clone().unwrap_or(existing.url);
+        let secret = match &payload.secret {
+            None => existing.secret,
+            Some(v) => v.clone(),
+        };
+        let description = match &payload.description {
+            None => existing.description,
+            Some(v) => v.clone(),
+        };
+        let enabled = payload.enabled.unwrap_or(existing.enabled);
+        let event_types = match &payload.event_types {
+            None => existing.event_types,
+            Some(v) => v.clone().map(sqlx::types::Json),
+        };
+        let repo_ids = match &payload.repo_ids {
+            None => existing.repo_ids,
+            Some(v) => v.clone().map(sqlx::types::Json),
+        };
+        let workspace_glob = match &payload.workspace_glob {
+            None => existing.workspace_glob,
+            Some(v) => v.clone(),
+        };
+        let branch_glob = match &payload.branch_glob {
+            None => existing.branch_glob,
+            Some(v) => v.clone(),
+        };
+
+        Ok(sqlx::query_as!(
+            Webhook,
+            r#"UPDATE webhooks
+               SET url = $1,
+                   secret = $2,
+                   description = $3,
+                   enabled = $4,
+                   event_types = $5,
+                   repo_ids = $6,
+                   workspace_glob = $7,
+                   branch_glob = $8,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $9
+               RETURNING id AS "id!: Uuid",
+                         url,
+                         secret,
+                         description,
+                         enabled AS "enabled!: bool",
+                         event_types AS "event_types: sqlx::types::Json<Vec<String>>",
+                         repo_ids AS "repo_ids: sqlx::types::Json<Vec<Uuid>>",
+                         workspace_glob,
+                         branch_glob,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            url,
+            secret,
+            description,
+            enabled,
+            event_types,
+            repo_ids,
+            workspace_glob,
+            branch_glob,
+            id
         )
         .fetch_one(pool)
         .await?)
@@ -0,0 +1,253 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::webhook::WebhookError;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first attempt or for the next retry
+    Pending,
+    Delivered,
+    /// Gave up after exhausting all attempts (dead-letter)
+    Dead,
+}
+
+/// A single event queued for (or already sent to) a webhook
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: String,
+    #[ts(type = "JsonValue")]
+    pub payload: sqlx::types::Json<Value>,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last response, if the receiver answered at all
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id AS "id!: Uuid",
+                      webhook_id AS "webhook_id!: Uuid",
+                      event_type,
+                      payload AS "payload!: sqlx::types::Json<Value>",
+                      status AS "status!: WebhookDeliveryStatus",
+                      attempts AS "attempts!: i64",
+                      next_attempt_at AS "next_attempt_at!: DateTime<Utc>",
+                      last_attempt_at AS "last_attempt_at?: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Most recent deliveries for a webhook, newest first
+    pub async fn find_by_webhook_id(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT id AS "id!: Uuid",
+                      webhook_id AS "webhook_id!: Uuid",
+                      event_type,
+                      payload AS "payload!: sqlx::types::Json<Value>",
+                      status AS "status!: WebhookDeliveryStatus",
+                      attempts AS "attempts!: i64",
+                      next_attempt_at AS "next_attempt_at!: DateTime<Utc>",
+                      last_attempt_at AS "last_attempt_at?: DateTime<Utc>",
+                      response_status,
+                      last_error,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries
+               WHERE webhook_id = $1
+               ORDER BY created_at DESC
+               LIMIT $2"#,
+            webhook_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first. Deliveries
+    /// belonging to disabled webhooks stay parked until the webhook is
+    /// re-enabled.
+    pub async fn find_due(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"SELECT d.id AS "id!: Uuid",
+                      d.webhook_id AS "webhook_id!: Uuid",
+                      d.event_type,
+                      d.payload AS "payload!: sqlx::types::Json<Value>",
+                      d.status AS "status!: WebhookDeliveryStatus",
+                      d.attempts AS "attempts!: i64",
+                      d.next_attempt_at AS "next_attempt_at!: DateTime<Utc>",
+                      d.last_attempt_at AS "last_attempt_at?: DateTime<Utc>",
+                      d.response_status,
+                      d.last_error,
+                      d.created_at AS "created_at!: DateTime<Utc>",
+                      d.updated_at AS "updated_at!: DateTime<Utc>"
+               FROM webhook_deliveries d
+               JOIN webhooks w ON w.id = d.webhook_id
+               WHERE d.status = 'pending'
+                 AND d.next_attempt_at <= $1
+                 AND w.enabled = 1
+               ORDER BY d.next_attempt_at ASC
+               LIMIT $2"#,
+            now,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        event_type: &str,
+        payload: &Value,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        let payload = sqlx::types::Json(payload);
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"INSERT INTO webhook_deliveries
+                   (id, webhook_id, event_type, payload, next_attempt_at, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $5, $5)
+               RETURNING id AS "id!: Uuid",
+                         webhook_id AS "webhook_id!: Uuid",
+                         event_type,
+                         payload AS "payload!: sqlx::types::Json<Value>",
+                         status AS "status!: WebhookDeliveryStatus",
+                         attempts AS "attempts!: i64",
+                         next_attempt_at AS "next_attempt_at!: DateTime<Utc>",
+                         last_attempt_at AS "last_attempt_at?: DateTime<Utc>",
+                         response_status,
+                         last_error,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            webhook_id,
+            event_type,
+            payload,
+            now,
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the outcome of one delivery attempt. `next_attempt_at` is only
+    /// meaningful when `status` is still `Pending`.
+    pub async fn record_attempt(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: WebhookDeliveryStatus,
+        response_status: Option<i64>,
+        last_error: Option<&str>,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE webhook_deliveries
+               SET status = $1,
+                   attempts = attempts + 1,
+                   response_status = $2,
+                   last_error = $3,
+                   next_attempt_at = $4,
+                   last_attempt_at = $5,
+                   updated_at = $5
+               WHERE id = $6"#,
+            status,
+            response_status,
+            last_error,
+            next_attempt_at,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Put a delivery (typically a dead-lettered one) back into the queue
+    /// with a fresh attempt budget.
+    pub async fn reset_for_redelivery(
+        pool: &SqlitePool,
+        webhook_id: Uuid,
+        id: Uuid,
+    ) -> Result<Self, WebhookError> {
+        let now = Utc::now();
+        sqlx::query_as!(
+            WebhookDelivery,
+            r#"UPDATE webhook_deliveries
+               SET status = 'pending',
+                   attempts = 0,
+                   next_attempt_at = $1,
+                   updated_at = $1
+               WHERE id = $2 AND webhook_id = $3
+               RETURNING id AS "id!: Uuid",
+                         webhook_id AS "webhook_id!: Uuid",
+                         event_type,
+                         payload AS "payload!: sqlx::types::Json<Value>",
+                         status AS "status!: WebhookDeliveryStatus",
+                         attempts AS "attempts!: i64",
+                         next_attempt_at AS "next_attempt_at!: DateTime<Utc>",
+                         last_attempt_at AS "last_attempt_at?: DateTime<Utc>",
+                         response_status,
+                         last_error,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            now,
+            id,
+            webhook_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(WebhookError::DeliveryNotFound)
+    }
+
+    /// Delete successfully delivered rows older than `before` so the delivery
+    /// log doesn't grow without bound. Dead-lettered rows are kept for
+    /// inspection until their webhook is deleted.
+    pub async fn prune_delivered(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM webhook_deliveries
+               WHERE status = 'delivered' AND created_at < $1"#,
+            before
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
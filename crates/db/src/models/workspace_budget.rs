@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::token_usage::UsageTotals;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceBudgetError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid budget: {0}")]
+    Invalid(String),
+}
+
+/// Spending limits of a workspace. Once the workspace's executions use more,
+/// running agents are stopped and new ones refuse to start.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceBudget {
+    pub workspace_id: Uuid,
+    pub max_cost_usd: Option<f64>,
+    pub max_tokens: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetWorkspaceBudget {
+    pub max_cost_usd: Option<f64>,
+    pub max_tokens: Option<i64>,
+}
+
+impl WorkspaceBudget {
+    /// Why `totals` exceed this budget, or `None` if they don't
+    pub fn exceeded_by(&self, totals: &UsageTotals) -> Option<String> {
+        if let Some(max_cost_usd) = self.max_cost_usd
+            && totals.cost_usd > max_cost_usd
+        {
+            return Some(format!(
+                "Workspace budget exceeded: ${:.2} spent of ${:.2}",
+                totals.cost_usd, max_cost_usd
+            ));
+        }
+        if let Some(max_tokens) = self.max_tokens
+            && totals.total_tokens() > max_tokens
+        {
+            return Some(format!(
+                "Workspace budget exceeded: {} tokens used of {}",
+                totals.total_tokens(),
+                max_tokens
+            ));
+        }
+        None
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceBudget,
+            r#"SELECT workspace_id AS "workspace_id!: Uuid",
+                      max_cost_usd AS "max_cost_usd?: f64",
+                      max_tokens AS "max_tokens?: i64",
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM workspace_budgets
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn set(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        data: &SetWorkspaceBudget,
+    ) -> Result<Self, WorkspaceBudgetError> {
+        if data.max_cost_usd.is_none() && data.max_tokens.is_none() {
+            return Err(WorkspaceBudgetError::Invalid(
+                "set max_cost_usd, max_tokens or both".to_string(),
+            ));
+        }
+        if data
+            .max_cost_usd
+            .is_some_and(|max| !max.is_finite() || max < 0.0)
+            || data.max_tokens.is_some_and(|max| max < 0)
+        {
+            return Err(WorkspaceBudgetError::Invalid(
+                "limits must not be negative".to_string(),
+            ));
+        }
+
+        Ok(sqlx::query_as!(
+            WorkspaceBudget,
+            r#"INSERT INTO workspace_budgets (workspace_id, max_cost_usd, max_tokens)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(workspace_id) DO UPDATE SET
+                   max_cost_usd = excluded.max_cost_usd,
+                   max_tokens = excluded.max_tokens,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING workspace_id AS "workspace_id!: Uuid",
+                         max_cost_usd AS "max_cost_usd?: f64",
+                         max_tokens AS "max_tokens?: i64",
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            workspace_id,
+            data.max_cost_usd,
+            data.max_tokens
+        )
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn delete(pool: &SqlitePool, workspace_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM workspace_budgets WHERE workspace_id = $1",
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
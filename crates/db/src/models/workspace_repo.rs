@@ -6,7 +6,7 @@ use sqlx::{FromRow, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::repo::Repo;
+use super::repo::{GitHubAccess, Repo, SigningMode};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct WorkspaceRepo {
@@ -120,8 +120,19 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.verify_after_agent as "verify_after_agent!: bool",
+                      r.require_verification as "require_verification!: bool",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_trailers as "agent_commit_trailers!: bool",
+                      r.commit_signing as "commit_signing!: SigningMode",
+                      r.signing_key,
+                      r.cpu_limit,
+                      r.memory_limit_mb,
+                      r.pids_limit,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.github_access as "github_access!: GitHubAccess",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -149,8 +160,19 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.verify_after_agent as "verify_after_agent!: bool",
+                      r.require_verification as "require_verification!: bool",
+                      r.commit_message_template,
+                      r.conventional_commits as "conventional_commits!: bool",
+                      r.agent_commit_trailers as "agent_commit_trailers!: bool",
+                      r.commit_signing as "commit_signing!: SigningMode",
+                      r.signing_key,
+                      r.cpu_limit,
+                      r.memory_limit_mb,
+                      r.pids_limit,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.github_access as "github_access!: GitHubAccess",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -177,8 +199,19 @@ impl WorkspaceRepo {
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
+                    verify_after_agent: row.verify_after_agent,
+                    require_verification: row.require_verification,
+                    commit_message_template: row.commit_message_template,
+                    conventional_commits: row.conventional_commits,
+                    agent_commit_trailers: row.agent_commit_trailers,
+                    commit_signing: row.commit_signing,
+                    signing_key: row.signing_key,
+                    cpu_limit: row.cpu_limit,
+                    memory_limit_mb: row.memory_limit_mb,
+                    pids_limit: row.pids_limit,
                     default_target_branch: row.default_target_branch,
                     default_working_dir: row.default_working_dir,
+                    github_access: row.github_access,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -236,11 +269,15 @@ impl WorkspaceRepo {
             r#"UPDATE workspace_repos
                SET target_branch = $1, updated_at = datetime('now')
                WHERE target_branch = $2
-                 AND workspace_id IN (
-                     SELECT w.id FROM workspaces w
-                     JOIN tasks t ON w.task_id = t.id
-                     WHERE t.parent_workspace_id = $3
-                 )"#,
+                 AND (workspace_id IN (
+                          SELECT w.id FROM workspaces w
+                          JOIN tasks t ON w.task_id = t.id
+                          WHERE t.parent_workspace_id = $3
+                      )
+                      OR workspace_id IN (
+                          SELECT workspace_id FROM workspace_stacks
+                          WHERE parent_workspace_id = $3
+                      ))"#,
             new_branch,
             old_branch,
             parent_workspace_id
@@ -250,6 +287,34 @@ impl WorkspaceRepo {
         Ok(result.rows_affected())
     }
 
+    /// Point `repo_id` in the workspaces stacked on `parent_workspace_id` from
+    /// `old_branch` to `new_branch`, returning the workspaces that changed
+    pub async fn retarget_stacked_children(
+        pool: &SqlitePool,
+        parent_workspace_id: Uuid,
+        repo_id: Uuid,
+        old_branch: &str,
+        new_branch: &str,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"UPDATE workspace_repos
+               SET target_branch = $1, updated_at = datetime('now')
+               WHERE repo_id = $2
+                 AND target_branch = $3
+                 AND workspace_id IN (
+                     SELECT workspace_id FROM workspace_stacks
+                     WHERE parent_workspace_id = $4
+                 )
+               RETURNING workspace_id AS "workspace_id!: Uuid""#,
+            new_branch,
+            repo_id,
+            old_branch,
+            parent_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Find repos for a workspace with their copy_files configuration.
     pub async fn find_repos_with_copy_files(
         pool: &SqlitePool,
@@ -276,3 +341,73 @@ impl WorkspaceRepo {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::workspace_stack::WorkspaceStack,
+        test_support::{insert_repo, insert_workspace, memory_pool},
+    };
+
+    async fn insert_workspace_repo(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        target_branch: &str,
+    ) {
+        sqlx::query(
+            "INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(workspace_id)
+        .bind(repo_id)
+        .bind(target_branch)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn target_branch(pool: &SqlitePool, workspace_id: Uuid, repo_id: Uuid) -> String {
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace_id, repo_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .target_branch
+    }
+
+    #[tokio::test]
+    async fn retarget_stacked_children_moves_only_children_on_the_merged_branch() {
+        let pool = memory_pool().await;
+        let repo = insert_repo(&pool, "app").await;
+        let other_repo = insert_repo(&pool, "lib").await;
+        let parent = insert_workspace(&pool).await;
+        insert_workspace_repo(&pool, parent, repo, "main").await;
+
+        let child = insert_workspace(&pool).await;
+        WorkspaceStack::create(&pool, child, parent).await.unwrap();
+        insert_workspace_repo(&pool, child, repo, "vk/parent").await;
+        insert_workspace_repo(&pool, child, other_repo, "vk/parent").await;
+        // Already moved off the parent's branch by hand
+        let moved = insert_workspace(&pool).await;
+        WorkspaceStack::create(&pool, moved, parent).await.unwrap();
+        insert_workspace_repo(&pool, moved, repo, "develop").await;
+        // Stacked one level further up
+        let grandchild = insert_workspace(&pool).await;
+        WorkspaceStack::create(&pool, grandchild, child)
+            .await
+            .unwrap();
+        insert_workspace_repo(&pool, grandchild, repo, "vk/parent").await;
+
+        let retargeted =
+            WorkspaceRepo::retarget_stacked_children(&pool, parent, repo, "vk/parent", "main")
+                .await
+                .unwrap();
+
+        assert_eq!(retargeted, vec![child]);
+        assert_eq!(target_branch(&pool, child, repo).await, "main");
+        assert_eq!(target_branch(&pool, child, other_repo).await, "vk/parent");
+        assert_eq!(target_branch(&pool, moved, repo).await, "develop");
+        assert_eq!(target_branch(&pool, grandchild, repo).await, "vk/parent");
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Link from a stacked workspace to the workspace whose branch it builds on
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceStack {
+    pub workspace_id: Uuid,
+    pub parent_workspace_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A workspace's place in the stack it belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackEntry {
+    pub workspace_id: Uuid,
+    pub parent_workspace_id: Option<Uuid>,
+    /// 0 for the bottom of the stack
+    pub depth: u32,
+}
+
+impl WorkspaceStack {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        parent_workspace_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceStack,
+            r#"INSERT INTO workspace_stacks (workspace_id, parent_workspace_id)
+               VALUES ($1, $2)
+               RETURNING workspace_id AS "workspace_id!: Uuid",
+                         parent_workspace_id AS "parent_workspace_id!: Uuid",
+                         created_at AS "created_at!: DateTime<Utc>""#,
+            workspace_id,
+            parent_workspace_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_parent_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT parent_workspace_id AS "parent_workspace_id!: Uuid"
+               FROM workspace_stacks
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Workspaces stacked directly on `parent_workspace_id`, oldest first
+    pub async fn find_child_ids(
+        pool: &SqlitePool,
+        parent_workspace_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT workspace_id AS "workspace_id!: Uuid"
+               FROM workspace_stacks
+               WHERE parent_workspace_id = $1
+               ORDER BY created_at ASC"#,
+            parent_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The workspace at the bottom of the stack containing `workspace_id`
+    pub async fn find_root_id(pool: &SqlitePool, workspace_id: Uuid) -> Result<Uuid, sqlx::Error> {
+        let mut root = workspace_id;
+        let mut seen = HashSet::from([workspace_id]);
+        while let Some(parent_id) = Self::find_parent_id(pool, root).await? {
+            if !seen.insert(parent_id) {
+                break;
+            }
+            root = parent_id;
+        }
+        Ok(root)
+    }
+
+    /// Every workspace in the stack containing `workspace_id`, parents before
+    /// their children and siblings oldest first
+    pub async fn find_stack(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<StackEntry>, sqlx::Error> {
+        let mut pending = vec![StackEntry {
+            workspace_id: Self::find_root_id(pool, workspace_id).await?,
+            parent_workspace_id: None,
+            depth: 0,
+        }];
+        let mut seen = HashSet::new();
+        let mut stack = Vec::new();
+        while let Some(entry) = pending.pop() {
+            if !seen.insert(entry.workspace_id) {
+                continue;
+            }
+            let children = Self::find_child_ids(pool, entry.workspace_id).await?;
+            // Reversed so the oldest child is visited first
+            pending.extend(children.into_iter().rev().map(|child_id| StackEntry {
+                workspace_id: child_id,
+                parent_workspace_id: Some(entry.workspace_id),
+                depth: entry.depth + 1,
+            }));
+            stack.push(entry);
+        }
+        Ok(stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_workspace, memory_pool};
+
+    async fn stack_on(pool: &SqlitePool, parent_id: Uuid, created_at: &str) -> Uuid {
+        let workspace_id = insert_workspace(pool).await;
+        WorkspaceStack::create(pool, workspace_id, parent_id)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE workspace_stacks SET created_at = $1 WHERE workspace_id = $2")
+            .bind(created_at)
+            .bind(workspace_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        workspace_id
+    }
+
+    #[tokio::test]
+    async fn find_stack_walks_from_the_root_parents_first() {
+        let pool = memory_pool().await;
+        let root = insert_workspace(&pool).await;
+        // Created out of order to check siblings come back oldest first
+        let second = stack_on(&pool, root, "2026-01-01 00:00:02").await;
+        let first = stack_on(&pool, root, "2026-01-01 00:00:01").await;
+        let grandchild = stack_on(&pool, first, "2026-01-01 00:00:03").await;
+        let unrelated = insert_workspace(&pool).await;
+
+        assert_eq!(
+            WorkspaceStack::find_root_id(&pool, grandchild)
+                .await
+                .unwrap(),
+            root
+        );
+        assert_eq!(
+            WorkspaceStack::find_root_id(&pool, unrelated)
+                .await
+                .unwrap(),
+            unrelated
+        );
+
+        let stack = WorkspaceStack::find_stack(&pool, grandchild).await.unwrap();
+        assert_eq!(
+            stack,
+            vec![
+                StackEntry {
+                    workspace_id: root,
+                    parent_workspace_id: None,
+                    depth: 0,
+                },
+                StackEntry {
+                    workspace_id: first,
+                    parent_workspace_id: Some(root),
+                    depth: 1,
+                },
+                StackEntry {
+                    workspace_id: grandchild,
+                    parent_workspace_id: Some(first),
+                    depth: 2,
+                },
+                StackEntry {
+                    workspace_id: second,
+                    parent_workspace_id: Some(root),
+                    depth: 1,
+                },
+            ]
+        );
+
+        let alone = WorkspaceStack::find_stack(&pool, unrelated).await.unwrap();
+        assert_eq!(alone.len(), 1);
+        assert_eq!(alone[0].workspace_id, unrelated);
+    }
+}
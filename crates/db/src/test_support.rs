@@ -0,0 +1,61 @@
+//! Fixtures for model tests that run against a migrated in-memory database.
+
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// A fresh in-memory database with every migration applied
+pub(crate) async fn memory_pool() -> SqlitePool {
+    let pool = SqlitePool::connect("sqlite::memory:")
+        .await
+        .expect("in-memory sqlite");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("migrations");
+    pool
+}
+
+pub(crate) async fn insert_workspace(pool: &SqlitePool) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO workspaces (id, branch) VALUES ($1, $2)")
+        .bind(id)
+        .bind(format!("vk/{}", &id.to_string()[..8]))
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+pub(crate) async fn insert_session(pool: &SqlitePool, workspace_id: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO sessions (id, workspace_id) VALUES ($1, $2)")
+        .bind(id)
+        .bind(workspace_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+pub(crate) async fn insert_repo(pool: &SqlitePool, name: &str) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO repos (id, path, name, display_name) VALUES ($1, $2, $3, $3)")
+        .bind(id)
+        .bind(format!("/repos/{name}-{id}"))
+        .bind(name)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+pub(crate) async fn insert_execution_process(pool: &SqlitePool, session_id: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query("INSERT INTO execution_processes (id, session_id) VALUES ($1, $2)")
+        .bind(id)
+        .bind(session_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
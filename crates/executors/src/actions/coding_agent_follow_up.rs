@@ -80,13 +80,16 @@ impl Executable for CodingAgentFollowUpRequest {
             }
             agent.use_approvals(approvals.clone());
 
+            let env = env
+                .clone()
+                .with_writable_paths(agent.sandbox_writable_paths());
             agent
                 .spawn_follow_up(
                     &effective_dir,
                     &self.prompt,
                     &self.session_id,
                     self.reset_to_message_id.as_deref(),
-                    env,
+                    &env,
                 )
                 .await
         }
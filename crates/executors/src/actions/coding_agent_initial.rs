@@ -69,7 +69,10 @@ impl Executable for CodingAgentInitialRequest {
             }
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            let env = env
+                .clone()
+                .with_writable_paths(agent.sandbox_writable_paths());
+            agent.spawn(&effective_dir, &self.prompt, &env).await
         }
     }
 }
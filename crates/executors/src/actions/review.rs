@@ -68,12 +68,15 @@ impl Executable for ReviewRequest {
         }
         agent.use_approvals(approvals.clone());
 
+        let env = env
+            .clone()
+            .with_writable_paths(agent.sandbox_writable_paths());
         agent
             .spawn_review(
                 &effective_dir,
                 &self.prompt,
                 self.session_id.as_deref(),
-                env,
+                &env,
             )
             .await
     }
@@ -1,4 +1,7 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -25,6 +28,11 @@ pub enum ScriptContext {
     ArchiveScript,
     DevServer,
     ToolInstallScript,
+    /// A race's test script, run to compare the attempts
+    TestScript,
+    /// A repo's verification step, run after agent turns, on demand or by the
+    /// merge queue
+    VerificationScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -53,14 +61,19 @@ impl Executable for ScriptRequest {
         };
 
         let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+        let (program, args) = env
+            .sandboxed(
+                PathBuf::from(shell_cmd),
+                vec![shell_arg.to_string(), self.script.clone()],
+            )
+            .await?;
+        let mut command = Command::new(program);
         command
             .kill_on_drop(true)
             .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
+            .args(&args)
             .current_dir(&effective_dir);
 
         // Apply environment variables
@@ -6,7 +6,7 @@ use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 use workspace_utils::approvals::{ApprovalStatus, QuestionStatus};
 
-use crate::logs::AskUserQuestionItem;
+use crate::logs::{ActionType, AskUserQuestionItem, NormalizedEntry, NormalizedEntryType};
 
 /// Errors emitted by executor approval services.
 #[derive(Debug, Error)]
@@ -27,11 +27,55 @@ impl ExecutorApprovalError {
     }
 }
 
+/// An approval settled by a repository approval rule instead of the user.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolicyDecision {
+    pub approved: bool,
+    /// Which rule matched and why, shown to reviewers in the conversation.
+    pub reason: String,
+}
+
+impl PolicyDecision {
+    pub fn status(&self) -> ApprovalStatus {
+        if self.approved {
+            ApprovalStatus::Approved
+        } else {
+            ApprovalStatus::Denied {
+                reason: Some(self.reason.clone()),
+            }
+        }
+    }
+
+    pub fn to_normalized_entry(&self) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::SystemMessage,
+            content: self.reason.clone(),
+            metadata: None,
+        }
+    }
+}
+
+/// A created tool approval. When `policy_decision` is set the approval is
+/// already resolved and `wait_tool_approval` returns immediately.
+#[derive(Debug, Clone)]
+pub struct ToolApproval {
+    pub approval_id: String,
+    pub policy_decision: Option<PolicyDecision>,
+}
+
 /// Abstraction for executor approval backends.
 #[async_trait]
 pub trait ExecutorApprovalService: Send + Sync {
-    /// Creates a tool approval request. Returns the approval_id immediately.
-    async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError>;
+    /// Creates a tool approval request. Returns immediately; `actions`
+    /// describe what the tool is about to do, when the executor knows, so
+    /// approval rules can match on the command or the path of each file it
+    /// edits.
+    async fn create_tool_approval(
+        &self,
+        tool_name: &str,
+        actions: &[ActionType],
+    ) -> Result<ToolApproval, ExecutorApprovalError>;
 
     /// Creates a question approval request. Returns the approval_id immediately.
     async fn create_question_approval(
@@ -63,8 +107,12 @@ impl ExecutorApprovalService for NoopExecutorApprovalService {
     async fn create_tool_approval(
         &self,
         _tool_name: &str,
-    ) -> Result<String, ExecutorApprovalError> {
-        Ok("noop".to_string())
+        _actions: &[ActionType],
+    ) -> Result<ToolApproval, ExecutorApprovalError> {
+        Ok(ToolApproval {
+            approval_id: "noop".to_string(),
+            policy_decision: None,
+        })
     }
 
     async fn create_question_approval(
@@ -102,6 +150,22 @@ mod tests {
     use super::*;
     use crate::logs::{AskUserQuestionItem, AskUserQuestionOption};
 
+    #[test]
+    fn policy_denial_carries_reason() {
+        let decision = PolicyDecision {
+            approved: false,
+            reason: "Denied `Bash` by approval rule #1 of app".to_string(),
+        };
+        assert!(matches!(
+            decision.status(),
+            ApprovalStatus::Denied { reason: Some(ref r) } if r == &decision.reason
+        ));
+        assert!(matches!(
+            decision.to_normalized_entry().entry_type,
+            NormalizedEntryType::SystemMessage
+        ));
+    }
+
     #[tokio::test]
     async fn noop_service_accepts_structured_questions() {
         let svc = NoopExecutorApprovalService;
@@ -6,7 +6,7 @@ use thiserror::Error;
 use ts_rs::TS;
 use workspace_utils::shell::resolve_executable_path;
 
-use crate::executors::ExecutorError;
+use crate::{executors::ExecutorError, resource_limits::ResourceLimits, sandbox::SandboxOverrides};
 
 #[derive(Debug, Error)]
 pub enum CommandBuildError {
@@ -60,6 +60,18 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Process Sandbox",
+        description = "Network access and extra writable paths when running in the Vibe Kanban sandbox"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_sandbox: Option<SandboxOverrides>,
+    #[schemars(
+        title = "Resource Limits",
+        description = "CPU, memory and process limits, combined with the repository's by taking the stricter of each"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<ResourceLimits>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
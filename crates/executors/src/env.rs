@@ -3,7 +3,7 @@ use std::{collections::HashMap, path::PathBuf};
 use git::GitService;
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, executors::ExecutorError, sandbox::Sandbox};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -81,6 +81,11 @@ pub struct ExecutionEnv {
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
     pub commit_reminder_prompt: String,
+    /// Sandbox that executor and script processes run in, when enabled
+    pub sandbox: Option<Sandbox>,
+    /// cgroup directory spawned processes join before they exec, so the
+    /// resource limits cover everything they fork
+    pub cgroup: Option<PathBuf>,
 }
 
 impl ExecutionEnv {
@@ -94,6 +99,8 @@ impl ExecutionEnv {
             repo_context,
             commit_reminder,
             commit_reminder_prompt,
+            sandbox: None,
+            cgroup: None,
         }
     }
 
@@ -114,8 +121,13 @@ impl ExecutionEnv {
         self
     }
 
-    /// Return a new env with profile env from CmdOverrides merged in.
-    pub fn with_profile(self, cmd: &CmdOverrides) -> Self {
+    /// Return a new env with profile env and sandbox settings from CmdOverrides applied.
+    pub fn with_profile(mut self, cmd: &CmdOverrides) -> Self {
+        if let (Some(sandbox), Some(overrides)) =
+            (self.sandbox.as_mut(), cmd.process_sandbox.as_ref())
+        {
+            sandbox.apply_overrides(overrides);
+        }
         if let Some(ref profile_env) = cmd.env {
             self.with_overrides(profile_env)
         } else {
@@ -123,11 +135,35 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Return a new env whose sandbox, if any, may also write to `paths`
+    pub fn with_writable_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        if let Some(sandbox) = self.sandbox.as_mut() {
+            sandbox.add_paths(paths);
+        }
+        self
+    }
+
+    /// The program and arguments to spawn, wrapped in the sandbox when there is one
+    pub async fn sandboxed(
+        &self,
+        program: PathBuf,
+        args: Vec<String>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        match &self.sandbox {
+            Some(sandbox) => sandbox.wrap(program, args).await,
+            None => Ok((program, args)),
+        }
+    }
+
+    /// Apply all environment variables, and the cgroup if any, to a Command
     pub fn apply_to_command(&self, command: &mut Command) {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        #[cfg(target_os = "linux")]
+        if let Some(cgroup) = &self.cgroup {
+            crate::resource_limits::join_cgroup_before_exec(command, cgroup);
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
@@ -10,6 +10,7 @@ use workspace_utils::approvals::ApprovalStatus;
 use crate::{
     approvals::{ExecutorApprovalError, ExecutorApprovalService},
     executors::acp::{AcpEvent, ApprovalResponse},
+    logs::{ActionType, CommandCategory},
 };
 
 /// ACP client that handles agent-client protocol communication
@@ -105,14 +106,25 @@ impl acp::Client for AcpClient {
             .ok_or(ExecutorApprovalError::ServiceUnavailable)
             .map_err(|_| acp::Error::invalid_request())?;
 
-        let approval_id = match approval_service.create_tool_approval(tool_name).await {
-            Ok(id) => id,
+        let action = approval_action(&args.tool_call.fields);
+        let approval = match approval_service
+            .create_tool_approval(tool_name, action.as_slice())
+            .await
+        {
+            Ok(approval) => approval,
             Err(err) => return self.handle_approval_error(err, &tool_call_id),
         };
-
-        self.send_event(AcpEvent::ApprovalRequested {
-            tool_call_id: tool_call_id.clone(),
-            approval_id: approval_id.clone(),
+        let approval_id = approval.approval_id;
+
+        self.send_event(match approval.policy_decision {
+            Some(decision) => AcpEvent::ApprovalPolicyDecision {
+                tool_call_id: tool_call_id.clone(),
+                decision,
+            },
+            None => AcpEvent::ApprovalRequested {
+                tool_call_id: tool_call_id.clone(),
+                approval_id: approval_id.clone(),
+            },
         });
 
         let status = match approval_service
@@ -279,3 +291,38 @@ impl AcpClient {
         }
     }
 }
+
+/// The command or file a permission request acts on, for matching approval rules.
+fn approval_action(fields: &acp::ToolCallUpdateFields) -> Option<ActionType> {
+    match fields.kind? {
+        acp::ToolKind::Execute => {
+            let command = fields
+                .raw_input
+                .as_ref()
+                .and_then(|input| input.get("command"))
+                .and_then(|command| command.as_str())
+                .map(str::to_string)
+                .or_else(|| {
+                    fields.title.as_deref().and_then(|title| {
+                        title
+                            .split(" [current working directory ")
+                            .next()
+                            .map(|command| command.trim().to_string())
+                    })
+                })?;
+            Some(ActionType::CommandRun {
+                category: CommandCategory::from_command(&command),
+                command,
+                result: None,
+            })
+        }
+        acp::ToolKind::Edit | acp::ToolKind::Delete | acp::ToolKind::Move => {
+            let location = fields.locations.as_ref()?.first()?;
+            Some(ActionType::FileEdit {
+                path: location.path.to_string_lossy().to_string(),
+                changes: vec![],
+            })
+        }
+        _ => None,
+    }
+}
@@ -26,6 +26,7 @@ use crate::{
     command::{CmdOverrides, CommandParts},
     env::ExecutionEnv,
     executors::{ExecutorError, ExecutorExitResult, SpawnedChild, acp::AcpEvent},
+    logs::TokenUsage,
 };
 
 /// Reusable harness for ACP-based conns (Gemini, Qwen, etc.)
@@ -90,7 +91,9 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
+        let env = env.clone().with_profile(cmd_overrides);
         let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -102,9 +105,7 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
@@ -145,7 +146,9 @@ impl AcpAgentHarness {
         cmd_overrides: &CmdOverrides,
         approvals: Option<std::sync::Arc<dyn ExecutorApprovalService>>,
     ) -> Result<SpawnedChild, ExecutorError> {
+        let env = env.clone().with_profile(cmd_overrides);
         let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
         let mut command = Command::new(program_path);
         command
             .kill_on_drop(true)
@@ -157,9 +160,7 @@ impl AcpAgentHarness {
             .env("NODE_NO_WARNINGS", "1")
             .args(&args);
 
-        env.clone()
-            .with_profile(cmd_overrides)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
@@ -481,6 +482,10 @@ impl AcpAgentHarness {
 
                             match prompt_result {
                                 Ok(resp) => {
+                                    if let Some(mut usage) = prompt_response_usage(&resp) {
+                                        usage.model = model.clone();
+                                        let _ = log_tx.send(AcpEvent::Usage(usage).to_string());
+                                    }
                                     // Emit done with stop_reason
                                     let stop_reason = serde_json::to_string(&resp.stop_reason)
                                         .unwrap_or_default();
@@ -547,3 +552,33 @@ impl AcpAgentHarness {
         Ok(())
     }
 }
+
+/// Token counts an agent attached to a prompt response, either as `usage`
+/// or under `_meta.usage`
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptResponseUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cached_read_tokens: u64,
+    #[serde(default)]
+    cached_write_tokens: u64,
+}
+
+fn prompt_response_usage(resp: &proto::PromptResponse) -> Option<TokenUsage> {
+    let value = serde_json::to_value(resp).ok()?;
+    let usage = value
+        .get("usage")
+        .or_else(|| value.get("_meta").and_then(|meta| meta.get("usage")))?;
+    let usage: PromptResponseUsage = serde_json::from_value(usage.clone()).ok()?;
+    Some(TokenUsage {
+        model: None,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        cache_read_tokens: usage.cached_read_tokens,
+        cache_write_tokens: usage.cached_write_tokens,
+    })
+}
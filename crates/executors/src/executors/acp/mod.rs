@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 pub use session::SessionManager;
 use workspace_utils::approvals::ApprovalStatus;
 
+use crate::{approvals::PolicyDecision, logs::TokenUsage};
+
 /// Parsed event types for internal processing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AcpEvent {
@@ -30,7 +32,15 @@ pub enum AcpEvent {
         approval_id: String,
     },
     ApprovalResponse(ApprovalResponse),
+    /// Sent instead of `ApprovalRequested` when an approval rule settled the
+    /// request.
+    ApprovalPolicyDecision {
+        tool_call_id: String,
+        decision: PolicyDecision,
+    },
     Error(String),
+    /// Token usage the agent reported for one prompt turn
+    Usage(TokenUsage),
     Done(String),
     Other(agent_client_protocol::SessionNotification),
 }
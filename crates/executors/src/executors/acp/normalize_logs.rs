@@ -17,7 +17,8 @@ use crate::{
     approvals::ToolCallMetadata,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        TodoItem, TokenUsage, TokenUsageInfo, ToolResult, ToolResultValueType,
+        ToolStatus as LogToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         stderr_processor::normalize_stderr_logs,
         utils::{ConversationPatch, EntryIndexProvider, shell_command_parsing::CommandCategory},
@@ -60,6 +61,7 @@ pub fn normalize_logs_with_suppressed_stderr_patterns(
         let mut stored_session_id = false;
         let mut streaming: StreamingState = StreamingState::default();
         let mut tool_states: ToolStates = HashMap::new();
+        let mut execution_usage = TokenUsage::default();
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
@@ -84,6 +86,21 @@ pub fn normalize_logs_with_suppressed_stderr_patterns(
                         };
                         msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
                     }
+                    AcpEvent::Usage(usage) => {
+                        execution_usage.add(&usage);
+                        let idx = entry_index.next();
+                        let entry = NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+                                total_tokens: usage.total_tokens() as u32,
+                                model_context_window: 0,
+                                usage: Some(execution_usage.clone()),
+                            }),
+                            content: format!("Tokens used: {}", usage.total_tokens()),
+                            metadata: None,
+                        };
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(idx, entry));
+                    }
                     AcpEvent::Done(_) => {
                         streaming.assistant_text = None;
                         streaming.thinking_text = None;
@@ -319,6 +336,13 @@ pub fn normalize_logs_with_suppressed_stderr_patterns(
                                 .push_patch(ConversationPatch::add_normalized_entry(idx, entry));
                         }
                     }
+                    AcpEvent::ApprovalPolicyDecision { decision, .. } => {
+                        let idx = entry_index.next();
+                        msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                            idx,
+                            decision.to_normalized_entry(),
+                        ));
+                    }
                     AcpEvent::User(_) | AcpEvent::Other(_) => (),
                 }
             }
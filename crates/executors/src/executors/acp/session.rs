@@ -71,6 +71,7 @@ impl SessionManager {
         match event {
             AcpEvent::SessionStart(..)
             | AcpEvent::Error(..)
+            | AcpEvent::Usage(..)
             | AcpEvent::Done(..)
             | AcpEvent::Other(..) => return None,
 
@@ -83,6 +84,7 @@ impl SessionManager {
             | AcpEvent::AvailableCommands(..)
             | AcpEvent::ApprovalRequested { .. }
             | AcpEvent::ApprovalResponse(..)
+            | AcpEvent::ApprovalPolicyDecision { .. }
             | AcpEvent::CurrentMode(..) => {}
 
             AcpEvent::RequestPermission(req) => event = AcpEvent::ToolUpdate(req.tool_call),
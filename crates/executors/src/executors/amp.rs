@@ -52,7 +52,9 @@ impl StandardCodingAgentExecutor for Amp {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
+        let env = env.clone().with_profile(&self.cmd);
         let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.sandboxed(executable_path, args).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -66,9 +68,7 @@ impl StandardCodingAgentExecutor for Amp {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
@@ -95,7 +95,10 @@ impl StandardCodingAgentExecutor for Amp {
             "continue".to_string(),
             session_id.to_string(),
         ])?;
+        let env = env.clone().with_profile(&self.cmd);
         let (continue_program, continue_args) = continue_line.into_resolved().await?;
+        let (continue_program, continue_args) =
+            env.sandboxed(continue_program, continue_args).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -109,9 +112,7 @@ impl StandardCodingAgentExecutor for Amp {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&continue_args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
@@ -150,6 +151,17 @@ impl StandardCodingAgentExecutor for Amp {
         dirs::home_dir().map(|home| home.join(".config").join("amp").join("settings.json"))
     }
 
+    fn sandbox_writable_paths(&self) -> Vec<std::path::PathBuf> {
+        dirs::home_dir()
+            .map(|home| {
+                vec![
+                    home.join(".config").join("amp"),
+                    home.join(".local").join("share").join("amp"),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
     fn get_preset_options(&self) -> ExecutorConfig {
         ExecutorConfig {
             executor: BaseCodingAgent::Amp,
@@ -35,7 +35,7 @@ use self::{
     types::{ControlRequestType, ControlResponseType, PermissionMode},
 };
 use crate::{
-    approvals::ExecutorApprovalService,
+    approvals::{ExecutorApprovalService, PolicyDecision},
     command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
     env::ExecutionEnv,
     executors::{
@@ -44,7 +44,8 @@ use crate::{
     },
     logs::{
         ActionType, AnsweredQuestion, AskUserQuestionItem, AskUserQuestionOption, FileChange,
-        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolStatus,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsage,
+        ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             EntryIndexProvider,
@@ -600,6 +601,14 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         dirs::home_dir().map(|home| home.join(".claude.json"))
     }
 
+    fn sandbox_writable_paths(&self) -> Vec<std::path::PathBuf> {
+        dirs::home_dir()
+            // `.claude.json` is replaced by renaming over it, so its directory
+            // has to be writable
+            .map(|home| vec![home.join(".claude"), home])
+            .unwrap_or_default()
+    }
+
     fn get_availability_info(&self) -> AvailabilityInfo {
         let auth_file_path = dirs::home_dir().map(|home| home.join(".claude.json"));
 
@@ -626,7 +635,9 @@ impl ClaudeCode {
         command_parts: CommandParts,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
+        let env = env.clone().with_profile(&self.cmd);
         let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
         let mut command = Command::new(program_path);
@@ -639,9 +650,7 @@ impl ClaudeCode {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         // Remove ANTHROPIC_API_KEY if disable_api_key is enabled
         if self.disable_api_key.unwrap_or(false) {
@@ -768,6 +777,8 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Token counts summed over the result messages of this execution
+    execution_usage: Option<TokenUsage>,
 }
 
 impl ClaudeLogProcessor {
@@ -787,6 +798,7 @@ impl ClaudeLogProcessor {
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            execution_usage: None,
         }
     }
 
@@ -931,6 +943,7 @@ impl ClaudeLogProcessor {
             ClaudeJson::StreamEvent { .. } => None, // session might not have been initialized yet
             ClaudeJson::ApprovalRequested { .. } => None,
             ClaudeJson::ApprovalResponse { .. } => None,
+            ClaudeJson::ApprovalPolicyDecision { .. } => None,
             ClaudeJson::QuestionResponse { .. } => None,
             ClaudeJson::ControlRequest { .. } => None,
             ClaudeJson::ControlResponse { .. } => None,
@@ -1826,19 +1839,36 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
                 subtype,
                 result,
                 permission_denials,
                 ..
             } => {
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
+                }
+                // each result reports the usage of the turn it ends
+                if let Some(usage) = usage {
+                    let turn_usage = TokenUsage {
+                        model: self.main_model_name.clone(),
+                        input_tokens: usage.input_tokens.unwrap_or(0),
+                        output_tokens: usage.output_tokens.unwrap_or(0),
+                        cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                        cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+                    };
+                    self.execution_usage
+                        .get_or_insert_with(TokenUsage::default)
+                        .add(&turn_usage);
+                }
+                if context_window.is_some() || usage.is_some() {
                     patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
@@ -1957,6 +1987,13 @@ impl ClaudeLogProcessor {
                     patches.push(ConversationPatch::add_normalized_entry(idx, entry));
                 }
             }
+            ClaudeJson::ApprovalPolicyDecision { decision, .. } => {
+                let idx = entry_index_provider.next();
+                patches.push(ConversationPatch::add_normalized_entry(
+                    idx,
+                    decision.to_normalized_entry(),
+                ));
+            }
             ClaudeJson::QuestionResponse {
                 tool_call_id,
                 tool_name: _,
@@ -2123,6 +2160,7 @@ impl ClaudeLogProcessor {
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                usage: self.execution_usage.clone(),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -2414,6 +2452,13 @@ pub enum ClaudeJson {
         tool_name: String,
         approval_status: ApprovalStatus,
     },
+    /// Logged instead of `ApprovalRequested` when an approval rule settled
+    /// the request; the matching `ApprovalResponse` follows.
+    ApprovalPolicyDecision {
+        tool_call_id: String,
+        tool_name: String,
+        decision: PolicyDecision,
+    },
     QuestionResponse {
         tool_call_id: String,
         tool_name: String,
@@ -2895,6 +2940,41 @@ mod tests {
         assert_eq!(entries[0].content, "Final result");
     }
 
+    #[test]
+    fn test_result_usage_is_summed_over_turns() {
+        let mut processor = ClaudeLogProcessor::new();
+        let init_json =
+            r#"{"type":"system","subtype":"init","session_id":"abc123","model":"claude-sonnet-4"}"#;
+        normalize_helper(
+            &mut processor,
+            &serde_json::from_str(init_json).unwrap(),
+            "",
+        );
+
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"usage":{"input_tokens":10,"output_tokens":20,"cache_creation_input_tokens":30,"cache_read_input_tokens":40}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        normalize_helper(&mut processor, &parsed, "");
+        let entries = normalize_helper(&mut processor, &parsed, "");
+
+        let usage = entries
+            .iter()
+            .find_map(|entry| match &entry.entry_type {
+                NormalizedEntryType::TokenUsageInfo(info) => info.usage.clone(),
+                _ => None,
+            })
+            .expect("token usage entry");
+        assert_eq!(
+            usage,
+            TokenUsage {
+                model: Some("claude-sonnet-4".to_string()),
+                input_tokens: 20,
+                output_tokens: 40,
+                cache_read_tokens: 80,
+                cache_write_tokens: 60,
+            }
+        );
+    }
+
     #[test]
     fn test_result_permission_denials_deserialization() {
         let result_json = r#"{"type":"result","subtype":"error","is_error":true,"permissionDenials":[{"toolName":"Bash"}]}"#;
@@ -3044,6 +3124,8 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                process_sandbox: None,
+                resource_limits: None,
             },
             approvals_service: None,
             disable_api_key: None,
@@ -10,7 +10,7 @@ use crate::{
     executors::{
         ExecutorError,
         claude::{
-            ClaudeJson,
+            ClaudeJson, ClaudeToolData,
             types::{
                 PermissionResult, PermissionUpdate, PermissionUpdateDestination,
                 PermissionUpdateType,
@@ -18,7 +18,7 @@ use crate::{
         },
         codex::client::LogWriter,
     },
-    logs::AskUserQuestionItem,
+    logs::{ActionType, AskUserQuestionItem, CommandCategory},
 };
 
 const EXIT_PLAN_MODE_NAME: &str = "ExitPlanMode";
@@ -69,22 +69,35 @@ impl ClaudeAgentClient {
             .as_ref()
             .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
 
-        let approval_id = match approval_service.create_tool_approval(&tool_name).await {
-            Ok(id) => id,
+        let action = approval_action(&tool_name, &tool_input);
+        let approval = match approval_service
+            .create_tool_approval(&tool_name, action.as_slice())
+            .await
+        {
+            Ok(approval) => approval,
             Err(err) => {
                 self.handle_approval_error(&tool_name, &tool_use_id, &err)
                     .await?;
                 return Err(err.into());
             }
         };
+        let approval_id = approval.approval_id;
 
-        let _ = self
-            .log_writer
-            .log_raw(&serde_json::to_string(&ClaudeJson::ApprovalRequested {
+        let event = match approval.policy_decision {
+            Some(decision) => ClaudeJson::ApprovalPolicyDecision {
+                tool_call_id: tool_use_id.clone(),
+                tool_name: tool_name.clone(),
+                decision,
+            },
+            None => ClaudeJson::ApprovalRequested {
                 tool_call_id: tool_use_id.clone(),
                 tool_name: tool_name.clone(),
                 approval_id: approval_id.clone(),
-            })?)
+            },
+        };
+        let _ = self
+            .log_writer
+            .log_raw(&serde_json::to_string(&event)?)
             .await;
 
         let status = match approval_service
@@ -384,8 +397,57 @@ impl ClaudeAgentClient {
     }
 }
 
+/// The command or file a tool call acts on, for matching approval rules.
+fn approval_action(tool_name: &str, tool_input: &serde_json::Value) -> Option<ActionType> {
+    let tool_data: ClaudeToolData = serde_json::from_value(serde_json::json!({
+        "name": tool_name,
+        "input": tool_input,
+    }))
+    .ok()?;
+    let path = match tool_data {
+        ClaudeToolData::Bash { command, .. } => {
+            return Some(ActionType::CommandRun {
+                category: CommandCategory::from_command(&command),
+                command,
+                result: None,
+            });
+        }
+        ClaudeToolData::Edit { file_path, .. }
+        | ClaudeToolData::MultiEdit { file_path, .. }
+        | ClaudeToolData::Write { file_path, .. } => file_path,
+        ClaudeToolData::NotebookEdit { notebook_path, .. } => notebook_path,
+        _ => return None,
+    };
+    Some(ActionType::FileEdit {
+        path,
+        changes: vec![],
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn approval_action_extracts_command_and_edited_path() {
+        let action = approval_action("Bash", &serde_json::json!({ "command": "cargo test" }));
+        assert!(matches!(
+            action,
+            Some(ActionType::CommandRun { ref command, .. }) if command == "cargo test"
+        ));
+
+        let action = approval_action(
+            "Write",
+            &serde_json::json!({ "file_path": "/work/src/lib.rs", "content": "" }),
+        );
+        assert!(matches!(
+            action,
+            Some(ActionType::FileEdit { ref path, .. }) if path == "/work/src/lib.rs"
+        ));
+
+        assert!(approval_action("WebFetch", &serde_json::json!({ "url": "https://x" })).is_none());
+    }
+
     #[test]
     fn answer_map_uses_header_as_key() {
         use workspace_utils::approvals::QuestionAnswer;
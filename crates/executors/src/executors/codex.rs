@@ -728,7 +728,9 @@ impl Codex {
         F: FnOnce(Arc<AppServerClient>, ExitSignalSender) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = Result<(), ExecutorError>> + Send + 'static,
     {
+        let env = env.clone().with_profile(&self.cmd);
         let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
 
         let mut process = Command::new(program_path);
         process
@@ -743,9 +745,7 @@ impl Codex {
             .env("RUST_LOG", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut process);
+        env.apply_to_command(&mut process);
 
         let mut child = process.group_spawn_no_window()?;
 
@@ -14,8 +14,8 @@ use codex_app_server_protocol::{
     ConfigReadResponse, ConfigWriteResponse, DynamicToolCallOutputContentItem,
     DynamicToolCallResponse, FileChangeApprovalDecision, FileChangeRequestApprovalResponse,
     GetAccountParams, GetAccountRateLimitsResponse, GetAccountResponse, InitializeCapabilities,
-    InitializeParams, InitializeResponse, ItemCompletedNotification, JSONRPCError,
-    JSONRPCNotification, JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams,
+    InitializeParams, InitializeResponse, ItemCompletedNotification, ItemStartedNotification,
+    JSONRPCError, JSONRPCNotification, JSONRPCRequest, JSONRPCResponse, ListMcpServerStatusParams,
     ListMcpServerStatusResponse, McpServerStatusDetail, RequestId, ReviewStartParams,
     ReviewStartResponse, ReviewTarget, ServerRequest, ThreadCompactStartParams,
     ThreadCompactStartResponse, ThreadForkParams, ThreadForkResponse, ThreadItem, ThreadReadParams,
@@ -36,10 +36,10 @@ use workspace_utils::approvals::{ApprovalStatus, QuestionStatus};
 
 use super::jsonrpc::{JsonRpcCallbacks, JsonRpcPeer};
 use crate::{
-    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    approvals::{ExecutorApprovalError, ExecutorApprovalService, ToolApproval},
     env::RepoContext,
     executors::{ExecutorError, codex::normalize_logs::Approval},
-    logs::{AskUserQuestionItem, AskUserQuestionOption},
+    logs::{ActionType, AskUserQuestionItem, AskUserQuestionOption, CommandCategory},
 };
 
 struct PendingPlan {
@@ -56,6 +56,9 @@ pub struct AppServerClient {
     plan_mode: bool,
     resolved_model: OnceLock<String>,
     pending_plan: Mutex<Option<PendingPlan>>,
+    /// Files each in-progress file change item edits, by item id, so their
+    /// approval can be checked against the repository's path rules
+    pending_file_changes: Mutex<HashMap<String, Vec<ActionType>>>,
     repo_context: RepoContext,
     commit_reminder: bool,
     commit_reminder_prompt: String,
@@ -83,6 +86,7 @@ impl AppServerClient {
             plan_mode,
             resolved_model: OnceLock::new(),
             pending_plan: Mutex::new(None),
+            pending_file_changes: Mutex::new(HashMap::new()),
             thread_id: Mutex::new(None),
             pending_feedback: Mutex::new(VecDeque::new()),
             repo_context,
@@ -309,8 +313,14 @@ impl AppServerClient {
         match request {
             ServerRequest::FileChangeRequestApproval { request_id, params } => {
                 let call_id = params.item_id.clone();
+                let edits = self
+                    .pending_file_changes
+                    .lock()
+                    .await
+                    .remove(&call_id)
+                    .unwrap_or_default();
                 let status = self
-                    .request_tool_approval("edit", "codex.apply_patch", &call_id)
+                    .request_tool_approval("edit", "codex.apply_patch", &call_id, edits)
                     .await
                     .inspect_err(|err| {
                         if !matches!(
@@ -344,8 +354,22 @@ impl AppServerClient {
             }
             ServerRequest::CommandExecutionRequestApproval { request_id, params } => {
                 let call_id = params.item_id.clone();
+                let action = params
+                    .command
+                    .clone()
+                    .filter(|command| !command.is_empty())
+                    .map(|command| ActionType::CommandRun {
+                        category: CommandCategory::from_command(&command),
+                        command,
+                        result: None,
+                    });
                 let status = self
-                    .request_tool_approval("bash", "codex.exec_command", &call_id)
+                    .request_tool_approval(
+                        "bash",
+                        "codex.exec_command",
+                        &call_id,
+                        action.into_iter().collect(),
+                    )
                     .await
                     .inspect_err(|err| {
                         if !matches!(
@@ -458,6 +482,7 @@ impl AppServerClient {
         tool_name: &str,
         display_tool_name: &str,
         tool_call_id: &str,
+        actions: Vec<ActionType>,
     ) -> Result<ApprovalStatus, ExecutorError> {
         if self.auto_approve {
             return Ok(ApprovalStatus::Approved);
@@ -467,25 +492,16 @@ impl AppServerClient {
             .as_ref()
             .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
 
-        let approval_id = approval_service
-            .create_tool_approval(tool_name)
+        let approval = approval_service
+            .create_tool_approval(tool_name, &actions)
             .or_else(|err| async {
                 self.handle_approval_error(display_tool_name, tool_call_id)
                     .await;
                 Err(err)
             })
             .await?;
-
-        let _ = self
-            .log_writer
-            .log_raw(
-                &Approval::approval_requested(
-                    tool_call_id.to_string(),
-                    display_tool_name.to_string(),
-                    approval_id.clone(),
-                )
-                .raw(),
-            )
+        let approval_id = self
+            .log_approval_created(tool_call_id, display_tool_name, approval)
             .await;
 
         approval_service
@@ -499,6 +515,30 @@ impl AppServerClient {
             .map_err(ExecutorError::from)
     }
 
+    /// Log that an approval was created, or that an approval rule settled it,
+    /// and return the id to wait on.
+    async fn log_approval_created(
+        &self,
+        tool_call_id: &str,
+        display_tool_name: &str,
+        approval: ToolApproval,
+    ) -> String {
+        let event = match approval.policy_decision {
+            Some(decision) => Approval::policy_decision(
+                tool_call_id.to_string(),
+                display_tool_name.to_string(),
+                decision,
+            ),
+            None => Approval::approval_requested(
+                tool_call_id.to_string(),
+                display_tool_name.to_string(),
+                approval.approval_id.clone(),
+            ),
+        };
+        let _ = self.log_writer.log_raw(&event.raw()).await;
+        approval.approval_id
+    }
+
     async fn handle_approval_error(&self, display_tool_name: &str, tool_call_id: &str) {
         let _ = self
             .log_writer
@@ -588,25 +628,16 @@ impl AppServerClient {
             .as_ref()
             .ok_or(ExecutorApprovalError::ServiceUnavailable)?;
 
-        let approval_id = approval_service
-            .create_tool_approval("plan")
+        let approval = approval_service
+            .create_tool_approval("plan", &[])
             .or_else(|err| async {
                 self.handle_approval_error("codex.plan", &plan.item_id)
                     .await;
                 Err(err)
             })
             .await?;
-
-        let _ = self
-            .log_writer
-            .log_raw(
-                &Approval::approval_requested(
-                    plan.item_id.clone(),
-                    "codex.plan".to_string(),
-                    approval_id.clone(),
-                )
-                .raw(),
-            )
+        let approval_id = self
+            .log_approval_created(&plan.item_id, "codex.plan", approval)
             .await;
 
         let status = approval_service
@@ -878,15 +909,36 @@ impl JsonRpcCallbacks for AppServerClient {
 
         let method = notification.method.as_str();
 
-        // Detect completed plan items in the notification stream
-        if self.plan_mode
-            && method == "item/completed"
+        if !self.auto_approve
+            && method == "item/started"
+            && let Some(ref params) = notification.params
+            && let Ok(started) = serde_json::from_value::<ItemStartedNotification>(params.clone())
+            && let ThreadItem::FileChange { id, changes, .. } = started.item
+        {
+            let edits = changes
+                .into_iter()
+                .map(|change| ActionType::FileEdit {
+                    path: change.path,
+                    changes: vec![],
+                })
+                .collect();
+            self.pending_file_changes.lock().await.insert(id, edits);
+        }
+        if method == "item/completed"
             && let Some(ref params) = notification.params
             && let Ok(completed) =
                 serde_json::from_value::<ItemCompletedNotification>(params.clone())
-            && let ThreadItem::Plan { id, .. } = completed.item
         {
-            *self.pending_plan.lock().await = Some(PendingPlan { item_id: id });
+            match completed.item {
+                // Detect completed plan items in the notification stream
+                ThreadItem::Plan { id, .. } if self.plan_mode => {
+                    *self.pending_plan.lock().await = Some(PendingPlan { item_id: id });
+                }
+                ThreadItem::FileChange { id, .. } => {
+                    self.pending_file_changes.lock().await.remove(&id);
+                }
+                _ => {}
+            }
         }
 
         // V2 turn completion detection
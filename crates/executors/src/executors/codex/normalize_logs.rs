@@ -47,11 +47,11 @@ use workspace_utils::{
 };
 
 use crate::{
-    approvals::ToolCallMetadata,
+    approvals::{PolicyDecision, ToolCallMetadata},
     logs::{
         ActionType, AnsweredQuestion, AskUserQuestionItem, AskUserQuestionOption,
         CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry, NormalizedEntryError,
-        NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType, ToolStatus,
+        NormalizedEntryType, TodoItem, TokenUsage, ToolResult, ToolResultValueType, ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -391,6 +391,9 @@ struct LogState {
     plans: HashMap<String, PlanState>,
     review: Option<ReviewState>,
     model_params: ModelParamsState,
+    // Thread usage before this execution's first turn; resumed threads report
+    // totals that include earlier executions
+    token_usage_baseline: Option<TokenUsage>,
 }
 
 struct ModelParamsState {
@@ -423,9 +426,21 @@ impl LogState {
                 model: None,
                 reasoning_effort: None,
             },
+            token_usage_baseline: None,
         }
     }
 
+    /// Usage of this execution from the thread's running total and the usage
+    /// of the latest turn.
+    fn execution_token_usage(&mut self, total: TokenUsage, last: &TokenUsage) -> TokenUsage {
+        let baseline = self
+            .token_usage_baseline
+            .get_or_insert_with(|| total.saturating_sub(last));
+        let mut usage = total.saturating_sub(baseline);
+        usage.model = self.model_params.model.clone();
+        usage
+    }
+
     fn streaming_text_update(
         &mut self,
         content: String,
@@ -749,9 +764,23 @@ fn upsert_dynamic_tool_state(
 
 fn add_thread_token_usage(
     notification: ThreadTokenUsageUpdatedNotification,
+    state: &mut LogState,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
 ) {
+    let token_usage = &notification.token_usage;
+    let usage = state.execution_token_usage(
+        codex_token_usage(
+            token_usage.total.input_tokens,
+            token_usage.total.cached_input_tokens,
+            token_usage.total.output_tokens,
+        ),
+        &codex_token_usage(
+            token_usage.last.input_tokens,
+            token_usage.last.cached_input_tokens,
+            token_usage.last.output_tokens,
+        ),
+    );
     add_normalized_entry(
         msg_store,
         entry_index,
@@ -763,6 +792,7 @@ fn add_thread_token_usage(
                     .token_usage
                     .model_context_window
                     .unwrap_or_default() as u32,
+                usage: Some(usage),
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -777,6 +807,21 @@ fn add_thread_token_usage(
     );
 }
 
+/// Codex counts cached input as part of the input tokens
+fn codex_token_usage(
+    input_tokens: i64,
+    cached_input_tokens: i64,
+    output_tokens: i64,
+) -> TokenUsage {
+    TokenUsage {
+        model: None,
+        input_tokens: (input_tokens - cached_input_tokens).max(0) as u64,
+        output_tokens: output_tokens.max(0) as u64,
+        cache_read_tokens: cached_input_tokens.max(0) as u64,
+        cache_write_tokens: 0,
+    }
+}
+
 trait QuestionLike {
     fn question(&self) -> &str;
     fn header(&self) -> &str;
@@ -1309,7 +1354,7 @@ fn handle_direct_notification(
             true
         }
         ServerNotification::ThreadTokenUsageUpdated(notification) => {
-            add_thread_token_usage(notification, msg_store, entry_index);
+            add_thread_token_usage(notification, state, msg_store, entry_index);
             true
         }
         ServerNotification::AgentMessageDelta(notification) => {
@@ -1578,6 +1623,11 @@ pub fn normalize_logs(
                             add_normalized_entry(&msg_store, &entry_index, entry);
                         }
                     }
+                    Approval::PolicyDecision { .. } => {
+                        if let Some(entry) = approval.to_normalized_entry_opt() {
+                            add_normalized_entry(&msg_store, &entry_index, entry);
+                        }
+                    }
                 }
                 continue;
             }
@@ -2278,6 +2328,18 @@ pub fn normalize_logs(
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        let usage = state.execution_token_usage(
+                            codex_token_usage(
+                                info.total_token_usage.input_tokens,
+                                info.total_token_usage.cached_input_tokens,
+                                info.total_token_usage.output_tokens,
+                            ),
+                            &codex_token_usage(
+                                info.last_token_usage.input_tokens,
+                                info.last_token_usage.cached_input_tokens,
+                                info.last_token_usage.output_tokens,
+                            ),
+                        );
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -2290,6 +2352,7 @@ pub fn normalize_logs(
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        usage: Some(usage),
                                     },
                                 ),
                                 content: format!(
@@ -2667,6 +2730,13 @@ pub enum Approval {
         call_id: String,
         question_status: QuestionStatus,
     },
+    /// Logged instead of `ApprovalRequested` when an approval rule settled
+    /// the request.
+    PolicyDecision {
+        call_id: String,
+        tool_name: String,
+        decision: PolicyDecision,
+    },
 }
 
 impl Approval {
@@ -2690,6 +2760,14 @@ impl Approval {
         }
     }
 
+    pub fn policy_decision(call_id: String, tool_name: String, decision: PolicyDecision) -> Self {
+        Self::PolicyDecision {
+            call_id,
+            tool_name,
+            decision,
+        }
+    }
+
     pub fn question_response(call_id: String, question_status: QuestionStatus) -> Self {
         Self::QuestionResponse {
             call_id,
@@ -2704,7 +2782,8 @@ impl Approval {
     pub fn display_tool_name(&self) -> String {
         match self {
             Self::ApprovalRequested { tool_name, .. }
-            | Self::ApprovalResponse { tool_name, .. } => match tool_name.as_str() {
+            | Self::ApprovalResponse { tool_name, .. }
+            | Self::PolicyDecision { tool_name, .. } => match tool_name.as_str() {
                 "codex.exec_command" => "Exec Command".to_string(),
                 "codex.apply_patch" => "Edit".to_string(),
                 "codex.question" => "Question".to_string(),
@@ -2750,6 +2829,7 @@ impl ToNormalizedEntryOpt for Approval {
                     QuestionStatus::TimedOut => None,
                 };
             }
+            Self::PolicyDecision { decision, .. } => return Some(decision.to_normalized_entry()),
             Self::ApprovalRequested { .. } => return None,
         };
         let tool_name = self.display_tool_name();
@@ -2873,6 +2953,30 @@ mod tests {
         .to_string()
     }
 
+    #[test]
+    fn execution_token_usage_excludes_earlier_executions_of_the_thread() {
+        let mut state = LogState::new(EntryIndexProvider::test_new());
+        state.model_params.model = Some("gpt-5".to_string());
+
+        // resumed thread: 1000 input tokens were used before this execution
+        let first = state.execution_token_usage(
+            codex_token_usage(1200, 300, 90),
+            &codex_token_usage(200, 100, 40),
+        );
+        assert_eq!(first.input_tokens, 100);
+        assert_eq!(first.cache_read_tokens, 100);
+        assert_eq!(first.output_tokens, 40);
+        assert_eq!(first.model.as_deref(), Some("gpt-5"));
+
+        let second = state.execution_token_usage(
+            codex_token_usage(1500, 400, 150),
+            &codex_token_usage(300, 100, 60),
+        );
+        assert_eq!(second.input_tokens, 300);
+        assert_eq!(second.cache_read_tokens, 200);
+        assert_eq!(second.output_tokens, 100);
+    }
+
     #[tokio::test]
     async fn preserves_direct_command_denial_without_item_started() {
         let call_id = "cmd-1";
@@ -193,7 +193,9 @@ impl StandardCodingAgentExecutor for CursorAgent {
 
         let command_parts = self.build_command_builder()?.build_initial()?;
 
+        let env = env.clone().with_profile(&self.cmd);
         let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.sandboxed(executable_path, args).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -207,9 +209,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
@@ -234,7 +234,9 @@ impl StandardCodingAgentExecutor for CursorAgent {
         let command_parts = self
             .build_command_builder()?
             .build_follow_up(&["--resume".to_string(), session_id.to_string()])?;
+        let env = env.clone().with_profile(&self.cmd);
         let (executable_path, args) = command_parts.into_resolved().await?;
+        let (executable_path, args) = env.sandboxed(executable_path, args).await?;
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
@@ -248,9 +250,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
             .env("NPM_CONFIG_LOGLEVEL", "error")
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let mut child = command.group_spawn_no_window()?;
 
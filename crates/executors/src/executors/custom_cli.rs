@@ -0,0 +1,383 @@
+use std::{path::Path, process::Stdio, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use derivative::Derivative;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use workspace_utils::{command_ext::GroupSpawnNoWindowExt, msg_store::MsgStore};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+        claude::{ClaudeLogProcessor, HistoryStrategy},
+    },
+    logs::{
+        NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider,
+    },
+    profile::ExecutorConfig,
+};
+
+/// Placeholder in `follow_up_params` replaced with the session id to resume
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the agent's stdout is turned into conversation entries
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomCliOutputFormat")]
+pub enum OutputFormat {
+    /// Unstructured text, shown as assistant messages
+    #[default]
+    PlainText,
+    /// Agent Client Protocol over stdio; the prompt and session are sent over
+    /// the protocol
+    Acp,
+    /// JSON lines in Claude Code's `stream-json` format
+    StreamJson,
+}
+
+/// How the prompt is handed to the agent
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename = "CustomCliPromptInput")]
+pub enum PromptInput {
+    /// Written to stdin, which is then closed
+    #[default]
+    Stdin,
+    /// Appended as the last command line argument
+    Argument,
+}
+
+/// An agent CLI described entirely by its profile, so new agents can be used
+/// without adding an executor.
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct CustomCli {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[serde(default)]
+    #[schemars(
+        title = "Command",
+        description = "Command line that starts the agent, e.g. `my-agent --print`"
+    )]
+    pub command: String,
+    #[serde(default)]
+    #[schemars(
+        title = "Prompt Input",
+        description = "How the prompt is passed to the agent: written to stdin, or appended as the last argument"
+    )]
+    pub prompt_input: PromptInput,
+    #[serde(default)]
+    #[schemars(
+        title = "Output Format",
+        description = "How the agent's output is parsed: plain text, the Agent Client Protocol, or JSON lines in Claude Code's stream-json format"
+    )]
+    pub output_format: OutputFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Follow-up Parameters",
+        description = "Parameters appended to resume a session, e.g. [\"--resume\", \"{session_id}\"]. Without them follow-ups are not supported. Ignored for ACP."
+    )]
+    pub follow_up_params: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        title = "Session ID Regex",
+        description = "Regex matched against each output line to find the session id; the first capture group is used if there is one. Ignored for ACP."
+    )]
+    pub session_id_regex: Option<String>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl CustomCli {
+    fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+        apply_overrides(CommandBuilder::new(self.command.as_str()), &self.cmd)
+    }
+
+    fn follow_up_args(&self, session_id: &str) -> Result<Vec<String>, ExecutorError> {
+        let params = self.follow_up_params.as_ref().ok_or_else(|| {
+            ExecutorError::FollowUpNotSupported(
+                "custom CLI profile has no follow_up_params".to_string(),
+            )
+        })?;
+        Ok(params
+            .iter()
+            .map(|param| param.replace(SESSION_ID_PLACEHOLDER, session_id))
+            .collect())
+    }
+
+    async fn spawn_cli(
+        &self,
+        command_parts: CommandParts,
+        prompt: &str,
+        current_dir: &Path,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let env = env.clone().with_profile(&self.cmd);
+        let (program_path, mut args) = command_parts.into_resolved().await?;
+        let combined_prompt = self.append_prompt.combine_prompt(prompt);
+        if self.prompt_input == PromptInput::Argument {
+            args.push(combined_prompt.clone());
+        }
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
+
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        env.apply_to_command(&mut command);
+
+        let mut child = command.group_spawn_no_window()?;
+
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            if self.prompt_input == PromptInput::Stdin {
+                stdin.write_all(combined_prompt.as_bytes()).await?;
+            }
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+
+    fn session_id_regex(&self) -> Option<Regex> {
+        let pattern = self.session_id_regex.as_deref()?;
+        match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid custom CLI session_id_regex '{pattern}': {e}");
+                None
+            }
+        }
+    }
+}
+
+/// The session id in `line`: the first capture group, or the whole match if
+/// the regex has no groups.
+fn extract_session_id(regex: &Regex, line: &str) -> Option<String> {
+    let captures = regex.captures(line)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|m| m.as_str().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Push the first session id found in stdout to the store.
+fn extract_session_id_from_stdout(
+    msg_store: Arc<MsgStore>,
+    regex: Regex,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = msg_store.stdout_lines_stream();
+        while let Some(Ok(line)) = lines.next().await {
+            let line = strip_ansi_escapes::strip_str(&line);
+            if let Some(session_id) = extract_session_id(&regex, &line) {
+                msg_store.push_session_id(session_id);
+                break;
+            }
+        }
+    })
+}
+
+fn normalize_plain_text_stdout(
+    msg_store: Arc<MsgStore>,
+    entry_index_provider: EntryIndexProvider,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut stdout = msg_store.stdout_chunked_stream();
+        let mut processor = PlainTextLogProcessor::builder()
+            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::AssistantMessage,
+                content: strip_ansi_escapes::strip_str(&content),
+                metadata: None,
+            }))
+            .time_gap(Duration::from_secs(2))
+            .index_provider(entry_index_provider)
+            .build();
+
+        while let Some(Ok(chunk)) = stdout.next().await {
+            for patch in processor.process(chunk) {
+                msg_store.push_patch(patch);
+            }
+        }
+    })
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for CustomCli {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command_builder()?.build_initial()?;
+        if self.output_format == OutputFormat::Acp {
+            return AcpAgentHarness::new()
+                .spawn_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        self.spawn_cli(command_parts, prompt, current_dir, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        _reset_to_message_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        if self.output_format == OutputFormat::Acp {
+            let command_parts = self.build_command_builder()?.build_follow_up(&[])?;
+            return AcpAgentHarness::new()
+                .spawn_follow_up_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    session_id,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+
+        let command_parts = self
+            .build_command_builder()?
+            .build_follow_up(&self.follow_up_args(session_id)?)?;
+        self.spawn_cli(command_parts, prompt, current_dir, env)
+            .await
+    }
+
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        current_dir: &Path,
+    ) -> Vec<tokio::task::JoinHandle<()>> {
+        if self.output_format == OutputFormat::Acp {
+            return super::acp::normalize_logs(msg_store, current_dir);
+        }
+
+        let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
+        let mut handles = vec![
+            match self.output_format {
+                OutputFormat::StreamJson => ClaudeLogProcessor::process_logs(
+                    msg_store.clone(),
+                    current_dir,
+                    entry_index_provider.clone(),
+                    HistoryStrategy::Default,
+                ),
+                _ => normalize_plain_text_stdout(msg_store.clone(), entry_index_provider.clone()),
+            },
+            normalize_stderr_logs(msg_store.clone(), entry_index_provider),
+        ];
+        if let Some(regex) = self.session_id_regex() {
+            handles.push(extract_session_id_from_stdout(msg_store, regex));
+        }
+        handles
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_preset_options(&self) -> ExecutorConfig {
+        ExecutorConfig {
+            executor: BaseCodingAgent::CustomCli,
+            variant: None,
+            model_id: None,
+            agent_id: None,
+            reasoning_id: None,
+            permission_policy: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_cli(json: serde_json::Value) -> CustomCli {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn session_id_uses_first_capture_group_or_whole_match() {
+        let grouped = Regex::new(r"session: ([0-9a-f-]+)").unwrap();
+        assert_eq!(
+            extract_session_id(&grouped, "started session: 1a2b-3c"),
+            Some("1a2b-3c".to_string())
+        );
+        assert_eq!(extract_session_id(&grouped, "no session here"), None);
+
+        let whole = Regex::new(r"ses_[0-9a-z]+").unwrap();
+        assert_eq!(
+            extract_session_id(&whole, "resume with ses_42abc"),
+            Some("ses_42abc".to_string())
+        );
+    }
+
+    #[test]
+    fn follow_up_params_substitute_session_id() {
+        let cli = custom_cli(serde_json::json!({
+            "command": "my-agent --print",
+            "follow_up_params": ["--resume", "{session_id}", "--tag=s-{session_id}"]
+        }));
+        assert_eq!(
+            cli.follow_up_args("abc").unwrap(),
+            vec!["--resume", "abc", "--tag=s-abc"]
+        );
+    }
+
+    #[test]
+    fn follow_up_without_params_is_unsupported() {
+        let cli = custom_cli(serde_json::json!({ "command": "my-agent" }));
+        assert_eq!(cli.output_format, OutputFormat::PlainText);
+        assert_eq!(cli.prompt_input, PromptInput::Stdin);
+        assert!(matches!(
+            cli.follow_up_args("abc"),
+            Err(ExecutorError::FollowUpNotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn empty_command_is_rejected() {
+        let cli = custom_cli(serde_json::json!({}));
+        assert!(matches!(
+            cli.build_command_builder().unwrap().build_initial(),
+            Err(CommandBuildError::EmptyCommand)
+        ));
+    }
+}
@@ -114,7 +114,9 @@ async fn spawn_droid(
     env: &ExecutionEnv,
     cmd_overrides: &crate::command::CmdOverrides,
 ) -> Result<SpawnedChild, ExecutorError> {
+    let env = env.clone().with_profile(cmd_overrides);
     let (program_path, args) = command_parts.into_resolved().await?;
+    let (program_path, args) = env.sandboxed(program_path, args).await?;
 
     let mut command = Command::new(program_path);
     command
@@ -126,9 +128,7 @@ async fn spawn_droid(
         .env("NPM_CONFIG_LOGLEVEL", "error")
         .args(args);
 
-    env.clone()
-        .with_profile(cmd_overrides)
-        .apply_to_command(&mut command);
+    env.apply_to_command(&mut command);
 
     let mut child = command.group_spawn_no_window()?;
 
@@ -19,7 +19,7 @@ use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CmdOverrides, CommandBuildError},
     env::ExecutionEnv,
     executors::{
         amp::Amp,
@@ -27,6 +27,7 @@ use crate::{
         codex::Codex,
         copilot::Copilot,
         cursor::CursorAgent,
+        custom_cli::CustomCli,
         droid::Droid,
         gemini::Gemini,
         opencode::Opencode,
@@ -43,6 +44,7 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom_cli;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -96,6 +98,8 @@ pub enum ExecutorError {
     SetupHelperNotSupported,
     #[error("Auth required: {0}")]
     AuthRequired(String),
+    #[error("Sandbox unavailable: {0}")]
+    Sandbox(String),
 }
 
 #[enum_dispatch]
@@ -126,6 +130,7 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    CustomCli,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -177,6 +182,24 @@ impl CodingAgent {
         }
     }
 
+    /// The profile's command overrides; `None` for executors without any
+    pub fn cmd_overrides(&self) -> Option<&CmdOverrides> {
+        match self {
+            Self::ClaudeCode(agent) => Some(&agent.cmd),
+            Self::Amp(agent) => Some(&agent.cmd),
+            Self::Gemini(agent) => Some(&agent.cmd),
+            Self::Codex(agent) => Some(&agent.cmd),
+            Self::Opencode(agent) => Some(&agent.cmd),
+            Self::CursorAgent(agent) => Some(&agent.cmd),
+            Self::QwenCode(agent) => Some(&agent.cmd),
+            Self::Copilot(agent) => Some(&agent.cmd),
+            Self::Droid(agent) => Some(&agent.cmd),
+            Self::CustomCli(agent) => Some(&agent.cmd),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => None,
+        }
+    }
+
     pub fn supports_mcp(&self) -> bool {
         self.default_mcp_config_path().is_some()
     }
@@ -200,7 +223,7 @@ impl CodingAgent {
                 vec![BaseAgentCapability::SessionFork]
             }
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Amp(_) | Self::Copilot(_) | Self::Droid(_) => vec![],
+            Self::Amp(_) | Self::Copilot(_) | Self::Droid(_) | Self::CustomCli(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
@@ -275,6 +298,15 @@ pub trait StandardCodingAgentExecutor {
     // MCP configuration methods
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf>;
 
+    /// Config and state directories the agent writes to, kept writable when it
+    /// runs in the sandbox. Defaults to the directory of its MCP config.
+    fn sandbox_writable_paths(&self) -> Vec<std::path::PathBuf> {
+        self.default_mcp_config_path()
+            .and_then(|config_path| config_path.parent().map(std::path::Path::to_path_buf))
+            .into_iter()
+            .collect()
+    }
+
     async fn get_setup_helper_action(&self) -> Result<ExecutorAction, ExecutorError> {
         Err(ExecutorError::SetupHelperNotSupported)
     }
@@ -108,7 +108,9 @@ impl Opencode {
         env: &ExecutionEnv,
     ) -> Result<(AsyncGroupChild, ServerPassword), ExecutorError> {
         let command_parts = self.build_command_builder()?.build_initial()?;
+        let env = env.clone().with_profile(&self.cmd);
         let (program_path, args) = command_parts.into_resolved().await?;
+        let (program_path, args) = env.sandboxed(program_path, args).await?;
 
         let server_password = generate_server_password();
 
@@ -126,9 +128,7 @@ impl Opencode {
             .env("OPENCODE_SERVER_PASSWORD", &server_password)
             .args(&args);
 
-        env.clone()
-            .with_profile(&self.cmd)
-            .apply_to_command(&mut command);
+        env.apply_to_command(&mut command);
 
         let child = command.group_spawn_no_window()?;
 
@@ -471,6 +471,22 @@ impl StandardCodingAgentExecutor for Opencode {
         }
     }
 
+    fn sandbox_writable_paths(&self) -> Vec<std::path::PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        vec![
+            std::env::var("XDG_CONFIG_HOME")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|_| home.join(".config"))
+                .join("opencode"),
+            std::env::var("XDG_DATA_HOME")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|_| home.join(".local").join("share"))
+                .join("opencode"),
+        ]
+    }
+
     fn get_availability_info(&self) -> AvailabilityInfo {
         let mcp_config_found = self
             .default_mcp_config_path()
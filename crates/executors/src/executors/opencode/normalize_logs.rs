@@ -22,7 +22,7 @@ use crate::{
     logs::{
         ActionType, AnsweredQuestion, AskUserQuestionItem, AskUserQuestionOption,
         CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry, NormalizedEntryError,
-        NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult, ToolStatus,
+        NormalizedEntryType, TodoItem, TokenUsage, TokenUsageInfo, ToolResult, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -52,6 +52,8 @@ pub(super) fn normalize_logs(
     let h2 = tokio::spawn(async move {
         let mut stored_session_id = false;
         let mut state = LogState::new(entry_index.clone(), msg_store.clone());
+        // Latest counts of each assistant message, summed for the execution
+        let mut message_usage: HashMap<String, TokenUsage> = HashMap::new();
 
         let mut stdout_lines = msg_store.stdout_lines_stream();
         while let Some(Ok(line)) = stdout_lines.next().await {
@@ -83,7 +85,22 @@ pub(super) fn normalize_logs(
                 OpencodeExecutorEvent::TokenUsage {
                     total_tokens,
                     model_context_window,
+                    message_id,
+                    usage,
                 } => {
+                    let usage = match (message_id, usage) {
+                        (Some(message_id), Some(usage)) => {
+                            message_usage.insert(message_id, usage);
+                            Some(message_usage.values().fold(
+                                TokenUsage::default(),
+                                |mut total, usage| {
+                                    total.add(usage);
+                                    total
+                                },
+                            ))
+                        }
+                        _ => None,
+                    };
                     add_normalized_entry(
                         &msg_store,
                         &entry_index,
@@ -92,6 +109,7 @@ pub(super) fn normalize_logs(
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                usage,
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -1523,7 +1541,7 @@ fn extract_diff_from_metadata(metadata: &Value) -> Option<&str> {
     })
 }
 
-fn extract_file_path_from_permission_metadata(metadata: &Value) -> Option<&str> {
+pub(super) fn extract_file_path_from_permission_metadata(metadata: &Value) -> Option<&str> {
     let candidate = metadata
         .get("filePath")
         .and_then(Value::as_str)
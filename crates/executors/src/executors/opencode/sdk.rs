@@ -21,13 +21,15 @@ use tokio_util::sync::CancellationToken;
 use workspace_utils::approvals::{ApprovalStatus, QuestionAnswer, QuestionStatus};
 
 use super::{
+    normalize_logs::extract_file_path_from_permission_metadata,
     slash_commands,
-    types::{OpencodeExecutorEvent, ProviderInfo, ProviderListResponse},
+    types::{OpencodeExecutorEvent, PermissionAskedEvent, ProviderInfo, ProviderListResponse},
 };
 use crate::{
-    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    approvals::{ExecutorApprovalError, ExecutorApprovalService, ToolApproval},
     env::RepoContext,
     executors::{ExecutorError, opencode::models::maybe_emit_token_usage},
+    logs::{ActionType, CommandCategory},
 };
 
 #[derive(Clone)]
@@ -1496,6 +1498,12 @@ async fn process_event_stream(
                     .and_then(Value::as_str)
                     .unwrap_or("tool")
                     .to_string();
+                let action = data
+                    .get("properties")
+                    .and_then(|properties| {
+                        serde_json::from_value::<PermissionAskedEvent>(properties.clone()).ok()
+                    })
+                    .and_then(|event| permission_action(&event));
 
                 let approvals = ctx.approvals.clone();
                 let client = ctx.client.clone();
@@ -1510,6 +1518,7 @@ async fn process_event_stream(
                         auto_approve,
                         approvals.clone(),
                         &permission,
+                        action.as_ref(),
                     )
                     .await
                     {
@@ -1545,12 +1554,16 @@ async fn process_event_stream(
                         }
                     };
 
-                    let _ = log_writer
-                        .log_event(&OpencodeExecutorEvent::ApprovalRequested {
+                    let event = match &created.policy_decision {
+                        Some(decision) => OpencodeExecutorEvent::SystemMessage {
+                            content: decision.reason.clone(),
+                        },
+                        None => OpencodeExecutorEvent::ApprovalRequested {
                             tool_call_id: tool_call_id.clone(),
                             approval_id: created.approval_id.clone(),
-                        })
-                        .await;
+                        },
+                    };
+                    let _ = log_writer.log_event(&event).await;
 
                     let status =
                         match wait_permission_approval(approvals, &created.approval_id, cancel)
@@ -1714,7 +1727,8 @@ async fn create_permission_approval(
     auto_approve: bool,
     approvals: Option<Arc<dyn ExecutorApprovalService>>,
     tool_name: &str,
-) -> Result<Option<ApprovalCreated>, ExecutorApprovalError> {
+    action: Option<&ActionType>,
+) -> Result<Option<ToolApproval>, ExecutorApprovalError> {
     if auto_approve {
         return Ok(None);
     }
@@ -1723,8 +1737,9 @@ async fn create_permission_approval(
         return Ok(None);
     };
 
-    match approvals.create_tool_approval(tool_name).await {
-        Ok(approval_id) => Ok(Some(ApprovalCreated { approval_id })),
+    let actions = action.map(std::slice::from_ref).unwrap_or_default();
+    match approvals.create_tool_approval(tool_name, actions).await {
+        Ok(approval) => Ok(Some(approval)),
         Err(
             ExecutorApprovalError::ServiceUnavailable | ExecutorApprovalError::SessionNotRegistered,
         ) => Ok(None),
@@ -1732,6 +1747,38 @@ async fn create_permission_approval(
     }
 }
 
+/// The command or file a permission request acts on, for matching approval rules.
+fn permission_action(event: &PermissionAskedEvent) -> Option<ActionType> {
+    let pattern = event
+        .patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .find(|pattern| !pattern.is_empty());
+    match event.permission.as_str() {
+        "bash" => {
+            let command = event
+                .metadata
+                .get("command")
+                .and_then(Value::as_str)
+                .or(pattern)?
+                .to_string();
+            Some(ActionType::CommandRun {
+                category: CommandCategory::from_command(&command),
+                command,
+                result: None,
+            })
+        }
+        "edit" => {
+            let path = extract_file_path_from_permission_metadata(&event.metadata).or(pattern)?;
+            Some(ActionType::FileEdit {
+                path: path.to_string(),
+                changes: vec![],
+            })
+        }
+        _ => None,
+    }
+}
+
 async fn wait_permission_approval(
     approvals: Option<Arc<dyn ExecutorApprovalService>>,
     approval_id: &str,
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use workspace_utils::approvals::{ApprovalStatus, QuestionStatus};
 
+use crate::logs::TokenUsage;
+
 /// JSON log events emitted by the OpenCode SDK executor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -23,6 +25,12 @@ pub(super) enum OpencodeExecutorEvent {
     TokenUsage {
         total_tokens: u32,
         model_context_window: u32,
+        /// Assistant message the counts belong to; OpenCode re-reports a
+        /// message's counts as it grows
+        #[serde(default)]
+        message_id: Option<String>,
+        #[serde(default)]
+        usage: Option<TokenUsage>,
     },
     ApprovalRequested {
         tool_call_id: String,
@@ -173,6 +181,8 @@ pub(super) struct MessageTokens {
 pub(super) struct MessageTokensCache {
     #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
     pub(super) read: u32,
+    #[serde(default, deserialize_with = "deserialize_f64_as_u32")]
+    pub(super) write: u32,
 }
 
 fn deserialize_f64_as_u32<'de, D>(deserializer: D) -> Result<u32, D::Error>
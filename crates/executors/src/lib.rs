@@ -8,4 +8,6 @@ pub mod logs;
 pub mod mcp_config;
 pub mod model_selector;
 pub mod profile;
+pub mod resource_limits;
+pub mod sandbox;
 pub mod stdout_dup;
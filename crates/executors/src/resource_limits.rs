@@ -0,0 +1,105 @@
+//! CPU, memory and process limits for an execution. Repos and executor
+//! profiles set them; the deployment enforces them around the spawned process.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+pub struct ResourceLimits {
+    #[schemars(
+        title = "CPU Limit",
+        description = "CPUs the executor and its child processes may use together, e.g. 1.5"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    #[schemars(
+        title = "Memory Limit (MiB)",
+        description = "Memory the executor and its child processes may use together"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    #[schemars(
+        title = "Process Limit",
+        description = "Processes and threads the executor may run at once"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pids: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.cpus.is_none() && self.memory_mb.is_none() && self.pids.is_none()
+    }
+
+    /// The stricter of each limit set in either
+    pub fn tightest(self, other: Self) -> Self {
+        fn min<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            cpus: min(self.cpus, other.cpus),
+            memory_mb: min(self.memory_mb, other.memory_mb),
+            pids: min(self.pids, other.pids),
+        }
+    }
+}
+
+/// Have `command` move its process into the cgroup at `cgroup` between fork
+/// and exec, so nothing it starts runs outside the limits
+#[cfg(target_os = "linux")]
+pub fn join_cgroup_before_exec(command: &mut tokio::process::Command, cgroup: &std::path::Path) {
+    use std::{ffi::CString, io, os::unix::ffi::OsStrExt};
+
+    let procs = CString::new(cgroup.join("cgroup.procs").as_os_str().as_bytes()).ok();
+    // SAFETY: the hook makes only async-signal-safe libc calls and doesn't
+    // allocate
+    unsafe {
+        command.pre_exec(move || {
+            let Some(procs) = &procs else {
+                return Err(io::Error::from_raw_os_error(libc::EINVAL));
+            };
+            let fd = libc::open(procs.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // Writing 0 moves the writing process
+            let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+            let error = io::Error::last_os_error();
+            libc::close(fd);
+            if written < 0 { Err(error) } else { Ok(()) }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tightest_keeps_the_stricter_of_each_limit() {
+        let repo = ResourceLimits {
+            cpus: Some(2.0),
+            memory_mb: Some(4096),
+            pids: None,
+        };
+        let profile = ResourceLimits {
+            cpus: Some(0.5),
+            memory_mb: None,
+            pids: Some(512),
+        };
+
+        assert_eq!(
+            repo.tightest(profile),
+            ResourceLimits {
+                cpus: Some(0.5),
+                memory_mb: Some(4096),
+                pids: Some(512),
+            }
+        );
+        assert!(ResourceLimits::default().is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+//! Optional jail for coding agents and repository scripts on Linux.
+//!
+//! Sandboxed processes are started through bubblewrap (`bwrap`) in their own
+//! user, PID, IPC and UTS namespaces. The whole filesystem is mounted
+//! read-only with a private `/tmp`, and only the workspace, the coding agent's
+//! config directories and declared extra paths are bound writable. Denying
+//! network access also gives the process an empty network namespace.
+//!
+//! The host runs git in the same repositories outside the sandbox, so the
+//! files that make git run programs (hooks and config) stay read-only even
+//! inside writable git directories.
+
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use workspace_utils::{path::expand_tilde, shell::resolve_executable_path};
+
+use crate::executors::ExecutorError;
+
+/// Directories under the home directory that package managers started by
+/// agents and scripts write to
+const TOOL_CACHE_DIRS: &[&str] = &[".npm", ".cache", ".cargo/registry", ".cargo/git"];
+
+/// Whether sandboxed processes may reach the network
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SandboxNetwork {
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// Sandbox settings of the user config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SandboxConfig {
+    /// Run coding agents and repository scripts in the sandbox
+    #[serde(default)]
+    pub enabled: bool,
+    /// Network access of sandboxed processes, unless their profile overrides it
+    #[serde(default)]
+    pub network: SandboxNetwork,
+    /// Paths besides the workspace that sandboxed processes may write to
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+}
+
+/// Sandbox settings of an executor profile, applied when the sandbox is enabled
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+pub struct SandboxOverrides {
+    #[schemars(
+        title = "Sandbox Network",
+        description = "Allow or deny network access when running in the sandbox"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<SandboxNetwork>,
+    #[schemars(
+        title = "Sandbox Writable Paths",
+        description = "Extra paths the executor may write to when running in the sandbox"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub writable_paths: Option<Vec<String>>,
+}
+
+/// The sandbox a process is started in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandbox {
+    pub network: SandboxNetwork,
+    /// Paths bound writable, when they exist
+    pub writable_paths: Vec<PathBuf>,
+    /// Paths inside writable paths bound read-only again, when they exist
+    pub read_only_paths: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// The sandbox for a workspace's processes, or `None` when it's disabled.
+    /// `writable_paths` are the workspace and any other paths its processes
+    /// must write to; git directories are added with [`Sandbox::add_git_dir`].
+    pub fn from_config(config: &SandboxConfig, writable_paths: Vec<PathBuf>) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let mut sandbox = Self {
+            network: config.network,
+            writable_paths: Vec::new(),
+            read_only_paths: Vec::new(),
+        };
+        sandbox.add_paths(writable_paths);
+        sandbox.add_paths(config.writable_paths.iter().map(|p| expand_tilde(p)));
+        if let Some(home) = dirs::home_dir() {
+            sandbox.add_paths(TOOL_CACHE_DIRS.iter().map(|dir| home.join(dir)));
+        }
+        Some(sandbox)
+    }
+
+    pub fn add_paths(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths {
+            if !self.writable_paths.contains(&path) {
+                self.writable_paths.push(path);
+            }
+        }
+    }
+
+    /// Keep `paths` read-only even when they are inside a writable path
+    pub fn add_read_only_paths(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths {
+            if !self.read_only_paths.contains(&path) {
+                self.read_only_paths.push(path);
+            }
+        }
+    }
+
+    /// Make a repository's common git directory writable, so worktrees can
+    /// commit, except for its hooks and config. The hooks directory is created
+    /// when missing, since a hook planted in a new one would run on the host.
+    pub fn add_git_dir(&mut self, common_dir: &Path) {
+        let hooks_dir = common_dir.join("hooks");
+        if let Err(err) = std::fs::create_dir_all(&hooks_dir) {
+            tracing::warn!("Failed to create {}: {err}", hooks_dir.display());
+        }
+        self.add_paths([common_dir.to_path_buf()]);
+        self.add_read_only_paths([hooks_dir, common_dir.join("config")]);
+    }
+
+    /// Apply an executor profile's sandbox settings
+    pub fn apply_overrides(&mut self, overrides: &SandboxOverrides) {
+        if let Some(network) = overrides.network {
+            self.network = network;
+        }
+        if let Some(paths) = &overrides.writable_paths {
+            self.add_paths(paths.iter().map(|p| expand_tilde(p)));
+        }
+    }
+
+    /// The program and arguments that run `program` with `args` in the
+    /// sandbox. Fails rather than running unsandboxed when bubblewrap isn't
+    /// available.
+    pub async fn wrap(
+        &self,
+        program: PathBuf,
+        args: Vec<String>,
+    ) -> Result<(PathBuf, Vec<String>), ExecutorError> {
+        if !cfg!(target_os = "linux") {
+            return Err(ExecutorError::Sandbox(
+                "the sandbox is only supported on Linux".to_string(),
+            ));
+        }
+        let bwrap = resolve_executable_path("bwrap").await.ok_or_else(|| {
+            ExecutorError::Sandbox("bubblewrap (`bwrap`) is not installed".to_string())
+        })?;
+        Ok((bwrap, self.bwrap_args(&program, args)))
+    }
+
+    fn bwrap_args(&self, program: &Path, args: Vec<String>) -> Vec<String> {
+        // No `--new-session`: the process must stay in the group that stopping
+        // an execution kills
+        let mut bwrap = vec!["--die-with-parent", "--unshare-all"];
+        if self.network == SandboxNetwork::Allow {
+            bwrap.push("--share-net");
+        }
+        bwrap.extend([
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+            "--tmpfs",
+            "/tmp",
+        ]);
+        let mut bwrap: Vec<String> = bwrap.into_iter().map(str::to_string).collect();
+        let mut writable: Vec<&Path> = Vec::new();
+        for path in self.writable_paths.iter().filter(|p| p.exists()) {
+            // Tools replace files by renaming over them, which fails on a file
+            // bind mount, so a file makes its directory writable
+            let path = match path.parent() {
+                Some(dir) if path.is_file() => dir,
+                _ => path.as_path(),
+            };
+            if !writable.contains(&path) {
+                writable.push(path);
+            }
+        }
+        for path in writable {
+            let path = path.to_string_lossy().into_owned();
+            bwrap.extend(["--bind".to_string(), path.clone(), path]);
+        }
+        // Later binds shadow earlier ones, so these go after the writable binds
+        for path in self.read_only_paths.iter().filter(|p| p.exists()) {
+            let path = path.to_string_lossy().into_owned();
+            bwrap.extend(["--ro-bind".to_string(), path.clone(), path]);
+        }
+        bwrap.push("--".to_string());
+        bwrap.push(program.to_string_lossy().into_owned());
+        bwrap.extend(args);
+        bwrap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_existing_writable_paths_and_drops_network_when_denied() {
+        let workspace = std::env::temp_dir();
+        let workspace_path = workspace.to_string_lossy().into_owned();
+        let mut sandbox = Sandbox::from_config(
+            &SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            vec![workspace.clone(), PathBuf::from("/missing/dir")],
+        )
+        .unwrap();
+
+        let args = sandbox.bwrap_args(Path::new("/usr/bin/node"), vec!["agent.js".to_string()]);
+        assert!(args.contains(&"--share-net".to_string()));
+        assert!(
+            args.windows(3)
+                .any(|w| w == ["--bind", workspace_path.as_str(), workspace_path.as_str()])
+        );
+        assert!(!args.iter().any(|a| a == "/missing/dir"));
+        assert_eq!(args[args.len() - 3..], ["--", "/usr/bin/node", "agent.js"]);
+
+        sandbox.apply_overrides(&SandboxOverrides {
+            network: Some(SandboxNetwork::Deny),
+            writable_paths: None,
+        });
+        let args = sandbox.bwrap_args(Path::new("/usr/bin/node"), Vec::new());
+        assert!(!args.contains(&"--share-net".to_string()));
+    }
+
+    #[test]
+    fn keeps_git_hooks_and_config_read_only() {
+        let common_dir = tempfile::tempdir().unwrap();
+        std::fs::write(common_dir.path().join("config"), "[core]\n").unwrap();
+        let mut sandbox = Sandbox::from_config(
+            &SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+        sandbox.add_git_dir(common_dir.path());
+
+        let bind = |flag: &str, path: PathBuf| {
+            let path = path.to_string_lossy().into_owned();
+            [flag.to_string(), path.clone(), path]
+        };
+        let args = sandbox.bwrap_args(Path::new("/usr/bin/node"), Vec::new());
+        let position = |expected: [String; 3]| args.windows(3).position(|w| w == expected);
+        let writable = position(bind("--bind", common_dir.path().to_path_buf())).unwrap();
+        let hooks = position(bind("--ro-bind", common_dir.path().join("hooks"))).unwrap();
+        let config = position(bind("--ro-bind", common_dir.path().join("config"))).unwrap();
+        assert!(common_dir.path().join("hooks").is_dir());
+        assert!(writable < hooks && writable < config);
+    }
+
+    #[test]
+    fn binds_the_directory_of_a_writable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("settings.json");
+        std::fs::write(&file, "{}").unwrap();
+        let sandbox = Sandbox::from_config(
+            &SandboxConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            vec![file.clone()],
+        )
+        .unwrap();
+
+        let args = sandbox.bwrap_args(Path::new("/usr/bin/node"), Vec::new());
+        let dir_path = dir.path().to_string_lossy().into_owned();
+        assert!(
+            args.windows(3)
+                .any(|w| w == ["--bind", dir_path.as_str(), dir_path.as_str()])
+        );
+        assert!(!args.contains(&file.to_string_lossy().into_owned()));
+    }
+
+    #[test]
+    fn disabled_config_has_no_sandbox() {
+        assert!(Sandbox::from_config(&SandboxConfig::default(), Vec::new()).is_none());
+    }
+}
@@ -256,6 +256,40 @@ impl GitHostProvider for AzureDevOpsProvider {
         Err(GitHostError::UnsupportedProvider)
     }
 
+    async fn get_open_review_threads(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError> {
+        // Comment ids are only unique within their thread, see
+        // `reply_to_review_comment`
+        Ok(None)
+    }
+
+    async fn update_pr_base(
+        &self,
+        _pr_url: &str,
+        _base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        // `az repos pr update` cannot change the target branch
+        Err(GitHostError::UnsupportedProvider)
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _pr_number: i64,
+        _comment_id: i64,
+        _body: &str,
+        _resolve: bool,
+    ) -> Result<bool, GitHostError> {
+        // Azure DevOps comment ids are only unique within their thread, so a
+        // comment id alone does not identify the thread to reply in
+        Err(GitHostError::UnsupportedProvider)
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
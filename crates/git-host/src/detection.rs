@@ -1,6 +1,80 @@
 //! Git hosting provider detection from repository URLs.
 
-use crate::types::ProviderKind;
+use crate::types::{GitHostConfig, ProviderKind};
+
+/// Where a repository lives, parsed from a remote or pull request URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RemoteRepo {
+    /// Host name without port, lowercased
+    pub host: String,
+    /// Port given in an HTTP(S) URL; SSH ports don't say where the web UI is
+    pub port: Option<u16>,
+    /// Scheme, host and port of the web UI, e.g. `https://git.example.com`
+    pub web_url: String,
+    /// Path of the repository without `.git`, e.g. `group/subgroup/repo`
+    pub path: String,
+}
+
+/// Parse `https://host/path.git`, `ssh://git@host:22/path.git` or `git@host:path.git`
+pub(crate) fn parse_remote_url(url: &str) -> Option<RemoteRepo> {
+    let url = url.trim();
+    let (host, port, web_url, path) = if url.contains("://") {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_lowercase();
+        match parsed.scheme() {
+            "http" | "https" => {
+                let port = parsed.port();
+                let web_url = match port {
+                    Some(port) => format!("{}://{host}:{port}", parsed.scheme()),
+                    None => format!("{}://{host}", parsed.scheme()),
+                };
+                (host, port, web_url, parsed.path().to_string())
+            }
+            _ => {
+                let web_url = format!("https://{host}");
+                (host, None, web_url, parsed.path().to_string())
+            }
+        }
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        let host = authority.rsplit('@').next()?.to_lowercase();
+        let web_url = format!("https://{host}");
+        (host, None, web_url, path.to_string())
+    };
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(RemoteRepo {
+        host,
+        port,
+        web_url,
+        path: path.to_string(),
+    })
+}
+
+/// The configured host that `url` points at, matched by host name or `host:port`
+pub(crate) fn find_host_config<'a>(
+    url: &str,
+    hosts: &'a [GitHostConfig],
+) -> Option<&'a GitHostConfig> {
+    let remote = parse_remote_url(url)?;
+    let host_with_port = remote.port.map(|port| format!("{}:{port}", remote.host));
+    hosts.iter().find(|config| {
+        let configured = config.host.trim().to_lowercase();
+        configured == remote.host || Some(&configured) == host_with_port.as_ref()
+    })
+}
+
+/// Detect the provider of `url`, preferring the configured self-hosted hosts
+pub(crate) fn detect_provider(url: &str, hosts: &[GitHostConfig]) -> ProviderKind {
+    if let Some(config) = find_host_config(url, hosts) {
+        return config.provider;
+    }
+    detect_provider_from_url(url)
+}
 
 /// Detect the git hosting provider from a remote URL.
 ///
@@ -8,6 +82,10 @@ use crate::types::ProviderKind;
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - GitLab.com: `https://gitlab.com/group/repo`
+/// - Codeberg (Forgejo, served through the Gitea API): `https://codeberg.org/owner/repo`
+///
+/// Self-hosted GitLab and Gitea instances are only recognised through [`detect_provider`].
 pub(crate) fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
@@ -33,6 +111,14 @@ pub(crate) fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::GitHub;
     }
 
+    if url_lower.contains("gitlab.com") {
+        return ProviderKind::GitLab;
+    }
+
+    if url_lower.contains("codeberg.org") {
+        return ProviderKind::Gitea;
+    }
+
     ProviderKind::Unknown
 }
 
@@ -137,9 +223,21 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab_com_and_codeberg() {
         assert_eq!(
             detect_provider_from_url("https://gitlab.com/owner/repo"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@codeberg.org:owner/repo.git"),
+            ProviderKind::Gitea
+        );
+    }
+
+    #[test]
+    fn test_unknown_provider() {
+        assert_eq!(
+            detect_provider_from_url("https://git.example.com/owner/repo"),
             ProviderKind::Unknown
         );
         assert_eq!(
@@ -175,4 +273,74 @@ mod tests {
             ProviderKind::AzureDevOps
         );
     }
+
+    fn host(host: &str, provider: ProviderKind) -> GitHostConfig {
+        GitHostConfig {
+            host: host.to_string(),
+            provider,
+            api_url: None,
+            token: None,
+        }
+    }
+
+    #[test]
+    fn test_configured_self_hosted_hosts() {
+        let hosts = [
+            host("git.example.com", ProviderKind::GitLab),
+            host("forge.example.com:3000", ProviderKind::Gitea),
+        ];
+        assert_eq!(
+            detect_provider("https://git.example.com/group/sub/repo.git", &hosts),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider("git@GIT.example.com:group/repo.git", &hosts),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider("http://forge.example.com:3000/owner/repo", &hosts),
+            ProviderKind::Gitea
+        );
+        // The port is part of the configured host, so other ports don't match
+        assert_eq!(
+            detect_provider("http://forge.example.com/owner/repo", &hosts),
+            ProviderKind::Unknown
+        );
+        assert_eq!(
+            detect_provider("https://github.com/owner/repo", &hosts),
+            ProviderKind::GitHub
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        assert_eq!(
+            parse_remote_url("git@git.example.com:group/sub/repo.git"),
+            Some(RemoteRepo {
+                host: "git.example.com".to_string(),
+                port: None,
+                web_url: "https://git.example.com".to_string(),
+                path: "group/sub/repo".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@git.example.com:2222/owner/repo.git"),
+            Some(RemoteRepo {
+                host: "git.example.com".to_string(),
+                port: None,
+                web_url: "https://git.example.com".to_string(),
+                path: "owner/repo".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_remote_url("http://localhost:3000/owner/repo/"),
+            Some(RemoteRepo {
+                host: "localhost".to_string(),
+                port: Some(3000),
+                web_url: "http://localhost:3000".to_string(),
+                path: "owner/repo".to_string(),
+            })
+        );
+        assert_eq!(parse_remote_url("https://git.example.com/"), None);
+    }
 }
@@ -0,0 +1,291 @@
+//! Minimal client for the Gitea REST API (v1), which Forgejo serves as well.
+
+use chrono::{DateTime, Utc};
+use db::models::merge::MergeStatus;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    http::ApiClient,
+    types::{GitHostError, ProviderKind, PullRequestDetail, UnifiedPrComment},
+};
+
+/// Gitea's default maximum page size
+const PAGE_SIZE: u32 = 50;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GiteaApi {
+    client: ApiClient,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtBranch {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtPullRequest {
+    pub number: i64,
+    pub html_url: String,
+    pub title: String,
+    pub state: String,
+    #[serde(default)]
+    pub merged: bool,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub merge_commit_sha: Option<String>,
+    pub base: GtBranch,
+    pub head: GtBranch,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtUser {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtComment {
+    pub id: i64,
+    pub body: String,
+    pub user: GtUser,
+    pub created_at: DateTime<Utc>,
+    pub html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtReview {
+    pub id: i64,
+    #[serde(default)]
+    pub comments_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GtReviewComment {
+    pub id: i64,
+    pub body: String,
+    pub user: GtUser,
+    pub created_at: DateTime<Utc>,
+    pub html_url: Option<String>,
+    pub path: String,
+    pub diff_hunk: Option<String>,
+    /// Line in the new file; 0 when the comment is on a removed line
+    #[serde(default)]
+    pub position: i64,
+    /// Line in the old file
+    #[serde(default)]
+    pub original_position: i64,
+    /// Who resolved the comment's conversation, if anyone did
+    #[serde(default)]
+    pub resolver: Option<GtUser>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GtCreatePullRequest<'a> {
+    /// `branch`, or `owner:branch` for a pull request from a fork
+    pub head: String,
+    pub base: &'a str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GtCreateComment<'a> {
+    pub body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GtUpdatePullRequest<'a> {
+    pub base: &'a str,
+}
+
+impl GiteaApi {
+    pub(crate) fn new(api_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        Ok(Self {
+            client: ApiClient::new(
+                ProviderKind::Gitea,
+                api_url,
+                token
+                    .map(|token| ("Authorization", format!("token {token}")))
+                    .into_iter()
+                    .collect(),
+            )?,
+        })
+    }
+
+    pub(crate) async fn create_pull_request(
+        &self,
+        repo: &str,
+        request: &GtCreatePullRequest<'_>,
+    ) -> Result<GtPullRequest, GitHostError> {
+        self.client
+            .post(&format!("/repos/{repo}/pulls"), request)
+            .await
+    }
+
+    pub(crate) async fn pull_request(
+        &self,
+        repo: &str,
+        number: i64,
+    ) -> Result<GtPullRequest, GitHostError> {
+        self.client
+            .get(&format!("/repos/{repo}/pulls/{number}"), &[])
+            .await
+    }
+
+    pub(crate) async fn update_pull_request_base(
+        &self,
+        repo: &str,
+        number: i64,
+        base: &str,
+    ) -> Result<GtPullRequest, GitHostError> {
+        self.client
+            .patch(
+                &format!("/repos/{repo}/pulls/{number}"),
+                &GtUpdatePullRequest { base },
+            )
+            .await
+    }
+
+    /// Every pull request matching `query`
+    pub(crate) async fn pull_requests(
+        &self,
+        repo: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<GtPullRequest>, GitHostError> {
+        self.client
+            .get_all(&format!("/repos/{repo}/pulls"), query, "limit", PAGE_SIZE)
+            .await
+    }
+
+    /// The first page of pull requests matching `query`
+    pub(crate) async fn pull_requests_page(
+        &self,
+        repo: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<GtPullRequest>, GitHostError> {
+        self.client
+            .get(&format!("/repos/{repo}/pulls"), query)
+            .await
+    }
+
+    pub(crate) async fn issue_comments(
+        &self,
+        repo: &str,
+        number: i64,
+    ) -> Result<Vec<GtComment>, GitHostError> {
+        self.client
+            .get_all(
+                &format!("/repos/{repo}/issues/{number}/comments"),
+                &[],
+                "limit",
+                PAGE_SIZE,
+            )
+            .await
+    }
+
+    pub(crate) async fn create_issue_comment(
+        &self,
+        repo: &str,
+        number: i64,
+        body: &str,
+    ) -> Result<GtComment, GitHostError> {
+        self.client
+            .post(
+                &format!("/repos/{repo}/issues/{number}/comments"),
+                &GtCreateComment { body },
+            )
+            .await
+    }
+
+    /// Comments of every review of a pull request
+    pub(crate) async fn review_comments(
+        &self,
+        repo: &str,
+        number: i64,
+    ) -> Result<Vec<GtReviewComment>, GitHostError> {
+        let reviews: Vec<GtReview> = self
+            .client
+            .get_all(
+                &format!("/repos/{repo}/pulls/{number}/reviews"),
+                &[],
+                "limit",
+                PAGE_SIZE,
+            )
+            .await?;
+
+        let mut comments = Vec::new();
+        for review in reviews.iter().filter(|review| review.comments_count > 0) {
+            let review_comments: Vec<GtReviewComment> = self
+                .client
+                .get(
+                    &format!(
+                        "/repos/{repo}/pulls/{number}/reviews/{}/comments",
+                        review.id
+                    ),
+                    &[],
+                )
+                .await?;
+            comments.extend(review_comments);
+        }
+        Ok(comments)
+    }
+}
+
+impl From<GtPullRequest> for PullRequestDetail {
+    fn from(pr: GtPullRequest) -> Self {
+        let status = match (pr.merged, pr.state.as_str()) {
+            (true, _) => MergeStatus::Merged,
+            (false, "open") => MergeStatus::Open,
+            (false, "closed") => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestDetail {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+            title: pr.title,
+            base_branch: pr.base.ref_name,
+            head_branch: pr.head.ref_name,
+        }
+    }
+}
+
+impl From<GtComment> for UnifiedPrComment {
+    fn from(comment: GtComment) -> Self {
+        UnifiedPrComment::General {
+            id: comment.id.to_string(),
+            author: comment.user.login,
+            author_association: None,
+            body: comment.body,
+            created_at: comment.created_at,
+            url: comment.html_url,
+        }
+    }
+}
+
+impl From<GtReviewComment> for UnifiedPrComment {
+    fn from(comment: GtReviewComment) -> Self {
+        let (line, side) = if comment.position > 0 {
+            (Some(comment.position), "RIGHT")
+        } else if comment.original_position > 0 {
+            (Some(comment.original_position), "LEFT")
+        } else {
+            (None, "RIGHT")
+        };
+        UnifiedPrComment::Review {
+            id: comment.id,
+            author: comment.user.login,
+            author_association: None,
+            body: comment.body,
+            created_at: comment.created_at,
+            url: comment.html_url,
+            path: comment.path,
+            line,
+            side: Some(side.to_string()),
+            diff_hunk: comment.diff_hunk,
+        }
+    }
+}
@@ -0,0 +1,529 @@
+//! Gitea and Forgejo hosting service implementation, talking to the REST API directly.
+
+mod api;
+
+use std::path::Path;
+
+use api::{GiteaApi, GtCreatePullRequest};
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{
+    GitHostProvider,
+    detection::{find_host_config, parse_remote_url},
+    http::with_retry,
+    types::{
+        CreatePrRequest, GitHostConfig, GitHostError, ProviderKind, PullRequestDetail,
+        UnifiedPrComment,
+    },
+};
+
+/// How many recently closed pull requests `list_open_prs` adds
+const RECENT_CLOSED_LIMIT: u32 = 20;
+
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    hosts: Vec<GitHostConfig>,
+}
+
+/// A Gitea repository, addressed as `owner/repo` by the API
+#[derive(Debug, Clone)]
+struct GiteaRepo {
+    owner: String,
+    full_name: String,
+}
+
+impl GiteaProvider {
+    pub fn new(hosts: &[GitHostConfig]) -> Result<Self, GitHostError> {
+        Ok(Self {
+            hosts: hosts.to_vec(),
+        })
+    }
+
+    /// API client for the host of `url`, with the repository it points at
+    fn api_for(&self, url: &str) -> Result<(GiteaApi, GiteaRepo), GitHostError> {
+        let repo = Self::repo_from_url(url)?;
+        let remote = parse_remote_url(url)
+            .ok_or_else(|| GitHostError::Repository(format!("Unrecognised Gitea URL: {url}")))?;
+        let config = find_host_config(url, &self.hosts);
+        let api_url = config
+            .and_then(|config| config.api_url.clone())
+            .unwrap_or_else(|| format!("{}/api/v1", remote.web_url));
+        let token = config
+            .and_then(|config| config.token.clone())
+            .or_else(|| std::env::var("GITEA_TOKEN").ok())
+            .filter(|token| !token.is_empty());
+        Ok((GiteaApi::new(&api_url, token)?, repo))
+    }
+
+    /// The last two path segments of `url`, so instances served under a
+    /// sub-path (`https://host/gitea/owner/repo`) work too
+    fn repo_from_url(url: &str) -> Result<GiteaRepo, GitHostError> {
+        let invalid = || GitHostError::Repository(format!("Unrecognised Gitea URL: {url}"));
+        let remote = parse_remote_url(url).ok_or_else(invalid)?;
+        let mut segments = remote.path.rsplit('/');
+        let name = segments
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(invalid)?;
+        let owner = segments
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(invalid)?;
+        Ok(GiteaRepo {
+            owner: owner.to_string(),
+            full_name: format!("{owner}/{name}"),
+        })
+    }
+
+    /// Split `https://host/owner/repo/pulls/12` into the repository URL and number
+    fn parse_pull_request_url(pr_url: &str) -> Result<(&str, i64), GitHostError> {
+        let invalid =
+            || GitHostError::PullRequest(format!("Invalid Gitea pull request URL: {pr_url}"));
+        let (repo_url, rest) = pr_url.rsplit_once("/pulls/").ok_or_else(invalid)?;
+        let number: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Ok((repo_url, number.parse().map_err(|_| invalid())?))
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GiteaProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (api, target) = self.api_for(remote_url)?;
+
+        // For cross-fork PRs the head is given as "owner:branch"
+        let head = match request.head_repo_url.as_deref() {
+            Some(head_url) => {
+                let head_repo = Self::repo_from_url(head_url)?;
+                if head_repo.owner != target.owner {
+                    format!("{}:{}", head_repo.owner, request.head_branch)
+                } else {
+                    request.head_branch.clone()
+                }
+            }
+            None => request.head_branch.clone(),
+        };
+        // Gitea treats titles starting with "WIP:" as drafts
+        let title = if request.draft == Some(true) && !request.title.starts_with("WIP:") {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = GtCreatePullRequest {
+            head,
+            base: &request.base_branch,
+            title,
+            body: request.body.as_deref(),
+        };
+
+        let pull_request = with_retry(ProviderKind::Gitea, || {
+            api.create_pull_request(&target.full_name, &body)
+        })
+        .await?;
+
+        info!(
+            "Created Gitea PR #{} for branch {}",
+            pull_request.number, request.head_branch
+        );
+
+        Ok(pull_request.into())
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+        let (repo_url, number) = Self::parse_pull_request_url(pr_url)?;
+        let (api, repo) = self.api_for(repo_url)?;
+        let pull_request = with_retry(ProviderKind::Gitea, || {
+            api.pull_request(&repo.full_name, number)
+        })
+        .await?;
+        Ok(pull_request.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        // The list endpoint can't filter by head branch, so filter the most recent ones
+        let query = [
+            ("state", "all".to_string()),
+            ("sort", "recentupdate".to_string()),
+        ];
+        let pull_requests = with_retry(ProviderKind::Gitea, || {
+            api.pull_requests(&repo.full_name, &query)
+        })
+        .await?;
+        Ok(pull_requests
+            .into_iter()
+            .filter(|pr| pr.head.ref_name == branch_name)
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+
+        let (general_result, review_result) = tokio::join!(
+            with_retry(ProviderKind::Gitea, || api
+                .issue_comments(&repo.full_name, pr_number)),
+            with_retry(ProviderKind::Gitea, || api
+                .review_comments(&repo.full_name, pr_number)),
+        );
+
+        let mut unified: Vec<UnifiedPrComment> =
+            general_result?.into_iter().map(Into::into).collect();
+        unified.extend(review_result?.into_iter().map(Into::into));
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+
+        let open_query = [("state", "open".to_string())];
+        let closed_query = [
+            ("state", "closed".to_string()),
+            ("sort", "recentupdate".to_string()),
+            ("limit", RECENT_CLOSED_LIMIT.to_string()),
+        ];
+        let (open, closed) = tokio::join!(
+            with_retry(ProviderKind::Gitea, || api
+                .pull_requests(&repo.full_name, &open_query)),
+            with_retry(ProviderKind::Gitea, || api
+                .pull_requests_page(&repo.full_name, &closed_query)),
+        );
+
+        let mut pull_requests = open?;
+        pull_requests.extend(closed?);
+        pull_requests.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        Ok(pull_requests.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_open_review_threads(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        let mut review_comments = with_retry(ProviderKind::Gitea, || {
+            api.review_comments(&repo.full_name, pr_number)
+        })
+        .await?;
+
+        // Gitea shows the comments on one line of a file as a conversation,
+        // started by the earliest one
+        review_comments.sort_by_key(|comment| comment.created_at);
+        let mut conversations: Vec<((String, i64, i64), i64, bool)> = Vec::new();
+        for comment in review_comments {
+            let key = (comment.path, comment.position, comment.original_position);
+            let resolved = comment.resolver.is_some();
+            match conversations.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, conversation_resolved)) => *conversation_resolved |= resolved,
+                None => conversations.push((key, comment.id, resolved)),
+            }
+        }
+        Ok(Some(
+            conversations
+                .into_iter()
+                .filter(|(_, _, resolved)| !resolved)
+                .map(|(_, root, _)| root)
+                .collect(),
+        ))
+    }
+
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (repo_url, number) = Self::parse_pull_request_url(pr_url)?;
+        let (api, repo) = self.api_for(repo_url)?;
+        let pull_request = with_retry(ProviderKind::Gitea, || {
+            api.update_pull_request_base(&repo.full_name, number, base_branch)
+        })
+        .await?;
+        Ok(pull_request.into())
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+        _resolve: bool,
+    ) -> Result<bool, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        let review_comments = with_retry(ProviderKind::Gitea, || {
+            api.review_comments(&repo.full_name, pr_number)
+        })
+        .await?;
+        let comment = review_comments
+            .into_iter()
+            .find(|comment| comment.id == comment_id)
+            .ok_or_else(|| {
+                GitHostError::PullRequest(format!(
+                    "Review comment {comment_id} was not found on pull request #{pr_number}"
+                ))
+            })?;
+
+        // The API cannot reply in a review thread or resolve it, so the reply
+        // is a pull request comment pointing at the review comment
+        let reply = match comment.html_url {
+            Some(url) => format!("{url}\n\n{body}"),
+            None => format!("`{}`: {body}", comment.path),
+        };
+        // Not retried: a retry after a timeout could post the reply twice
+        api.create_issue_comment(&repo.full_name, pr_number, &reply)
+            .await?;
+        Ok(false)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Gitea
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Json, Router,
+        extract::{Path as AxumPath, Query},
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::get,
+    };
+    use db::models::merge::MergeStatus;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::http::test_support::serve;
+
+    fn provider(api_url: String) -> GiteaProvider {
+        GiteaProvider::new(&[GitHostConfig {
+            host: "git.example.com".to_string(),
+            provider: ProviderKind::Gitea,
+            api_url: Some(api_url),
+            token: Some("secret".to_string()),
+        }])
+        .unwrap()
+    }
+
+    fn pull_request(number: i64, head: &str, state: &str, merged: bool) -> Value {
+        json!({
+            "number": number,
+            "html_url": format!("https://git.example.com/owner/repo/pulls/{number}"),
+            "title": format!("PR {number}"),
+            "state": state,
+            "merged": merged,
+            "merged_at": if merged { json!("2026-01-02T03:04:05Z") } else { Value::Null },
+            "merge_commit_sha": if merged { json!("abc123") } else { Value::Null },
+            "base": { "ref": "main" },
+            "head": { "ref": head },
+            "updated_at": "2026-01-02T03:04:05Z",
+        })
+    }
+
+    #[tokio::test]
+    async fn get_pr_status_reports_merged_pull_requests() {
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            get(
+                |AxumPath((owner, repo, number)): AxumPath<(String, String, i64)>,
+                 headers: HeaderMap| async move {
+                    if headers
+                        .get("authorization")
+                        .is_none_or(|value| value != "token secret")
+                    {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({}))).into_response();
+                    }
+                    assert_eq!((owner.as_str(), repo.as_str()), ("owner", "repo"));
+                    Json(pull_request(number, "vk/feature", "closed", true)).into_response()
+                },
+            ),
+        );
+        let provider = provider(serve(router).await);
+
+        let detail = provider
+            .get_pr_status("https://git.example.com/owner/repo/pulls/4")
+            .await
+            .unwrap();
+
+        assert_eq!(detail.number, 4);
+        assert!(matches!(detail.status, MergeStatus::Merged));
+        assert_eq!(detail.merge_commit_sha.as_deref(), Some("abc123"));
+    }
+
+    #[tokio::test]
+    async fn list_prs_for_branch_filters_by_head_branch() {
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls",
+            get(|Query(query): Query<Vec<(String, String)>>| async move {
+                assert!(query.contains(&("state".to_string(), "all".to_string())));
+                Json(json!([
+                    pull_request(1, "vk/feature", "open", false),
+                    pull_request(2, "other", "open", false),
+                    pull_request(3, "vk/feature", "closed", false),
+                ]))
+            }),
+        );
+        let provider = provider(serve(router).await);
+
+        let prs = provider
+            .list_prs_for_branch(
+                Path::new("."),
+                "git@git.example.com:owner/repo.git",
+                "vk/feature",
+            )
+            .await
+            .unwrap();
+
+        let numbers: Vec<i64> = prs.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, vec![1, 3]);
+        assert!(matches!(prs[1].status, MergeStatus::Closed));
+    }
+
+    #[tokio::test]
+    async fn get_pr_comments_merges_issue_and_review_comments() {
+        let router = Router::new()
+            .route(
+                "/repos/{owner}/{repo}/issues/{number}/comments",
+                get(|| async {
+                    Json(json!([{
+                        "id": 10,
+                        "body": "Thanks!",
+                        "user": { "login": "alice" },
+                        "created_at": "2026-01-01T00:02:00Z",
+                        "html_url": "https://git.example.com/owner/repo/pulls/4#issuecomment-10"
+                    }]))
+                }),
+            )
+            .route(
+                "/repos/{owner}/{repo}/pulls/{number}/reviews",
+                get(|| async {
+                    Json(json!([
+                        { "id": 1, "comments_count": 1 },
+                        { "id": 2, "comments_count": 0 }
+                    ]))
+                }),
+            )
+            .route(
+                "/repos/{owner}/{repo}/pulls/{number}/reviews/{review}/comments",
+                get(
+                    |AxumPath((_, _, _, review)): AxumPath<(String, String, i64, i64)>| async move {
+                        assert_eq!(review, 1);
+                        Json(json!([{
+                            "id": 20,
+                            "body": "Off by one",
+                            "user": { "login": "bob" },
+                            "created_at": "2026-01-01T00:01:00Z",
+                            "html_url": null,
+                            "path": "src/main.rs",
+                            "diff_hunk": "@@ -1,3 +1,3 @@",
+                            "position": 12,
+                            "original_position": 0
+                        }]))
+                    },
+                ),
+            );
+        let provider = provider(serve(router).await);
+
+        let comments = provider
+            .get_pr_comments(Path::new("."), "https://git.example.com/owner/repo.git", 4)
+            .await
+            .unwrap();
+
+        assert_eq!(comments.len(), 2);
+        match &comments[0] {
+            UnifiedPrComment::Review {
+                id,
+                path,
+                line,
+                side,
+                ..
+            } => {
+                assert_eq!(*id, 20);
+                assert_eq!(path, "src/main.rs");
+                assert_eq!(*line, Some(12));
+                assert_eq!(side.as_deref(), Some("RIGHT"));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+        assert!(matches!(
+            &comments[1],
+            UnifiedPrComment::General { author, .. } if author == "alice"
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_open_review_threads_groups_comments_by_line() {
+        let comment = |id: i64, minute: i64, position: i64, resolver: Option<&str>| {
+            json!({
+                "id": id,
+                "body": "Comment",
+                "user": { "login": "bob" },
+                "created_at": format!("2026-01-01T00:{minute:02}:00Z"),
+                "html_url": null,
+                "path": "src/main.rs",
+                "diff_hunk": null,
+                "position": position,
+                "original_position": 0,
+                "resolver": resolver.map(|login| json!({ "login": login }))
+            })
+        };
+        let router = Router::new()
+            .route(
+                "/repos/{owner}/{repo}/pulls/{number}/reviews",
+                get(|| async {
+                    Json(json!([
+                        { "id": 1, "comments_count": 3 },
+                        { "id": 2, "comments_count": 1 }
+                    ]))
+                }),
+            )
+            .route(
+                "/repos/{owner}/{repo}/pulls/{number}/reviews/{review}/comments",
+                get(
+                    move |AxumPath((_, _, _, review)): AxumPath<(String, String, i64, i64)>| async move {
+                        Json(match review {
+                            // A reply on line 12, then a resolved conversation on line 30
+                            1 => json!([
+                                comment(21, 5, 12, None),
+                                comment(30, 2, 30, None),
+                                comment(31, 3, 30, Some("alice"))
+                            ]),
+                            _ => json!([comment(20, 1, 12, None)]),
+                        })
+                    },
+                ),
+            );
+        let provider = provider(serve(router).await);
+
+        let roots = provider
+            .get_open_review_threads(Path::new("."), "https://git.example.com/owner/repo.git", 4)
+            .await
+            .unwrap();
+
+        assert_eq!(roots, Some(vec![20]));
+    }
+}
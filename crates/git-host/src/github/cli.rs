@@ -18,6 +18,9 @@ use thiserror::Error;
 use url::Url;
 use utils::{command_ext::NoWindowExt, shell::resolve_executable_path_blocking};
 
+use super::review_threads::{
+    RESOLVE_THREAD_MUTATION, REVIEW_THREADS_QUERY, ReviewThread, ReviewThreadsResponse,
+};
 use crate::types::{
     CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, PullRequestDetail,
     ReviewCommentUser,
@@ -275,6 +278,12 @@ impl GhCli {
         Self::parse_pr_view(&raw)
     }
 
+    /// Change the base branch of a pull request.
+    pub fn edit_pr_base(&self, pr_url: &str, base_branch: &str) -> Result<(), GhCliError> {
+        self.run(["pr", "edit", pr_url, "--base", base_branch], None)?;
+        Ok(())
+    }
+
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
@@ -400,6 +409,102 @@ impl GhCli {
         Self::parse_pr_review_comments(&raw)
     }
 
+    /// Reply in the thread of an inline review comment.
+    pub fn reply_to_review_comment(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+    ) -> Result<(), GhCliError> {
+        let args = Self::api_args(
+            repo_info,
+            [
+                "-X".to_string(),
+                "POST".to_string(),
+                format!(
+                    "repos/{}/{}/pulls/{}/comments/{}/replies",
+                    repo_info.owner, repo_info.repo_name, pr_number, comment_id
+                ),
+                "-f".to_string(),
+                format!("body={body}"),
+            ],
+        );
+        self.run(args, None)?;
+        Ok(())
+    }
+
+    /// Find the review thread holding an inline review comment.
+    pub(crate) fn review_thread_of(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+        comment_id: i64,
+    ) -> Result<Option<ReviewThread>, GhCliError> {
+        Ok(self
+            .review_threads(repo_info, pr_number)?
+            .into_thread_of(comment_id))
+    }
+
+    /// The review threads of a pull request.
+    pub(crate) fn review_threads(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        pr_number: i64,
+    ) -> Result<ReviewThreadsResponse, GhCliError> {
+        let args = Self::api_args(
+            repo_info,
+            [
+                "graphql".to_string(),
+                "-f".to_string(),
+                format!("query={REVIEW_THREADS_QUERY}"),
+                "-f".to_string(),
+                format!("owner={}", repo_info.owner),
+                "-f".to_string(),
+                format!("name={}", repo_info.repo_name),
+                "-F".to_string(),
+                format!("number={pr_number}"),
+            ],
+        );
+        let raw = self.run(args, None)?;
+        serde_json::from_str(raw.trim()).map_err(|err| {
+            GhCliError::UnexpectedOutput(format!(
+                "Failed to parse review threads response: {err}; raw: {raw}"
+            ))
+        })
+    }
+
+    /// Mark a review thread resolved.
+    pub fn resolve_review_thread(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        thread_id: &str,
+    ) -> Result<(), GhCliError> {
+        let args = Self::api_args(
+            repo_info,
+            [
+                "graphql".to_string(),
+                "-f".to_string(),
+                format!("query={RESOLVE_THREAD_MUTATION}"),
+                "-f".to_string(),
+                format!("threadId={thread_id}"),
+            ],
+        );
+        self.run(args, None)?;
+        Ok(())
+    }
+
+    /// `gh api` arguments aimed at the repository's host
+    fn api_args(repo_info: &GitHubRepoInfo, args: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut api_args = vec!["api".to_string()];
+        api_args.extend(args);
+        if let Some(ref host) = repo_info.hostname {
+            api_args.push("--hostname".to_string());
+            api_args.push(host.clone());
+        }
+        api_args
+    }
+
     pub fn pr_checkout(
         &self,
         repo_path: &Path,
@@ -1,6 +1,8 @@
 //! GitHub hosting service implementation.
 
 mod cli;
+mod rest;
+mod review_threads;
 
 use std::{path::Path, time::Duration};
 
@@ -8,6 +10,7 @@ use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 pub use cli::GhCli;
 use cli::{GhCliError, GitHubRepoInfo};
+pub use rest::GitHubRestProvider;
 use tokio::task;
 use tracing::info;
 
@@ -393,6 +396,127 @@ impl GitHostProvider for GitHubProvider {
         .await
     }
 
+    async fn get_open_review_threads(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+
+        (|| async {
+            let cli = cli.clone();
+            let repo_info = repo_info.clone();
+            let threads = task::spawn_blocking(move || cli.review_threads(&repo_info, pr_number))
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute GitHub CLI for fetching review threads: {err}"
+                    ))
+                })?;
+            threads
+                .map(|threads| Some(threads.into_open_thread_roots()))
+                .map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        let base = base_branch.to_string();
+
+        (|| async {
+            let cli = cli.clone();
+            let url = url.clone();
+            let base = base.clone();
+            let pr = task::spawn_blocking(move || {
+                cli.edit_pr_base(&url, &base)?;
+                cli.view_pr(&url)
+            })
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for editing PR: {err}"
+                ))
+            })?;
+            pr.map_err(GitHostError::from)
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|err: &GitHostError| err.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "GitHub API call failed, retrying after {:.2}s: {}",
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+        resolve: bool,
+    ) -> Result<bool, GitHostError> {
+        let repo_info = self.get_repo_info(remote_url, repo_path).await?;
+        let cli = self.gh_cli.clone();
+        let body = body.to_string();
+
+        // Not retried: a retry after a timeout could post the reply twice
+        task::spawn_blocking(move || {
+            cli.reply_to_review_comment(&repo_info, pr_number, comment_id, &body)?;
+            if !resolve {
+                return Ok(false);
+            }
+            match cli.review_thread_of(&repo_info, pr_number, comment_id)? {
+                Some(thread) if thread.is_resolved => Ok(true),
+                Some(thread) => {
+                    cli.resolve_review_thread(&repo_info, &thread.id)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for replying to a review comment: {err}"
+            ))
+        })?
+        .map_err(|err: GhCliError| err.into())
+    }
+
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
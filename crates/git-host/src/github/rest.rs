@@ -0,0 +1,709 @@
+//! GitHub hosting service implementation on the REST API, for machines
+//! without the `gh` CLI.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::MergeStatus;
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeOwned, IgnoredAny},
+};
+use serde_json::{Value, json};
+use tracing::info;
+
+use super::review_threads::{RESOLVE_THREAD_MUTATION, REVIEW_THREADS_QUERY, ReviewThreadsResponse};
+use crate::{
+    GitHostProvider,
+    detection::{RemoteRepo, find_host_config, parse_remote_url},
+    http::{ApiClient, with_retry},
+    types::{
+        CreatePrRequest, GitHostConfig, GitHostError, GitHostOptions, PrReviewComment,
+        ProviderKind, PullRequestDetail, UnifiedPrComment,
+    },
+};
+
+const PAGE_SIZE: u32 = 100;
+
+/// How many recently closed pull requests `list_open_prs` adds
+const RECENT_CLOSED_LIMIT: u32 = 20;
+
+const API_VERSION: &str = "2022-11-28";
+
+#[derive(Debug, Clone)]
+pub struct GitHubRestProvider {
+    hosts: Vec<GitHostConfig>,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhBranch {
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhPullRequest {
+    number: i64,
+    html_url: String,
+    title: String,
+    state: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    base: GhBranch,
+    head: GhBranch,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhIssueComment {
+    id: i64,
+    body: String,
+    user: GhUser,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    author_association: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GhCreateReply<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct GhUpdatePullRequest<'a> {
+    base: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct GhCreatePullRequest<'a> {
+    title: &'a str,
+    /// `branch`, or `owner:branch` for a pull request from a fork
+    head: String,
+    base: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+    draft: bool,
+}
+
+impl GitHubRestProvider {
+    pub fn new(hosts: &[GitHostConfig], token: String) -> Result<Self, GitHostError> {
+        Ok(Self {
+            hosts: hosts.to_vec(),
+            token,
+        })
+    }
+
+    /// Token for `url`: the host's own, then the configured one, then
+    /// `GITHUB_TOKEN` or `GH_TOKEN`
+    pub(crate) fn token_for(url: &str, options: &GitHostOptions) -> Option<String> {
+        find_host_config(url, &options.hosts)
+            .and_then(|config| config.token.clone())
+            .or_else(|| options.github_token.clone())
+            .or_else(|| std::env::var("GITHUB_TOKEN").ok())
+            .or_else(|| std::env::var("GH_TOKEN").ok())
+            .filter(|token| !token.is_empty())
+    }
+
+    /// REST API URL for the host of `url`, with the repository it points at
+    fn api_url_for(&self, url: &str) -> Result<(String, RemoteRepo), GitHostError> {
+        let remote = parse_remote_url(url)
+            .ok_or_else(|| GitHostError::Repository(format!("Unrecognised GitHub URL: {url}")))?;
+        let api_url = find_host_config(url, &self.hosts)
+            .and_then(|config| config.api_url.clone())
+            .unwrap_or_else(|| match remote.host.as_str() {
+                "github.com" => "https://api.github.com".to_string(),
+                // GitHub Enterprise Server
+                _ => format!("{}/api/v3", remote.web_url),
+            });
+        Ok((api_url, remote))
+    }
+
+    fn client(&self, base_url: &str) -> Result<ApiClient, GitHostError> {
+        ApiClient::new(
+            ProviderKind::GitHub,
+            base_url,
+            vec![
+                ("Authorization", format!("Bearer {}", self.token)),
+                ("X-GitHub-Api-Version", API_VERSION.to_string()),
+            ],
+        )
+    }
+
+    /// API client for the host of `url`, with the repository it points at
+    fn api_for(&self, url: &str) -> Result<(ApiClient, RemoteRepo), GitHostError> {
+        let (api_url, remote) = self.api_url_for(url)?;
+        Ok((self.client(&api_url)?, remote))
+    }
+
+    /// GraphQL client for the host of `url`. GitHub Enterprise Server serves
+    /// GraphQL at `/api/graphql` rather than under `/api/v3`.
+    fn graphql_for(&self, url: &str) -> Result<ApiClient, GitHostError> {
+        let (api_url, _) = self.api_url_for(url)?;
+        self.client(api_url.strip_suffix("/v3").unwrap_or(&api_url))
+    }
+
+    /// Run a GraphQL request. Errors come back with a 200 status, so they are
+    /// picked out of the response body.
+    async fn graphql<T: DeserializeOwned>(
+        api: &ApiClient,
+        query: &str,
+        variables: Value,
+    ) -> Result<T, GitHostError> {
+        let request = json!({ "query": query, "variables": variables });
+        let response: Value =
+            with_retry(ProviderKind::GitHub, || api.post("/graphql", &request)).await?;
+        if let Some(errors) = response.get("errors").and_then(Value::as_array) {
+            let messages: Vec<&str> = errors
+                .iter()
+                .filter_map(|error| error.get("message").and_then(Value::as_str))
+                .collect();
+            return Err(GitHostError::PullRequest(format!(
+                "GitHub GraphQL request failed: {}",
+                messages.join("; ")
+            )));
+        }
+        serde_json::from_value(response).map_err(|err| {
+            GitHostError::UnexpectedOutput(format!("Unexpected GraphQL response: {err}"))
+        })
+    }
+
+    /// Split `https://github.com/owner/repo/pull/12` into the repository URL and number
+    fn parse_pull_request_url(pr_url: &str) -> Result<(&str, i64), GitHostError> {
+        let invalid =
+            || GitHostError::PullRequest(format!("Invalid GitHub pull request URL: {pr_url}"));
+        let (repo_url, rest) = pr_url.split_once("/pull/").ok_or_else(invalid)?;
+        let number: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Ok((repo_url, number.parse().map_err(|_| invalid())?))
+    }
+
+    fn owner(repo: &RemoteRepo) -> &str {
+        repo.path.split('/').next().unwrap_or_default()
+    }
+}
+
+impl From<GhPullRequest> for PullRequestDetail {
+    fn from(pr: GhPullRequest) -> Self {
+        let status = match (pr.merged_at.is_some(), pr.state.as_str()) {
+            (true, _) => MergeStatus::Merged,
+            (false, "open") => MergeStatus::Open,
+            (false, "closed") => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestDetail {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            // Open pull requests report their test merge commit here
+            merge_commit_sha: pr.merged_at.and(pr.merge_commit_sha),
+            title: pr.title,
+            base_branch: pr.base.ref_name,
+            head_branch: pr.head.ref_name,
+        }
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitHubRestProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (api, target) = self.api_for(remote_url)?;
+
+        let head = match request.head_repo_url.as_deref().and_then(parse_remote_url) {
+            Some(head) if Self::owner(&head) != Self::owner(&target) => {
+                format!("{}:{}", Self::owner(&head), request.head_branch)
+            }
+            _ => request.head_branch.clone(),
+        };
+        let body = GhCreatePullRequest {
+            title: &request.title,
+            head,
+            base: &request.base_branch,
+            body: request.body.as_deref(),
+            draft: request.draft.unwrap_or(false),
+        };
+
+        let path = format!("/repos/{}/pulls", target.path);
+        let pr: GhPullRequest = with_retry(ProviderKind::GitHub, || api.post(&path, &body)).await?;
+
+        info!(
+            "Created GitHub PR #{} for branch {}",
+            pr.number, request.head_branch
+        );
+
+        Ok(pr.into())
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+        let (repo_url, number) = Self::parse_pull_request_url(pr_url)?;
+        let (api, repo) = self.api_for(repo_url)?;
+        let path = format!("/repos/{}/pulls/{number}", repo.path);
+        let pr: GhPullRequest = with_retry(ProviderKind::GitHub, || api.get(&path, &[])).await?;
+        Ok(pr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        let query = [
+            ("state", "all".to_string()),
+            ("head", format!("{}:{branch_name}", Self::owner(&repo))),
+        ];
+        let path = format!("/repos/{}/pulls", repo.path);
+        let prs: Vec<GhPullRequest> = with_retry(ProviderKind::GitHub, || {
+            api.get_all(&path, &query, "per_page", PAGE_SIZE)
+        })
+        .await?;
+        Ok(prs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+
+        let issue_comments_path = format!("/repos/{}/issues/{pr_number}/comments", repo.path);
+        let review_comments_path = format!("/repos/{}/pulls/{pr_number}/comments", repo.path);
+        let (general, review) = tokio::join!(
+            with_retry(ProviderKind::GitHub, || api.get_all::<GhIssueComment>(
+                &issue_comments_path,
+                &[],
+                "per_page",
+                PAGE_SIZE,
+            )),
+            with_retry(ProviderKind::GitHub, || api.get_all::<PrReviewComment>(
+                &review_comments_path,
+                &[],
+                "per_page",
+                PAGE_SIZE,
+            )),
+        );
+
+        let mut unified: Vec<UnifiedPrComment> = Vec::new();
+        for c in general? {
+            unified.push(UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: Some(c.author_association),
+                body: c.body,
+                created_at: c.created_at,
+                url: Some(c.html_url),
+            });
+        }
+        for c in review? {
+            unified.push(UnifiedPrComment::Review {
+                id: c.id,
+                author: c.user.login,
+                author_association: Some(c.author_association),
+                body: c.body,
+                created_at: c.created_at,
+                url: Some(c.html_url),
+                path: c.path,
+                line: c.line,
+                side: c.side,
+                diff_hunk: Some(c.diff_hunk),
+            });
+        }
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        let path = format!("/repos/{}/pulls", repo.path);
+
+        let open_query = [("state", "open".to_string())];
+        let recent_query = [
+            ("state", "closed".to_string()),
+            ("sort", "updated".to_string()),
+            ("direction", "desc".to_string()),
+            ("per_page", RECENT_CLOSED_LIMIT.to_string()),
+        ];
+        let (open, recent) = tokio::join!(
+            with_retry(ProviderKind::GitHub, || api.get_all::<GhPullRequest>(
+                &path,
+                &open_query,
+                "per_page",
+                PAGE_SIZE,
+            )),
+            with_retry(ProviderKind::GitHub, || api
+                .get::<Vec<GhPullRequest>>(&path, &recent_query)),
+        );
+
+        let mut prs = open?;
+        prs.extend(recent?);
+        prs.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        Ok(prs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_open_review_threads(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError> {
+        let (_, repo) = self.api_url_for(remote_url)?;
+        let graphql = self.graphql_for(remote_url)?;
+        let (owner, name) = repo.path.split_once('/').unwrap_or((&repo.path, ""));
+        let threads: ReviewThreadsResponse = Self::graphql(
+            &graphql,
+            REVIEW_THREADS_QUERY,
+            json!({ "owner": owner, "name": name, "number": pr_number }),
+        )
+        .await?;
+        Ok(Some(threads.into_open_thread_roots()))
+    }
+
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (repo_url, number) = Self::parse_pull_request_url(pr_url)?;
+        let (api, repo) = self.api_for(repo_url)?;
+        let path = format!("/repos/{}/pulls/{number}", repo.path);
+        let body = GhUpdatePullRequest { base: base_branch };
+        let pr: GhPullRequest =
+            with_retry(ProviderKind::GitHub, || api.patch(&path, &body)).await?;
+        Ok(pr.into())
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+        resolve: bool,
+    ) -> Result<bool, GitHostError> {
+        let (api, repo) = self.api_for(remote_url)?;
+        let path = format!(
+            "/repos/{}/pulls/{pr_number}/comments/{comment_id}/replies",
+            repo.path
+        );
+        // Not retried: a retry after a timeout could post the reply twice
+        let _: IgnoredAny = api.post(&path, &GhCreateReply { body }).await?;
+        if !resolve {
+            return Ok(false);
+        }
+
+        let graphql = self.graphql_for(remote_url)?;
+        let (owner, name) = repo.path.split_once('/').unwrap_or((&repo.path, ""));
+        let threads: ReviewThreadsResponse = Self::graphql(
+            &graphql,
+            REVIEW_THREADS_QUERY,
+            json!({ "owner": owner, "name": name, "number": pr_number }),
+        )
+        .await?;
+        let Some(thread) = threads.into_thread_of(comment_id) else {
+            return Ok(false);
+        };
+        if !thread.is_resolved {
+            let _: Value = Self::graphql(
+                &graphql,
+                RESOLVE_THREAD_MUTATION,
+                json!({ "threadId": thread.id }),
+            )
+            .await?;
+        }
+        Ok(true)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitHub
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{
+        Json, Router,
+        extract::Path as AxumPath,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, patch, post},
+    };
+    use db::models::repo::GitHubAccess;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{GitHostService, http::test_support::serve};
+
+    fn hosts(api_url: String) -> Vec<GitHostConfig> {
+        vec![GitHostConfig {
+            host: "github.example.com".to_string(),
+            provider: ProviderKind::GitHub,
+            api_url: Some(api_url),
+            token: None,
+        }]
+    }
+
+    fn provider(api_url: String) -> GitHubRestProvider {
+        GitHubRestProvider::new(&hosts(api_url), "secret".to_string()).unwrap()
+    }
+
+    fn pull_request(number: i64, state: &str, merged: bool) -> Value {
+        json!({
+            "number": number,
+            "html_url": format!("https://github.example.com/owner/repo/pull/{number}"),
+            "title": "Add feature",
+            "state": state,
+            "merged_at": if merged { json!("2026-01-02T03:04:05Z") } else { Value::Null },
+            "merge_commit_sha": "abc123",
+            "base": { "ref": "main" },
+            "head": { "ref": "vk/feature" },
+            "updated_at": "2026-01-02T03:04:05Z",
+        })
+    }
+
+    const PR_URL: &str = "https://github.example.com/owner/repo/pull/7";
+
+    #[tokio::test]
+    async fn get_pr_status_reads_the_pull_request() {
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            get(
+                |AxumPath((owner, repo, number)): AxumPath<(String, String, i64)>,
+                 headers: HeaderMap| async move {
+                    if headers
+                        .get("authorization")
+                        .is_none_or(|v| v != "Bearer secret")
+                    {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({}))).into_response();
+                    }
+                    assert_eq!((owner.as_str(), repo.as_str()), ("owner", "repo"));
+                    Json(pull_request(number, "closed", true)).into_response()
+                },
+            ),
+        );
+        let provider = provider(serve(router).await);
+
+        let detail = provider.get_pr_status(PR_URL).await.unwrap();
+
+        assert_eq!(detail.number, 7);
+        assert!(matches!(detail.status, MergeStatus::Merged));
+        assert_eq!(detail.merge_commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(detail.head_branch, "vk/feature");
+    }
+
+    #[tokio::test]
+    async fn unchanged_responses_are_served_from_the_etag_cache() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            get(move |headers: HeaderMap| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    if headers.get("if-none-match").is_some_and(|v| v == "\"v1\"") {
+                        return StatusCode::NOT_MODIFIED.into_response();
+                    }
+                    ([("etag", "\"v1\"")], Json(pull_request(7, "open", false))).into_response()
+                }
+            }),
+        );
+        let provider = provider(serve(router).await);
+
+        let first = provider.get_pr_status(PR_URL).await.unwrap();
+        let second = provider.get_pr_status(PR_URL).await.unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(matches!(first.status, MergeStatus::Open));
+        assert!(matches!(second.status, MergeStatus::Open));
+        assert_eq!(second.merge_commit_sha, None);
+    }
+
+    #[tokio::test]
+    async fn exhausted_rate_limit_fails_without_a_request() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            get(move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let reset = (Utc::now().timestamp() + 3600).to_string();
+                    (
+                        [
+                            ("x-ratelimit-remaining", "0".to_string()),
+                            ("x-ratelimit-reset", reset),
+                        ],
+                        Json(pull_request(7, "open", false)),
+                    )
+                }
+            }),
+        );
+        let provider = provider(serve(router).await);
+
+        provider.get_pr_status(PR_URL).await.unwrap();
+        let result = provider.get_pr_status(PR_URL).await;
+
+        assert!(matches!(result, Err(GitHostError::RateLimited(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn create_pr_prefixes_fork_heads_with_their_owner() {
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls",
+            post(|Json(body): Json<Value>| async move {
+                assert_eq!(body["head"], "fork-owner:vk/feature");
+                assert_eq!(body["base"], "main");
+                assert_eq!(body["draft"], true);
+                Json(pull_request(3, "open", false))
+            }),
+        );
+        let provider = provider(serve(router).await);
+        let request = CreatePrRequest {
+            title: "Add feature".to_string(),
+            body: None,
+            head_branch: "vk/feature".to_string(),
+            base_branch: "main".to_string(),
+            draft: Some(true),
+            head_repo_url: Some("git@github.example.com:fork-owner/repo.git".to_string()),
+        };
+
+        let detail = provider
+            .create_pr(
+                Path::new("."),
+                "git@github.example.com:owner/repo.git",
+                &request,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(detail.number, 3);
+        assert!(matches!(detail.status, MergeStatus::Open));
+    }
+
+    #[tokio::test]
+    async fn update_pr_base_patches_the_pull_request() {
+        let router = Router::new().route(
+            "/repos/{owner}/{repo}/pulls/{number}",
+            patch(
+                |AxumPath((_, _, number)): AxumPath<(String, String, i64)>,
+                 Json(body): Json<Value>| async move {
+                    assert_eq!(body, json!({ "base": "main" }));
+                    Json(pull_request(number, "open", false))
+                },
+            ),
+        );
+        let provider = provider(serve(router).await);
+
+        let detail = provider.update_pr_base(PR_URL, "main").await.unwrap();
+
+        assert_eq!(detail.number, 7);
+        assert_eq!(detail.base_branch, "main");
+    }
+
+    #[tokio::test]
+    async fn reply_to_review_comment_replies_then_resolves_the_thread() {
+        let router = Router::new()
+            .route(
+                "/repos/{owner}/{repo}/pulls/{number}/comments/{comment_id}/replies",
+                post(
+                    |AxumPath((_, _, number, comment_id)): AxumPath<(String, String, i64, i64)>,
+                     Json(body): Json<Value>| async move {
+                        assert_eq!((number, comment_id), (7, 21));
+                        assert_eq!(body, json!({ "body": "Addressed in abc1234" }));
+                        Json(json!({ "id": 22 }))
+                    },
+                ),
+            )
+            .route(
+                "/graphql",
+                post(|Json(body): Json<Value>| async move {
+                    if body["variables"].get("threadId").is_some() {
+                        assert_eq!(body["variables"]["threadId"], "T2");
+                        return Json(json!({
+                            "data": { "resolveReviewThread": { "thread": { "isResolved": true } } }
+                        }));
+                    }
+                    assert_eq!(
+                        body["variables"],
+                        json!({ "owner": "owner", "name": "repo", "number": 7 })
+                    );
+                    Json(
+                        json!({ "data": { "repository": { "pullRequest": { "reviewThreads": {
+                        "nodes": [
+                            { "id": "T1", "isResolved": false,
+                              "comments": { "nodes": [{ "databaseId": 10 }] } },
+                            { "id": "T2", "isResolved": false,
+                              "comments": { "nodes": [{ "databaseId": 21 }] } }
+                        ]
+                    } } } } }),
+                    )
+                }),
+            );
+        let provider = provider(serve(router).await);
+
+        let resolved = provider
+            .reply_to_review_comment(
+                Path::new("."),
+                "https://github.example.com/owner/repo.git",
+                7,
+                21,
+                "Addressed in abc1234",
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert!(resolved);
+    }
+
+    #[test]
+    fn access_mode_picks_the_provider() {
+        let options = |github_access, github_token: Option<&str>| GitHostOptions {
+            hosts: Vec::new(),
+            github_token: github_token.map(str::to_string),
+            github_access,
+        };
+        let url = "https://github.com/owner/repo.git";
+
+        assert!(matches!(
+            GitHostService::from_url(url, &options(GitHubAccess::Api, Some("secret"))),
+            Ok(GitHostService::GitHubRest(_))
+        ));
+        assert!(matches!(
+            GitHostService::from_url(url, &options(GitHubAccess::Auto, Some("secret"))),
+            Ok(GitHostService::GitHubRest(_))
+        ));
+        assert!(matches!(
+            GitHostService::from_url(url, &options(GitHubAccess::Cli, Some("secret"))),
+            Ok(GitHostService::GitHub(_))
+        ));
+    }
+}
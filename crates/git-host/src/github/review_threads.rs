@@ -0,0 +1,135 @@
+//! Review threads of a pull request. Only GitHub's GraphQL API can tell which
+//! thread a review comment belongs to and resolve it, for both the CLI and the
+//! REST provider.
+
+use serde::Deserialize;
+
+/// Threads of a pull request with the database ids of their comments
+pub(crate) const REVIEW_THREADS_QUERY: &str = r#"query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id
+          isResolved
+          comments(first: 100) { nodes { databaseId } }
+        }
+      }
+    }
+  }
+}"#;
+
+pub(crate) const RESOLVE_THREAD_MUTATION: &str = r#"mutation($threadId: ID!) {
+  resolveReviewThread(input: { threadId: $threadId }) { thread { isResolved } }
+}"#;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReviewThreadsResponse {
+    data: ReviewThreadsData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewThreadsData {
+    repository: Option<ReviewThreadsRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadsRepository {
+    pull_request: Option<ReviewThreadsPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadsPullRequest {
+    review_threads: Nodes<ReviewThread>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReviewThread {
+    pub id: String,
+    pub is_resolved: bool,
+    comments: Nodes<ReviewThreadComment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadComment {
+    database_id: Option<i64>,
+}
+
+impl ReviewThreadsResponse {
+    fn into_threads(self) -> Vec<ReviewThread> {
+        self.data
+            .repository
+            .and_then(|repository| repository.pull_request)
+            .map(|pull_request| pull_request.review_threads.nodes)
+            .unwrap_or_default()
+    }
+
+    /// The thread holding the review comment `comment_id`
+    pub(crate) fn into_thread_of(self, comment_id: i64) -> Option<ReviewThread> {
+        self.into_threads().into_iter().find(|thread| {
+            thread
+                .comments
+                .nodes
+                .iter()
+                .any(|comment| comment.database_id == Some(comment_id))
+        })
+    }
+
+    /// The first comment of each thread that isn't resolved; later comments
+    /// are replies
+    pub(crate) fn into_open_thread_roots(self) -> Vec<i64> {
+        self.into_threads()
+            .into_iter()
+            .filter(|thread| !thread.is_resolved)
+            .filter_map(|thread| thread.comments.nodes.first()?.database_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_thread_of_a_reply() {
+        let response: ReviewThreadsResponse = serde_json::from_str(
+            r#"{"data": {"repository": {"pullRequest": {"reviewThreads": {"nodes": [
+                {"id": "T1", "isResolved": false, "comments": {"nodes": [{"databaseId": 10}]}},
+                {"id": "T2", "isResolved": true, "comments": {"nodes": [
+                    {"databaseId": 20}, {"databaseId": 21}
+                ]}}
+            ]}}}}}"#,
+        )
+        .unwrap();
+
+        let thread = response.into_thread_of(21).unwrap();
+
+        assert_eq!(thread.id, "T2");
+        assert!(thread.is_resolved);
+    }
+
+    #[test]
+    fn open_thread_roots_leave_out_resolved_threads_and_replies() {
+        let response: ReviewThreadsResponse = serde_json::from_str(
+            r#"{"data": {"repository": {"pullRequest": {"reviewThreads": {"nodes": [
+                {"id": "T1", "isResolved": false, "comments": {"nodes": [
+                    {"databaseId": 10}, {"databaseId": 11}
+                ]}},
+                {"id": "T2", "isResolved": true, "comments": {"nodes": [{"databaseId": 20}]}},
+                {"id": "T3", "isResolved": false, "comments": {"nodes": [{"databaseId": 30}]}}
+            ]}}}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.into_open_thread_roots(), vec![10, 30]);
+    }
+}
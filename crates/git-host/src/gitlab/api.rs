@@ -0,0 +1,332 @@
+//! Minimal client for the GitLab REST API (v4).
+
+use chrono::{DateTime, Utc};
+use db::models::merge::MergeStatus;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    http::ApiClient,
+    types::{GitHostError, ProviderKind, PullRequestDetail, UnifiedPrComment},
+};
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GitLabApi {
+    client: ApiClient,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlProject {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlMergeRequest {
+    pub iid: i64,
+    pub web_url: String,
+    pub title: String,
+    pub state: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub merge_commit_sha: Option<String>,
+    pub squash_commit_sha: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlUser {
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlNotePosition {
+    pub new_path: Option<String>,
+    pub old_path: Option<String>,
+    pub new_line: Option<i64>,
+    pub old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlNote {
+    pub id: i64,
+    pub body: String,
+    pub author: GlUser,
+    pub created_at: DateTime<Utc>,
+    /// Notes GitLab writes itself, such as "added 1 commit"
+    #[serde(default)]
+    pub system: bool,
+    /// Set on notes left on a line of the diff
+    pub position: Option<GlNotePosition>,
+    /// Whether the note's discussion can be resolved
+    #[serde(default)]
+    pub resolvable: bool,
+    /// Whether the note's discussion is resolved
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+/// A thread of notes on a merge request
+#[derive(Debug, Deserialize)]
+pub(crate) struct GlDiscussion {
+    pub id: String,
+    pub notes: Vec<GlNote>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GlCreateNote<'a> {
+    pub body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GlResolveDiscussion {
+    pub resolved: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GlCreateMergeRequest<'a> {
+    pub source_branch: &'a str,
+    pub target_branch: &'a str,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+    /// Target project of a merge request opened from a fork
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_project_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GlUpdateMergeRequest<'a> {
+    pub target_branch: &'a str,
+}
+
+impl GitLabApi {
+    pub(crate) fn new(api_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        Ok(Self {
+            client: ApiClient::new(
+                ProviderKind::GitLab,
+                api_url,
+                token
+                    .map(|token| ("PRIVATE-TOKEN", token))
+                    .into_iter()
+                    .collect(),
+            )?,
+        })
+    }
+
+    /// `/projects/:id` with the project path as its URL-encoded id
+    fn project_url(project_path: &str) -> String {
+        format!("/projects/{}", project_path.replace('/', "%2F"))
+    }
+
+    pub(crate) async fn project(&self, project_path: &str) -> Result<GlProject, GitHostError> {
+        self.client.get(&Self::project_url(project_path), &[]).await
+    }
+
+    pub(crate) async fn create_merge_request(
+        &self,
+        project_path: &str,
+        request: &GlCreateMergeRequest<'_>,
+    ) -> Result<GlMergeRequest, GitHostError> {
+        self.client
+            .post(
+                &format!("{}/merge_requests", Self::project_url(project_path)),
+                request,
+            )
+            .await
+    }
+
+    pub(crate) async fn merge_request(
+        &self,
+        project_path: &str,
+        iid: i64,
+    ) -> Result<GlMergeRequest, GitHostError> {
+        self.client
+            .get(
+                &format!("{}/merge_requests/{iid}", Self::project_url(project_path)),
+                &[],
+            )
+            .await
+    }
+
+    pub(crate) async fn update_merge_request_target(
+        &self,
+        project_path: &str,
+        iid: i64,
+        target_branch: &str,
+    ) -> Result<GlMergeRequest, GitHostError> {
+        self.client
+            .put(
+                &format!("{}/merge_requests/{iid}", Self::project_url(project_path)),
+                &GlUpdateMergeRequest { target_branch },
+            )
+            .await
+    }
+
+    /// Every merge request matching `query`
+    pub(crate) async fn merge_requests(
+        &self,
+        project_path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<GlMergeRequest>, GitHostError> {
+        self.client
+            .get_all(
+                &format!("{}/merge_requests", Self::project_url(project_path)),
+                query,
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await
+    }
+
+    /// The first page of merge requests matching `query`
+    pub(crate) async fn merge_requests_page(
+        &self,
+        project_path: &str,
+        query: &[(&str, String)],
+    ) -> Result<Vec<GlMergeRequest>, GitHostError> {
+        self.client
+            .get(
+                &format!("{}/merge_requests", Self::project_url(project_path)),
+                query,
+            )
+            .await
+    }
+
+    pub(crate) async fn notes(
+        &self,
+        project_path: &str,
+        iid: i64,
+    ) -> Result<Vec<GlNote>, GitHostError> {
+        self.client
+            .get_all(
+                &format!(
+                    "{}/merge_requests/{iid}/notes",
+                    Self::project_url(project_path)
+                ),
+                &[("sort", "asc".to_string())],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await
+    }
+
+    pub(crate) async fn discussions(
+        &self,
+        project_path: &str,
+        iid: i64,
+    ) -> Result<Vec<GlDiscussion>, GitHostError> {
+        self.client
+            .get_all(
+                &format!(
+                    "{}/merge_requests/{iid}/discussions",
+                    Self::project_url(project_path)
+                ),
+                &[],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await
+    }
+
+    /// Add a note to a discussion thread
+    pub(crate) async fn reply_to_discussion(
+        &self,
+        project_path: &str,
+        iid: i64,
+        discussion_id: &str,
+        body: &str,
+    ) -> Result<GlNote, GitHostError> {
+        self.client
+            .post(
+                &format!(
+                    "{}/merge_requests/{iid}/discussions/{discussion_id}/notes",
+                    Self::project_url(project_path)
+                ),
+                &GlCreateNote { body },
+            )
+            .await
+    }
+
+    pub(crate) async fn resolve_discussion(
+        &self,
+        project_path: &str,
+        iid: i64,
+        discussion_id: &str,
+    ) -> Result<GlDiscussion, GitHostError> {
+        self.client
+            .put(
+                &format!(
+                    "{}/merge_requests/{iid}/discussions/{discussion_id}",
+                    Self::project_url(project_path)
+                ),
+                &GlResolveDiscussion { resolved: true },
+            )
+            .await
+    }
+}
+
+impl From<GlMergeRequest> for PullRequestDetail {
+    fn from(mr: GlMergeRequest) -> Self {
+        let status = match mr.state.as_str() {
+            // `locked` is the short-lived state while a merge is in progress
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        PullRequestDetail {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+            title: mr.title,
+            base_branch: mr.target_branch,
+            head_branch: mr.source_branch,
+        }
+    }
+}
+
+impl GlNote {
+    /// Convert to a unified comment; `None` for system notes
+    pub(crate) fn into_unified(self, merge_request_url: &str) -> Option<UnifiedPrComment> {
+        if self.system {
+            return None;
+        }
+        let url = Some(format!("{merge_request_url}#note_{}", self.id));
+        let diff_position = self.position.and_then(|position| {
+            // Lines only on the old side are comments on removed code
+            let (path, line, side) = match (position.new_line, position.old_line) {
+                (Some(line), _) => (position.new_path, Some(line), "RIGHT"),
+                (None, Some(line)) => (position.old_path, Some(line), "LEFT"),
+                (None, None) => (position.new_path.or(position.old_path), None, "RIGHT"),
+            };
+            path.map(|path| (path, line, side))
+        });
+
+        Some(match diff_position {
+            Some((path, line, side)) => UnifiedPrComment::Review {
+                id: self.id,
+                author: self.author.username,
+                author_association: None,
+                body: self.body,
+                created_at: self.created_at,
+                url,
+                path,
+                line,
+                side: Some(side.to_string()),
+                diff_hunk: None,
+            },
+            None => UnifiedPrComment::General {
+                id: self.id.to_string(),
+                author: self.author.username,
+                author_association: None,
+                body: self.body,
+                created_at: self.created_at,
+                url,
+            },
+        })
+    }
+}
@@ -0,0 +1,594 @@
+//! GitLab hosting service implementation, talking to the REST API directly.
+
+mod api;
+
+use std::path::Path;
+
+use api::{GitLabApi, GlCreateMergeRequest};
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{
+    GitHostProvider,
+    detection::{RemoteRepo, find_host_config, parse_remote_url},
+    http::with_retry,
+    types::{
+        CreatePrRequest, GitHostConfig, GitHostError, ProviderKind, PullRequestDetail,
+        UnifiedPrComment,
+    },
+};
+
+/// How many recently closed or merged merge requests `list_open_prs` adds
+const RECENT_CLOSED_LIMIT: u32 = 20;
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    hosts: Vec<GitHostConfig>,
+}
+
+impl GitLabProvider {
+    pub fn new(hosts: &[GitHostConfig]) -> Result<Self, GitHostError> {
+        Ok(Self {
+            hosts: hosts.to_vec(),
+        })
+    }
+
+    /// API client for the host of `url`, with the project it points at
+    fn api_for(&self, url: &str) -> Result<(GitLabApi, RemoteRepo), GitHostError> {
+        let remote = parse_remote_url(url)
+            .ok_or_else(|| GitHostError::Repository(format!("Unrecognised GitLab URL: {url}")))?;
+        let config = find_host_config(url, &self.hosts);
+        let api_url = config
+            .and_then(|config| config.api_url.clone())
+            .unwrap_or_else(|| format!("{}/api/v4", remote.web_url));
+        let token = config
+            .and_then(|config| config.token.clone())
+            .or_else(|| std::env::var("GITLAB_TOKEN").ok())
+            .filter(|token| !token.is_empty());
+        Ok((GitLabApi::new(&api_url, token)?, remote))
+    }
+
+    /// Split `https://host/group/repo/-/merge_requests/12` into the project URL and iid
+    fn parse_merge_request_url(pr_url: &str) -> Result<(&str, i64), GitHostError> {
+        let invalid =
+            || GitHostError::PullRequest(format!("Invalid GitLab merge request URL: {pr_url}"));
+        let (project_url, rest) = pr_url
+            .split_once("/-/merge_requests/")
+            .ok_or_else(invalid)?;
+        let iid: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Ok((project_url, iid.parse().map_err(|_| invalid())?))
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (api, target) = self.api_for(remote_url)?;
+
+        // A merge request from a fork is opened on the fork and points at the target project
+        let source_path = request
+            .head_repo_url
+            .as_deref()
+            .and_then(parse_remote_url)
+            .map(|head| head.path)
+            .filter(|path| path != &target.path);
+        let target_project_id = match &source_path {
+            Some(_) => Some(
+                with_retry(ProviderKind::GitLab, || api.project(&target.path))
+                    .await?
+                    .id,
+            ),
+            None => None,
+        };
+        let project_path = source_path.as_deref().unwrap_or(&target.path);
+
+        let title = if request.draft == Some(true) && !request.title.starts_with("Draft:") {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = GlCreateMergeRequest {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref(),
+            target_project_id,
+        };
+
+        let merge_request = with_retry(ProviderKind::GitLab, || {
+            api.create_merge_request(project_path, &body)
+        })
+        .await?;
+
+        info!(
+            "Created GitLab MR !{} for branch {}",
+            merge_request.iid, request.head_branch
+        );
+
+        Ok(merge_request.into())
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+        let (project_url, iid) = Self::parse_merge_request_url(pr_url)?;
+        let (api, project) = self.api_for(project_url)?;
+        let merge_request = with_retry(ProviderKind::GitLab, || {
+            api.merge_request(&project.path, iid)
+        })
+        .await?;
+        Ok(merge_request.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, project) = self.api_for(remote_url)?;
+        let query = [
+            ("source_branch", branch_name.to_string()),
+            ("state", "all".to_string()),
+        ];
+        let merge_requests = with_retry(ProviderKind::GitLab, || {
+            api.merge_requests(&project.path, &query)
+        })
+        .await?;
+        Ok(merge_requests.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let (api, project) = self.api_for(remote_url)?;
+        let notes =
+            with_retry(ProviderKind::GitLab, || api.notes(&project.path, pr_number)).await?;
+
+        let merge_request_url = format!(
+            "{}/{}/-/merge_requests/{pr_number}",
+            project.web_url, project.path
+        );
+        let mut unified: Vec<UnifiedPrComment> = notes
+            .into_iter()
+            .filter_map(|note| note.into_unified(&merge_request_url))
+            .collect();
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        let (api, project) = self.api_for(remote_url)?;
+
+        let open_query = [("state", "opened".to_string())];
+        let recent_query = [
+            ("state", "all".to_string()),
+            ("order_by", "updated_at".to_string()),
+            ("per_page", RECENT_CLOSED_LIMIT.to_string()),
+        ];
+        let (open, recent) = tokio::join!(
+            with_retry(ProviderKind::GitLab, || api
+                .merge_requests(&project.path, &open_query)),
+            with_retry(ProviderKind::GitLab, || api
+                .merge_requests_page(&project.path, &recent_query)),
+        );
+
+        let mut merge_requests = open?;
+        merge_requests.extend(
+            recent?
+                .into_iter()
+                .filter(|mr| !matches!(mr.state.as_str(), "opened" | "locked")),
+        );
+        merge_requests.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        Ok(merge_requests.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_open_review_threads(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError> {
+        let (api, project) = self.api_for(remote_url)?;
+        let discussions = with_retry(ProviderKind::GitLab, || {
+            api.discussions(&project.path, pr_number)
+        })
+        .await?;
+        Ok(Some(
+            discussions
+                .iter()
+                .filter_map(|discussion| discussion.notes.first())
+                .filter(|note| !note.resolved)
+                .map(|note| note.id)
+                .collect(),
+        ))
+    }
+
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let (project_url, iid) = Self::parse_merge_request_url(pr_url)?;
+        let (api, project) = self.api_for(project_url)?;
+        let merge_request = with_retry(ProviderKind::GitLab, || {
+            api.update_merge_request_target(&project.path, iid, base_branch)
+        })
+        .await?;
+        Ok(merge_request.into())
+    }
+
+    async fn reply_to_review_comment(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+        resolve: bool,
+    ) -> Result<bool, GitHostError> {
+        let (api, project) = self.api_for(remote_url)?;
+        let discussions = with_retry(ProviderKind::GitLab, || {
+            api.discussions(&project.path, pr_number)
+        })
+        .await?;
+        let discussion = discussions
+            .into_iter()
+            .find(|discussion| discussion.notes.iter().any(|note| note.id == comment_id))
+            .ok_or_else(|| {
+                GitHostError::PullRequest(format!(
+                    "Review comment {comment_id} was not found on merge request !{pr_number}"
+                ))
+            })?;
+
+        // Not retried: a retry after a timeout could post the reply twice
+        api.reply_to_discussion(&project.path, pr_number, &discussion.id, body)
+            .await?;
+
+        let resolvable = discussion.notes.first().is_some_and(|note| note.resolvable);
+        if !resolve || !resolvable {
+            return Ok(false);
+        }
+        with_retry(ProviderKind::GitLab, || {
+            api.resolve_discussion(&project.path, pr_number, &discussion.id)
+        })
+        .await?;
+        Ok(true)
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        Json, Router,
+        extract::Path as AxumPath,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, post, put},
+    };
+    use db::models::merge::MergeStatus;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::http::test_support::serve;
+
+    fn provider(api_url: String, token: Option<&str>) -> GitLabProvider {
+        GitLabProvider::new(&[GitHostConfig {
+            host: "gitlab.example.com".to_string(),
+            provider: ProviderKind::GitLab,
+            api_url: Some(api_url),
+            token: token.map(str::to_string),
+        }])
+        .unwrap()
+    }
+
+    fn authorized(headers: &HeaderMap) -> bool {
+        headers
+            .get("private-token")
+            .is_some_and(|token| token == "secret")
+    }
+
+    fn merge_request(iid: i64, state: &str, title: &str) -> Value {
+        json!({
+            "iid": iid,
+            "web_url": format!("https://gitlab.example.com/group/repo/-/merge_requests/{iid}"),
+            "title": title,
+            "state": state,
+            "source_branch": "vk/feature",
+            "target_branch": "main",
+            "merged_at": if state == "merged" { json!("2026-01-02T03:04:05Z") } else { Value::Null },
+            "merge_commit_sha": if state == "merged" { json!("abc123") } else { Value::Null },
+            "squash_commit_sha": null,
+            "updated_at": "2026-01-02T03:04:05Z",
+        })
+    }
+
+    #[tokio::test]
+    async fn get_pr_status_reads_the_merge_request() {
+        let router = Router::new().route(
+            "/projects/{project}/merge_requests/{iid}",
+            get(
+                |AxumPath((project, iid)): AxumPath<(String, i64)>, headers: HeaderMap| async move {
+                    if !authorized(&headers) {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({}))).into_response();
+                    }
+                    assert_eq!(project, "group/repo");
+                    Json(merge_request(iid, "merged", "Add feature")).into_response()
+                },
+            ),
+        );
+        let provider = provider(serve(router).await, Some("secret"));
+
+        let detail = provider
+            .get_pr_status("https://gitlab.example.com/group/repo/-/merge_requests/7")
+            .await
+            .unwrap();
+
+        assert_eq!(detail.number, 7);
+        assert!(matches!(detail.status, MergeStatus::Merged));
+        assert_eq!(detail.merge_commit_sha.as_deref(), Some("abc123"));
+        assert_eq!(detail.head_branch, "vk/feature");
+        assert_eq!(detail.base_branch, "main");
+    }
+
+    #[tokio::test]
+    async fn missing_token_is_an_auth_failure() {
+        let router = Router::new().route(
+            "/projects/{project}/merge_requests/{iid}",
+            get(|| async {
+                (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({"message": "401 Unauthorized"})),
+                )
+            }),
+        );
+        let provider = provider(serve(router).await, None);
+
+        let result = provider
+            .get_pr_status("https://gitlab.example.com/group/repo/-/merge_requests/7")
+            .await;
+
+        assert!(matches!(result, Err(GitHostError::AuthFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn create_pr_marks_drafts_in_the_title() {
+        let router = Router::new().route(
+            "/projects/{project}/merge_requests",
+            post(|Json(body): Json<Value>| async move {
+                assert_eq!(body["source_branch"], "vk/feature");
+                assert_eq!(body["target_branch"], "main");
+                assert!(body.get("target_project_id").is_none());
+                Json(merge_request(3, "opened", body["title"].as_str().unwrap()))
+            }),
+        );
+        let provider = provider(serve(router).await, Some("secret"));
+        let request = CreatePrRequest {
+            title: "Add feature".to_string(),
+            body: Some("Details".to_string()),
+            head_branch: "vk/feature".to_string(),
+            base_branch: "main".to_string(),
+            draft: Some(true),
+            head_repo_url: Some("git@gitlab.example.com:group/repo.git".to_string()),
+        };
+
+        let detail = provider
+            .create_pr(
+                Path::new("."),
+                "git@gitlab.example.com:group/repo.git",
+                &request,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(detail.number, 3);
+        assert_eq!(detail.title, "Draft: Add feature");
+        assert!(matches!(detail.status, MergeStatus::Open));
+    }
+
+    #[tokio::test]
+    async fn get_pr_comments_skips_system_notes_and_keeps_diff_positions() {
+        let router = Router::new().route(
+            "/projects/{project}/merge_requests/{iid}/notes",
+            get(|| async {
+                Json(json!([
+                    {
+                        "id": 1,
+                        "body": "added 1 commit",
+                        "author": { "username": "alice" },
+                        "created_at": "2026-01-01T00:00:00Z",
+                        "system": true,
+                        "position": null
+                    },
+                    {
+                        "id": 2,
+                        "body": "Looks good overall",
+                        "author": { "username": "bob" },
+                        "created_at": "2026-01-01T00:01:00Z",
+                        "system": false,
+                        "position": null
+                    },
+                    {
+                        "id": 3,
+                        "body": "Why was this removed?",
+                        "author": { "username": "carol" },
+                        "created_at": "2026-01-01T00:02:00Z",
+                        "system": false,
+                        "position": {
+                            "new_path": "src/lib.rs",
+                            "old_path": "src/lib.rs",
+                            "new_line": null,
+                            "old_line": 42
+                        }
+                    }
+                ]))
+            }),
+        );
+        let provider = provider(serve(router).await, Some("secret"));
+
+        let comments = provider
+            .get_pr_comments(
+                Path::new("."),
+                "https://gitlab.example.com/group/repo.git",
+                5,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(comments.len(), 2);
+        match &comments[0] {
+            UnifiedPrComment::General { author, url, .. } => {
+                assert_eq!(author, "bob");
+                assert_eq!(
+                    url.as_deref(),
+                    Some("https://gitlab.example.com/group/repo/-/merge_requests/5#note_2")
+                );
+            }
+            other => panic!("expected a general comment, got {other:?}"),
+        }
+        match &comments[1] {
+            UnifiedPrComment::Review {
+                path, line, side, ..
+            } => {
+                assert_eq!(path, "src/lib.rs");
+                assert_eq!(*line, Some(42));
+                assert_eq!(side.as_deref(), Some("LEFT"));
+            }
+            other => panic!("expected a review comment, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn reply_to_review_comment_replies_in_the_discussion_and_resolves_it() {
+        let router = Router::new()
+            .route(
+                "/projects/{project}/merge_requests/{iid}/discussions",
+                get(|| async {
+                    Json(json!([
+                        {
+                            "id": "d1",
+                            "notes": [{
+                                "id": 2,
+                                "body": "Looks good overall",
+                                "author": { "username": "bob" },
+                                "created_at": "2026-01-01T00:01:00Z",
+                                "system": false,
+                                "position": null,
+                                "resolvable": false
+                            }]
+                        },
+                        {
+                            "id": "d2",
+                            "notes": [{
+                                "id": 3,
+                                "body": "Why was this removed?",
+                                "author": { "username": "carol" },
+                                "created_at": "2026-01-01T00:02:00Z",
+                                "system": false,
+                                "position": null,
+                                "resolvable": true
+                            }]
+                        }
+                    ]))
+                }),
+            )
+            .route(
+                "/projects/{project}/merge_requests/{iid}/discussions/{discussion}/notes",
+                post(
+                    |AxumPath((_, _, discussion)): AxumPath<(String, i64, String)>,
+                     Json(body): Json<Value>| async move {
+                        assert_eq!(discussion, "d2");
+                        assert_eq!(body["body"], "Restored it");
+                        Json(json!({
+                            "id": 4,
+                            "body": "Restored it",
+                            "author": { "username": "vk" },
+                            "created_at": "2026-01-01T00:03:00Z",
+                            "system": false,
+                            "position": null
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/projects/{project}/merge_requests/{iid}/discussions/{discussion}",
+                put(
+                    |AxumPath((_, _, discussion)): AxumPath<(String, i64, String)>,
+                     Json(body): Json<Value>| async move {
+                        assert_eq!(discussion, "d2");
+                        assert_eq!(body, json!({ "resolved": true }));
+                        Json(json!({ "id": "d2", "notes": [] }))
+                    },
+                ),
+            );
+        let provider = provider(serve(router).await, Some("secret"));
+
+        let resolved = provider
+            .reply_to_review_comment(
+                Path::new("."),
+                "https://gitlab.example.com/group/repo.git",
+                5,
+                3,
+                "Restored it",
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert!(resolved);
+    }
+
+    #[tokio::test]
+    async fn get_open_review_threads_returns_first_notes_of_unresolved_discussions() {
+        let note = |id: i64, resolved: bool| {
+            json!({
+                "id": id,
+                "body": "Comment",
+                "author": { "username": "bob" },
+                "created_at": "2026-01-01T00:01:00Z",
+                "system": false,
+                "position": null,
+                "resolvable": true,
+                "resolved": resolved
+            })
+        };
+        let router = Router::new().route(
+            "/projects/{project}/merge_requests/{iid}/discussions",
+            get(move || async move {
+                Json(json!([
+                    { "id": "d1", "notes": [note(1, false), note(2, false)] },
+                    { "id": "d2", "notes": [note(3, true), note(4, true)] },
+                    { "id": "d3", "notes": [note(5, false)] }
+                ]))
+            }),
+        );
+        let provider = provider(serve(router).await, Some("secret"));
+
+        let roots = provider
+            .get_open_review_threads(
+                Path::new("."),
+                "https://gitlab.example.com/group/repo.git",
+                5,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(roots, Some(vec![1, 5]));
+    }
+}
@@ -0,0 +1,375 @@
+//! Shared HTTP plumbing for providers that talk to a REST API directly.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{LazyLock, Mutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use backon::{ExponentialBuilder, Retryable};
+use reqwest::{
+    Client, Method, RequestBuilder, StatusCode,
+    header::{ETAG, HeaderMap, IF_NONE_MATCH, RETRY_AFTER},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::types::{GitHostError, ProviderKind};
+
+/// Most pages fetched when listing, so a huge PR cannot stall a request
+const MAX_PAGES: u32 = 10;
+
+/// Most responses kept for conditional requests before the cache is reset
+const MAX_CACHED_RESPONSES: usize = 512;
+
+/// Most characters of a response body quoted in an error message
+const MAX_ERROR_BODY_CHARS: usize = 500;
+
+/// Bodies of earlier GET responses by URL, revalidated with `If-None-Match`.
+/// GitHub does not count a `304 Not Modified` against the rate limit, which
+/// keeps polling PR status cheap.
+static RESPONSE_CACHE: LazyLock<Mutex<HashMap<String, CachedResponse>>> =
+    LazyLock::new(Default::default);
+
+/// Last known rate limit of each API, by base URL and request headers hash,
+/// since every token has its own limit
+static RATE_LIMITS: LazyLock<Mutex<HashMap<(String, u64), RateLimit>>> =
+    LazyLock::new(Default::default);
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    /// Hash of the request headers, so a response is never served to another token
+    headers_hash: u64,
+    etag: String,
+    body: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RateLimit {
+    remaining: u64,
+    /// Unix time at which the limit resets
+    reset_at: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ApiClient {
+    client: Client,
+    provider: ProviderKind,
+    base_url: String,
+    /// Headers sent with every request, such as the one carrying the access token
+    headers: Vec<(&'static str, String)>,
+}
+
+impl ApiClient {
+    pub(crate) fn new(
+        provider: ProviderKind,
+        base_url: &str,
+        headers: Vec<(&'static str, String)>,
+    ) -> Result<Self, GitHostError> {
+        let client = Client::builder()
+            .user_agent("vibe-kanban")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|err| {
+                GitHostError::Repository(format!("Failed to create {provider} API client: {err}"))
+            })?;
+        Ok(Self {
+            client,
+            provider,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            headers,
+        })
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, GitHostError> {
+        self.send(self.request(Method::GET, path).query(query))
+            .await
+    }
+
+    pub(crate) async fn post<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, GitHostError> {
+        self.send(self.request(Method::POST, path).json(body)).await
+    }
+
+    pub(crate) async fn patch<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, GitHostError> {
+        self.send(self.request(Method::PATCH, path).json(body))
+            .await
+    }
+
+    pub(crate) async fn put<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, GitHostError> {
+        self.send(self.request(Method::PUT, path).json(body)).await
+    }
+
+    /// Fetch every page of a list endpoint that pages with `page` and `page_size_param`
+    pub(crate) async fn get_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        page_size_param: &str,
+        page_size: u32,
+    ) -> Result<Vec<T>, GitHostError> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let mut page_query = query.to_vec();
+            page_query.push((page_size_param, page_size.to_string()));
+            page_query.push(("page", page.to_string()));
+            let batch: Vec<T> = self.get(path, &page_query).await?;
+            let done = batch.len() < page_size as usize;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.headers.iter().fold(
+            self.client
+                .request(method, format!("{}{}", self.base_url, path))
+                .header(reqwest::header::ACCEPT, "application/json"),
+            |request, (name, value)| request.header(*name, value),
+        )
+    }
+
+    fn headers_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.headers.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, GitHostError> {
+        let provider = self.provider;
+        if let Some(limit) = self.exhausted_rate_limit() {
+            return Err(GitHostError::RateLimited(format!(
+                "{provider} API rate limit exhausted until {}",
+                format_reset(limit.reset_at)
+            )));
+        }
+
+        let mut request = request.build().map_err(|err| {
+            GitHostError::PullRequest(format!("Failed to build {provider} API request: {err}"))
+        })?;
+        let cache_key = (request.method() == Method::GET).then(|| request.url().to_string());
+        let headers_hash = self.headers_hash();
+        let cached = cache_key.as_ref().and_then(|key| {
+            let cache = RESPONSE_CACHE
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            cache
+                .get(key)
+                .filter(|cached| cached.headers_hash == headers_hash)
+                .cloned()
+        });
+        if let Some(cached) = &cached
+            && let Ok(etag) = cached.etag.parse()
+        {
+            request.headers_mut().insert(IF_NONE_MATCH, etag);
+        }
+
+        let response = self.client.execute(request).await.map_err(|err| {
+            GitHostError::PullRequest(format!("{provider} API request failed: {err}"))
+        })?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        self.record_rate_limit(&headers);
+
+        let body = match (status, cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => cached.body,
+            _ => response.text().await.map_err(|err| {
+                GitHostError::PullRequest(format!("Failed to read {provider} API response: {err}"))
+            })?,
+        };
+        if status != StatusCode::NOT_MODIFIED && !status.is_success() {
+            return Err(status_error(provider, status, &headers, &body));
+        }
+
+        let parsed = serde_json::from_str(&body).map_err(|err| {
+            GitHostError::UnexpectedOutput(format!(
+                "Failed to parse {provider} API response: {err}; raw: {}",
+                truncate_body(&body)
+            ))
+        })?;
+        if status.is_success()
+            && let Some(key) = cache_key
+            && let Some(etag) = headers.get(ETAG).and_then(|etag| etag.to_str().ok())
+        {
+            let mut cache = RESPONSE_CACHE
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if cache.len() >= MAX_CACHED_RESPONSES {
+                cache.clear();
+            }
+            cache.insert(
+                key,
+                CachedResponse {
+                    headers_hash,
+                    etag: etag.to_string(),
+                    body,
+                },
+            );
+        }
+        Ok(parsed)
+    }
+
+    /// The rate limit of this API if the last response said it was used up
+    fn exhausted_rate_limit(&self) -> Option<RateLimit> {
+        let limits = RATE_LIMITS.lock().unwrap_or_else(PoisonError::into_inner);
+        limits
+            .get(&self.rate_limit_key())
+            .copied()
+            .filter(|limit| limit.remaining == 0 && limit.reset_at > unix_now())
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(limit) = rate_limit_from_headers(headers) {
+            RATE_LIMITS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(self.rate_limit_key(), limit);
+        }
+    }
+
+    fn rate_limit_key(&self) -> (String, u64) {
+        (self.base_url.clone(), self.headers_hash())
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+fn format_reset(reset_at: u64) -> String {
+    chrono::DateTime::from_timestamp(reset_at as i64, 0)
+        .map(|reset| reset.to_rfc3339())
+        .unwrap_or_else(|| reset_at.to_string())
+}
+
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    })
+}
+
+/// Read the rate limit from GitHub's `x-ratelimit-*` or GitLab's `ratelimit-*`
+/// headers, or from `retry-after` on a throttled response
+fn rate_limit_from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+    if let Some(retry_after) = header_u64(headers, &[RETRY_AFTER.as_str()]) {
+        return Some(RateLimit {
+            remaining: 0,
+            reset_at: unix_now() + retry_after,
+        });
+    }
+    let remaining = header_u64(headers, &["x-ratelimit-remaining", "ratelimit-remaining"])?;
+    let reset_at = header_u64(headers, &["x-ratelimit-reset", "ratelimit-reset"])?;
+    Some(RateLimit {
+        remaining,
+        reset_at,
+    })
+}
+
+fn status_error(
+    provider: ProviderKind,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> GitHostError {
+    let message = format!("{provider} API returned {status}: {}", truncate_body(body));
+    let throttled = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN
+            && rate_limit_from_headers(headers).is_some_and(|limit| limit.remaining == 0));
+    if throttled {
+        return GitHostError::RateLimited(message);
+    }
+    match status {
+        StatusCode::UNAUTHORIZED => GitHostError::AuthFailed(message),
+        StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(message),
+        StatusCode::NOT_FOUND => GitHostError::RepoNotFoundOrNoAccess(message),
+        _ => GitHostError::PullRequest(message),
+    }
+}
+
+/// `body` trimmed and cut to `MAX_ERROR_BODY_CHARS`, so an HTML error page
+/// doesn't end up in full in an error message
+fn truncate_body(body: &str) -> String {
+    let body = body.trim();
+    match body.char_indices().nth(MAX_ERROR_BODY_CHARS) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+/// Run an API call with the same backoff the CLI-based providers use
+pub(crate) async fn with_retry<T, F, Fut>(
+    provider: ProviderKind,
+    call: F,
+) -> Result<T, GitHostError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, GitHostError>>,
+{
+    call.retry(
+        &ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter(),
+    )
+    .when(|e: &GitHostError| e.should_retry())
+    .notify(|err: &GitHostError, dur: Duration| {
+        tracing::warn!(
+            "{} API call failed, retrying after {:.2}s: {}",
+            provider,
+            dur.as_secs_f64(),
+            err
+        );
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_bodies_are_truncated_on_char_boundaries() {
+        assert_eq!(truncate_body("  not found \n"), "not found");
+        let long = "é".repeat(MAX_ERROR_BODY_CHARS + 10);
+        let truncated = truncate_body(&long);
+        assert_eq!(truncated.chars().count(), MAX_ERROR_BODY_CHARS + 1);
+        assert!(truncated.ends_with('…'));
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    /// Serve `router` on a free local port and return its base URL
+    pub(crate) async fn serve(router: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}")
+    }
+}
@@ -1,21 +1,29 @@
 mod detection;
+mod http;
 mod types;
 
 pub mod azure;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 
 use std::path::Path;
 
 use async_trait::async_trait;
-pub use db::models::merge::PullRequestInfo;
-use detection::detect_provider_from_url;
+pub use db::models::{merge::PullRequestInfo, repo::GitHubAccess};
+use detection::detect_provider;
 use enum_dispatch::enum_dispatch;
 pub use types::{
-    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment, ProviderKind,
-    PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostConfig, GitHostError, GitHostOptions, PrComment, PrCommentAuthor,
+    PrReviewComment, ProviderKind, PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::AzureDevOpsProvider,
+    gitea::GiteaProvider,
+    github::{GitHubProvider, GitHubRestProvider},
+    gitlab::GitLabProvider,
+};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -49,20 +57,74 @@ pub trait GitHostProvider: Send + Sync {
         remote_url: &str,
     ) -> Result<Vec<PullRequestDetail>, GitHostError>;
 
+    /// Ids of the inline review comments that start a thread which isn't
+    /// resolved, leaving out replies. `None` when the host doesn't tell
+    /// threads apart.
+    async fn get_open_review_threads(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Option<Vec<i64>>, GitHostError>;
+
+    /// Point an open pull request at a different base branch
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        base_branch: &str,
+    ) -> Result<PullRequestDetail, GitHostError>;
+
+    /// Reply in the thread of the inline review comment `comment_id` and, with
+    /// `resolve`, mark the thread resolved. Returns whether the thread was
+    /// resolved; hosts without resolvable threads return `false`.
+    async fn reply_to_review_comment(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+        comment_id: i64,
+        body: &str,
+        resolve: bool,
+    ) -> Result<bool, GitHostError>;
+
     fn provider_kind(&self) -> ProviderKind;
 }
 
 #[enum_dispatch]
 pub enum GitHostService {
     GitHub(GitHubProvider),
+    GitHubRest(GitHubRestProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
+    Gitea(GiteaProvider),
 }
 
 impl GitHostService {
-    pub fn from_url(url: &str) -> Result<Self, GitHostError> {
-        match detect_provider_from_url(url) {
-            ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
+    /// Pick the provider for `url`. `options.hosts` maps self-hosted instances,
+    /// which can't be recognised from their URL, to their provider.
+    pub fn from_url(url: &str, options: &GitHostOptions) -> Result<Self, GitHostError> {
+        let hosts = &options.hosts;
+        match detect_provider(url, hosts) {
+            ProviderKind::GitHub => {
+                match (
+                    options.github_access,
+                    GitHubRestProvider::token_for(url, options),
+                ) {
+                    (GitHubAccess::Cli, _) | (GitHubAccess::Auto, None) => {
+                        Ok(Self::GitHub(GitHubProvider::new()?))
+                    }
+                    (GitHubAccess::Api | GitHubAccess::Auto, Some(token)) => {
+                        Ok(Self::GitHubRest(GitHubRestProvider::new(hosts, token)?))
+                    }
+                    (GitHubAccess::Api, None) => Err(GitHostError::AuthFailed(
+                        "GitHub API access needs a token; connect GitHub or set GITHUB_TOKEN"
+                            .to_string(),
+                    )),
+                }
+            }
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new(hosts)?)),
+            ProviderKind::Gitea => Ok(Self::Gitea(GiteaProvider::new(hosts)?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::{
+    merge::{MergeStatus, PullRequestInfo},
+    repo::GitHubAccess,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
@@ -9,6 +12,8 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    GitLab,
+    Gitea,
     Unknown,
 }
 
@@ -17,11 +22,40 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::GitLab => write!(f, "GitLab"),
+            ProviderKind::Gitea => write!(f, "Gitea"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+/// A self-hosted git host that cannot be recognised from its URL alone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct GitHostConfig {
+    /// Host name as it appears in remote URLs, e.g. `git.example.com`. Include
+    /// the port if the remotes do, e.g. `git.example.com:8443`.
+    pub host: String,
+    pub provider: ProviderKind,
+    /// Base URL of the REST API. Defaults to `https://{host}/api/v4` for GitLab
+    /// and `https://{host}/api/v1` for Gitea.
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Access token. Falls back to `GITLAB_TOKEN` or `GITEA_TOKEN`.
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Everything needed to pick and authenticate the provider for a remote
+#[derive(Debug, Clone, Default)]
+pub struct GitHostOptions {
+    pub hosts: Vec<GitHostConfig>,
+    /// Token for the GitHub REST API, usually the PAT or OAuth token from the
+    /// app config. Falls back to `GITHUB_TOKEN` or `GH_TOKEN`.
+    pub github_token: Option<String>,
+    /// Whether GitHub remotes go through the REST API or the `gh` CLI
+    pub github_access: GitHubAccess,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
@@ -53,6 +87,8 @@ pub enum GitHostError {
     UnsupportedProvider,
     #[error("CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
 }
 
 impl GitHostError {
@@ -65,6 +101,7 @@ impl GitHostError {
                 | GitHostError::CliNotInstalled { .. }
                 | GitHostError::NotAGitRepository(_)
                 | GitHostError::UnsupportedProvider
+                | GitHostError::RateLimited(_)
         )
     }
 }
@@ -25,7 +25,10 @@ use std::{
 use thiserror::Error;
 use utils::{path::ALWAYS_SKIP_DIRS, shell::resolve_executable_path_blocking};
 
-use super::Commit;
+use super::{
+    Commit,
+    signing::{CommitSignature, CommitSigning, SIGNATURE_LOG_FORMAT, parse_signatures},
+};
 
 #[derive(Debug, Error)]
 pub enum GitCliError {
@@ -181,7 +184,30 @@ impl GitCli {
         )];
 
         // Use a temp index from HEAD to accurately track renames in untracked files
-        let _ = self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.stage_worktree_into(worktree_path, &envs)?;
+        // git diff --cached
+        let mut args: Vec<OsString> = vec![
+            "-c".into(),
+            "core.quotepath=false".into(),
+            "diff".into(),
+            "--cached".into(),
+            "-M".into(),
+            "--name-status".into(),
+            OsString::from(base_commit.to_string()),
+        ];
+        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
+        let out = self.git_with_env(worktree_path, args, &envs)?;
+        Ok(Self::parse_name_status(&out))
+    }
+
+    /// Fill the index named by `envs` with HEAD plus every change in the
+    /// working tree, including untracked files.
+    fn stage_worktree_into(
+        &self,
+        worktree_path: &Path,
+        envs: &[(OsString, OsString)],
+    ) -> Result<(), GitCliError> {
+        let _ = self.git_with_env(worktree_path, ["read-tree", "HEAD"], envs)?;
 
         // Stage changed and untracked files explicitly, which is faster than `git add -A` for large repos.
         // Use raw paths from `get_worktree_status` to avoid lossy UTF-8 conversions for odd filenames.
@@ -210,21 +236,45 @@ impl GitCli {
                 OsString::from("--pathspec-from-file=-"),
                 OsString::from("--pathspec-file-nul"),
             ];
-            self.git_with_stdin(worktree_path, args, Some(&envs), &input)?;
+            self.git_with_stdin(worktree_path, args, Some(envs), &input)?;
         }
-        // git diff --cached
-        let mut args: Vec<OsString> = vec![
-            "-c".into(),
-            "core.quotepath=false".into(),
-            "diff".into(),
-            "--cached".into(),
-            "-M".into(),
-            "--name-status".into(),
-            OsString::from(base_commit.to_string()),
-        ];
-        args = Self::apply_pathspec_filter(args, opts.path_filter.as_ref());
-        let out = self.git_with_env(worktree_path, args, &envs)?;
-        Ok(Self::parse_name_status(&out))
+        Ok(())
+    }
+
+    /// Write the working tree, including untracked files, as a tree object
+    /// without touching the index. Returns the tree id.
+    pub fn write_worktree_tree(&self, worktree_path: &Path) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+
+        self.stage_worktree_into(worktree_path, &envs)?;
+        let tree = self.git_with_env(worktree_path, ["write-tree"], &envs)?;
+        Ok(tree.trim().to_string())
+    }
+
+    /// Commit the working tree, including untracked files, on top of HEAD
+    /// without touching the index, HEAD or any branch. Returns the commit id.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let tree = self.write_worktree_tree(worktree_path)?;
+        let commit = self.git(
+            worktree_path,
+            ["commit-tree", &tree, "-p", "HEAD", "-m", message],
+        )?;
+        Ok(commit.trim().to_string())
+    }
+
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["update-ref", "-d", refname])
+            .map(|_| ())
     }
 
     /// Return `git status --porcelain` parsed into a structured summary
@@ -338,10 +388,63 @@ impl GitCli {
     }
 
     /// Commit staged changes with the given message.
-    pub fn commit(&self, worktree_path: &Path, message: &str) -> Result<(), GitCliError> {
-        self.git(worktree_path, ["commit", "-m", message])?;
+    pub fn commit(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitCliError> {
+        self.git_signed(worktree_path, signing, ["commit", "-m", message])?;
         Ok(())
     }
+
+    /// Create a commit of `tree` with the given parents without touching any
+    /// ref or the worktree. Returns the new commit's sha.
+    pub fn commit_tree(
+        &self,
+        repo_path: &Path,
+        tree: &str,
+        parents: &[&str],
+        message: &str,
+        identity: (&str, &str),
+        signing: Option<&CommitSigning>,
+    ) -> Result<String, GitCliError> {
+        let mut args: Vec<OsString> = vec!["commit-tree".into(), tree.into()];
+        for parent in parents {
+            args.push("-p".into());
+            args.push(parent.into());
+        }
+        args.push("-m".into());
+        args.push(message.into());
+        // Unlike `commit`, `commit-tree` ignores `commit.gpgsign`
+        if signing.is_some() {
+            args.push("-S".into());
+        }
+        let (name, email) = identity;
+        let envs: Vec<(OsString, OsString)> = [
+            ("GIT_AUTHOR_NAME", name),
+            ("GIT_AUTHOR_EMAIL", email),
+            ("GIT_COMMITTER_NAME", name),
+            ("GIT_COMMITTER_EMAIL", email),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect();
+        let out = self.git_with_env(repo_path, Self::with_signing(signing, args), &envs)?;
+        Ok(out.trim().to_string())
+    }
+
+    /// Signature verification of the commits `revs` selects, newest first
+    pub fn commit_signatures(
+        &self,
+        repo_path: &Path,
+        revs: &[&str],
+    ) -> Result<Vec<CommitSignature>, GitCliError> {
+        let mut args = vec!["log", SIGNATURE_LOG_FORMAT];
+        args.extend_from_slice(revs);
+        let out = self.git(repo_path, args)?;
+        Ok(parse_signatures(&out))
+    }
     /// Fetch a branch to the given remote using native git authentication.
     pub fn fetch_with_refspec(
         &self,
@@ -523,13 +626,15 @@ impl GitCli {
         Ok(out.trim().to_string())
     }
 
-    /// Perform `git rebase --onto <new_base> <old_base>` on <task_branch> in `worktree_path`.
+    /// Perform `git rebase --onto <new_base> <old_base>` on <task_branch> in `worktree_path`,
+    /// signing the replayed commits when `signing` is given.
     pub fn rebase_onto(
         &self,
         worktree_path: &Path,
         new_base: &str,
         old_base: &str,
         task_branch: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<(), GitCliError> {
         // If a rebase is in progress, refuse to proceed. The caller can
         // choose to abort or continue; we avoid destructive actions here.
@@ -541,8 +646,9 @@ impl GitCli {
             .merge_base(worktree_path, old_base, task_branch)
             .unwrap_or(old_base.to_string());
 
-        self.git(
+        self.git_signed(
             worktree_path,
+            signing,
             ["rebase", "--onto", new_base, &merge_base, task_branch],
         )?;
         Ok(())
@@ -607,16 +713,141 @@ impl GitCli {
         self.git(worktree_path, ["rebase", "--quit"]).map(|_| ())
     }
 
-    /// Continue an in-progress rebase. Returns error if no rebase is in progress
-    /// or if there are unresolved conflicts.
-    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+    /// Continue an in-progress rebase, signing the commits it creates when
+    /// `signing` is given. Returns error if no rebase is in progress or if
+    /// there are unresolved conflicts.
+    pub fn continue_rebase(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitCliError> {
         if !self.is_rebase_in_progress(worktree_path)? {
             return Err(GitCliError::CommandFailed(
                 "No rebase in progress".to_string(),
             ));
         }
-        self.git(worktree_path, ["rebase", "--continue"])
-            .map(|_| ())
+        self.git_with_env(
+            worktree_path,
+            Self::with_signing(signing, ["rebase", "--continue"]),
+            &Self::non_interactive_editor(),
+        )
+        .map(|_| ())
+    }
+
+    /// Conclude an in-progress merge once its conflicts are staged, keeping
+    /// the prepared merge message.
+    pub fn continue_merge(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitCliError> {
+        if !self.is_merge_in_progress(worktree_path)? {
+            return Err(GitCliError::CommandFailed(
+                "No merge in progress".to_string(),
+            ));
+        }
+        self.git_with_env(
+            worktree_path,
+            Self::with_signing(signing, ["merge", "--continue"]),
+            &Self::non_interactive_editor(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_cherry_pick(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitCliError> {
+        if !self.is_cherry_pick_in_progress(worktree_path)? {
+            return Err(GitCliError::CommandFailed(
+                "No cherry-pick in progress".to_string(),
+            ));
+        }
+        self.git_with_env(
+            worktree_path,
+            Self::with_signing(signing, ["cherry-pick", "--continue"]),
+            &Self::non_interactive_editor(),
+        )
+        .map(|_| ())
+    }
+
+    pub fn continue_revert(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitCliError> {
+        if !self.is_revert_in_progress(worktree_path)? {
+            return Err(GitCliError::CommandFailed(
+                "No revert in progress".to_string(),
+            ));
+        }
+        self.git_with_env(
+            worktree_path,
+            Self::with_signing(signing, ["revert", "--continue"]),
+            &Self::non_interactive_editor(),
+        )
+        .map(|_| ())
+    }
+
+    /// Stage the given paths, including deletions.
+    pub fn add_paths(&self, worktree_path: &Path, paths: &[String]) -> Result<(), GitCliError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args: Vec<OsString> = vec!["add".into(), "-A".into(), "--".into()];
+        args.extend(paths.iter().map(OsString::from));
+        self.git(worktree_path, args).map(|_| ())
+    }
+
+    /// Write `content` to the object database as the content of `path`,
+    /// applying the path's clean filters, and return the blob id.
+    pub fn hash_object(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        content: &[u8],
+    ) -> Result<String, GitCliError> {
+        let path_arg = format!("--path={path}");
+        let out = self.git_with_stdin(
+            worktree_path,
+            ["hash-object", "-w", "--stdin", path_arg.as_str()],
+            None,
+            content,
+        )?;
+        Ok(out.trim().to_string())
+    }
+
+    /// Point the index entry of `path` at the blob `oid`, adding it if needed.
+    pub fn update_index_entry(
+        &self,
+        worktree_path: &Path,
+        mode: u32,
+        oid: &str,
+        path: &str,
+    ) -> Result<(), GitCliError> {
+        let cacheinfo = format!("{mode:o},{oid},{path}");
+        self.git(
+            worktree_path,
+            ["update-index", "--add", "--cacheinfo", cacheinfo.as_str()],
+        )
+        .map(|_| ())
+    }
+
+    /// Restore `path` in the working tree to its content at `source`, leaving
+    /// the index alone.
+    pub fn restore_worktree_path(
+        &self,
+        worktree_path: &Path,
+        source: &str,
+        path: &str,
+    ) -> Result<(), GitCliError> {
+        let source_arg = format!("--source={source}");
+        self.git(
+            worktree_path,
+            ["restore", "--worktree", source_arg.as_str(), "--", path],
+        )
+        .map(|_| ())
     }
 
     /// Return true if there are staged changes (index differs from HEAD)
@@ -649,11 +880,12 @@ impl GitCli {
         base_branch: &str,
         from_branch: &str,
         message: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<String, GitCliError> {
         self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
         self.git(repo_path, ["merge", "--squash", "--no-commit", from_branch])
             .map(|_| ())?;
-        self.git(repo_path, ["commit", "-m", message]).map(|_| ())?;
+        self.commit(repo_path, message, signing)?;
         let sha = self
             .git(repo_path, ["rev-parse", "HEAD"])?
             .trim()
@@ -674,11 +906,13 @@ impl GitCli {
         base_branch: &str,
         from_branch: &str,
         message: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<String, GitCliError> {
         self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
         if let Err(e) = self
-            .git(
+            .git_signed(
                 repo_path,
+                signing,
                 ["merge", "--no-ff", "--no-edit", "-m", message, from_branch],
             )
             .map(|_| ())
@@ -714,6 +948,8 @@ impl GitCli {
     ///   `git rebase` refuses to start with a dirty index, so a successful
     ///   rebase implies the worktree was clean at the call boundary.
     ///
+    /// The replayed commits are signed when `signing` is given.
+    ///
     /// Returns the new HEAD sha on the base branch.
     pub fn merge_rebase(
         &self,
@@ -721,6 +957,7 @@ impl GitCli {
         base_repo_path: &Path,
         base_branch: &str,
         from_branch: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<String, GitCliError> {
         // Capture the original task branch tip so we can roll the rebase back
         // if the subsequent fast-forward step fails.
@@ -735,7 +972,11 @@ impl GitCli {
         // at in the task worktree. `git rebase` will check out `from_branch`
         // before replaying commits.
         if let Err(e) = self
-            .git(task_repo_path, ["rebase", base_branch, from_branch])
+            .git_signed(
+                task_repo_path,
+                signing,
+                ["rebase", base_branch, from_branch],
+            )
             .map(|_| ())
         {
             // Best-effort cleanup so we don't leave the worktree mid-rebase.
@@ -846,6 +1087,11 @@ impl GitCli {
 
 // Private methods
 impl GitCli {
+    /// Accept the prepared commit message instead of waiting on an editor
+    fn non_interactive_editor() -> Vec<(OsString, OsString)> {
+        vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))]
+    }
+
     fn classify_cli_error(&self, msg: String) -> GitCliError {
         let lower = msg.to_ascii_lowercase();
         if lower.contains("authentication failed")
@@ -981,6 +1227,34 @@ impl GitCli {
         Ok(String::from_utf8_lossy(&out).to_string())
     }
 
+    /// Run a git command that creates commits, signing them when `signing`
+    /// is given
+    fn git_signed<I, S>(
+        &self,
+        repo_path: &Path,
+        signing: Option<&CommitSigning>,
+        args: I,
+    ) -> Result<String, GitCliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.git(repo_path, Self::with_signing(signing, args))
+    }
+
+    fn with_signing<I, S>(signing: Option<&CommitSigning>, args: I) -> Vec<OsString>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        signing
+            .map(CommitSigning::config_args)
+            .unwrap_or_default()
+            .into_iter()
+            .chain(args.into_iter().map(|a| a.as_ref().to_os_string()))
+            .collect()
+    }
+
     fn git_with_env<I, S>(
         &self,
         repo_path: &Path,
@@ -0,0 +1,193 @@
+//! Hunk-level selection of a file's changes.
+//!
+//! Hunks are computed in-process from the two sides of a file with the same
+//! three lines of context `git diff` uses, so a hunk is identified by the
+//! start lines of its `@@ -old_start,.. +new_start,.. @@` header. Applying a
+//! subset of hunks rebuilds the new side from the old one line by line, which
+//! avoids round-tripping patches through `git apply`.
+
+use git2::{DiffOptions, Patch};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// One hunk of a file's changes, as `git diff` prints it
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    /// Each line prefixed with ` `, `+` or `-`, without its line ending
+    pub lines: Vec<String>,
+}
+
+/// Identifies a hunk by the start lines of its header
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct DiffHunkId {
+    pub old_start: u32,
+    pub new_start: u32,
+}
+
+/// The changes of one file to act on
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DiffSelection {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Hunks of the file to act on. The whole file when omitted.
+    #[serde(default)]
+    pub hunks: Option<Vec<DiffHunkId>>,
+}
+
+/// A hunk together with the raw bytes of its lines
+#[derive(Debug, Clone)]
+pub(crate) struct RawHunk {
+    pub(crate) hunk: DiffHunk,
+    /// Origin (` `, `+` or `-`) and content including the line ending
+    lines: Vec<(char, Vec<u8>)>,
+}
+
+impl RawHunk {
+    pub(crate) fn id(&self) -> DiffHunkId {
+        DiffHunkId {
+            old_start: self.hunk.old_start,
+            new_start: self.hunk.new_start,
+        }
+    }
+}
+
+/// The hunks turning `old` into `new`. Binary content has no hunks.
+pub(crate) fn file_hunks(old: &[u8], new: &[u8]) -> Result<Vec<RawHunk>, git2::Error> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(3);
+    let patch = Patch::from_buffers(old, None, new, None, Some(&mut opts))?;
+    if patch.delta().flags().is_binary() {
+        return Ok(Vec::new());
+    }
+
+    let mut hunks = Vec::with_capacity(patch.num_hunks());
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx)?;
+        let header = String::from_utf8_lossy(hunk.header())
+            .trim_end()
+            .to_string();
+        let mut raw = RawHunk {
+            hunk: DiffHunk {
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                header,
+                lines: Vec::with_capacity(line_count),
+            },
+            lines: Vec::with_capacity(line_count),
+        };
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            // Skip the "\ No newline at end of file" markers; the content of
+            // the last line already tells whether it ends with a newline
+            let origin = line.origin();
+            if !matches!(origin, ' ' | '+' | '-') {
+                continue;
+            }
+            let content = line.content().to_vec();
+            let text = String::from_utf8_lossy(&content);
+            raw.hunk
+                .lines
+                .push(format!("{origin}{}", text.trim_end_matches(['\n', '\r'])));
+            raw.lines.push((origin, content));
+        }
+        hunks.push(raw);
+    }
+    Ok(hunks)
+}
+
+/// Rebuild a file from `old`, applying the hunks for which `apply` returns
+/// true and leaving the old lines in place for the rest. `hunks` must have
+/// been computed from `old`.
+pub(crate) fn apply_hunks(
+    old: &[u8],
+    hunks: &[RawHunk],
+    apply: impl Fn(&RawHunk) -> bool,
+) -> Vec<u8> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|b| *b == b'\n').collect();
+    let mut out = Vec::with_capacity(old.len());
+    // Index of the next old line not yet copied
+    let mut next = 0usize;
+    for hunk in hunks {
+        // A hunk that only adds lines starts after line `old_start`, every
+        // other hunk starts at it
+        let start = if hunk.hunk.old_lines == 0 {
+            hunk.hunk.old_start as usize
+        } else {
+            (hunk.hunk.old_start as usize).saturating_sub(1)
+        };
+        for line in &old_lines[next.min(old_lines.len())..start.min(old_lines.len())] {
+            out.extend_from_slice(line);
+        }
+        next = start + hunk.hunk.old_lines as usize;
+
+        let keep_origin = if apply(hunk) { '-' } else { '+' };
+        for (origin, content) in &hunk.lines {
+            if *origin != keep_origin {
+                out.extend_from_slice(content);
+            }
+        }
+    }
+    for line in &old_lines[next.min(old_lines.len())..] {
+        out.extend_from_slice(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\n";
+    const NEW: &str = "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nn\nnew\n";
+
+    #[test]
+    fn hunks_follow_git_diff() {
+        let hunks = file_hunks(OLD.as_bytes(), NEW.as_bytes()).unwrap();
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].hunk.header, "@@ -1,4 +1,4 @@");
+        assert_eq!(hunks[0].hunk.lines, ["-a", "+A", " b", " c", " d"]);
+        assert_eq!(hunks[1].hunk.header, "@@ -11,4 +11,4 @@");
+        assert_eq!(hunks[1].hunk.lines, [" k", " l", "-m", " n", "+new"]);
+    }
+
+    #[test]
+    fn applying_a_subset_of_hunks_keeps_the_rest_old() {
+        let hunks = file_hunks(OLD.as_bytes(), NEW.as_bytes()).unwrap();
+
+        let all = apply_hunks(OLD.as_bytes(), &hunks, |_| true);
+        let none = apply_hunks(OLD.as_bytes(), &hunks, |_| false);
+        let first = apply_hunks(OLD.as_bytes(), &hunks, |h| h.hunk.old_start == 1);
+
+        assert_eq!(all, NEW.as_bytes());
+        assert_eq!(none, OLD.as_bytes());
+        assert_eq!(
+            String::from_utf8(first).unwrap(),
+            "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\n"
+        );
+    }
+
+    #[test]
+    fn applies_pure_additions_and_missing_trailing_newlines() {
+        let old = "one\ntwo";
+        let new = "zero\none\ntwo\nthree";
+        let hunks = file_hunks(old.as_bytes(), new.as_bytes()).unwrap();
+
+        assert_eq!(
+            apply_hunks(old.as_bytes(), &hunks, |_| true),
+            new.as_bytes()
+        );
+        assert_eq!(
+            apply_hunks(b"", &file_hunks(b"", b"x\n").unwrap(), |_| true),
+            b"x\n"
+        );
+    }
+}
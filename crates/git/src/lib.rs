@@ -11,10 +11,14 @@ use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind};
 
 mod cli;
+mod hunks;
+mod signing;
 mod validation;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+pub use hunks::{DiffHunk, DiffHunkId, DiffSelection};
+pub use signing::{CommitSignature, CommitSigning, SignatureStatus, SigningFormat};
 pub use utils::path::ALWAYS_SKIP_DIRS;
 pub use validation::is_valid_branch_prefix;
 
@@ -41,6 +45,19 @@ impl MergeStrategy {
     }
 }
 
+impl std::str::FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "squash" => Ok(MergeStrategy::Squash),
+            "rebase" => Ok(MergeStrategy::Rebase),
+            "merge" => Ok(MergeStrategy::Merge),
+            other => Err(format!("unknown merge strategy '{other}'")),
+        }
+    }
+}
+
 /// Statistics for a single file based on git history
 #[derive(Clone, Debug)]
 pub struct FileStat {
@@ -75,6 +92,16 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error(
+        "No hunk at -{old_start} +{new_start} in {path}; the file changed since its diff was read"
+    )]
+    HunkNotFound {
+        path: String,
+        old_start: u32,
+        new_start: u32,
+    },
 }
 
 /// Service for managing Git operations in task execution workflows
@@ -95,6 +122,27 @@ pub enum ConflictOp {
     Revert,
 }
 
+impl ConflictOp {
+    /// The serialized name, e.g. `cherry_pick`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "rebase",
+            ConflictOp::Merge => "merge",
+            ConflictOp::CherryPick => "cherry_pick",
+            ConflictOp::Revert => "revert",
+        }
+    }
+}
+
+/// Subjects of the two commits whose changes conflict
+#[derive(Debug, Clone, Default)]
+pub struct ConflictCommits {
+    /// The commit being built on (HEAD)
+    pub ours: Option<String>,
+    /// The commit being applied
+    pub theirs: Option<String>,
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -134,6 +182,18 @@ impl std::fmt::Display for Commit {
     }
 }
 
+/// A snapshot of a worktree, uncommitted changes included
+#[derive(Debug, Clone)]
+pub struct CheckpointSnapshot {
+    /// Commit holding the whole worktree, with HEAD as its parent
+    pub commit: String,
+    /// HEAD when the snapshot was taken
+    pub head: String,
+    pub files_changed: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct WorktreeResetOptions {
     pub perform_reset: bool,
@@ -327,6 +387,17 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_signed(path, message, None)
+    }
+
+    /// Stage and commit all changes, signing the commit when `signing` is
+    /// given. Returns false when there is nothing to commit.
+    pub fn commit_signed(
+        &self,
+        path: &Path,
+        message: &str,
+        signing: Option<&CommitSigning>,
+    ) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -341,11 +412,236 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
         // Only ensure identity once we know we're about to commit
         self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
+        git.commit(path, message, signing)
             .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
         Ok(true)
     }
 
+    /// Commit what is staged, leaving unstaged changes in the worktree.
+    /// Returns false when nothing is staged.
+    pub fn commit_staged(
+        &self,
+        path: &Path,
+        message: &str,
+        signing: Option<&CommitSigning>,
+    ) -> Result<bool, GitServiceError> {
+        let git = GitCli::new();
+        let has_staged = git.has_staged_changes(path).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git diff --cached failed: {e}"))
+        })?;
+        if !has_staged {
+            return Ok(false);
+        }
+        self.ensure_cli_commit_identity(path)?;
+        git.commit(path, message, signing)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        Ok(true)
+    }
+
+    /// Hunks of the worktree's changes to `path`, against `base` or, when
+    /// `None`, against the index
+    pub fn get_file_hunks(
+        &self,
+        worktree_path: &Path,
+        base: Option<&Commit>,
+        path: &str,
+    ) -> Result<Vec<DiffHunk>, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let abs_path = Self::worktree_file(worktree_path, path)?;
+        let old = Self::old_side(&repo, base, path)?
+            .map(|(content, _)| content)
+            .unwrap_or_default();
+        let new = Self::read_worktree_file(&abs_path)?.unwrap_or_default();
+        Ok(hunks::file_hunks(&old, &new)?
+            .into_iter()
+            .map(|hunk| hunk.hunk)
+            .collect())
+    }
+
+    /// Undo the selected changes of the worktree relative to `base`, leaving
+    /// the index untouched. A whole file is restored to its content at `base`,
+    /// or removed if it did not exist there. Every selection is checked before
+    /// the worktree is changed.
+    pub fn revert_changes(
+        &self,
+        worktree_path: &Path,
+        base: &Commit,
+        selections: &[DiffSelection],
+    ) -> Result<(), GitServiceError> {
+        enum Revert {
+            Write(PathBuf, Vec<u8>),
+            Restore(String),
+            Remove(PathBuf),
+        }
+
+        let repo = self.open_repo(worktree_path)?;
+        let mut reverts = Vec::with_capacity(selections.len());
+        for selection in selections {
+            let abs_path = Self::worktree_file(worktree_path, &selection.path)?;
+            let old = Self::old_side(&repo, Some(base), &selection.path)?;
+            if let Some(ids) = &selection.hunks {
+                let old = old.as_ref().map(|(content, _)| content.as_slice());
+                let new = Self::read_worktree_file(&abs_path)?.unwrap_or_default();
+                let hunks =
+                    Self::selected_hunks(&selection.path, old.unwrap_or_default(), &new, ids)?;
+                if !hunks.iter().all(|hunk| ids.contains(&hunk.id())) {
+                    let content = hunks::apply_hunks(old.unwrap_or_default(), &hunks, |hunk| {
+                        !ids.contains(&hunk.id())
+                    });
+                    reverts.push(Revert::Write(abs_path, content));
+                    continue;
+                }
+            }
+            reverts.push(match old {
+                Some(_) => Revert::Restore(selection.path.clone()),
+                None => Revert::Remove(abs_path),
+            });
+        }
+
+        let git = GitCli::new();
+        let source = base.to_string();
+        for revert in reverts {
+            match revert {
+                Revert::Write(abs_path, content) => std::fs::write(abs_path, content)?,
+                Revert::Restore(path) => {
+                    git.restore_worktree_path(worktree_path, &source, &path)?
+                }
+                Revert::Remove(abs_path) => {
+                    if let Err(e) = std::fs::remove_file(abs_path)
+                        && e.kind() != std::io::ErrorKind::NotFound
+                    {
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stage the selected changes of the worktree relative to the index.
+    /// Partially staged files keep their other hunks as unstaged changes.
+    /// Every selection is checked before the index is changed.
+    pub fn stage_changes(
+        &self,
+        worktree_path: &Path,
+        selections: &[DiffSelection],
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let mut whole_files = Vec::new();
+        let mut partial_files = Vec::new();
+        for selection in selections {
+            let abs_path = Self::worktree_file(worktree_path, &selection.path)?;
+            let Some(ids) = &selection.hunks else {
+                whole_files.push(selection.path.clone());
+                continue;
+            };
+            let (old, mode) = Self::old_side(&repo, None, &selection.path)?
+                .unwrap_or_else(|| (Vec::new(), Self::worktree_file_mode(&abs_path)));
+            let new = Self::read_worktree_file(&abs_path)?.unwrap_or_default();
+            let hunks = Self::selected_hunks(&selection.path, &old, &new, ids)?;
+            if hunks.iter().all(|hunk| ids.contains(&hunk.id())) {
+                whole_files.push(selection.path.clone());
+                continue;
+            }
+            let content = hunks::apply_hunks(&old, &hunks, |hunk| ids.contains(&hunk.id()));
+            partial_files.push((selection.path.as_str(), mode, content));
+        }
+
+        let git = GitCli::new();
+        for (path, mode, content) in partial_files {
+            let oid = git.hash_object(worktree_path, path, &content)?;
+            git.update_index_entry(worktree_path, mode, &oid, path)?;
+        }
+        git.add_paths(worktree_path, &whole_files)?;
+        Ok(())
+    }
+
+    /// `path` joined onto the worktree, rejecting paths that leave it
+    fn worktree_file(worktree_path: &Path, path: &str) -> Result<PathBuf, GitServiceError> {
+        let rel = Path::new(path);
+        if path.is_empty()
+            || !rel
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(GitServiceError::InvalidPath(path.to_string()));
+        }
+        Ok(worktree_path.join(rel))
+    }
+
+    /// Content and mode of `path` at `base` or, when `None`, in the index
+    fn old_side(
+        repo: &Repository,
+        base: Option<&Commit>,
+        path: &str,
+    ) -> Result<Option<(Vec<u8>, u32)>, GitServiceError> {
+        let rel = Path::new(path);
+        let Some(base) = base else {
+            let index = repo.index()?;
+            return match index.get_path(rel, 0) {
+                Some(entry) => Ok(Some((
+                    repo.find_blob(entry.id)?.content().to_vec(),
+                    entry.mode,
+                ))),
+                None => Ok(None),
+            };
+        };
+        let tree = repo.find_commit(base.as_oid())?.tree()?;
+        match tree.get_path(rel) {
+            Ok(entry) if entry.kind() == Some(git2::ObjectType::Blob) => Ok(Some((
+                repo.find_blob(entry.id())?.content().to_vec(),
+                entry.filemode() as u32,
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn read_worktree_file(abs_path: &Path) -> Result<Option<Vec<u8>>, GitServiceError> {
+        match std::fs::read(abs_path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Index mode for a file that is not in the index yet
+    fn worktree_file_mode(abs_path: &Path) -> u32 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(metadata) = std::fs::metadata(abs_path)
+                && metadata.permissions().mode() & 0o111 != 0
+            {
+                return 0o100755;
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = abs_path;
+        0o100644
+    }
+
+    /// The hunks turning `old` into `new`, after checking every id in `ids`
+    /// names one of them
+    fn selected_hunks(
+        path: &str,
+        old: &[u8],
+        new: &[u8],
+        ids: &[DiffHunkId],
+    ) -> Result<Vec<hunks::RawHunk>, GitServiceError> {
+        let hunks = hunks::file_hunks(old, new)?;
+        if let Some(missing) = ids
+            .iter()
+            .find(|id| !hunks.iter().any(|hunk| hunk.id() == **id))
+        {
+            return Err(GitServiceError::HunkNotFound {
+                path: path.to_string(),
+                old_start: missing.old_start,
+                new_start: missing.new_start,
+            });
+        }
+        Ok(hunks)
+    }
+
     /// Get worktree diffs against a base commit
     pub fn get_diffs(
         &self,
@@ -596,7 +892,9 @@ impl GitService {
 
     /// Merge changes from a task branch into the base branch using the
     /// specified strategy. The legacy behavior corresponds to
-    /// `MergeStrategy::Squash`.
+    /// `MergeStrategy::Squash`. The commits the merge creates are signed
+    /// when `signing` is given.
+    #[allow(clippy::too_many_arguments)]
     pub fn merge_changes(
         &self,
         base_worktree_path: &Path,
@@ -605,6 +903,7 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
         strategy: MergeStrategy,
+        signing: Option<&CommitSigning>,
     ) -> Result<String, GitServiceError> {
         // Open the repositories
         let task_repo = self.open_repo(task_worktree_path)?;
@@ -655,18 +954,21 @@ impl GitService {
                         base_branch_name,
                         task_branch_name,
                         commit_message,
+                        signing,
                     ),
                     MergeStrategy::Merge => git_cli.merge_no_ff_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
+                        signing,
                     ),
                     MergeStrategy::Rebase => git_cli.merge_rebase(
                         task_worktree_path,
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
+                        signing,
                     ),
                 }
                 .map_err(|e| {
@@ -721,6 +1023,7 @@ impl GitService {
                     &signature,
                     commit_message,
                     base_branch_name,
+                    signing,
                 )?;
 
                 // Update the task branch to the new squash commit so follow-up
@@ -908,6 +1211,29 @@ impl GitService {
         Ok(HeadInfo { branch, oid })
     }
 
+    /// Signature verification of the worktree's HEAD commit
+    pub fn get_head_signature(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Option<CommitSignature>, GitServiceError> {
+        let signatures = GitCli::new().commit_signatures(worktree_path, &["-1", "HEAD"])?;
+        Ok(signatures.into_iter().next())
+    }
+
+    /// Number of commits on HEAD but not on `base` that carry no signature
+    pub fn count_unsigned_commits(
+        &self,
+        worktree_path: &Path,
+        base: &str,
+    ) -> Result<usize, GitServiceError> {
+        let range = format!("{base}..HEAD");
+        let signatures = GitCli::new().commit_signatures(worktree_path, &[&range])?;
+        Ok(signatures
+            .iter()
+            .filter(|signature| !signature.status.is_signed())
+            .count())
+    }
+
     pub fn get_current_branch(&self, repo_path: &Path) -> Result<String, GitServiceError> {
         Ok(self.get_head_info(repo_path)?.branch)
     }
@@ -1022,6 +1348,80 @@ impl GitService {
         Ok(())
     }
 
+    /// Snapshot the worktree, untracked files included, without touching its
+    /// index or branch, and keep the snapshot reachable from `ref_name`. Diff
+    /// stats are counted from `since`, or from HEAD when `None`.
+    pub fn create_checkpoint(
+        &self,
+        worktree_path: &Path,
+        ref_name: &str,
+        message: &str,
+        since: Option<&str>,
+    ) -> Result<CheckpointSnapshot, GitServiceError> {
+        let head = self.get_head_info(worktree_path)?.oid;
+        self.ensure_cli_commit_identity(worktree_path)?;
+        let git = GitCli::new();
+        let commit = git.snapshot_worktree(worktree_path, message)?;
+        git.update_ref(worktree_path, ref_name, &commit)?;
+
+        let repo = self.open_repo(worktree_path)?;
+        let from_tree = repo
+            .revparse_single(since.unwrap_or(&head))?
+            .peel_to_tree()?;
+        let to_tree = repo.revparse_single(&commit)?.peel_to_tree()?;
+        let stats = repo
+            .diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?
+            .stats()?;
+
+        Ok(CheckpointSnapshot {
+            commit,
+            head,
+            files_changed: stats.files_changed(),
+            lines_added: stats.insertions(),
+            lines_removed: stats.deletions(),
+        })
+    }
+
+    /// Put the worktree back to a snapshot from `create_checkpoint`: the
+    /// branch is reset to the snapshot's HEAD and its uncommitted changes are
+    /// restored unstaged. If `force` is false and the worktree is dirty,
+    /// returns WorktreeDirty error.
+    pub fn restore_checkpoint(
+        &self,
+        worktree_path: &Path,
+        head: &str,
+        commit: &str,
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        self.reset_worktree_to_commit(worktree_path, head, force)?;
+        let git = GitCli::new();
+        git.git(worktree_path, ["read-tree", "-u", "--reset", commit])
+            .map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git read-tree failed: {e}"))
+            })?;
+        git.git(worktree_path, ["reset", "-q"])
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git reset failed: {e}")))?;
+        Ok(())
+    }
+
+    /// Whether the worktree, uncommitted and untracked files included, has
+    /// exactly the content of `commit`
+    pub fn worktree_matches_commit(
+        &self,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<bool, GitServiceError> {
+        let tree = GitCli::new().write_worktree_tree(worktree_path)?;
+        let repo = self.open_repo(worktree_path)?;
+        let commit_tree = repo.revparse_single(commit)?.peel_to_tree()?;
+        Ok(commit_tree.id().to_string() == tree)
+    }
+
+    pub fn delete_ref(&self, repo_path: &Path, ref_name: &str) -> Result<(), GitServiceError> {
+        GitCli::new().delete_ref(repo_path, ref_name)?;
+        Ok(())
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
@@ -1145,6 +1545,7 @@ impl GitService {
     }
 
     /// Perform a squash merge of task branch into base branch, but fail on conflicts
+    #[allow(clippy::too_many_arguments)]
     fn perform_squash_merge(
         &self,
         repo: &Repository,
@@ -1153,6 +1554,7 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
@@ -1175,14 +1577,36 @@ impl GitService {
         let tree = repo.find_tree(tree_id)?;
 
         // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
-            None,           // Don't update any reference yet
-            signature,      // Author
-            signature,      // Committer
-            commit_message, // Custom message
-            &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
-        )?;
+        let squash_commit_id = match signing {
+            // libgit2 can't invoke the signing program, so signed commits
+            // are created by `git commit-tree`
+            Some(signing) => {
+                let sha = GitCli::new()
+                    .commit_tree(
+                        repo.workdir().unwrap_or(repo.path()),
+                        &tree_id.to_string(),
+                        &[&base_commit.id().to_string()],
+                        commit_message,
+                        (
+                            signature.name().unwrap_or("Vibe Kanban"),
+                            signature.email().unwrap_or("noreply@vibekanban.com"),
+                        ),
+                        Some(signing),
+                    )
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git commit-tree failed: {e}"))
+                    })?;
+                git2::Oid::from_str(&sha)?
+            }
+            None => repo.commit(
+                None,           // Don't update any reference yet
+                signature,      // Author
+                signature,      // Committer
+                commit_message, // Custom message
+                &tree,          // Merged tree content
+                &[base_commit], // Single parent: base branch commit
+            )?,
+        };
 
         // Update the base branch reference to point to the new commit
         let refname = format!("refs/heads/{base_branch_name}");
@@ -1191,7 +1615,8 @@ impl GitService {
         Ok(squash_commit_id)
     }
 
-    /// Rebase a worktree branch onto a new base
+    /// Rebase a worktree branch onto a new base, signing the rewritten
+    /// commits when `signing` is given
     pub fn rebase_branch(
         &self,
         repo_path: &Path,
@@ -1199,6 +1624,7 @@ impl GitService {
         new_base_branch: &str,
         old_base_branch: &str,
         task_branch: &str,
+        signing: Option<&CommitSigning>,
     ) -> Result<String, GitServiceError> {
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
@@ -1225,7 +1651,13 @@ impl GitService {
         // Ensure identity for any commits produced by rebase
         self.ensure_cli_commit_identity(worktree_path)?;
         // Use git CLI rebase to carry out the operation safely
-        match git.rebase_onto(worktree_path, new_base_branch, old_base_branch, task_branch) {
+        match git.rebase_onto(
+            worktree_path,
+            new_base_branch,
+            old_base_branch,
+            task_branch,
+            signing,
+        ) {
             Ok(()) => {}
             Err(GitCliError::RebaseInProgress) => {
                 return Err(GitServiceError::RebaseInProgress);
@@ -1388,10 +1820,15 @@ impl GitService {
         })
     }
 
-    /// Continue an in-progress rebase. Fails if there are unresolved conflicts.
-    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+    /// Continue an in-progress rebase, signing the commits it creates when
+    /// `signing` is given. Fails if there are unresolved conflicts.
+    pub fn continue_rebase(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitServiceError> {
         let git = GitCli::new();
-        git.continue_rebase(worktree_path).map_err(|e| {
+        git.continue_rebase(worktree_path, signing).map_err(|e| {
             GitServiceError::InvalidRepository(format!("git rebase --continue failed: {e}"))
         })
     }
@@ -1433,6 +1870,122 @@ impl GitService {
         Ok(())
     }
 
+    /// Continue whichever operation is in progress once its conflicts are
+    /// staged, signing the commits it creates when `signing` is given. Fails
+    /// if nothing is in progress or conflicts remain.
+    pub fn continue_conflicts(
+        &self,
+        worktree_path: &Path,
+        signing: Option<&CommitSigning>,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        let (result, command) = match self.detect_conflict_op(worktree_path)? {
+            Some(ConflictOp::Rebase) => return self.continue_rebase(worktree_path, signing),
+            Some(ConflictOp::Merge) => (git.continue_merge(worktree_path, signing), "merge"),
+            Some(ConflictOp::CherryPick) => (
+                git.continue_cherry_pick(worktree_path, signing),
+                "cherry-pick",
+            ),
+            Some(ConflictOp::Revert) => (git.continue_revert(worktree_path, signing), "revert"),
+            None => {
+                return Err(GitServiceError::InvalidRepository(
+                    "No rebase, merge, cherry-pick or revert in progress".to_string(),
+                ));
+            }
+        };
+        result.map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git {command} --continue failed: {e}"))
+        })
+    }
+
+    /// Stage the resolved `paths` and continue the stopped operation. Returns
+    /// the files the next commit conflicts in, which is how a rebase with
+    /// several conflicting commits stops again: `git rebase --continue` then
+    /// exits non-zero although the resolution was applied.
+    pub fn continue_resolved_conflicts(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+        signing: Option<&CommitSigning>,
+    ) -> Result<Vec<String>, GitServiceError> {
+        // The resolver may already have continued or aborted the operation
+        if let Ok(None) = self.detect_conflict_op(worktree_path) {
+            return Ok(Vec::new());
+        }
+        self.stage_paths(worktree_path, paths)?;
+        match self.continue_conflicts(worktree_path, signing) {
+            Ok(()) => Ok(self.get_conflicted_files(worktree_path).unwrap_or_default()),
+            Err(e) => {
+                let next_conflicts = match self.detect_conflict_op(worktree_path) {
+                    Ok(Some(ConflictOp::Rebase)) => {
+                        self.get_conflicted_files(worktree_path).unwrap_or_default()
+                    }
+                    _ => Vec::new(),
+                };
+                if next_conflicts.is_empty() {
+                    Err(e)
+                } else {
+                    Ok(next_conflicts)
+                }
+            }
+        }
+    }
+
+    /// Mark the given paths as resolved by staging them.
+    pub fn stage_paths(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.add_paths(worktree_path, paths)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))
+    }
+
+    /// The subset of `paths` that still contain conflict markers. Deleted
+    /// files count as resolved.
+    pub fn files_with_conflict_markers(
+        &self,
+        worktree_path: &Path,
+        paths: &[String],
+    ) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| {
+                let Ok(content) = std::fs::read(worktree_path.join(path)) else {
+                    return false;
+                };
+                String::from_utf8_lossy(&content)
+                    .lines()
+                    .any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> "))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Subjects of the commits on either side of the conflicting operation
+    pub fn conflict_commits(&self, worktree_path: &Path, op: &ConflictOp) -> ConflictCommits {
+        let Ok(repo) = self.open_repo(worktree_path) else {
+            return ConflictCommits::default();
+        };
+        let subject = |spec: &str| {
+            repo.revparse_single(spec)
+                .and_then(|object| object.peel_to_commit())
+                .ok()
+                .and_then(|commit| commit.summary().map(|summary| summary.to_string()))
+        };
+        let theirs = match op {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        };
+        ConflictCommits {
+            ours: subject("HEAD"),
+            theirs: subject(theirs),
+        }
+    }
+
     pub(crate) fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
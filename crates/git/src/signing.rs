@@ -0,0 +1,172 @@
+//! Signing of the commits `GitService` creates, and verification of existing
+//! ones.
+//!
+//! Signing goes through the `git` CLI with `commit.gpgsign` turned on for the
+//! one command, so git picks the signing program and key the same way it does
+//! for the user's own commits. A `CommitSigning` can override the
+//! repository's `gpg.format` and `user.signingkey` for that command only.
+
+use std::ffi::OsString;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Which program signs commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningFormat {
+    OpenPgp,
+    Ssh,
+}
+
+impl SigningFormat {
+    /// The `gpg.format` value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigningFormat::OpenPgp => "openpgp",
+            SigningFormat::Ssh => "ssh",
+        }
+    }
+}
+
+/// How to sign a commit. Unset fields fall back to the repository's config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitSigning {
+    pub format: Option<SigningFormat>,
+    /// GPG key id, or path to an SSH key
+    pub key: Option<String>,
+}
+
+impl CommitSigning {
+    /// `-c` options that go before the git subcommand
+    pub(crate) fn config_args(&self) -> Vec<OsString> {
+        let mut config = vec!["commit.gpgsign=true".to_string()];
+        if let Some(format) = self.format {
+            config.push(format!("gpg.format={}", format.as_str()));
+        }
+        if let Some(key) = &self.key {
+            config.push(format!("user.signingkey={key}"));
+        }
+        config
+            .into_iter()
+            .flat_map(|c| [OsString::from("-c"), OsString::from(c)])
+            .collect()
+    }
+}
+
+/// How a commit's signature verifies, from `git log --format=%G?`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// A valid signature by a trusted key
+    Good,
+    /// A valid signature by a key of unknown validity
+    Untrusted,
+    /// A valid signature that has expired, or was made by an expired key
+    Expired,
+    /// A valid signature by a revoked key
+    Revoked,
+    /// A signature that doesn't match the commit
+    Bad,
+    /// Signed, but the key to check the signature with isn't available
+    UnknownKey,
+    Unsigned,
+}
+
+impl SignatureStatus {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "G" => SignatureStatus::Good,
+            "U" => SignatureStatus::Untrusted,
+            "X" | "Y" => SignatureStatus::Expired,
+            "R" => SignatureStatus::Revoked,
+            "B" => SignatureStatus::Bad,
+            "E" => SignatureStatus::UnknownKey,
+            _ => SignatureStatus::Unsigned,
+        }
+    }
+
+    /// Whether the commit carries a signature at all, valid or not
+    pub fn is_signed(&self) -> bool {
+        !matches!(self, SignatureStatus::Unsigned)
+    }
+}
+
+/// A commit's signature as the local `git` verifies it
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    /// Who signed, e.g. `Jane Doe <jane@example.com>`
+    pub signer: Option<String>,
+    /// Fingerprint or id of the signing key
+    pub key: Option<String>,
+}
+
+/// `git log` format producing one line per commit for `parse_signatures`
+pub(crate) const SIGNATURE_LOG_FORMAT: &str = "--format=%G?%x1f%GS%x1f%GK";
+
+pub(crate) fn parse_signatures(out: &str) -> Vec<CommitSignature> {
+    let non_empty = |s: Option<&str>| {
+        s.map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    out.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.split('\x1f');
+            CommitSignature {
+                status: SignatureStatus::from_code(fields.next().unwrap_or_default().trim()),
+                signer: non_empty(fields.next()),
+                key: non_empty(fields.next()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_overrides_only_what_is_set() {
+        let from_config = CommitSigning::default();
+        let ssh = CommitSigning {
+            format: Some(SigningFormat::Ssh),
+            key: Some("/keys/agent.pub".to_string()),
+        };
+
+        assert_eq!(from_config.config_args(), ["-c", "commit.gpgsign=true"]);
+        assert_eq!(
+            ssh.config_args(),
+            [
+                "-c",
+                "commit.gpgsign=true",
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "user.signingkey=/keys/agent.pub"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_signature_log_lines() {
+        let out = "G\x1fJane <jane@example.com>\x1fABCD1234\nN\x1f\x1f\nE\x1f\x1fSHA256:xyz\n";
+
+        let signatures = parse_signatures(out);
+
+        assert_eq!(
+            signatures[0],
+            CommitSignature {
+                status: SignatureStatus::Good,
+                signer: Some("Jane <jane@example.com>".to_string()),
+                key: Some("ABCD1234".to_string()),
+            }
+        );
+        assert_eq!(signatures[1].status, SignatureStatus::Unsigned);
+        assert!(!signatures[1].status.is_signed());
+        assert_eq!(signatures[2].status, SignatureStatus::UnknownKey);
+        assert_eq!(signatures[2].key.as_deref(), Some("SHA256:xyz"));
+    }
+}
@@ -418,6 +418,7 @@ fn rebase_preserves_untracked_files() {
         "new-base",
         "old-base",
         "feature",
+        None,
     );
     assert!(res.is_ok(), "rebase should succeed: {res:?}");
 
@@ -440,6 +441,7 @@ fn rebase_aborts_on_uncommitted_tracked_changes() {
         "new-base",
         "old-base",
         "feature",
+        None,
     );
     assert!(res.is_err(), "rebase should fail on dirty worktree");
 
@@ -461,6 +463,7 @@ fn rebase_aborts_if_untracked_would_be_overwritten_by_base() {
         "new-base",
         "old-base",
         "feature",
+        None,
     );
     assert!(
         res.is_err(),
@@ -492,6 +495,7 @@ fn merge_does_not_overwrite_main_repo_untracked_files() {
         "main",
         "squash merge",
         MergeStrategy::Squash,
+        None,
     );
     assert!(
         res.is_err(),
@@ -536,6 +540,7 @@ fn merge_does_not_touch_tracked_uncommitted_changes_in_base_worktree() {
         "main",
         "squash merge",
         MergeStrategy::Squash,
+        None,
     );
     assert!(
         res.is_ok(),
@@ -573,6 +578,7 @@ fn merge_refuses_with_staged_changes_on_base() {
         "main",
         "squash",
         MergeStrategy::Squash,
+        None,
     );
     assert!(res.is_err(), "should refuse merge due to staged changes");
     // staged file remains
@@ -602,6 +608,7 @@ fn merge_preserves_unstaged_changes_on_base() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .unwrap();
     // local edit preserved
@@ -635,6 +642,7 @@ fn update_ref_does_not_destroy_feature_worktree_dirty_state() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .unwrap();
     // uncommitted change in feature worktree preserved
@@ -670,6 +678,7 @@ fn libgit2_merge_updates_base_ref_in_both_repos() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .expect("merge should succeed via libgit2 path");
 
@@ -699,6 +708,7 @@ fn libgit2_merge_updates_task_ref_and_feature_head_preserves_dirty() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .expect("merge should succeed via libgit2 path");
 
@@ -732,6 +742,7 @@ fn rebase_refuses_to_abort_existing_rebase() {
             "new-base",
             "old-base",
             "feature",
+            None,
         )
         .expect_err("first rebase should error and leave in-progress state");
 
@@ -743,6 +754,7 @@ fn rebase_refuses_to_abort_existing_rebase() {
         "new-base",
         "old-base",
         "feature",
+        None,
     );
     assert!(res.is_err(), "should error because rebase is in progress");
     // Note: We do not auto-abort; user should resolve or abort explicitly
@@ -763,6 +775,7 @@ fn rebase_fast_forwards_when_no_unique_commits() {
             "new-base",
             "old-base",
             "feature",
+            None,
         )
         .expect("rebase should succeed");
     let after_oid = g.get_head_info(&worktree_path).unwrap().oid;
@@ -794,6 +807,7 @@ fn rebase_applies_multiple_commits_onto_ahead_base() {
             "new-base",
             "old-base",
             "feature",
+            None,
         )
         .expect("rebase should succeed");
 
@@ -831,6 +845,7 @@ fn merge_when_base_ahead_and_feature_ahead_fails() {
         "main",
         "squash merge",
         MergeStrategy::Squash,
+        None,
     );
 
     assert!(
@@ -864,6 +879,7 @@ fn merge_conflict_does_not_move_base_ref() {
         "main",
         "squash merge",
         MergeStrategy::Squash,
+        None,
     );
 
     assert!(res.is_err(), "conflicting merge should fail");
@@ -908,6 +924,7 @@ fn merge_delete_vs_modify_conflict_behaves_safely() {
         "main",
         "squash merge",
         MergeStrategy::Squash,
+        None,
     );
 
     // Should now fail due to base branch being ahead, not due to merge conflicts
@@ -942,6 +959,7 @@ fn rebase_preserves_rename_changes() {
             "new-base",
             "old-base",
             "feature",
+            None,
         )
         .expect("rebase should succeed");
     // after rebase, renamed file present; original absent
@@ -980,6 +998,7 @@ fn merge_refreshes_main_worktree_when_on_base() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .unwrap();
     // Since main is on base branch and we use safe CLI merge, both working tree
@@ -1117,6 +1136,7 @@ fn merge_binary_conflict_does_not_move_ref() {
         "main",
         "merge bin",
         MergeStrategy::Squash,
+        None,
     );
     assert!(res.is_err(), "binary conflict should fail");
     let after = s.get_branch_oid(&repo_path, "main").unwrap();
@@ -1157,6 +1177,7 @@ fn merge_rename_vs_modify_conflict_does_not_move_ref() {
         "main",
         "merge rename",
         MergeStrategy::Squash,
+        None,
     );
     match res {
         Err(_) => {
@@ -1196,6 +1217,7 @@ fn merge_leaves_no_staged_changes_on_target_branch() {
             "main",
             "merge feature",
             MergeStrategy::Squash,
+            None,
         )
         .expect("merge should succeed");
 
@@ -1267,6 +1289,7 @@ fn worktree_to_worktree_merge_leaves_no_staged_changes() {
         "feature-b",
         "merge feature-a into feature-b",
         MergeStrategy::Squash,
+        None,
     );
 
     // Verify no staged changes were introduced
@@ -1325,6 +1348,7 @@ fn merge_into_orphaned_branch_uses_libgit2_fallback() {
             "orphaned-feature",
             "merge into orphaned branch",
             MergeStrategy::Squash,
+            None,
         )
         .expect("libgit2 merge into orphaned branch should succeed");
 
@@ -1397,6 +1421,7 @@ fn merge_base_ahead_of_task_should_error() {
         "main",
         "attempt merge when base ahead",
         MergeStrategy::Squash,
+        None,
     );
 
     // TDD: This test will initially fail because merge currently succeeds
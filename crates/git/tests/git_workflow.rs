@@ -4,7 +4,10 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitService, MergeStrategy};
+use git::{
+    CommitSigning, DiffHunkId, DiffSelection, GitCli, GitService, MergeStrategy, SignatureStatus,
+    SigningFormat,
+};
 use git2::{Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 #[cfg(unix)]
@@ -423,6 +426,7 @@ fn squash_merge_libgit2_sets_author_without_user() {
             "main",
             "squash",
             MergeStrategy::Squash,
+            None,
         )
         .unwrap();
 
@@ -437,3 +441,440 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn resolve_merge_conflict_and_continue() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+
+    write_file(&repo_path, "a.txt", "base\n");
+    s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "main\n");
+    s.commit(&repo_path, "main change").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "feature\n");
+    s.commit(&repo_path, "feature change").unwrap();
+
+    assert!(git.git(&repo_path, ["merge", "main"]).is_err());
+    assert_eq!(
+        s.detect_conflict_op(&repo_path).unwrap(),
+        Some(git::ConflictOp::Merge)
+    );
+    let commits = s.conflict_commits(&repo_path, &git::ConflictOp::Merge);
+    assert_eq!(commits.ours.as_deref(), Some("feature change"));
+    assert_eq!(commits.theirs.as_deref(), Some("main change"));
+
+    let conflicted = s.get_conflicted_files(&repo_path).unwrap();
+    assert_eq!(conflicted, vec!["a.txt".to_string()]);
+    assert_eq!(
+        s.files_with_conflict_markers(&repo_path, &conflicted),
+        conflicted
+    );
+
+    write_file(&repo_path, "a.txt", "feature and main\n");
+    assert!(
+        s.files_with_conflict_markers(&repo_path, &conflicted)
+            .is_empty()
+    );
+    s.stage_paths(&repo_path, &conflicted).unwrap();
+    s.continue_conflicts(&repo_path, None).unwrap();
+
+    assert_eq!(s.detect_conflict_op(&repo_path).unwrap(), None);
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+}
+
+#[test]
+fn continue_resolved_conflicts_reports_the_next_conflicting_rebase_commit() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let git = GitCli::new();
+
+    write_file(&repo_path, "a.txt", "base\n");
+    write_file(&repo_path, "b.txt", "base\n");
+    s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "main\n");
+    write_file(&repo_path, "b.txt", "main\n");
+    s.commit(&repo_path, "main change").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "feature\n");
+    s.commit(&repo_path, "feature change to a").unwrap();
+    write_file(&repo_path, "b.txt", "feature\n");
+    s.commit(&repo_path, "feature change to b").unwrap();
+
+    assert!(git.git(&repo_path, ["rebase", "main"]).is_err());
+    assert_eq!(
+        s.detect_conflict_op(&repo_path).unwrap(),
+        Some(git::ConflictOp::Rebase)
+    );
+    let conflicted = s.get_conflicted_files(&repo_path).unwrap();
+    assert_eq!(conflicted, vec!["a.txt".to_string()]);
+
+    // Continuing past the first commit stops at the second one
+    write_file(&repo_path, "a.txt", "feature and main\n");
+    let next = s
+        .continue_resolved_conflicts(&repo_path, &conflicted, None)
+        .unwrap();
+    assert_eq!(next, vec!["b.txt".to_string()]);
+    assert_eq!(
+        s.detect_conflict_op(&repo_path).unwrap(),
+        Some(git::ConflictOp::Rebase)
+    );
+
+    write_file(&repo_path, "b.txt", "feature and main\n");
+    let next = s
+        .continue_resolved_conflicts(&repo_path, &next, None)
+        .unwrap();
+    assert!(next.is_empty());
+    assert_eq!(s.detect_conflict_op(&repo_path).unwrap(), None);
+    assert!(s.is_worktree_clean(&repo_path).unwrap());
+    assert_eq!(
+        fs::read_to_string(repo_path.join("b.txt")).unwrap(),
+        "feature and main\n"
+    );
+}
+
+const LETTERS: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\n";
+const LETTERS_CHANGED: &str = "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nN\n";
+
+fn hunk(old_start: u32, new_start: u32) -> DiffHunkId {
+    DiffHunkId {
+        old_start,
+        new_start,
+    }
+}
+
+#[test]
+fn revert_changes_undoes_selected_hunks_and_files_against_base() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "letters.txt", LETTERS);
+    write_file(&repo_path, "kept.txt", "kept\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "baseline").unwrap();
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+
+    // One change committed on the branch, the rest left uncommitted
+    write_file(&repo_path, "letters.txt", LETTERS_CHANGED);
+    s.commit(&repo_path, "agent changes").unwrap();
+    write_file(&repo_path, "kept.txt", "changed\n");
+    write_file(&repo_path, "new.txt", "new\n");
+
+    let base_commit = s.get_base_commit(&repo_path, "feature", "main").unwrap();
+    let hunks = s
+        .get_file_hunks(&repo_path, Some(&base_commit), "letters.txt")
+        .unwrap();
+    assert_eq!(hunks.len(), 2);
+    assert_eq!(hunks[0].header, "@@ -1,4 +1,4 @@");
+
+    s.revert_changes(
+        &repo_path,
+        &base_commit,
+        &[
+            DiffSelection {
+                path: "letters.txt".to_string(),
+                hunks: Some(vec![hunk(1, 1)]),
+            },
+            DiffSelection {
+                path: "kept.txt".to_string(),
+                hunks: None,
+            },
+            DiffSelection {
+                path: "new.txt".to_string(),
+                hunks: None,
+            },
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(repo_path.join("letters.txt")).unwrap(),
+        "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nN\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("kept.txt")).unwrap(),
+        "kept\n"
+    );
+    assert!(!repo_path.join("new.txt").exists());
+    // Reverting only touches the worktree
+    assert!(!GitCli::new().has_staged_changes(&repo_path).unwrap());
+}
+
+#[test]
+fn revert_changes_rejects_stale_hunks_and_paths_outside_the_worktree() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "letters.txt", LETTERS);
+    let s = GitService::new();
+    s.commit(&repo_path, "baseline").unwrap();
+    write_file(&repo_path, "letters.txt", LETTERS_CHANGED);
+    let base_commit = s.get_base_commit(&repo_path, "main", "main").unwrap();
+
+    let stale = s.revert_changes(
+        &repo_path,
+        &base_commit,
+        &[DiffSelection {
+            path: "letters.txt".to_string(),
+            hunks: Some(vec![hunk(5, 5)]),
+        }],
+    );
+    let outside = s.revert_changes(
+        &repo_path,
+        &base_commit,
+        &[DiffSelection {
+            path: "../letters.txt".to_string(),
+            hunks: None,
+        }],
+    );
+
+    assert!(matches!(
+        stale,
+        Err(git::GitServiceError::HunkNotFound { old_start: 5, .. })
+    ));
+    assert!(matches!(outside, Err(git::GitServiceError::InvalidPath(_))));
+    assert_eq!(
+        fs::read_to_string(repo_path.join("letters.txt")).unwrap(),
+        LETTERS_CHANGED
+    );
+}
+
+#[test]
+fn stage_and_commit_a_single_hunk() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "letters.txt", LETTERS);
+    let s = GitService::new();
+    s.commit(&repo_path, "baseline").unwrap();
+    write_file(&repo_path, "letters.txt", LETTERS_CHANGED);
+    write_file(&repo_path, "untouched.txt", "untouched\n");
+
+    assert!(!s.commit_staged(&repo_path, "nothing staged", None).unwrap());
+
+    let hunks = s.get_file_hunks(&repo_path, None, "letters.txt").unwrap();
+    assert_eq!(hunks.len(), 2);
+    s.stage_changes(
+        &repo_path,
+        &[DiffSelection {
+            path: "letters.txt".to_string(),
+            hunks: Some(vec![hunk(hunks[1].old_start, hunks[1].new_start)]),
+        }],
+    )
+    .unwrap();
+    assert!(
+        s.commit_staged(&repo_path, "Capitalise the last letter", None)
+            .unwrap()
+    );
+
+    let repo = Repository::open(&repo_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("Capitalise the last letter"));
+    let blob = head
+        .tree()
+        .unwrap()
+        .get_path(Path::new("letters.txt"))
+        .unwrap()
+        .to_object(&repo)
+        .unwrap()
+        .peel_to_blob()
+        .unwrap();
+    assert_eq!(
+        std::str::from_utf8(blob.content()).unwrap(),
+        "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nN\n"
+    );
+    assert!(
+        head.tree()
+            .unwrap()
+            .get_path(Path::new("untouched.txt"))
+            .is_err()
+    );
+
+    // The unselected hunk stays as an unstaged change in the worktree
+    assert_eq!(
+        fs::read_to_string(repo_path.join("letters.txt")).unwrap(),
+        LETTERS_CHANGED
+    );
+    let remaining = s.get_file_hunks(&repo_path, None, "letters.txt").unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].lines[..2], ["-a", "+A"]);
+}
+
+#[test]
+fn checkpoint_restores_head_and_uncommitted_changes() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    write_file(&repo_path, "tracked.txt", "one\n");
+    let s = GitService::new();
+    s.commit(&repo_path, "baseline").unwrap();
+    let baseline = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "tracked.txt", "two\n");
+    write_file(&repo_path, "untracked.txt", "new\n");
+    let ref_name = "refs/vibe/checkpoints/test/turn-1";
+    let snapshot = s
+        .create_checkpoint(&repo_path, ref_name, "Checkpoint", None)
+        .unwrap();
+    assert_eq!(snapshot.head, baseline);
+    assert_eq!(snapshot.files_changed, 2);
+    assert_eq!((snapshot.lines_added, snapshot.lines_removed), (2, 1));
+    // Taking a checkpoint leaves the worktree and index alone
+    assert!(!GitCli::new().has_staged_changes(&repo_path).unwrap());
+
+    // A later turn commits and leaves more changes behind
+    s.commit(&repo_path, "later turn").unwrap();
+    write_file(&repo_path, "tracked.txt", "three\n");
+    write_file(&repo_path, "later.txt", "later\n");
+
+    let dirty = s.restore_checkpoint(&repo_path, &snapshot.head, &snapshot.commit, false);
+    assert!(matches!(
+        dirty,
+        Err(git::GitServiceError::WorktreeDirty(..))
+    ));
+
+    s.restore_checkpoint(&repo_path, &snapshot.head, &snapshot.commit, true)
+        .unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, baseline);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("tracked.txt")).unwrap(),
+        "two\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("untracked.txt")).unwrap(),
+        "new\n"
+    );
+    assert!(!repo_path.join("later.txt").exists());
+    // The restored changes are uncommitted and unstaged
+    assert!(!GitCli::new().has_staged_changes(&repo_path).unwrap());
+    assert!(!s.is_worktree_clean(&repo_path).unwrap());
+
+    s.delete_ref(&repo_path, ref_name).unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    assert!(repo.find_reference(ref_name).is_err());
+}
+
+/// An SSH signing key that `repo_path` trusts
+fn ssh_signing(td: &TempDir, repo_path: &Path) -> CommitSigning {
+    let key = td.path().join("signing_key");
+    let keygen = std::process::Command::new("ssh-keygen")
+        .args([
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-C",
+            "test@example.com",
+            "-f",
+        ])
+        .arg(&key)
+        .status()
+        .unwrap();
+    assert!(keygen.success());
+    let public_key = fs::read_to_string(key.with_extension("pub")).unwrap();
+    let allowed_signers = td.path().join("allowed_signers");
+    fs::write(&allowed_signers, format!("test@example.com {public_key}")).unwrap();
+    Repository::open(repo_path)
+        .unwrap()
+        .config()
+        .unwrap()
+        .set_str(
+            "gpg.ssh.allowedSignersFile",
+            allowed_signers.to_str().unwrap(),
+        )
+        .unwrap();
+    CommitSigning {
+        format: Some(SigningFormat::Ssh),
+        key: Some(key.to_string_lossy().into_owned()),
+    }
+}
+
+#[test]
+fn signs_commits_and_squash_merges_with_an_ssh_key() {
+    if utils::shell::resolve_executable_path_blocking("ssh-keygen").is_none() {
+        return;
+    }
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let signing = ssh_signing(&td, &repo_path);
+    let s = GitService::new();
+
+    write_file(&repo_path, "a.txt", "a\n");
+    assert!(
+        s.commit_signed(&repo_path, "signed", Some(&signing))
+            .unwrap()
+    );
+    let head = s.get_head_signature(&repo_path).unwrap().unwrap();
+    assert_eq!(head.status, SignatureStatus::Good);
+    assert_eq!(head.signer.as_deref(), Some("test@example.com"));
+
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "unsigned").unwrap();
+    assert_eq!(s.count_unsigned_commits(&repo_path, "main").unwrap(), 1);
+
+    // main isn't checked out, so the squash commit is built in-memory
+    let merge_sha = s
+        .merge_changes(
+            &repo_path,
+            &repo_path,
+            "feature",
+            "main",
+            "squash",
+            MergeStrategy::Squash,
+            Some(&signing),
+        )
+        .unwrap();
+    let merged = GitCli::new()
+        .git(&repo_path, ["log", "-1", "--format=%G?", &merge_sha])
+        .unwrap();
+    assert_eq!(merged.trim(), "G");
+}
+
+#[test]
+fn signs_commits_rewritten_by_a_rebase_and_its_continue() {
+    if utils::shell::resolve_executable_path_blocking("ssh-keygen").is_none() {
+        return;
+    }
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let signing = ssh_signing(&td, &repo_path);
+    let s = GitService::new();
+
+    write_file(&repo_path, "a.txt", "base\n");
+    s.commit(&repo_path, "base").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "a.txt", "main\n");
+    s.commit(&repo_path, "main change").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "b.txt", "b\n");
+    s.commit(&repo_path, "feature adds b").unwrap();
+    write_file(&repo_path, "a.txt", "feature\n");
+    s.commit(&repo_path, "feature change to a").unwrap();
+
+    // The first commit is replayed cleanly, the second one conflicts
+    let res = s.rebase_branch(
+        &repo_path,
+        &repo_path,
+        "main",
+        "main",
+        "feature",
+        Some(&signing),
+    );
+    assert!(matches!(
+        res,
+        Err(git::GitServiceError::MergeConflicts { .. })
+    ));
+    write_file(&repo_path, "a.txt", "feature and main\n");
+    s.stage_paths(&repo_path, &["a.txt".to_string()]).unwrap();
+    s.continue_rebase(&repo_path, Some(&signing)).unwrap();
+
+    let rebased = GitCli::new()
+        .git(&repo_path, ["log", "--format=%G?", "main..feature"])
+        .unwrap();
+    assert_eq!(rebased.lines().collect::<Vec<_>>(), vec!["G", "G"]);
+}
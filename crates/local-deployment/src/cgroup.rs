@@ -0,0 +1,257 @@
+//! Resource limits and usage accounting for execution processes through a
+//! cgroup v2 subtree on Linux.
+//!
+//! Every limited execution gets a cgroup of its own under the server's cgroup,
+//! which its process joins before exec. cgroup v2 only hands controllers down
+//! from a cgroup without processes of its own, so the first limited execution
+//! moves the server and its child processes into a `server` leaf and enables
+//! the cpu, memory and pids controllers for the children. The server's cgroup
+//! has to be writable by its user and not shared with unrelated processes,
+//! e.g. a systemd unit with `Delegate=yes`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use executors::resource_limits::ResourceLimits;
+use uuid::Uuid;
+
+const CGROUP_MOUNT: &str = "/sys/fs/cgroup";
+const CONTROLLERS: &[&str] = &["cpu", "memory", "pids"];
+/// Period `cpu.max` quotas are relative to, in microseconds
+const CPU_PERIOD_USEC: u64 = 100_000;
+
+/// What an execution used, read from its cgroup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// `None` before Linux 5.19
+    pub peak_memory_bytes: Option<u64>,
+    pub cpu_time_ms: Option<u64>,
+    /// Processes the kernel killed for going over the memory limit
+    pub oom_kills: u64,
+}
+
+/// The cgroup of one execution's processes
+#[derive(Debug)]
+pub struct ExecutionCgroup {
+    path: PathBuf,
+}
+
+impl ExecutionCgroup {
+    /// Create the cgroup for an execution with `limits` applied
+    pub fn create(exec_id: Uuid, limits: &ResourceLimits) -> io::Result<Self> {
+        let cgroup = Self {
+            path: delegated_root()?.join(format!("vk-{exec_id}")),
+        };
+        fs::create_dir_all(&cgroup.path)?;
+        if let Err(e) = cgroup.apply_limits(limits) {
+            let _ = fs::remove_dir(&cgroup.path);
+            return Err(e);
+        }
+        Ok(cgroup)
+    }
+
+    fn apply_limits(&self, limits: &ResourceLimits) -> io::Result<()> {
+        if let Some(cpus) = limits.cpus {
+            let quota = ((cpus * CPU_PERIOD_USEC as f64) as u64).max(1_000);
+            self.write("cpu.max", &format!("{quota} {CPU_PERIOD_USEC}"))?;
+        }
+        if let Some(memory_mb) = limits.memory_mb {
+            self.write(
+                "memory.max",
+                &memory_mb.saturating_mul(1024 * 1024).to_string(),
+            )?;
+            // Reaching the limit should OOM-kill rather than swap
+            if self.path.join("memory.swap.max").exists() {
+                self.write("memory.swap.max", "0")?;
+            }
+        }
+        if let Some(pids) = limits.pids {
+            self.write("pids.max", &pids.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// The cgroup directory, for spawned processes to join before exec
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn usage(&self) -> ResourceUsage {
+        let read = |file: &str| fs::read_to_string(self.path.join(file)).ok();
+        ResourceUsage {
+            peak_memory_bytes: read("memory.peak").and_then(|peak| peak.trim().parse().ok()),
+            cpu_time_ms: read("cpu.stat")
+                .and_then(|stat| stat_value(&stat, "usage_usec"))
+                .map(|usec| usec / 1_000),
+            oom_kills: read("memory.events")
+                .and_then(|events| stat_value(&events, "oom_kill"))
+                .unwrap_or(0),
+        }
+    }
+
+    /// Kill the processes left in the cgroup and remove it
+    pub async fn remove(self) {
+        // `cgroup.kill` needs Linux 5.14; the process group kill covers older
+        // kernels
+        let _ = self.write("cgroup.kill", "1");
+        for _ in 0..20 {
+            match fs::remove_dir(&self.path) {
+                Ok(()) => return,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+                // Busy until the killed processes are gone
+                Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+            }
+        }
+        tracing::warn!("Failed to remove cgroup {}", self.path.display());
+    }
+
+    fn write(&self, file: &str, value: &str) -> io::Result<()> {
+        fs::write(self.path.join(file), value)
+    }
+}
+
+/// A `key value` line of `cpu.stat` or `memory.events`
+fn stat_value(stat: &str, key: &str) -> Option<u64> {
+    stat.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        if name == key {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// The server's cgroup, ready to hold execution cgroups. Failures aren't
+/// remembered, so e.g. a cgroup that's busy now can be prepared later.
+fn delegated_root() -> io::Result<PathBuf> {
+    static ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+    let mut root = ROOT.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(root) = root.as_ref() {
+        return Ok(root.clone());
+    }
+    let prepared =
+        prepare_root().map_err(|e| io::Error::other(format!("cgroup limits unavailable: {e}")))?;
+    *root = Some(prepared.clone());
+    Ok(prepared)
+}
+
+fn prepare_root() -> io::Result<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Err(io::Error::other("only supported on Linux"));
+    }
+    // The unified cgroup v2 hierarchy is listed as `0::/path`
+    let own = fs::read_to_string("/proc/self/cgroup")?;
+    let relative = own
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .ok_or_else(|| io::Error::other("cgroup v2 is not mounted"))?
+        .trim_start_matches('/');
+    let root = Path::new(CGROUP_MOUNT).join(relative);
+
+    let available = fs::read_to_string(root.join("cgroup.controllers"))?;
+    let missing: Vec<&str> = CONTROLLERS
+        .iter()
+        .copied()
+        .filter(|c| !available.split_whitespace().any(|a| a == *c))
+        .collect();
+    if !missing.is_empty() {
+        return Err(io::Error::other(format!(
+            "controllers {} are not delegated to {}",
+            missing.join(", "),
+            root.display()
+        )));
+    }
+
+    let enabled = fs::read_to_string(root.join("cgroup.subtree_control"))?;
+    if CONTROLLERS
+        .iter()
+        .all(|c| enabled.split_whitespace().any(|e| e == *c))
+    {
+        return Ok(root);
+    }
+
+    // The root cgroup may keep its processes; any other has to hand them to a
+    // leaf before enabling controllers for its children. Only the server and
+    // the processes it started are moved.
+    if !relative.is_empty() {
+        let own = std::process::id();
+        let pids: Vec<u32> = fs::read_to_string(root.join("cgroup.procs"))?
+            .lines()
+            .filter_map(|pid| pid.trim().parse().ok())
+            .collect();
+        if let Some(other) = pids.iter().find(|&&pid| !descends_from(pid, own)) {
+            return Err(io::Error::other(format!(
+                "{} is shared with process {other}, which Vibe Kanban didn't start",
+                root.display()
+            )));
+        }
+        let leaf = root.join("server");
+        fs::create_dir_all(&leaf)?;
+        for pid in pids {
+            // Processes may exit in the meantime
+            let _ = fs::write(leaf.join("cgroup.procs"), pid.to_string());
+        }
+    }
+    let control: Vec<String> = CONTROLLERS.iter().map(|c| format!("+{c}")).collect();
+    fs::write(root.join("cgroup.subtree_control"), control.join(" "))?;
+    Ok(root)
+}
+
+/// Whether `pid` is `ancestor` or one of its descendants
+fn descends_from(mut pid: u32, ancestor: u32) -> bool {
+    while pid > 1 {
+        if pid == ancestor {
+            return true;
+        }
+        match fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| parent_pid(&stat))
+        {
+            Some(parent) => pid = parent,
+            // Exited processes don't matter
+            None => return true,
+        }
+    }
+    false
+}
+
+/// The parent PID in `/proc/<pid>/stat`, after the parenthesized command
+/// name that may itself contain spaces and parentheses
+fn parent_pid(stat: &str) -> Option<u32> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_stat_values() {
+        let cpu_stat = "usage_usec 2500000\nuser_usec 2000000\nsystem_usec 500000\n";
+        let memory_events = "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\noom_group_kill 0\n";
+
+        assert_eq!(stat_value(cpu_stat, "usage_usec"), Some(2_500_000));
+        assert_eq!(stat_value(memory_events, "oom_kill"), Some(1));
+        assert_eq!(stat_value(memory_events, "missing"), None);
+    }
+
+    #[test]
+    fn reads_parent_pid_past_odd_command_names() {
+        assert_eq!(parent_pid("4321 (node) S 1234 4321 4321 0"), Some(1234));
+        assert_eq!(parent_pid("4321 (a) b (c) S 99 4321 4321 0"), Some(99));
+        assert_eq!(parent_pid("garbage"), None);
+    }
+
+    #[test]
+    fn server_descends_from_itself() {
+        let own = std::process::id();
+        assert!(descends_from(own, own));
+        assert!(!descends_from(1, own));
+    }
+}
@@ -2,7 +2,10 @@ use std::{
     collections::HashMap,
     io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::{Duration, Instant},
 };
 
@@ -12,15 +15,27 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        checkpoint::{Checkpoint, CreateCheckpoint},
         coding_agent_turn::CodingAgentTurn,
+        conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionContext, ExecutionFailureReason, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        fix_up_loop::{FixUpLoop, FixUpLoopStatus, FixUpTransition},
+        model_price::ModelPrice,
+        pr_comment_fix::{PrCommentFix, PrCommentFixStatus},
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         session::{Session, SessionError},
+        token_usage::ExecutionTokenUsage,
+        verification::{
+            VerificationResult, VerificationResultStatus, VerificationRun, VerificationRunStatus,
+            VerificationTrigger,
+        },
         workspace::Workspace,
+        workspace_budget::WorkspaceBudget,
         workspace_repo::WorkspaceRepo,
     },
 };
@@ -29,7 +44,7 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, script::ScriptContext,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
@@ -38,26 +53,35 @@ use executors::{
         claude::{protocol::ProtocolPeer, session_recovery},
     },
     logs::{
-        NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TokenUsage, TokenUsageInfo,
         utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
     },
+    profile::{ExecutorConfig, ExecutorConfigs},
+    resource_limits::ResourceLimits,
+    sandbox::Sandbox,
 };
-use futures::{FutureExt, TryStreamExt, stream::select};
+use futures::{FutureExt, StreamExt, TryStreamExt, stream::select};
 use git::GitService;
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
+    commit_message::{self, CommitMessageContext},
+    config::{ConcurrencyConfig, Config, DEFAULT_COMMIT_REMINDER_PROMPT},
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
     file::FileService,
     notification::NotificationService,
+    pr_comments,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
     remote_sync,
+    repo::commit_signing,
+};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
 };
-use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
@@ -67,10 +91,17 @@ use utils::{
 use uuid::Uuid;
 use workspace_manager::{RepoWorkspaceInput, WorkspaceError, WorkspaceManager};
 
-use crate::{command, copy};
+use crate::{
+    cgroup::{ExecutionCgroup, ResourceUsage},
+    command, copy,
+};
 
 const WORKSPACE_TOUCH_DEBOUNCE: Duration = Duration::from_mins(2);
 
+/// How much of a failing verification step's output a fix-up follow-up carries
+const MAX_FIX_UP_OUTPUT_LINES: usize = 200;
+const MAX_FIX_UP_OUTPUT_BYTES: usize = 8 * 1024;
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -94,6 +125,11 @@ pub struct LocalContainerService {
     /// Holds the live ProtocolPeer for every running Claude Code process so that
     /// messages can be injected while the process is active.
     protocol_peers: Arc<RwLock<HashMap<Uuid, ProtocolPeer>>>,
+    /// Cgroups of running executions with resource limits
+    cgroups: Arc<RwLock<HashMap<Uuid, ExecutionCgroup>>>,
+    start_queue_lock: Arc<Mutex<()>>,
+    /// Set on shutdown so killed agents don't hand their slots to queued ones
+    stopping_all: Arc<AtomicBool>,
 }
 
 impl LocalContainerService {
@@ -116,6 +152,9 @@ impl LocalContainerService {
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
         let workspace_touch_times = Arc::new(RwLock::new(HashMap::new()));
         let protocol_peers = Arc::new(RwLock::new(HashMap::new()));
+        let cgroups = Arc::new(RwLock::new(HashMap::new()));
+        let start_queue_lock = Arc::new(Mutex::new(()));
+        let stopping_all = Arc::new(AtomicBool::new(false));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
@@ -136,6 +175,9 @@ impl LocalContainerService {
             notification_service,
             remote_client,
             protocol_peers,
+            cgroups,
+            start_queue_lock,
+            stopping_all,
         };
 
         container.spawn_workspace_cleanup();
@@ -351,20 +393,71 @@ impl LocalContainerService {
         }
     }
 
-    /// Get the commit message based on the execution run reason.
-    async fn get_commit_message(&self, ctx: &ExecutionContext) -> String {
+    /// What the commit message templates of a run are filled from. The linked
+    /// issue is only looked up when a template asks for it.
+    async fn commit_message_context(&self, ctx: &ExecutionContext) -> CommitMessageContext {
+        let turn = match CodingAgentTurn::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(turn) => turn,
+            Err(e) => {
+                tracing::debug!(
+                    "Failed to retrieve summary for execution process {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                None
+            }
+        };
+        let wants_issue = ctx.repos.iter().any(|repo| {
+            repo.commit_message_template
+                .as_deref()
+                .is_some_and(|template| template.contains("{issue}"))
+        });
+        let issue = match &self.remote_client {
+            Some(client) if wants_issue => {
+                remote_sync::linked_issue_identifier(client, ctx.workspace.id).await
+            }
+            _ => None,
+        };
+        let (summary, prompt) = turn
+            .map(|turn| (turn.summary, turn.prompt))
+            .unwrap_or_default();
+
+        CommitMessageContext {
+            summary,
+            prompt,
+            executor: ctx
+                .execution_process
+                .executor_action()
+                .ok()
+                .and_then(|action| action.base_executor())
+                .map(|executor| executor.to_string()),
+            workspace_name: ctx.workspace.name.clone(),
+            issue,
+        }
+    }
+
+    /// The commit message for a repo, based on the execution run reason and
+    /// the repo's commit message settings.
+    fn get_commit_message(
+        &self,
+        ctx: &ExecutionContext,
+        message_ctx: &CommitMessageContext,
+        repo: &Repo,
+    ) -> String {
         match ctx.execution_process.run_reason {
             ExecutionProcessRunReason::CodingAgent => {
-                // Try to retrieve the task summary from the coding agent turn
-                // otherwise fallback to default message
-                match CodingAgentTurn::find_by_execution_process_id(
-                    &self.db().pool,
-                    ctx.execution_process.id,
-                )
-                .await
-                {
-                    Ok(Some(turn)) if turn.summary.is_some() => turn.summary.unwrap(),
-                    Ok(_) => {
+                let message = repo
+                    .commit_message_template
+                    .as_deref()
+                    .map(|template| commit_message::render_template(template, message_ctx))
+                    .filter(|message| !message.is_empty())
+                    .or_else(|| message_ctx.summary.clone())
+                    .unwrap_or_else(|| {
                         tracing::debug!(
                             "No summary found for execution process {}, using default message",
                             ctx.execution_process.id
@@ -373,60 +466,759 @@ impl LocalContainerService {
                             "Commit changes from coding agent for workspace {}",
                             ctx.workspace.id
                         )
+                    });
+                let message = if repo.conventional_commits {
+                    commit_message::apply_conventional_prefix(&message)
+                } else {
+                    message
+                };
+                match &message_ctx.executor {
+                    Some(executor) if repo.agent_commit_trailers => {
+                        commit_message::append_agent_trailers(
+                            &message,
+                            executor,
+                            &ctx.session.id.to_string(),
+                        )
+                    }
+                    _ => message,
+                }
+            }
+            ExecutionProcessRunReason::CleanupScript => {
+                let message = format!("Cleanup script changes for workspace {}", ctx.workspace.id);
+                if repo.conventional_commits {
+                    commit_message::with_conventional_type(&message, "chore")
+                } else {
+                    message
+                }
+            }
+            _ => format!(
+                "Changes from execution process {}",
+                ctx.execution_process.id
+            ),
+        }
+    }
+
+    /// Check which repos have uncommitted changes. Fails if any repo is inaccessible.
+    fn check_repos_for_changes(
+        &self,
+        workspace_root: &Path,
+        repos: &[Repo],
+    ) -> Result<Vec<(Repo, PathBuf)>, ContainerError> {
+        let git = GitService::new();
+        let mut repos_with_changes = Vec::new();
+
+        for repo in repos {
+            let worktree_path = workspace_root.join(&repo.name);
+
+            // Committing now would record conflict markers in the middle of
+            // the stopped operation
+            if let Ok(Some(op)) = git.detect_conflict_op(&worktree_path) {
+                tracing::debug!(
+                    "Not committing in repo '{}' while a {:?} is in progress",
+                    repo.name,
+                    op
+                );
+                continue;
+            }
+
+            match git.get_worktree_status(&worktree_path) {
+                Ok(ws) if !ws.entries.is_empty() => {
+                    repos_with_changes.push((repo.clone(), worktree_path));
+                }
+                Ok(_) => {
+                    tracing::debug!("No changes in repo '{}'", repo.name);
+                }
+                Err(e) => {
+                    return Err(ContainerError::Other(anyhow!(
+                        "Pre-flight check failed for repo '{}': {}",
+                        repo.name,
+                        e
+                    )));
+                }
+            }
+        }
+
+        Ok(repos_with_changes)
+    }
+
+    /// Wrap up a run started to resolve conflicts: once the agent has left no
+    /// conflict markers in the conflicted files, stage them and continue the
+    /// stopped operation.
+    async fn finish_conflict_resolution(&self, ctx: &ExecutionContext, success: bool) {
+        let resolution = match ConflictResolution::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(Some(resolution)) if resolution.status == ConflictResolutionStatus::Running => {
+                resolution
+            }
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load conflict resolution for execution {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                return;
+            }
+        };
+        let Some(repo) = ctx.repos.iter().find(|repo| repo.id == resolution.repo_id) else {
+            return;
+        };
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+        let files = &resolution.conflicted_files.0;
+
+        let (status, error, notification) = if !success {
+            (
+                ConflictResolutionStatus::Failed,
+                Some("The coding agent run did not complete".to_string()),
+                format!("Resolving the conflicts in {} did not finish", repo.name),
+            )
+        } else {
+            let remaining = self.git.files_with_conflict_markers(&worktree_path, files);
+            if !remaining.is_empty() {
+                let error = format!("Conflict markers remain in {}", remaining.join(", "));
+                (
+                    ConflictResolutionStatus::Unresolved,
+                    Some(error.clone()),
+                    error,
+                )
+            } else {
+                match self.git.continue_resolved_conflicts(
+                    &worktree_path,
+                    files,
+                    commit_signing(repo).as_ref(),
+                ) {
+                    Ok(next_conflicts) => {
+                        let notification = if next_conflicts.is_empty() {
+                            format!("Resolved the conflicts in {}", repo.name)
+                        } else {
+                            format!(
+                                "Resolved the conflicts in {}, but the next commit conflicts in {}",
+                                repo.name,
+                                next_conflicts.join(", ")
+                            )
+                        };
+                        (ConflictResolutionStatus::Continued, None, notification)
                     }
                     Err(e) => {
-                        tracing::debug!(
-                            "Failed to retrieve summary for execution process {}: {}",
-                            ctx.execution_process.id,
-                            e
-                        );
-                        format!(
-                            "Commit changes from coding agent for workspace {}",
-                            ctx.workspace.id
+                        let error = format!("Could not continue: {e}");
+                        (ConflictResolutionStatus::Failed, Some(error.clone()), error)
+                    }
+                }
+            }
+        };
+
+        if let Err(e) = ConflictResolution::update_status(
+            &self.db.pool,
+            resolution.id,
+            status,
+            error.as_deref(),
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to update conflict resolution {}: {}",
+                resolution.id,
+                e
+            );
+        }
+        self.notification_service
+            .notify("Conflict resolution", &notification, Some(ctx.workspace.id))
+            .await;
+    }
+
+    /// Record how a verification step ended. A step that was stopped
+    /// cancels the rest of its run.
+    async fn finish_verification_step(
+        &self,
+        ctx: &ExecutionContext,
+        status: &ExecutionProcessStatus,
+        exit_code: Option<i64>,
+    ) {
+        let is_verification_step = matches!(
+            ctx.execution_process.executor_action().map(|action| action.typ()),
+            Ok(ExecutorActionType::ScriptRequest(request))
+                if request.context == ScriptContext::VerificationScript
+        );
+        if !is_verification_step {
+            return;
+        }
+
+        let result_status =
+            if ExecutionProcess::was_stopped(&self.db.pool, ctx.execution_process.id).await {
+                VerificationResultStatus::Cancelled
+            } else if *status == ExecutionProcessStatus::Completed && exit_code == Some(0) {
+                VerificationResultStatus::Passed
+            } else {
+                VerificationResultStatus::Failed
+            };
+        match VerificationResult::finish(
+            &self.db.pool,
+            ctx.execution_process.id,
+            result_status,
+            exit_code,
+        )
+        .await
+        {
+            Ok(Some(run)) => self.continue_fix_up_loop(ctx, &run, exit_code).await,
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to record verification result for execution {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Drive the session's fix-up loop once one of its verification runs has
+    /// settled: stop when the steps passed or the iterations are used up,
+    /// otherwise send the failing step's output to the coding agent.
+    async fn continue_fix_up_loop(
+        &self,
+        ctx: &ExecutionContext,
+        run: &VerificationRun,
+        exit_code: Option<i64>,
+    ) {
+        let pool = &self.db.pool;
+        let fix_up = match FixUpLoop::find_running_by_session_id(pool, ctx.session.id).await {
+            Ok(Some(fix_up)) => fix_up,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load fix-up loop for session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        // A message the user queued goes first; the loop ends there
+        let has_queued_message = run.status == VerificationRunStatus::Failed
+            && self
+                .queued_message_service
+                .has_queued(ctx.session.id)
+                .await
+                .unwrap_or(false);
+        let (status, error, notification) = match fix_up.transition(run.status, has_queued_message)
+        {
+            FixUpTransition::Wait => return,
+            FixUpTransition::Finish {
+                status,
+                error,
+                notification,
+            } => (status, error, notification),
+            FixUpTransition::SendQueuedMessage => match self.start_next_queued_follow_up(ctx).await
+            {
+                Ok(true) => {
+                    let error = "A queued follow-up message was sent instead".to_string();
+                    (FixUpLoopStatus::Cancelled, Some(error.clone()), error)
+                }
+                Ok(false) => {
+                    let error = "The queued follow-up message was removed".to_string();
+                    (FixUpLoopStatus::Cancelled, Some(error.clone()), error)
+                }
+                Err(e) => {
+                    let error = format!("Could not send the queued follow-up message: {e}");
+                    (FixUpLoopStatus::Failed, Some(error.clone()), error)
+                }
+            },
+            FixUpTransition::Iterate => {
+                let output = self.script_output_tail(&ctx.execution_process.id).await;
+                match self
+                    .start_fix_up_iteration(ctx, &fix_up, exit_code, &output)
+                    .await
+                {
+                    Ok(()) => return,
+                    Err(e) => {
+                        let error = format!("Could not start a fix-up follow-up: {e}");
+                        (FixUpLoopStatus::Failed, Some(error.clone()), error)
+                    }
+                }
+            }
+        };
+
+        if let Err(e) = FixUpLoop::update_status(pool, fix_up.id, status, error.as_deref()).await {
+            tracing::warn!("Failed to update fix-up loop {}: {}", fix_up.id, e);
+        }
+        self.notification_service
+            .notify("Fix-up loop", &notification, Some(ctx.workspace.id))
+            .await;
+    }
+
+    /// Take the session's next queued message and start it as a follow-up.
+    /// The exit monitor keeps the queue after a failed process, which a
+    /// failing verification step always is, so the fix-up loop sends it
+    /// itself. Returns whether there was a message to send.
+    async fn start_next_queued_follow_up(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let Some(queued_msg) = self
+            .queued_message_service
+            .take_next(ctx.session.id)
+            .await
+            .map_err(|e| ContainerError::Other(anyhow!(e)))?
+        else {
+            return Ok(false);
+        };
+        if let Err(e) =
+            Scratch::delete(&self.db.pool, ctx.session.id, &ScratchType::DraftFollowUp).await
+        {
+            tracing::warn!(
+                "Failed to delete scratch after consuming queued message: {}",
+                e
+            );
+        }
+        self.start_queued_follow_up(ctx, &queued_msg.data).await?;
+        Ok(true)
+    }
+
+    /// Send the failure of the verification step `ctx` ran to the session's
+    /// coding agent, chaining the cleanup scripts and a new verification run
+    /// after the follow-up.
+    async fn start_fix_up_iteration(
+        &self,
+        ctx: &ExecutionContext,
+        fix_up: &FixUpLoop,
+        exit_code: Option<i64>,
+        output: &str,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let Some(info) = CodingAgentTurn::find_latest_session_info(pool, ctx.session.id).await?
+        else {
+            return Err(ContainerError::Other(anyhow!(
+                "The session has no coding agent turn to follow up on"
+            )));
+        };
+        let Some(profile) =
+            ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id).await?
+        else {
+            return Err(ContainerError::Other(anyhow!(
+                "The session has no coding agent run to follow up on"
+            )));
+        };
+
+        let failed_step =
+            VerificationResult::find_by_execution_process_id(pool, ctx.execution_process.id)
+                .await?;
+        let step_name = failed_step
+            .as_ref()
+            .map(
+                |result| match ctx.repos.iter().find(|repo| repo.id == result.repo_id) {
+                    Some(repo) => format!("'{}' of {}", result.name, repo.name),
+                    None => format!("'{}'", result.name),
+                },
+            )
+            .unwrap_or_else(|| "A verification step".to_string());
+        let prompt = fix_up_prompt(&step_name, exit_code, output);
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, ctx.workspace.id).await?;
+        let Some((steps, verification_action)) = self.verification_action_for_repos(&repos).await?
+        else {
+            return Err(ContainerError::Other(anyhow!(
+                "No verification steps are configured"
+            )));
+        };
+        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let working_dir = ctx
+            .session
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                reset_to_message_id: info.reset_to_message_id(),
+                session_id: info.session_id,
+                executor_config: ExecutorConfig::from(profile),
+                working_dir,
+            }),
+            cleanup_action.map(Box::new),
+        )
+        .append_action(verification_action);
+
+        let run = VerificationRun::create(
+            pool,
+            ctx.workspace.id,
+            ctx.session.id,
+            VerificationTrigger::Agent,
+            &steps,
+        )
+        .await?;
+        let execution_process = match self
+            .start_execution(
+                &ctx.workspace,
+                &ctx.session,
+                &action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+        {
+            Ok(execution_process) => execution_process,
+            Err(e) => {
+                VerificationResult::abandon_run(pool, run.run.id, VerificationRunStatus::Failed)
+                    .await?;
+                return Err(e);
+            }
+        };
+        FixUpLoop::record_iteration(pool, fix_up.id, execution_process.id, run.run.id).await?;
+
+        tracing::info!(
+            "Fix-up loop {} sent follow-up {} of {} for session {}",
+            fix_up.id,
+            fix_up.iterations + 1,
+            fix_up.max_iterations,
+            ctx.session.id
+        );
+        Ok(())
+    }
+
+    /// End the fix-up loop whose latest follow-up `ctx` ran when the chain
+    /// stops before its verification run, releasing that run.
+    async fn end_fix_up_iteration(
+        &self,
+        ctx: &ExecutionContext,
+        status: FixUpLoopStatus,
+        error: &str,
+    ) {
+        let pool = &self.db.pool;
+        let fix_up = match FixUpLoop::find_running_by_session_id(pool, ctx.session.id).await {
+            Ok(Some(fix_up)) if fix_up.execution_process_id == Some(ctx.execution_process.id) => {
+                fix_up
+            }
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load fix-up loop for session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        if let Some(run_id) = fix_up.verification_run_id
+            && let Err(e) =
+                VerificationResult::abandon_run(pool, run_id, VerificationRunStatus::Cancelled)
+                    .await
+        {
+            tracing::warn!("Failed to cancel verification run {}: {}", run_id, e);
+        }
+        if let Err(e) = FixUpLoop::update_status(pool, fix_up.id, status, Some(error)).await {
+            tracing::warn!("Failed to update fix-up loop {}: {}", fix_up.id, e);
+        }
+        self.notification_service
+            .notify("Fix-up loop", error, Some(ctx.workspace.id))
+            .await;
+    }
+
+    /// End the PR comment fix whose follow-up `ctx` ran when the chain stops
+    /// before the comments can be answered.
+    async fn end_pr_comment_fix(
+        &self,
+        ctx: &ExecutionContext,
+        status: PrCommentFixStatus,
+        error: &str,
+    ) {
+        let pool = &self.db.pool;
+        let fix = match PrCommentFix::find_running_by_session_id(pool, ctx.session.id).await {
+            Ok(Some(fix)) if fix.execution_process_id == ctx.execution_process.id => fix,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load PR comment fix for session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return;
+            }
+        };
+        if let Err(e) = PrCommentFix::update_status(pool, fix.id, status, Some(error)).await {
+            tracing::warn!("Failed to update PR comment fix {}: {}", fix.id, e);
+        }
+        self.notification_service
+            .notify("Review comments", error, Some(ctx.workspace.id))
+            .await;
+    }
+
+    /// Once the follow-up addressing review comments has finished with its
+    /// cleanup scripts, push the branch and answer each comment on the host.
+    async fn publish_pr_comment_fix(
+        &self,
+        ctx: &ExecutionContext,
+        status: &ExecutionProcessStatus,
+    ) {
+        let pool = &self.db.pool;
+        let fix = match PrCommentFix::find_running_by_session_id(pool, ctx.session.id).await {
+            Ok(Some(fix)) => fix,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load PR comment fix for session {}: {}",
+                    ctx.session.id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let (status, error, notification) =
+            if ExecutionProcess::was_stopped(pool, ctx.execution_process.id).await {
+                let error = "The follow-up was stopped".to_string();
+                (PrCommentFixStatus::Cancelled, Some(error.clone()), error)
+            } else if *status != ExecutionProcessStatus::Completed {
+                let error = "A cleanup script failed, so nothing was pushed".to_string();
+                (PrCommentFixStatus::Failed, Some(error.clone()), error)
+            } else {
+                match self.push_and_reply(ctx, &fix).await {
+                    Ok(outcome) => {
+                        let mut notification = match outcome.replied {
+                            1 => "Pushed and replied to 1 review comment".to_string(),
+                            n => format!("Pushed and replied to {n} review comments"),
+                        };
+                        if outcome.resolved > 0 {
+                            notification.push_str(&format!(", resolving {}", outcome.resolved));
+                        }
+                        let error = (!outcome.failures.is_empty())
+                            .then(|| format!("Could not reply to {}", outcome.failures.join("; ")));
+                        if let Err(e) = PrCommentFix::mark_addressed(
+                            pool,
+                            fix.id,
+                            outcome.replied,
+                            error.as_deref(),
                         )
+                        .await
+                        {
+                            tracing::warn!("Failed to update PR comment fix {}: {}", fix.id, e);
+                        }
+                        self.notification_service
+                            .notify("Review comments", &notification, Some(ctx.workspace.id))
+                            .await;
+                        return;
+                    }
+                    Err(e) => {
+                        let error = format!("Could not push and reply: {e}");
+                        (PrCommentFixStatus::Failed, Some(error.clone()), error)
                     }
                 }
+            };
+
+        if let Err(e) = PrCommentFix::update_status(pool, fix.id, status, error.as_deref()).await {
+            tracing::warn!("Failed to update PR comment fix {}: {}", fix.id, e);
+        }
+        self.notification_service
+            .notify("Review comments", &notification, Some(ctx.workspace.id))
+            .await;
+    }
+
+    async fn push_and_reply(
+        &self,
+        ctx: &ExecutionContext,
+        fix: &PrCommentFix,
+    ) -> Result<pr_comments::RepliesOutcome, ContainerError> {
+        let Some(repo) = ctx.repos.iter().find(|repo| repo.id == fix.repo_id) else {
+            return Err(ContainerError::Other(anyhow!(
+                "The repo is no longer part of the workspace"
+            )));
+        };
+        let Some(workspace_repo) =
+            WorkspaceRepo::find_by_workspace_and_repo_id(&self.db.pool, ctx.workspace.id, repo.id)
+                .await?
+        else {
+            return Err(ContainerError::Other(anyhow!(
+                "The repo is no longer part of the workspace"
+            )));
+        };
+        let worktree_path = self
+            .workspace_to_current_dir(&ctx.workspace)
+            .join(&repo.name);
+        let git_host_options = self
+            .config
+            .read()
+            .await
+            .git_host_options(repo.github_access);
+
+        pr_comments::push_and_reply(
+            &self.git,
+            &git_host_options,
+            fix,
+            repo,
+            &worktree_path,
+            &ctx.workspace.branch,
+            &workspace_repo.target_branch,
+        )
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!(e)))
+    }
+
+    /// The tail of what a script wrote to stdout and stderr, short enough to
+    /// hand to a coding agent
+    async fn script_output_tail(&self, exec_id: &Uuid) -> String {
+        let msg_stores = self.msg_stores.read().await;
+        let Some(msg_store) = msg_stores.get(exec_id) else {
+            return String::new();
+        };
+        let output: String = msg_store
+            .get_history()
+            .iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect();
+        tail_of(
+            output.trim_end(),
+            MAX_FIX_UP_OUTPUT_LINES,
+            MAX_FIX_UP_OUTPUT_BYTES,
+        )
+        .to_string()
+    }
+
+    /// Verify the repos that ask for it once a coding agent turn, including
+    /// its cleanup scripts, has finished.
+    async fn verify_after_agent_turn(&self, ctx: &ExecutionContext) {
+        let repo_ids: Vec<Uuid> = ctx
+            .repos
+            .iter()
+            .filter(|repo| repo.verify_after_agent)
+            .map(|repo| repo.id)
+            .collect();
+        if repo_ids.is_empty() {
+            return;
+        }
+        match self
+            .start_verification(
+                &ctx.workspace,
+                &ctx.session,
+                Some(&repo_ids),
+                VerificationTrigger::Agent,
+            )
+            .await
+        {
+            Ok(Some(run)) => {
+                tracing::debug!(
+                    "Started verification run {} for workspace {}",
+                    run.run.id,
+                    ctx.workspace.id
+                );
             }
-            ExecutionProcessRunReason::CleanupScript => {
-                format!("Cleanup script changes for workspace {}", ctx.workspace.id)
+            Ok(None) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to start verification for workspace {}: {}",
+                    ctx.workspace.id,
+                    e
+                );
             }
-            _ => format!(
-                "Changes from execution process {}",
-                ctx.execution_process.id
-            ),
         }
     }
 
-    /// Check which repos have uncommitted changes. Fails if any repo is inaccessible.
-    fn check_repos_for_changes(
-        &self,
-        workspace_root: &Path,
-        repos: &[Repo],
-    ) -> Result<Vec<(Repo, PathBuf)>, ContainerError> {
-        let git = GitService::new();
-        let mut repos_with_changes = Vec::new();
+    /// Whether a run's logs show the agent calling any tool. Turns that only
+    /// talked cannot have changed the worktree and get no checkpoint.
+    async fn used_tools(&self, exec_id: &Uuid) -> bool {
+        let msg_stores = self.msg_stores.read().await;
+        let Some(msg_store) = msg_stores.get(exec_id) else {
+            return false;
+        };
+        msg_store.get_history().iter().any(|msg| {
+            if let LogMsg::JsonPatch(patch) = msg
+                && let Some((_, entry)) = extract_normalized_entry_from_patch(patch)
+            {
+                matches!(entry.entry_type, NormalizedEntryType::ToolUse { .. })
+            } else {
+                false
+            }
+        })
+    }
 
-        for repo in repos {
-            let worktree_path = workspace_root.join(&repo.name);
+    /// Snapshot every repo of the workspace under a hidden ref once a coding
+    /// agent turn that used tools has ended, so the session can later be
+    /// restored or branched from this turn.
+    async fn create_checkpoints(&self, ctx: &ExecutionContext) {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent
+        ) || !self.used_tools(&ctx.execution_process.id).await
+        {
+            return;
+        }
 
-            match git.get_worktree_status(&worktree_path) {
-                Ok(ws) if !ws.entries.is_empty() => {
-                    repos_with_changes.push((repo.clone(), worktree_path));
-                }
-                Ok(_) => {
-                    tracing::debug!("No changes in repo '{}'", repo.name);
-                }
+        let repo_states = ExecutionProcessRepoState::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await
+        .unwrap_or_default();
+        let workspace_root = self.workspace_to_current_dir(&ctx.workspace);
+        let ref_name = Checkpoint::ref_name(ctx.workspace.id, ctx.execution_process.id);
+        let message = format!(
+            "Checkpoint after execution process {}",
+            ctx.execution_process.id
+        );
+
+        for repo in &ctx.repos {
+            let since = match Checkpoint::find_latest_commit(&self.db.pool, ctx.session.id, repo.id)
+                .await
+            {
+                Ok(Some(commit)) => Some(commit),
+                _ => repo_states
+                    .iter()
+                    .find(|s| s.repo_id == repo.id)
+                    .and_then(|s| s.before_head_commit.clone()),
+            };
+            let worktree_path = workspace_root.join(&repo.name);
+            let snapshot = match self.git().create_checkpoint(
+                &worktree_path,
+                &ref_name,
+                &message,
+                since.as_deref(),
+            ) {
+                Ok(snapshot) => snapshot,
                 Err(e) => {
-                    return Err(ContainerError::Other(anyhow!(
-                        "Pre-flight check failed for repo '{}': {}",
+                    tracing::warn!(
+                        "Failed to create checkpoint for repo '{}' after execution {}: {}",
                         repo.name,
+                        ctx.execution_process.id,
                         e
-                    )));
+                    );
+                    continue;
                 }
+            };
+            if let Err(e) = Checkpoint::create(
+                &self.db.pool,
+                &CreateCheckpoint {
+                    workspace_id: ctx.workspace.id,
+                    session_id: ctx.session.id,
+                    execution_process_id: ctx.execution_process.id,
+                    repo_id: repo.id,
+                    ref_name: ref_name.clone(),
+                    commit_sha: snapshot.commit,
+                    head_commit: snapshot.head,
+                    files_changed: snapshot.files_changed as i64,
+                    lines_added: snapshot.lines_added as i64,
+                    lines_removed: snapshot.lines_removed as i64,
+                },
+            )
+            .await
+            {
+                tracing::warn!(
+                    "Failed to record checkpoint for repo '{}' after execution {}: {}",
+                    repo.name,
+                    ctx.execution_process.id,
+                    e
+                );
             }
         }
-
-        Ok(repos_with_changes)
     }
 
     async fn has_commits_from_execution(
@@ -459,7 +1251,11 @@ impl LocalContainerService {
     }
 
     /// Commit changes to each repo. Logs failures but continues with other repos.
-    fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
+    fn commit_repos(
+        &self,
+        repos_with_changes: Vec<(Repo, PathBuf)>,
+        message_for: impl Fn(&Repo) -> String,
+    ) -> bool {
         let mut any_committed = false;
 
         for (repo, worktree_path) in repos_with_changes {
@@ -469,7 +1265,11 @@ impl LocalContainerService {
                 &worktree_path
             );
 
-            match self.git().commit(&worktree_path, message) {
+            let signing = commit_signing(&repo);
+            match self
+                .git()
+                .commit_signed(&worktree_path, &message_for(&repo), signing.as_ref())
+            {
                 Ok(true) => {
                     any_committed = true;
                     tracing::info!("Committed changes in repo '{}'", repo.name);
@@ -551,6 +1351,8 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
+            container.finish_cgroup(exec_id, &status).await;
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
@@ -639,6 +1441,70 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Completed
                 ) && exit_code == Some(0);
 
+                // Continue the rebase or merge this run was resolving before
+                // anything is committed on top of it
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    container.finish_conflict_resolution(&ctx, success).await;
+                }
+
+                // Settle the verification step before the next one claims
+                // its result
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::TestScript
+                ) {
+                    container
+                        .finish_verification_step(&ctx, &status, exit_code)
+                        .await;
+                }
+
+                // A fix-up follow-up that did not complete never reaches the
+                // verification run chained after it
+                if matches!(
+                    ctx.execution_process.run_reason,
+                    ExecutionProcessRunReason::CodingAgent
+                ) {
+                    if ExecutionProcess::was_stopped(&db.pool, exec_id).await {
+                        container
+                            .end_fix_up_iteration(
+                                &ctx,
+                                FixUpLoopStatus::Cancelled,
+                                "The coding agent was stopped",
+                            )
+                            .await;
+                        container
+                            .end_pr_comment_fix(
+                                &ctx,
+                                PrCommentFixStatus::Cancelled,
+                                "The coding agent was stopped",
+                            )
+                            .await;
+                    } else if status != ExecutionProcessStatus::Completed {
+                        container
+                            .end_fix_up_iteration(
+                                &ctx,
+                                FixUpLoopStatus::Failed,
+                                "The coding agent run did not complete",
+                            )
+                            .await;
+                        container
+                            .end_pr_comment_fix(
+                                &ctx,
+                                PrCommentFixStatus::Failed,
+                                "The coding agent run did not complete",
+                            )
+                            .await;
+                    }
+                }
+
+                // A failed or stopped turn can still have changed files
+                if !success {
+                    container.create_checkpoints(&ctx).await;
+                }
+
                 let cleanup_done = matches!(
                     ctx.execution_process.run_reason,
                     ExecutionProcessRunReason::CleanupScript
@@ -660,6 +1526,9 @@ impl LocalContainerService {
                         }
                     };
 
+                    // Snapshot before cleanup scripts can touch the worktree
+                    container.create_checkpoints(&ctx).await;
+
                     let should_start_next = if matches!(
                         ctx.execution_process.run_reason,
                         ExecutionProcessRunReason::CodingAgent
@@ -688,6 +1557,21 @@ impl LocalContainerService {
                         // Manually finalize task since we're bypassing normal execution flow
                         container.finalize_task(&ctx).await;
                         already_finalized = true;
+
+                        container
+                            .end_fix_up_iteration(
+                                &ctx,
+                                FixUpLoopStatus::Failed,
+                                "The coding agent made no changes",
+                            )
+                            .await;
+                        container
+                            .end_pr_comment_fix(
+                                &ctx,
+                                PrCommentFixStatus::Failed,
+                                "The coding agent made no changes",
+                            )
+                            .await;
                     }
                 }
 
@@ -700,60 +1584,75 @@ impl LocalContainerService {
                         .is_some();
                     let mut started_queued_follow_up = false;
 
-                    // Only execute queued messages if the execution succeeded
-                    // If it failed or was killed, just clear the queue and finalize
-                    let should_execute_queued = !matches!(
-                        ctx.execution_process.status,
-                        ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
-                    );
+                    // Push before a queued follow-up can touch the worktree
+                    if matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                            | ExecutionProcessRunReason::CleanupScript
+                    ) {
+                        container.publish_pr_comment_fix(&ctx, &status).await;
+                    }
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
+                    // Only execute queued messages if the execution succeeded.
+                    // If it failed or was killed, leave the queue untouched so the
+                    // user can edit or clear it, and finalize.
+                    let queued_msg = container
+                        .queued_message_service
+                        .take_next_after_turn(ctx.session.id, &ctx.execution_process.status)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::error!(
+                                "Failed to take queued message for session {}: {}",
+                                ctx.session.id,
+                                e
                             );
+                            None
+                        });
 
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
-                            .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
-                                    e
-                                );
-                            }
+                    if let Some(queued_msg) = queued_msg {
+                        tracing::info!(
+                            "Found queued message for session {}, starting follow-up execution",
+                            ctx.session.id
+                        );
 
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
+                        // Delete the scratch since we're consuming the queued message
+                        if let Err(e) =
+                            Scratch::delete(&db.pool, ctx.session.id, &ScratchType::DraftFollowUp)
                                 .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
-                                container.finalize_task(&ctx).await;
-                            } else {
-                                started_queued_follow_up = true;
-                            }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
+                        {
+                            tracing::warn!(
+                                "Failed to delete scratch after consuming queued message: {}",
+                                e
                             );
+                        }
+
+                        // Execute the queued follow-up
+                        if let Err(e) = container
+                            .start_queued_follow_up(&ctx, &queued_msg.data)
+                            .await
+                        {
+                            tracing::error!("Failed to start queued follow-up: {}", e);
+                            // Fall back to finalization if follow-up fails
                             container.finalize_task(&ctx).await;
+                        } else {
+                            started_queued_follow_up = true;
                         }
                     } else {
                         container.finalize_task(&ctx).await;
                     }
 
+                    let agent_turn_finished = matches!(
+                        ctx.execution_process.run_reason,
+                        ExecutionProcessRunReason::CodingAgent
+                            | ExecutionProcessRunReason::CleanupScript
+                    ) && status == ExecutionProcessStatus::Completed
+                        && !started_queued_follow_up;
+                    if agent_turn_finished
+                        && !ExecutionProcess::was_stopped(&db.pool, exec_id).await
+                    {
+                        container.verify_after_agent_turn(&ctx).await;
+                    }
+
                     let should_mark_turn_unseen = matches!(
                         ctx.execution_process.run_reason,
                         ExecutionProcessRunReason::CodingAgent
@@ -790,8 +1689,18 @@ impl LocalContainerService {
                     .unwrap_or(true);
 
                     if !has_running_agent
-                        && let Some(queued_msg) =
-                            container.queued_message_service.take_queued(ctx.session.id)
+                        && let Some(queued_msg) = container
+                            .queued_message_service
+                            .take_next(ctx.session.id)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!(
+                                    "Failed to take queued message for session {}: {}",
+                                    ctx.session.id,
+                                    e
+                                );
+                                None
+                            })
                     {
                         tracing::info!(
                             "Parallel setup script finished with queued message for session {}, starting follow-up",
@@ -885,13 +1794,23 @@ impl LocalContainerService {
 
             // Update DB status only after Finished frame is flushed so the
             // frontend status-change event never arrives before the stream ends.
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
+            let stopped = ExecutionProcess::was_stopped(&db.pool, exec_id).await;
+            if !stopped
                 && let Err(e) =
                     ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
             {
                 tracing::error!("Failed to update execution process completion: {}", e);
             }
 
+            // Hand the slot of a finished coding agent to the start queue.
+            // Stopping paths start queued agents themselves, except shutdown.
+            if !stopped
+                && !container.stopping_all.load(Ordering::SeqCst)
+                && let Err(e) = container.start_queued_executions().await
+            {
+                tracing::error!("Failed to start queued executions: {}", e);
+            }
+
             // Drop the ProtocolPeer and reap the child under a single
             // `protocol_peers` write guard. Holding the guard across the
             // `child_store` removal is what actually closes the late-
@@ -923,6 +1842,63 @@ impl LocalContainerService {
         })
     }
 
+    /// The cgroup with `limits` that an execution's process joins when it's
+    /// spawned. Runs without limits, noting why in its logs, where cgroups
+    /// are unavailable.
+    async fn create_cgroup(
+        &self,
+        exec_id: Uuid,
+        limits: &ResourceLimits,
+    ) -> Option<ExecutionCgroup> {
+        match ExecutionCgroup::create(exec_id, limits) {
+            Ok(cgroup) => Some(cgroup),
+            Err(e) => {
+                tracing::warn!("Resource limits not applied to execution {exec_id}: {e}");
+                if let Some(store) = self.get_msg_store_by_id(&exec_id).await {
+                    store.push(LogMsg::Stderr(format!(
+                        "Resource limits were not applied: {e}\n"
+                    )));
+                }
+                None
+            }
+        }
+    }
+
+    /// Record what a limited execution used and remove its cgroup, killing
+    /// any processes left in it
+    async fn finish_cgroup(&self, exec_id: Uuid, status: &ExecutionProcessStatus) {
+        let Some(cgroup) = self.cgroups.write().await.remove(&exec_id) else {
+            return;
+        };
+        let ResourceUsage {
+            peak_memory_bytes,
+            cpu_time_ms,
+            oom_kills,
+        } = cgroup.usage();
+        cgroup.remove().await;
+
+        if oom_kills > 0
+            && let Some(store) = self.get_msg_store_by_id(&exec_id).await
+        {
+            store.push(LogMsg::Stderr(format!(
+                "{oom_kills} process(es) killed for exceeding the memory limit\n"
+            )));
+        }
+        let failure_reason = (*status == ExecutionProcessStatus::Failed && oom_kills > 0)
+            .then_some(ExecutionFailureReason::OomKilled);
+        if let Err(e) = ExecutionProcess::update_resource_usage(
+            &self.db.pool,
+            exec_id,
+            peak_memory_bytes.and_then(|bytes| i64::try_from(bytes).ok()),
+            cpu_time_ms.and_then(|ms| i64::try_from(ms).ok()),
+            failure_reason,
+        )
+        .await
+        {
+            tracing::error!("Failed to record resource usage of execution {exec_id}: {e}");
+        }
+    }
+
     fn spawn_os_exit_watcher(
         &self,
         exec_id: Uuid,
@@ -1054,6 +2030,115 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Why the workspace is over its budget; `None` without a budget or while
+    /// within it
+    async fn workspace_budget_exceeded(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<Option<String>, ContainerError> {
+        let Some(budget) =
+            WorkspaceBudget::find_by_workspace_id(&self.db.pool, workspace_id).await?
+        else {
+            return Ok(None);
+        };
+        let totals = ExecutionTokenUsage::totals_for_workspace(&self.db.pool, workspace_id).await?;
+        Ok(budget.exceeded_by(&totals))
+    }
+
+    /// Persist the token usage an execution's normalized logs report and stop
+    /// the execution once its workspace runs over budget.
+    fn spawn_token_usage_tracker(
+        &self,
+        exec_id: Uuid,
+        workspace_id: Uuid,
+        msg_store: Arc<MsgStore>,
+    ) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            let mut stream = msg_store.history_plus_stream();
+            let mut last_usage: Option<TokenUsage> = None;
+            while let Some(Ok(msg)) = stream.next().await {
+                let patch = match msg {
+                    LogMsg::JsonPatch(patch) => patch,
+                    LogMsg::Finished => break,
+                    _ => continue,
+                };
+                let Some((
+                    _,
+                    NormalizedEntry {
+                        entry_type:
+                            NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
+                                usage: Some(usage),
+                                ..
+                            }),
+                        ..
+                    },
+                )) = extract_normalized_entry_from_patch(&patch)
+                else {
+                    continue;
+                };
+                if last_usage.as_ref() == Some(&usage) {
+                    continue;
+                }
+
+                match container
+                    .record_token_usage(exec_id, workspace_id, &usage)
+                    .await
+                {
+                    Ok(Some(reason)) => {
+                        msg_store.push(LogMsg::Stderr(format!("{reason}. Stopping the agent.\n")));
+                        container.stop_over_budget_execution(exec_id).await;
+                        break;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to record token usage for execution {exec_id}: {e}"
+                        );
+                    }
+                }
+                last_usage = Some(usage);
+            }
+        });
+    }
+
+    /// Store the latest usage of an execution. Returns why the workspace is
+    /// over budget, if it is.
+    async fn record_token_usage(
+        &self,
+        exec_id: Uuid,
+        workspace_id: Uuid,
+        usage: &TokenUsage,
+    ) -> Result<Option<String>, ContainerError> {
+        let price = match &usage.model {
+            Some(model) => ModelPrice::find_for_model(&self.db.pool, model).await?,
+            None => None,
+        };
+        let cost_usd = price.map(|price| price.cost_usd(usage));
+        ExecutionTokenUsage::upsert(&self.db.pool, exec_id, usage, cost_usd).await?;
+        self.workspace_budget_exceeded(workspace_id).await
+    }
+
+    async fn stop_over_budget_execution(&self, exec_id: Uuid) {
+        let process = match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+            Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::error!("Failed to load over-budget execution {exec_id}: {e}");
+                return;
+            }
+        };
+        if let Err(e) = self
+            .stop_execution(&process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!("Failed to stop over-budget execution {exec_id}: {e}");
+        }
+        if let Err(e) = self.start_queued_executions().await {
+            tracing::error!("Failed to start queued executions: {e}");
+        }
+    }
+
     /// Copy project files and workspace attachments to the workspace.
     /// Skips files that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -1204,8 +2289,8 @@ impl LocalContainerService {
         let action_type = if let Some(info) = latest_session_info {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
                 prompt: queued_data.message.clone(),
+                reset_to_message_id: info.reset_to_message_id(),
                 session_id: info.session_id,
-                reset_to_message_id: None,
                 executor_config: queued_data.executor_config.clone(),
                 working_dir: working_dir.clone(),
             })
@@ -1229,6 +2314,20 @@ impl LocalContainerService {
     }
 }
 
+/// Resource limits of the coding agent profile an action runs
+fn profile_resource_limits(executor_action: &ExecutorAction) -> ResourceLimits {
+    let executor_config = match executor_action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => &request.executor_config,
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => &request.executor_config,
+        ExecutorActionType::ReviewRequest(request) => &request.executor_config,
+        ExecutorActionType::ScriptRequest(_) => return ResourceLimits::default(),
+    };
+    ExecutorConfigs::get_cached()
+        .get_coding_agent(&executor_config.profile_id())
+        .and_then(|agent| agent.cmd_overrides()?.resource_limits)
+        .unwrap_or_default()
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -1242,6 +2341,41 @@ fn failure_exit_status() -> std::process::ExitStatus {
     }
 }
 
+/// The last `max_lines` lines of `output`, cut to at most `max_bytes` on a
+/// char boundary
+fn tail_of(output: &str, max_lines: usize, max_bytes: usize) -> &str {
+    let mut start = output
+        .rmatch_indices('\n')
+        .nth(max_lines.saturating_sub(1))
+        .map(|(idx, _)| idx + 1)
+        .unwrap_or(0);
+    if output.len() - start > max_bytes {
+        start = output.len() - max_bytes;
+        while !output.is_char_boundary(start) {
+            start += 1;
+        }
+    }
+    &output[start..]
+}
+
+fn fix_up_prompt(step_name: &str, exit_code: Option<i64>, output: &str) -> String {
+    let failed = match exit_code {
+        Some(code) => format!("{step_name} failed with exit code {code}."),
+        None => format!("{step_name} failed."),
+    };
+    let output = if output.is_empty() {
+        "The step produced no output.".to_string()
+    } else {
+        format!("The end of its output:\n\n```\n{output}\n```")
+    };
+    format!(
+        "The verification steps of this workspace failed. {failed}\n\n{output}\n\n\
+         Fix the code so that the verification steps pass. Do not change, skip or \
+         disable the verification steps themselves. The steps run again once you \
+         are done."
+    )
+}
+
 #[async_trait]
 impl ContainerService for LocalContainerService {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>> {
@@ -1305,6 +2439,14 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn concurrency_config(&self) -> ConcurrencyConfig {
+        self.config.read().await.concurrency.clone()
+    }
+
+    fn start_queue_lock(&self) -> &Mutex<()> {
+        &self.start_queue_lock
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1439,6 +2581,12 @@ impl ContainerService for LocalContainerService {
             )))?;
         let current_dir = PathBuf::from(container_ref);
 
+        if executor_action.base_executor().is_some()
+            && let Some(reason) = self.workspace_budget_exceeded(workspace.id).await?
+        {
+            return Err(ContainerError::Other(anyhow!(reason)));
+        }
+
         let approvals_service: Arc<dyn ExecutorApprovalService> =
             match executor_action.base_executor() {
                 Some(
@@ -1466,6 +2614,7 @@ impl ContainerService for LocalContainerService {
             .commit_reminder_prompt
             .clone()
             .unwrap_or_else(|| DEFAULT_COMMIT_REMINDER_PROMPT.to_string());
+        let sandbox_config = config.sandbox.clone();
         drop(config);
         let mut env = ExecutionEnv::new(
             repo_context,
@@ -1473,12 +2622,43 @@ impl ContainerService for LocalContainerService {
             commit_reminder_prompt,
         );
 
+        let mut sandbox = Sandbox::from_config(&sandbox_config, vec![current_dir.clone()]);
+        if let Some(sandbox) = sandbox.as_mut() {
+            for repo in &repos {
+                // Commits from a worktree write to the objects and refs of its repo
+                if let Ok(common_dir) = self.git.get_common_dir(&repo.path) {
+                    sandbox.add_git_dir(&common_dir);
+                }
+                // The host runs git in the worktree too, so the files pointing
+                // it at its git directory must not be redirected
+                let worktree_path = current_dir.join(&repo.name);
+                sandbox.add_read_only_paths([worktree_path.join(".git")]);
+                if let Ok(git_dir) = self.git.get_git_dir(&worktree_path) {
+                    sandbox.add_read_only_paths(
+                        ["commondir", "gitdir", "config.worktree"].map(|file| git_dir.join(file)),
+                    );
+                }
+            }
+        }
+        env.sandbox = sandbox;
+
         // Always inject workspace/session context
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        let limits = repos.iter().map(Repo::resource_limits).fold(
+            profile_resource_limits(executor_action),
+            ResourceLimits::tightest,
+        );
+        let cgroup = if limits.is_empty() {
+            None
+        } else {
+            self.create_cgroup(execution_process.id, &limits).await
+        };
+        env.cgroup = cgroup.as_ref().map(|cgroup| cgroup.path().to_path_buf());
+
         // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
+        let spawn_result = tokio::time::timeout(
             Duration::from_secs(30),
             executor_action.spawn(&current_dir, approvals_service, &env),
         )
@@ -1487,7 +2667,23 @@ impl ContainerService for LocalContainerService {
             ContainerError::Other(anyhow!(
                 "Timeout: process took more than 30 seconds to start"
             ))
-        })??;
+        })
+        .and_then(|spawned| spawned.map_err(ContainerError::from));
+        let mut spawned = match spawn_result {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                if let Some(cgroup) = cgroup {
+                    cgroup.remove().await;
+                }
+                return Err(e);
+            }
+        };
+        if let Some(cgroup) = cgroup {
+            self.cgroups
+                .write()
+                .await
+                .insert(execution_process.id, cgroup);
+        }
 
         if let Err(e) = self
             .track_child_msgs_in_store(execution_process.id, &mut spawned.child)
@@ -1510,6 +2706,12 @@ impl ContainerService for LocalContainerService {
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
+        if executor_action.base_executor().is_some()
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.spawn_token_usage_tracker(execution_process.id, workspace.id, msg_store);
+        }
+
         // If the executor vended a ProtocolPeer (e.g. Claude Code), receive it
         // and store it so `inject_message()` can reach it while the SDK is
         // mid-turn. Then wait for the per-turn-idle signal and drop the peer
@@ -1643,6 +2845,8 @@ impl ContainerService for LocalContainerService {
             let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
         }
 
+        self.finish_cgroup(execution_process.id, &status).await;
+
         // DB flip LAST — status event now always trails the Finished log frame
         ExecutionProcess::update_completion(&self.db.pool, execution_process.id, status, exit_code)
             .await?;
@@ -1741,8 +2945,6 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        let message = self.get_commit_message(ctx).await;
-
         let container_ref = ctx
             .workspace
             .container_ref
@@ -1756,7 +2958,10 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        Ok(self.commit_repos(repos_with_changes, &message))
+        let message_ctx = self.commit_message_context(ctx).await;
+        Ok(self.commit_repos(repos_with_changes, |repo| {
+            self.get_commit_message(ctx, &message_ctx, repo)
+        }))
     }
 
     /// Copy files from the original project directory to the worktree.
@@ -1784,6 +2989,7 @@ impl ContainerService for LocalContainerService {
 
     async fn kill_all_running_processes(&self) -> Result<(), ContainerError> {
         tracing::info!("Killing all running processes");
+        self.stopping_all.store(true, Ordering::SeqCst);
         let running_processes = ExecutionProcess::find_running(&self.db.pool).await?;
 
         tracing::info!(
@@ -31,7 +31,7 @@ use services::services::{
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
-    webhook_dispatcher::WebhookDispatcher,
+    webhook_dispatcher::{WebhookCache, WebhookDispatcher},
 };
 use tokio::sync::{Notify, RwLock};
 use tokio_util::sync::CancellationToken;
@@ -44,11 +44,17 @@ use uuid::Uuid;
 use workspace_manager::WorkspaceManager;
 use worktree_manager::WorktreeManager;
 
-use crate::{container::LocalContainerService, pty::PtyService};
+use crate::{
+    container::LocalContainerService, merge_queue::MergeQueueRunner, pty::PtyService,
+    scheduler::Scheduler,
+};
+mod cgroup;
 mod command;
 pub mod container;
 mod copy;
+mod merge_queue;
 pub mod pty;
+mod scheduler;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -81,6 +87,12 @@ pub struct LocalDeployment {
     ssh_config: Arc<russh::server::Config>,
     pty: PtyService,
     pr_sync_notify: Arc<Notify>,
+    /// Wakes the webhook dispatcher's delivery worker, e.g. after a manual
+    /// redeliver, so it doesn't wait for its next poll tick.
+    webhook_delivery_notify: Arc<Notify>,
+    /// The webhook dispatcher's cache of enabled webhooks, invalidated by
+    /// the webhook routes.
+    webhook_cache: WebhookCache,
     /// Abort handle for the webhook dispatcher background task. We keep an
     /// `AbortHandle` (which is `Clone`) rather than the `JoinHandle` itself
     /// because `LocalDeployment` derives `Clone`, so wrapping a
@@ -90,6 +102,10 @@ pub struct LocalDeployment {
     /// to force-abort on teardown paths that bypass the token.
     #[allow(dead_code)]
     webhook_dispatcher_abort: tokio::task::AbortHandle,
+    /// Wakes the scheduler so a newly created or resumed schedule is picked
+    /// up without waiting for its next poll tick.
+    schedule_notify: Arc<Notify>,
+    merge_queue_notify: Arc<Notify>,
     wal_monitor: db::wal_monitor::WalMonitorHandle,
     last_vacuum_time: Arc<RwLock<Option<DateTime<Utc>>>>,
 }
@@ -176,8 +192,9 @@ impl Deployment for LocalDeployment {
             });
         }
 
-        let approvals = Approvals::new();
-        let queued_message_service = QueuedMessageService::new();
+        let approvals = Approvals::new(db.clone());
+        let queued_message_service =
+            QueuedMessageService::new(db.pool.clone(), events_msg_store.clone());
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -260,9 +277,16 @@ impl Deployment for LocalDeployment {
         // so we can force-abort if needed. We drop the JoinHandle rather
         // than storing it because `LocalDeployment` is `Clone` and cloning
         // a JoinHandle through an Arc would misleadingly share ownership.
-        let webhook_dispatcher_handle =
-            WebhookDispatcher::new(db.pool.clone(), events.msg_store().clone())
-                .spawn(shutdown.child_token());
+        let webhook_delivery_notify = Arc::new(Notify::new());
+        let webhook_cache = WebhookCache::default();
+        let webhook_dispatcher_handle = WebhookDispatcher::new(
+            db.pool.clone(),
+            events.msg_store().clone(),
+            approvals.clone(),
+            webhook_delivery_notify.clone(),
+            webhook_cache.clone(),
+        )
+        .spawn(shutdown.child_token());
         let webhook_dispatcher_abort = webhook_dispatcher_handle.abort_handle();
 
         let file_search_cache = Arc::new(FileSearchCache::new());
@@ -289,9 +313,37 @@ impl Deployment for LocalDeployment {
             });
             let container = container.clone();
             let rc = remote_client.clone().ok();
-            PrMonitorService::spawn(db, analytics, container, rc, pr_sync_notify.clone()).await;
+            PrMonitorService::spawn(
+                db,
+                config.clone(),
+                analytics,
+                container,
+                rc,
+                pr_sync_notify.clone(),
+            )
+            .await;
         }
 
+        let schedule_notify = Arc::new(Notify::new());
+        Scheduler::new(
+            db.clone(),
+            container.clone(),
+            workspace_manager.clone(),
+            git.clone(),
+            schedule_notify.clone(),
+        )
+        .spawn(shutdown.child_token());
+
+        let merge_queue_notify = Arc::new(Notify::new());
+        MergeQueueRunner::new(
+            db.clone(),
+            container.clone(),
+            git.clone(),
+            remote_client.clone().ok(),
+            merge_queue_notify.clone(),
+        )
+        .spawn(shutdown.child_token());
+
         let deployment = Self {
             config,
             user_id,
@@ -322,7 +374,11 @@ impl Deployment for LocalDeployment {
             ssh_config,
             pty,
             pr_sync_notify,
+            webhook_delivery_notify,
+            webhook_cache,
             webhook_dispatcher_abort,
+            schedule_notify,
+            merge_queue_notify,
             wal_monitor,
             last_vacuum_time: Arc::new(RwLock::new(None)),
         };
@@ -518,6 +574,22 @@ impl LocalDeployment {
         self.pr_sync_notify.notify_one();
     }
 
+    pub fn trigger_webhook_delivery(&self) {
+        self.webhook_delivery_notify.notify_one();
+    }
+
+    pub async fn invalidate_webhook_cache(&self) {
+        self.webhook_cache.invalidate().await;
+    }
+
+    pub fn trigger_schedule_check(&self) {
+        self.schedule_notify.notify_one();
+    }
+
+    pub fn trigger_merge_queue(&self) {
+        self.merge_queue_notify.notify_one();
+    }
+
     pub fn wal_monitor(&self) -> &db::wal_monitor::WalMonitorHandle {
         &self.wal_monitor
     }
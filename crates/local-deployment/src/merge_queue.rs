@@ -0,0 +1,480 @@
+//! Background runner for the merge queue. Entries with the same repo and
+//! target branch form one queue, merged one at a time in order, while
+//! separate queues are processed concurrently: the
+//! workspace is rebased onto the current tip of the target, the repo's
+//! verification steps are run, and the branch is merged with the strategy
+//! stored on the entry. A conflict or failing check pauses that queue and
+//! sends a notification.
+
+use std::{collections::HashSet, path::Path, str::FromStr, sync::Arc, time::Duration};
+
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        merge::Merge,
+        merge_queue::{MergeQueueEntry, MergeQueueStatus},
+        repo::Repo,
+        session::{CreateSession, Session},
+        verification::{
+            VerificationResult, VerificationResultStatus, VerificationRun, VerificationRunStatus,
+            VerificationTrigger,
+        },
+        workspace::Workspace,
+    },
+};
+use git::{GitService, GitServiceError, MergeStrategy};
+use services::services::{
+    container::{ContainerError, ContainerService},
+    remote_client::RemoteClient,
+    remote_sync,
+    repo::commit_signing,
+};
+use thiserror::Error;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::container::LocalContainerService;
+
+/// How often queues are checked when nothing woke the runner up
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often a running verification run is checked for completion
+const VERIFICATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Longest a verification run may take before the entry fails
+const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Error)]
+enum MergeQueueRunError {
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// How processing an entry ended
+enum Outcome {
+    Merged {
+        merge_commit: String,
+    },
+    /// The workspace can no longer be merged, so it leaves the queue
+    Cancelled(String),
+    Conflict(String),
+    Failed(String),
+}
+
+pub struct MergeQueueRunner {
+    db: DBService,
+    container: LocalContainerService,
+    git: GitService,
+    remote_client: Option<RemoteClient>,
+    notify: Arc<Notify>,
+}
+
+impl MergeQueueRunner {
+    pub fn new(
+        db: DBService,
+        container: LocalContainerService,
+        git: GitService,
+        remote_client: Option<RemoteClient>,
+        notify: Arc<Notify>,
+    ) -> Self {
+        Self {
+            db,
+            container,
+            git,
+            remote_client,
+            notify,
+        }
+    }
+
+    /// Spawn the runner loop; it terminates when `shutdown` is cancelled.
+    pub fn spawn(self, shutdown: CancellationToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(Arc::new(self).run(shutdown))
+    }
+
+    async fn run(self: Arc<Self>, shutdown: CancellationToken) {
+        tracing::info!("Starting merge queue with interval {:?}", POLL_INTERVAL);
+        match MergeQueueEntry::fail_interrupted(&self.db.pool).await {
+            Ok(0) => {}
+            Ok(count) => tracing::warn!("merge queue: {count} interrupted entries marked failed"),
+            Err(e) => tracing::warn!("merge queue: db error failing interrupted entries: {e}"),
+        }
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    tracing::info!("merge queue: shutdown signalled, exiting");
+                    return;
+                }
+                _ = self.notify.notified() => {}
+                _ = poll.tick() => {}
+            }
+
+            self.run_ready().await;
+        }
+    }
+
+    /// Claim the head of every ready queue and process each in its own task,
+    /// so a long verification only holds up its own queue. A finished entry
+    /// wakes the runner to pick up the next head of its queue.
+    async fn run_ready(self: &Arc<Self>) {
+        let ready = match MergeQueueEntry::find_ready(&self.db.pool).await {
+            Ok(ready) => ready,
+            Err(e) => {
+                tracing::warn!("merge queue: db error loading queued entries: {e}");
+                return;
+            }
+        };
+        // One entry per workspace at a time, as they share its processes
+        let mut workspaces = HashSet::new();
+        for entry in ready {
+            if !workspaces.insert(entry.workspace_id) {
+                continue;
+            }
+            match MergeQueueEntry::claim(&self.db.pool, entry.id).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("merge queue: failed to claim entry {}: {e}", entry.id);
+                    continue;
+                }
+            }
+            let runner = Arc::clone(self);
+            tokio::spawn(async move {
+                runner.process(&entry).await;
+                runner.notify.notify_one();
+            });
+        }
+    }
+
+    async fn process(&self, entry: &MergeQueueEntry) {
+        tracing::info!(
+            entry_id = %entry.id,
+            workspace_id = %entry.workspace_id,
+            "Merging queued workspace into '{}'",
+            entry.target_branch
+        );
+        let outcome = self.try_merge(entry).await.unwrap_or_else(|e| {
+            tracing::error!(entry_id = %entry.id, "Merge queue entry failed: {e}");
+            Outcome::Failed(e.to_string())
+        });
+
+        let pool = &self.db.pool;
+        let result = match &outcome {
+            Outcome::Merged { merge_commit } => {
+                MergeQueueEntry::mark_merged(pool, entry.id, merge_commit).await
+            }
+            Outcome::Cancelled(reason) => {
+                MergeQueueEntry::update_status(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Cancelled,
+                    Some(reason),
+                )
+                .await
+            }
+            Outcome::Conflict(reason) => {
+                MergeQueueEntry::update_status(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Conflict,
+                    Some(reason),
+                )
+                .await
+            }
+            Outcome::Failed(reason) => {
+                MergeQueueEntry::update_status(
+                    pool,
+                    entry.id,
+                    MergeQueueStatus::Failed,
+                    Some(reason),
+                )
+                .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(entry_id = %entry.id, "Failed to record merge queue outcome: {e}");
+        }
+
+        if let Outcome::Conflict(reason) | Outcome::Failed(reason) = &outcome {
+            self.notify_paused(entry, reason).await;
+        }
+    }
+
+    async fn notify_paused(&self, entry: &MergeQueueEntry, reason: &str) {
+        let label = match Workspace::find_by_id(&self.db.pool, entry.workspace_id).await {
+            Ok(Some(workspace)) => workspace.name.unwrap_or(workspace.branch),
+            _ => entry.workspace_id.to_string(),
+        };
+        self.container
+            .notification_service()
+            .notify(
+                &format!("Merge queue for {} paused", entry.target_branch),
+                &format!("{label}: {reason}"),
+                Some(entry.workspace_id),
+            )
+            .await;
+    }
+
+    async fn try_merge(&self, entry: &MergeQueueEntry) -> Result<Outcome, MergeQueueRunError> {
+        let pool = &self.db.pool;
+        let Some(workspace) = Workspace::find_by_id(pool, entry.workspace_id).await? else {
+            return Ok(Outcome::Cancelled("Workspace was deleted".to_string()));
+        };
+        if workspace.archived {
+            return Ok(Outcome::Cancelled("Workspace was archived".to_string()));
+        }
+        let repo = Repo::find_by_id(pool, entry.repo_id)
+            .await?
+            .ok_or_else(|| MergeQueueRunError::Invalid("Repository not found".to_string()))?;
+        let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?;
+        if merges.iter().any(|m| matches!(m, Merge::Direct(_))) {
+            return Ok(Outcome::Cancelled(
+                "Workspace has already been merged".to_string(),
+            ));
+        }
+        if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+            .await?
+        {
+            return Ok(Outcome::Failed(
+                "A process is still running in the workspace".to_string(),
+            ));
+        }
+        let strategy =
+            MergeStrategy::from_str(&entry.strategy).map_err(MergeQueueRunError::Invalid)?;
+
+        let container_ref = self.container.ensure_container_exists(&workspace).await?;
+        let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+        // Bring the branch up to date with whatever merged ahead of it
+        let rebase = {
+            let git = self.git.clone();
+            let repo_path = repo.path.clone();
+            let worktree_path = worktree_path.clone();
+            let target_branch = entry.target_branch.clone();
+            let branch = workspace.branch.clone();
+            let signing = commit_signing(&repo);
+            tokio::task::spawn_blocking(move || {
+                git.rebase_branch(
+                    &repo_path,
+                    &worktree_path,
+                    &target_branch,
+                    &target_branch,
+                    &branch,
+                    signing.as_ref(),
+                )
+            })
+            .await
+            .map_err(|e| MergeQueueRunError::Invalid(format!("rebase task panicked: {e}")))?
+        };
+        match rebase {
+            Ok(_) => {}
+            Err(GitServiceError::MergeConflicts {
+                conflicted_files, ..
+            }) => {
+                return Ok(Outcome::Conflict(format!(
+                    "Rebasing onto {} conflicts in {}",
+                    entry.target_branch,
+                    conflicted_files.join(", ")
+                )));
+            }
+            Err(GitServiceError::RebaseInProgress) => {
+                return Ok(Outcome::Conflict(
+                    "A rebase is already in progress in the workspace".to_string(),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        if let Some(failure) = self.verify(entry, &workspace, &repo).await? {
+            return Ok(Outcome::Failed(failure));
+        }
+
+        let workspace_label = workspace.name.as_deref().unwrap_or(&workspace.branch);
+        let vk_id =
+            remote_sync::vibe_kanban_identifier(self.remote_client.as_ref(), workspace.id).await;
+        let commit_message = format!("{} (vibe-kanban {})", workspace_label, vk_id);
+        let merge_commit = {
+            let git = self.git.clone();
+            let repo_path = repo.path.clone();
+            let worktree_path = worktree_path.clone();
+            let branch = workspace.branch.clone();
+            let target_branch = entry.target_branch.clone();
+            let signing = commit_signing(&repo);
+            tokio::task::spawn_blocking(move || {
+                git.merge_changes(
+                    &repo_path,
+                    &worktree_path,
+                    &branch,
+                    &target_branch,
+                    &commit_message,
+                    strategy,
+                    signing.as_ref(),
+                )
+            })
+            .await
+            .map_err(|e| MergeQueueRunError::Invalid(format!("merge task panicked: {e}")))??
+        };
+
+        if let Err(e) = Merge::create_direct(
+            pool,
+            workspace.id,
+            repo.id,
+            &entry.target_branch,
+            &merge_commit,
+            strategy.as_str(),
+        )
+        .await
+        {
+            tracing::error!(
+                workspace_id = %workspace.id,
+                merge_commit_id = %merge_commit,
+                target_branch = %entry.target_branch,
+                "CRITICAL: merge queue committed to disk but DB write failed. \
+                 Manual recovery required. Error: {e}"
+            );
+            return Err(e.into());
+        }
+
+        if let Some(client) = self.remote_client.clone() {
+            let workspace_id = workspace.id;
+            tokio::spawn(async move {
+                remote_sync::sync_local_workspace_merge_to_remote(&client, workspace_id).await;
+            });
+        }
+        if !workspace.pinned
+            && let Err(e) = self.container.archive_workspace(workspace.id).await
+        {
+            tracing::error!("Failed to archive workspace {}: {}", workspace.id, e);
+        }
+
+        Ok(Outcome::Merged { merge_commit })
+    }
+
+    /// Run the repo's verification steps in the workspace and wait for
+    /// them. Returns why they failed, or `None` if they passed or the repo
+    /// has none.
+    async fn verify(
+        &self,
+        entry: &MergeQueueEntry,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<Option<String>, MergeQueueRunError> {
+        let pool = &self.db.pool;
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: None,
+                        name: None,
+                        host_id: None,
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        };
+
+        let started = tokio::time::Instant::now();
+        // A run started after the last agent turn would claim the steps of
+        // ours, so let it finish first
+        while VerificationRun::find_running_by_workspace_id(pool, workspace.id)
+            .await?
+            .is_some()
+        {
+            if started.elapsed() >= VERIFICATION_TIMEOUT {
+                return Ok(Some(
+                    "Timed out waiting for another verification run to finish".to_string(),
+                ));
+            }
+            tokio::time::sleep(VERIFICATION_POLL_INTERVAL).await;
+        }
+
+        let Some(run) = self
+            .container
+            .start_verification(
+                workspace,
+                &session,
+                Some(&[repo.id]),
+                VerificationTrigger::MergeQueue,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut current_process = None;
+        loop {
+            tokio::time::sleep(VERIFICATION_POLL_INTERVAL).await;
+            let status = VerificationRun::find_by_id(pool, run.run.id)
+                .await?
+                .ok_or_else(|| {
+                    MergeQueueRunError::Invalid("Verification run disappeared".to_string())
+                })?
+                .status;
+            let results = VerificationResult::find_by_run_id(pool, run.run.id).await?;
+            let latest_process = results
+                .iter()
+                .rev()
+                .find_map(|result| result.execution_process_id);
+            if let Some(process_id) = latest_process
+                && current_process != Some(process_id)
+            {
+                MergeQueueEntry::set_verification_execution_process(pool, entry.id, process_id)
+                    .await?;
+                current_process = Some(process_id);
+            }
+
+            match status {
+                VerificationRunStatus::Running if started.elapsed() < VERIFICATION_TIMEOUT => {}
+                VerificationRunStatus::Running => {
+                    if let Some(process_id) = current_process
+                        && let Some(process) =
+                            ExecutionProcess::find_by_id(pool, process_id).await?
+                        && process.status == ExecutionProcessStatus::Running
+                        && let Err(e) = self
+                            .container
+                            .stop_execution(&process, ExecutionProcessStatus::Killed)
+                            .await
+                    {
+                        tracing::warn!("Failed to stop verification step: {e}");
+                    }
+                    return Ok(Some(format!(
+                        "Verification timed out after {} minutes",
+                        VERIFICATION_TIMEOUT.as_secs() / 60
+                    )));
+                }
+                VerificationRunStatus::Passed => return Ok(None),
+                VerificationRunStatus::Failed => {
+                    let failed = results
+                        .iter()
+                        .find(|result| result.status == VerificationResultStatus::Failed);
+                    return Ok(Some(match failed {
+                        Some(VerificationResult {
+                            name,
+                            exit_code: Some(code),
+                            ..
+                        }) => format!("Verification step '{name}' exited with code {code}"),
+                        Some(result) => format!("Verification step '{}' failed", result.name),
+                        None => "Verification failed".to_string(),
+                    }));
+                }
+                VerificationRunStatus::Cancelled => {
+                    return Ok(Some("Verification was stopped".to_string()));
+                }
+            }
+        }
+    }
+}
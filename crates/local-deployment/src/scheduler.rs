@@ -0,0 +1,272 @@
+//! Background runner for scheduled agent runs. Every due schedule gets a
+//! fresh workspace on its repo, and the configured executor is started with
+//! the rendered prompt; the outcome is recorded as a `ScheduleRun`.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        requests::WorkspaceRepoInput,
+        schedule::{CreateScheduleRun, Schedule, ScheduleRun, ScheduleRunStatus},
+        workspace::{CreateWorkspace, Workspace},
+    },
+};
+use executors::profile::ExecutorConfig;
+use git::GitService;
+use services::services::{
+    container::{ContainerError, ContainerService},
+    schedule::{next_run_after, render_prompt, workspace_name},
+};
+use thiserror::Error;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use workspace_manager::{WorkspaceError, WorkspaceManager};
+
+use crate::container::LocalContainerService;
+
+/// How often due schedules are checked when nothing woke the runner up.
+/// Cron granularity is one minute, so this bounds how late a run can start.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+enum ScheduledRunError {
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+}
+
+pub struct Scheduler {
+    db: DBService,
+    container: LocalContainerService,
+    workspace_manager: WorkspaceManager,
+    git: GitService,
+    notify: Arc<Notify>,
+}
+
+impl Scheduler {
+    pub fn new(
+        db: DBService,
+        container: LocalContainerService,
+        workspace_manager: WorkspaceManager,
+        git: GitService,
+        notify: Arc<Notify>,
+    ) -> Self {
+        Self {
+            db,
+            container,
+            workspace_manager,
+            git,
+            notify,
+        }
+    }
+
+    /// Spawn the runner loop; it terminates when `shutdown` is cancelled.
+    pub fn spawn(self, shutdown: CancellationToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move { self.run(shutdown).await })
+    }
+
+    async fn run(&self, shutdown: CancellationToken) {
+        tracing::info!("Starting scheduler with interval {:?}", POLL_INTERVAL);
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    tracing::info!("scheduler: shutdown signalled, exiting");
+                    return;
+                }
+                _ = self.notify.notified() => {}
+                _ = poll.tick() => {}
+            }
+
+            self.run_due().await;
+        }
+    }
+
+    async fn run_due(&self) {
+        let now = Utc::now();
+        let due = match Schedule::find_due(&self.db.pool, now).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!("scheduler: db error loading due schedules: {e}");
+                return;
+            }
+        };
+
+        for schedule in due {
+            let Some(due_at) = schedule.next_run_at else {
+                continue;
+            };
+            // Ticks missed while the server was down collapse into this one
+            // run; the schedule then continues from the next tick after now.
+            let next_run_at = match next_run_after(&schedule.cron_expression, now) {
+                Ok(next) => next,
+                Err(e) => {
+                    tracing::warn!("scheduler: skipping schedule {}: {e}", schedule.id);
+                    continue;
+                }
+            };
+            match Schedule::claim_run(&self.db.pool, schedule.id, due_at, next_run_at).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::warn!("scheduler: failed to claim schedule {}: {e}", schedule.id);
+                    continue;
+                }
+            }
+
+            self.fire(&schedule, due_at).await;
+        }
+    }
+
+    async fn fire(&self, schedule: &Schedule, scheduled_for: DateTime<Utc>) {
+        tracing::info!(
+            schedule_id = %schedule.id,
+            "Starting scheduled run '{}'",
+            schedule.name
+        );
+
+        let run = match self.create_workspace_record(schedule, scheduled_for).await {
+            Ok(workspace) => match self.start(schedule, &workspace, scheduled_for).await {
+                Ok(session_id) => CreateScheduleRun {
+                    schedule_id: schedule.id,
+                    scheduled_for,
+                    status: ScheduleRunStatus::Started,
+                    workspace_id: Some(workspace.id),
+                    session_id: Some(session_id),
+                    error: None,
+                },
+                Err(e) => {
+                    self.discard_workspace(workspace).await;
+                    Self::failed_run(schedule, scheduled_for, None, e)
+                }
+            },
+            Err(e) => Self::failed_run(schedule, scheduled_for, None, e),
+        };
+
+        if let Err(e) = ScheduleRun::create(&self.db.pool, &run).await {
+            tracing::error!(
+                schedule_id = %schedule.id,
+                "Failed to record scheduled run: {e}"
+            );
+        }
+    }
+
+    fn failed_run(
+        schedule: &Schedule,
+        scheduled_for: DateTime<Utc>,
+        workspace_id: Option<Uuid>,
+        error: ScheduledRunError,
+    ) -> CreateScheduleRun {
+        tracing::error!(
+            schedule_id = %schedule.id,
+            "Scheduled run '{}' failed: {error}",
+            schedule.name
+        );
+        CreateScheduleRun {
+            schedule_id: schedule.id,
+            scheduled_for,
+            status: ScheduleRunStatus::Failed,
+            workspace_id,
+            session_id: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Delete the workspace of a run that failed to start, along with
+    /// anything the attempt left behind, so failed ticks don't pile up
+    /// workspaces nobody will use.
+    async fn discard_workspace(&self, workspace: Workspace) {
+        self.container.try_stop(&workspace, true).await;
+        // Starting may have created the worktrees since `workspace` was loaded
+        let workspace = match Workspace::find_by_id(&self.db.pool, workspace.id).await {
+            Ok(Some(workspace)) => workspace,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!("Failed to reload workspace {}: {e}", workspace.id);
+                workspace
+            }
+        };
+        let workspace_id = workspace.id;
+        let result = async {
+            let managed_workspace = self
+                .workspace_manager
+                .load_managed_workspace(workspace)
+                .await?;
+            let deletion_context = managed_workspace.prepare_deletion_context().await?;
+            managed_workspace.delete_record().await?;
+            Ok::<_, sqlx::Error>(deletion_context)
+        }
+        .await;
+        match result {
+            Ok(deletion_context) => {
+                WorkspaceManager::spawn_workspace_deletion_cleanup(deletion_context, true)
+            }
+            Err(e) => tracing::error!(
+                "Failed to delete workspace {} of a failed scheduled run: {e}",
+                workspace_id
+            ),
+        }
+    }
+
+    async fn create_workspace_record(
+        &self,
+        schedule: &Schedule,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Workspace, ScheduledRunError> {
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .container
+            .git_branch_from_workspace(&workspace_id, &schedule.name)
+            .await;
+
+        Ok(Workspace::create(
+            &self.db.pool,
+            &CreateWorkspace {
+                branch,
+                name: Some(workspace_name(&schedule.name, scheduled_for)),
+            },
+            workspace_id,
+        )
+        .await
+        .map_err(ContainerError::from)?)
+    }
+
+    /// Attach the schedule's repo and start the executor. Returns the id of
+    /// the session the run executes in.
+    async fn start(
+        &self,
+        schedule: &Schedule,
+        workspace: &Workspace,
+        scheduled_for: DateTime<Utc>,
+    ) -> Result<Uuid, ScheduledRunError> {
+        let mut managed_workspace = self
+            .workspace_manager
+            .load_managed_workspace(workspace.clone())
+            .await
+            .map_err(WorkspaceError::from)?;
+        managed_workspace
+            .add_repository(
+                &WorkspaceRepoInput {
+                    repo_id: schedule.repo_id,
+                    target_branch: schedule.target_branch.clone(),
+                },
+                &self.git,
+            )
+            .await?;
+
+        let prompt = render_prompt(&schedule.prompt_template, &schedule.name, scheduled_for);
+        let executor_config = ExecutorConfig::from(schedule.executor_profile_id.0.clone());
+        let execution_process = self
+            .container
+            .start_workspace(workspace, executor_config, prompt)
+            .await?;
+
+        Ok(execution_process.session_id)
+    }
+}
@@ -0,0 +1,183 @@
+use db::models::approval::Approval;
+use rmcp::{
+    ErrorData, handler::server::wrapper::Parameters, model::CallToolResult, schemars, tool,
+    tool_router,
+};
+use serde::{Deserialize, Serialize};
+use utils::approvals::{APPROVAL_CLIENT_HEADER, ApprovalOutcome, ApprovalResponse};
+use uuid::Uuid;
+
+use super::McpServer;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct ListApprovalsRequest {
+    #[schemars(
+        description = "Workspace ID to inspect. Optional when running inside a scoped orchestrator MCP."
+    )]
+    workspace_id: Option<Uuid>,
+    #[schemars(description = "Only return approvals that are still waiting for an answer")]
+    pending_only: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct RespondToApprovalRequest {
+    #[schemars(description = "The ID of the pending approval")]
+    approval_id: String,
+    #[schemars(description = "The execution that requested the approval")]
+    execution_id: Uuid,
+    #[schemars(description = "True to let the tool call run, false to deny it")]
+    approve: bool,
+    #[schemars(description = "Optional reason passed to the agent when denying")]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub(super) struct McpApprovalSummary {
+    #[schemars(description = "The unique identifier of the approval")]
+    id: String,
+    #[schemars(description = "The execution that requested the approval")]
+    execution_id: String,
+    #[schemars(description = "The tool the agent wanted to run, or the question tool")]
+    tool_name: String,
+    #[schemars(description = "True if the agent asked questions instead of requesting a tool")]
+    is_question: bool,
+    #[schemars(description = "'pending', 'approved', 'denied', 'answered' or 'timed_out'")]
+    status: String,
+    #[schemars(description = "Full outcome including the denial reason or answers")]
+    outcome: Option<serde_json::Value>,
+    #[schemars(description = "Who answered: 'local', 'relay', 'mcp' or 'policy'")]
+    responder: Option<String>,
+    #[schemars(description = "The paired relay client that answered")]
+    relay_client_id: Option<String>,
+    #[schemars(description = "When the approval was requested")]
+    requested_at: String,
+    #[schemars(description = "When the approval was resolved")]
+    resolved_at: Option<String>,
+}
+
+impl From<Approval> for McpApprovalSummary {
+    fn from(approval: Approval) -> Self {
+        let outcome = approval.outcome.map(|outcome| outcome.0);
+        let status = match &outcome {
+            None => "pending",
+            Some(ApprovalOutcome::Approved) => "approved",
+            Some(ApprovalOutcome::Denied { .. }) => "denied",
+            Some(ApprovalOutcome::Answered { .. }) => "answered",
+            Some(ApprovalOutcome::TimedOut) => "timed_out",
+        };
+        Self {
+            id: approval.id,
+            execution_id: approval.execution_process_id.to_string(),
+            tool_name: approval.tool_name,
+            is_question: approval.is_question,
+            status: status.to_string(),
+            outcome: outcome.and_then(|outcome| serde_json::to_value(outcome).ok()),
+            responder: approval
+                .responder
+                .and_then(|responder| serde_json::to_value(responder).ok())
+                .and_then(|value| value.as_str().map(str::to_string)),
+            relay_client_id: approval.relay_client_id.map(|id| id.to_string()),
+            requested_at: approval.requested_at.to_rfc3339(),
+            resolved_at: approval.resolved_at.map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ListApprovalsResponse {
+    workspace_id: String,
+    count: usize,
+    approvals: Vec<McpApprovalSummary>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct RespondToApprovalResponse {
+    approval_id: String,
+    #[schemars(description = "The recorded outcome")]
+    outcome: serde_json::Value,
+}
+
+#[tool_router(router = approvals_tools_router, vis = "pub")]
+impl McpServer {
+    #[tool(
+        description = "List the tool approvals and questions of a workspace's agents, oldest first, with who answered them."
+    )]
+    async fn list_approvals(
+        &self,
+        Parameters(ListApprovalsRequest {
+            workspace_id,
+            pending_only,
+        }): Parameters<ListApprovalsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let workspace_id = match self.resolve_workspace_id(workspace_id) {
+            Ok(id) => id,
+            Err(error_result) => return Ok(Self::tool_error(error_result)),
+        };
+        if let Err(error_result) = self.scope_allows_workspace(workspace_id) {
+            return Ok(Self::tool_error(error_result));
+        }
+
+        let url = self.url(&format!("/api/workspaces/{workspace_id}/approvals"));
+        let approvals: Vec<Approval> = match self.send_json(self.client.get(&url)).await {
+            Ok(approvals) => approvals,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        let approvals: Vec<McpApprovalSummary> = approvals
+            .into_iter()
+            .filter(|approval| !pending_only.unwrap_or(false) || approval.outcome.is_none())
+            .map(McpApprovalSummary::from)
+            .collect();
+        McpServer::success(&ListApprovalsResponse {
+            workspace_id: workspace_id.to_string(),
+            count: approvals.len(),
+            approvals,
+        })
+    }
+
+    #[tool(
+        description = "Approve or deny a pending tool approval. Use `list_approvals` with `pending_only` to find approval and execution IDs. Questions must be answered in the UI."
+    )]
+    async fn respond_to_approval(
+        &self,
+        Parameters(RespondToApprovalRequest {
+            approval_id,
+            execution_id,
+            approve,
+            reason,
+        }): Parameters<RespondToApprovalRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let status = if approve {
+            ApprovalOutcome::Approved
+        } else {
+            ApprovalOutcome::Denied {
+                reason: reason
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty()),
+            }
+        };
+        let payload = ApprovalResponse {
+            execution_process_id: execution_id,
+            status,
+        };
+
+        let url = self.url(&format!("/api/approvals/{approval_id}/respond"));
+        let outcome: ApprovalOutcome = match self
+            .send_json(
+                self.client
+                    .post(&url)
+                    .header(APPROVAL_CLIENT_HEADER, "mcp")
+                    .json(&payload),
+            )
+            .await
+        {
+            Ok(outcome) => outcome,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&RespondToApprovalResponse {
+            approval_id,
+            outcome: serde_json::to_value(outcome).unwrap_or_default(),
+        })
+    }
+}
@@ -36,6 +36,7 @@ impl ToolError {
     }
 }
 
+mod approvals;
 mod context;
 mod issue_assignees;
 mod issue_relationships;
@@ -44,6 +45,7 @@ mod organizations;
 mod remote_issues;
 mod remote_projects;
 mod repos;
+mod schedules;
 mod sessions;
 mod task_attempts;
 mod workspaces;
@@ -61,6 +63,8 @@ impl McpServer {
             + Self::issue_relationships_tools_router()
             + Self::task_attempts_tools_router()
             + Self::session_tools_router()
+            + Self::schedules_tools_router()
+            + Self::approvals_tools_router()
     }
 
     pub fn orchestrator_mode_router() -> rmcp::handler::server::tool::ToolRouter<Self> {
@@ -375,6 +379,7 @@ impl McpServer {
 
     fn execution_process_status_label(status: &ExecutionProcessStatus) -> &'static str {
         match status {
+            ExecutionProcessStatus::Queued => "queued",
             ExecutionProcessStatus::Running => "running",
             ExecutionProcessStatus::Completed => "completed",
             ExecutionProcessStatus::Failed => "failed",
@@ -435,6 +440,8 @@ mod tests {
 
         assert!(actual.contains("list_workspaces"));
         assert!(actual.contains("delete_workspace"));
+        assert!(actual.contains("create_schedule"));
+        assert!(actual.contains("pause_schedule"));
         assert!(!actual.contains("output_markdown"));
     }
 
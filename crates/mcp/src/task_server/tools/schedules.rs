@@ -0,0 +1,176 @@
+use db::models::schedule::{CreateSchedule, Schedule};
+use executors::profile::ExecutorProfileId;
+use rmcp::{
+    ErrorData, handler::server::wrapper::Parameters, model::CallToolResult, schemars, tool,
+    tool_router,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::McpServer;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct CreateScheduleRequest {
+    #[schemars(description = "Name for the schedule; also used to name the workspaces it creates")]
+    name: String,
+    #[schemars(
+        description = "Cron expression in the server's local timezone, e.g. '0 3 * * *' for every night at 3am"
+    )]
+    cron_expression: String,
+    #[schemars(description = "The repository each run creates its workspace on")]
+    repo_id: Uuid,
+    #[schemars(description = "The branch each run's workspace is created from")]
+    target_branch: String,
+    #[schemars(
+        description = "Prompt sent to the agent on every run. Supports {{date}}, {{datetime}} and {{schedule_name}} placeholders."
+    )]
+    prompt: String,
+    #[schemars(
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID', 'CUSTOM_CLI')"
+    )]
+    executor: String,
+    #[schemars(description = "Optional executor variant, if needed")]
+    variant: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct ScheduleIdRequest {
+    #[schemars(description = "The ID of the schedule")]
+    schedule_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct McpScheduleSummary {
+    #[schemars(description = "The unique identifier of the schedule")]
+    id: String,
+    #[schemars(description = "The name of the schedule")]
+    name: String,
+    #[schemars(description = "The cron expression the schedule runs on")]
+    cron_expression: String,
+    #[schemars(description = "The repository each run uses")]
+    repo_id: String,
+    #[schemars(description = "The branch each run's workspace is created from")]
+    target_branch: String,
+    #[schemars(description = "The executor profile, e.g. 'CLAUDE_CODE' or 'CLAUDE_CODE:PLAN'")]
+    executor_profile: String,
+    #[schemars(description = "Whether the schedule is paused")]
+    paused: bool,
+    #[schemars(description = "When the schedule fires next (absent while paused)")]
+    next_run_at: Option<String>,
+    #[schemars(description = "When the schedule last fired")]
+    last_run_at: Option<String>,
+}
+
+impl From<Schedule> for McpScheduleSummary {
+    fn from(schedule: Schedule) -> Self {
+        Self {
+            id: schedule.id.to_string(),
+            name: schedule.name,
+            cron_expression: schedule.cron_expression,
+            repo_id: schedule.repo_id.to_string(),
+            target_branch: schedule.target_branch,
+            executor_profile: schedule.executor_profile_id.0.to_string(),
+            paused: schedule.paused,
+            next_run_at: schedule.next_run_at.map(|t| t.to_rfc3339()),
+            last_run_at: schedule.last_run_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ListSchedulesResponse {
+    schedules: Vec<McpScheduleSummary>,
+    count: usize,
+}
+
+#[tool_router(router = schedules_tools_router, vis = "pub")]
+impl McpServer {
+    #[tool(
+        description = "Create a recurring agent run. On every cron tick a new workspace is created on the repository and the executor is started with the prompt. Use `list_repos` to find repo IDs."
+    )]
+    async fn create_schedule(
+        &self,
+        Parameters(CreateScheduleRequest {
+            name,
+            cron_expression,
+            repo_id,
+            target_branch,
+            prompt,
+            executor,
+            variant,
+        }): Parameters<CreateScheduleRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let executor = match Self::parse_executor_agent(executor.trim()) {
+            Ok(executor) => executor,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+        let variant = variant
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        let payload = CreateSchedule {
+            name,
+            cron_expression,
+            repo_id,
+            target_branch,
+            prompt_template: prompt,
+            executor_profile_id: ExecutorProfileId { executor, variant },
+        };
+
+        let url = self.url("/api/schedules");
+        let schedule: Schedule = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(schedule) => schedule,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&McpScheduleSummary::from(schedule))
+    }
+
+    #[tool(description = "List all schedules with their next and last run times.")]
+    async fn list_schedules(&self) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/schedules");
+        let schedules: Vec<Schedule> = match self.send_json(self.client.get(&url)).await {
+            Ok(schedules) => schedules,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        let schedules: Vec<McpScheduleSummary> = schedules
+            .into_iter()
+            .map(McpScheduleSummary::from)
+            .collect();
+        McpServer::success(&ListSchedulesResponse {
+            count: schedules.len(),
+            schedules,
+        })
+    }
+
+    #[tool(description = "Pause a schedule so it stops creating runs until resumed.")]
+    async fn pause_schedule(
+        &self,
+        Parameters(ScheduleIdRequest { schedule_id }): Parameters<ScheduleIdRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/schedules/{}/pause", schedule_id));
+        let schedule: Schedule = match self.send_json(self.client.post(&url)).await {
+            Ok(schedule) => schedule,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&McpScheduleSummary::from(schedule))
+    }
+
+    #[tool(
+        description = "Resume a paused schedule. Runs missed while paused are skipped; the next run is the next cron tick from now."
+    )]
+    async fn resume_schedule(
+        &self,
+        Parameters(ScheduleIdRequest { schedule_id }): Parameters<ScheduleIdRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/schedules/{}/resume", schedule_id));
+        let schedule: Schedule = match self.send_json(self.client.post(&url)).await {
+            Ok(schedule) => schedule,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&McpScheduleSummary::from(schedule))
+    }
+}
@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use db::models::{
+    approval::Approval,
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     session::Session,
@@ -10,7 +13,7 @@ use rmcp::{
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{McpServer, ToolError};
+use super::{McpServer, ToolError, approvals::McpApprovalSummary};
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct CreateSessionRequest {
@@ -199,6 +202,8 @@ struct SessionHistoryTurn {
     agent_session_id: Option<String>,
     #[schemars(description = "Upstream agent message ID when available")]
     agent_message_id: Option<String>,
+    #[schemars(description = "Tool approvals and questions raised during this turn, oldest first")]
+    approvals: Vec<McpApprovalSummary>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -524,7 +529,10 @@ impl McpServer {
             return Ok(Self::tool_error(error_result));
         }
 
-        let is_finished = execution_process.status != ExecutionProcessStatus::Running;
+        let is_finished = !matches!(
+            execution_process.status,
+            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+        );
 
         let execution_process_value = match Self::serialize_execution_process(&execution_process) {
             Ok(value) => value,
@@ -563,7 +571,7 @@ impl McpServer {
     }
 
     #[tool(
-        description = "Get coding-agent turn history for a session, including prompts and final summaries."
+        description = "Get coding-agent turn history for a session, including prompts, final summaries and the tool approvals of each turn."
     )]
     async fn get_session_history(
         &self,
@@ -633,6 +641,23 @@ impl McpServer {
                 }
             };
 
+        let approvals = match Approval::find_by_session_id(pool, session_id).await {
+            Ok(approvals) => approvals,
+            Err(error) => {
+                return Ok(Self::tool_error(ToolError::new(
+                    "Failed to load session approval history",
+                    Some(error.to_string()),
+                )));
+            }
+        };
+        let mut approvals_by_execution: HashMap<_, Vec<McpApprovalSummary>> = HashMap::new();
+        for approval in approvals {
+            approvals_by_execution
+                .entry(approval.execution_process_id)
+                .or_default()
+                .push(McpApprovalSummary::from(approval));
+        }
+
         let mut turns = Vec::new();
         for execution_process in execution_processes {
             let Some(turn) = turn_map.get(&execution_process.id) else {
@@ -642,7 +667,10 @@ impl McpServer {
             turns.push(SessionHistoryTurn {
                 execution_id: execution_process.id.to_string(),
                 status: Self::execution_process_status_label(&execution_process.status).to_string(),
-                is_finished: execution_process.status != ExecutionProcessStatus::Running,
+                is_finished: !matches!(
+                    execution_process.status,
+                    ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+                ),
                 dropped: execution_process.dropped,
                 created_at: execution_process.created_at.to_rfc3339(),
                 completed_at: execution_process.completed_at.map(|time| time.to_rfc3339()),
@@ -650,6 +678,9 @@ impl McpServer {
                 final_message: turn.summary.clone(),
                 agent_session_id: turn.agent_session_id.clone(),
                 agent_message_id: turn.agent_message_id.clone(),
+                approvals: approvals_by_execution
+                    .remove(&execution_process.id)
+                    .unwrap_or_default(),
             });
         }
 
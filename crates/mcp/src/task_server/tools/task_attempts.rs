@@ -29,7 +29,7 @@ struct StartWorkspaceRequest {
     )]
     prompt: Option<String>,
     #[schemars(
-        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID')"
+        description = "The coding agent executor to run ('CLAUDE_CODE', 'AMP', 'GEMINI', 'CODEX', 'OPENCODE', 'CURSOR_AGENT', 'QWEN_CODE', 'COPILOT', 'DROID', 'CUSTOM_CLI')"
     )]
     executor: String,
     #[schemars(description = "Optional executor variant, if needed")]
@@ -1,4 +1,4 @@
-use db::models::{requests::UpdateWorkspace, workspace::Workspace};
+use db::models::{pr_comment_fix::PrCommentFix, requests::UpdateWorkspace, workspace::Workspace};
 use rmcp::{
     ErrorData, handler::server::wrapper::Parameters, model::CallToolResult, schemars, tool,
     tool_router,
@@ -96,6 +96,35 @@ struct McpDeleteWorkspaceResponse {
     delete_branches: bool,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpAddressPrCommentsRequest {
+    #[schemars(
+        description = "Workspace ID whose pull request to address. Optional if running inside that workspace context."
+    )]
+    workspace_id: Option<Uuid>,
+    #[schemars(description = "Repo of the workspace whose open pull request has the comments")]
+    repo_id: Uuid,
+    #[schemars(
+        description = "Inline review comment IDs to address (default: the first comment of every unresolved thread not yet addressed)"
+    )]
+    comment_ids: Option<Vec<i64>>,
+    #[schemars(description = "Session to send the follow-up in (default: the latest session)")]
+    session_id: Option<Uuid>,
+    #[schemars(description = "Also resolve each replied thread on the host (default: false)")]
+    resolve_threads: Option<bool>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct McpAddressPrCommentsResponse {
+    #[schemars(description = "ID of the review comment fix")]
+    fix_id: String,
+    #[schemars(description = "Execution process of the coding agent follow-up")]
+    execution_process_id: String,
+    pr_number: i64,
+    #[schemars(description = "Review comments sent to the coding agent")]
+    comment_ids: Vec<i64>,
+}
+
 #[tool_router(router = workspaces_tools_router, vis = "pub")]
 impl McpServer {
     #[tool(description = "List local workspaces with optional filters and pagination.")]
@@ -247,4 +276,49 @@ impl McpServer {
             delete_branches,
         })
     }
+
+    #[tool(
+        description = "Send the inline review comments of a workspace's open pull request to its coding agent as a follow-up with file and line context. When the follow-up finishes, the branch is pushed and each comment is replied to, and optionally resolved, on the git host. `workspace_id` is optional if running inside that workspace context."
+    )]
+    async fn address_pr_review_comments(
+        &self,
+        Parameters(McpAddressPrCommentsRequest {
+            workspace_id,
+            repo_id,
+            comment_ids,
+            session_id,
+            resolve_threads,
+        }): Parameters<McpAddressPrCommentsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let workspace_id = match self.resolve_workspace_id(workspace_id) {
+            Ok(id) => id,
+            Err(error_result) => return Ok(Self::tool_error(error_result)),
+        };
+        if let Err(error_result) = self.scope_allows_workspace(workspace_id) {
+            return Ok(Self::tool_error(error_result));
+        }
+
+        let url = self.url(&format!(
+            "/api/workspaces/{}/pull-requests/comments/address",
+            workspace_id
+        ));
+        let payload = serde_json::json!({
+            "repo_id": repo_id,
+            "comment_ids": comment_ids,
+            "session_id": session_id,
+            "resolve_threads": resolve_threads.unwrap_or(false),
+        });
+
+        let fix: PrCommentFix = match self.send_json(self.client.post(&url).json(&payload)).await {
+            Ok(fix) => fix,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&McpAddressPrCommentsResponse {
+            fix_id: fix.id.to_string(),
+            execution_process_id: fix.execution_process_id.to_string(),
+            pr_number: fix.pr_number,
+            comment_ids: fix.comment_ids.0,
+        })
+    }
 }
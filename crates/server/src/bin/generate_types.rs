@@ -12,6 +12,8 @@ fn generate_types_content() -> String {
 
     let decls: Vec<String> = vec![
         db::models::repo::Repo::decl(),
+        db::models::repo::GitHubAccess::decl(),
+        db::models::repo::SigningMode::decl(),
         db::models::project::Project::decl(),
         db::models::repo::UpdateRepo::decl(),
         db::models::repo::SearchResult::decl(),
@@ -19,6 +21,7 @@ fn generate_types_content() -> String {
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
+        db::models::workspace_stack::WorkspaceStack::decl(),
         db::models::tag::Tag::decl(),
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
@@ -60,6 +63,7 @@ fn generate_types_content() -> String {
         db::models::session::Session::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
+        db::models::execution_process::ExecutionFailureReason::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
@@ -67,6 +71,28 @@ fn generate_types_content() -> String {
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge_queue::MergeQueueStatus::decl(),
+        db::models::merge_queue::MergeQueueEntry::decl(),
+        server::routes::merge_queue::MergeQueue::decl(),
+        db::models::conflict_resolution::ConflictResolutionStatus::decl(),
+        db::models::conflict_resolution::ConflictResolution::decl(),
+        db::models::verification::VerificationStep::decl(),
+        db::models::verification::VerificationStepInput::decl(),
+        db::models::verification::VerificationRunStatus::decl(),
+        db::models::verification::VerificationResultStatus::decl(),
+        db::models::verification::VerificationTrigger::decl(),
+        db::models::verification::VerificationRun::decl(),
+        db::models::verification::VerificationResult::decl(),
+        db::models::verification::VerificationRunWithResults::decl(),
+        server::routes::repo::ReplaceVerificationStepsRequest::decl(),
+        server::routes::workspaces::verification::RunVerificationRequest::decl(),
+        db::models::fix_up_loop::FixUpLoopStatus::decl(),
+        db::models::fix_up_loop::FixUpLoop::decl(),
+        server::routes::sessions::fix_up::StartFixUpLoopRequest::decl(),
+        db::models::checkpoint::Checkpoint::decl(),
+        server::routes::sessions::checkpoints::CheckpointTurn::decl(),
+        server::routes::sessions::checkpoints::RestoreCheckpointRequest::decl(),
+        server::routes::sessions::checkpoints::BranchFromCheckpointRequest::decl(),
         services::services::approvals::ApprovalInfo::decl(),
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::QuestionAnswer::decl(),
@@ -156,9 +182,15 @@ fn generate_types_content() -> String {
         server::routes::workspaces::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::workspaces::git::RebaseWorkspaceRequest::decl(),
         server::routes::workspaces::git::ContinueRebaseRequest::decl(),
+        server::routes::workspaces::git::ResolveConflictsRequest::decl(),
         server::routes::workspaces::git::AbortConflictsRequest::decl(),
         server::routes::workspaces::git::GitOperationError::decl(),
         server::routes::workspaces::git::PushError::decl(),
+        server::routes::workspaces::git::FileHunksQuery::decl(),
+        server::routes::workspaces::git::RevertChangesRequest::decl(),
+        server::routes::workspaces::git::StageChangesRequest::decl(),
+        server::routes::workspaces::git::CommitChangesRequest::decl(),
+        server::routes::workspaces::git::CommitChangesResponse::decl(),
         server::routes::workspaces::pr::PrError::decl(),
         server::routes::workspaces::execution::RunScriptError::decl(),
         server::routes::workspaces::attachments::AssociateWorkspaceAttachmentsRequest::decl(),
@@ -169,19 +201,29 @@ fn generate_types_content() -> String {
         server::routes::workspaces::pr::PrCommentsResponse::decl(),
         server::routes::workspaces::pr::GetPrCommentsError::decl(),
         server::routes::workspaces::pr::GetPrCommentsQuery::decl(),
+        server::routes::workspaces::pr::AddressPrCommentsRequest::decl(),
+        db::models::pr_comment_fix::PrCommentFixStatus::decl(),
+        db::models::pr_comment_fix::PrCommentFix::decl(),
         db::models::requests::CreateAndStartWorkspaceRequest::decl(),
         db::models::requests::CreateAndStartWorkspaceResponse::decl(),
         git_host::UnifiedPrComment::decl(),
         git_host::ProviderKind::decl(),
+        git_host::GitHostConfig::decl(),
         git_host::PullRequestDetail::decl(),
         git::GitRemote::decl(),
         git::MergeStrategy::decl(),
+        git::CommitSignature::decl(),
+        git::SignatureStatus::decl(),
         server::routes::repo::ListPrsError::decl(),
         server::routes::remote::pull_requests::LinkPrToIssueRequest::decl(),
         server::routes::workspaces::pr::CreateWorkspaceFromPrBody::decl(),
         server::routes::workspaces::pr::CreateWorkspaceFromPrResponse::decl(),
         server::routes::workspaces::pr::CreateFromPrError::decl(),
         server::routes::workspaces::git::RepoBranchStatus::decl(),
+        server::routes::workspaces::stack::CreateStackedWorkspaceRequest::decl(),
+        server::routes::workspaces::stack::StackedWorkspace::decl(),
+        server::routes::workspaces::stack::RestackResponse::decl(),
+        server::routes::workspaces::stack::RestackError::decl(),
         db::models::requests::UpdateWorkspace::decl(),
         db::models::requests::UpdateSession::decl(),
         server::routes::workspaces::workspace_summary::WorkspaceSummaryRequest::decl(),
@@ -210,10 +252,15 @@ fn generate_types_content() -> String {
         services::services::config::HostBannerConfig::decl(),
         services::services::config::LinksConfig::decl(),
         services::services::config::InputEditorMode::decl(),
+        services::services::config::ConcurrencyConfig::decl(),
         git::GitBranch::decl(),
-        services::services::queued_message::QueuedMessage::decl(),
+        db::models::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        db::models::execution_start_queue::QueuedExecution::decl(),
         git::ConflictOp::decl(),
+        git::DiffHunk::decl(),
+        git::DiffHunkId::decl(),
+        git::DiffSelection::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -226,6 +273,10 @@ fn generate_types_content() -> String {
         executors::executors::SlashCommandDescription::decl(),
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::sandbox::SandboxNetwork::decl(),
+        executors::sandbox::SandboxConfig::decl(),
+        executors::sandbox::SandboxOverrides::decl(),
+        executors::resource_limits::ResourceLimits::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorRecentModels::decl(),
         executors::profile::ExecutorProfile::decl(),
@@ -248,6 +299,9 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom_cli::CustomCli::decl(),
+        executors::executors::custom_cli::OutputFormat::decl(),
+        executors::executors::custom_cli::PromptInput::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -259,6 +313,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::TokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::AnsweredQuestion::decl(),
@@ -373,6 +428,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom_cli",
+            generate_json_schema::<executors::executors::custom_cli::CustomCli>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
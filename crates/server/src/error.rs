@@ -5,9 +5,12 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use db::models::{
-    execution_process::ExecutionProcessError, external_session::ExternalSessionError,
-    repo::RepoError, scratch::ScratchError, session::SessionError, webhook::WebhookError,
-    workspace::WorkspaceError,
+    approval_rule::ApprovalRuleError, execution_process::ExecutionProcessError,
+    execution_start_queue::ExecutionStartQueueError, external_session::ExternalSessionError,
+    merge_queue::MergeQueueError, model_price::ModelPriceError, queued_message::QueuedMessageError,
+    race::RaceError, repo::RepoError, schedule::ScheduleError, scratch::ScratchError,
+    session::SessionError, verification::VerificationError, webhook::WebhookError,
+    workspace::WorkspaceError, workspace_budget::WorkspaceBudgetError,
 };
 use deployment::{DeploymentError, RelayHostsNotConfigured, RemoteClientNotConfigured};
 use executors::{command::CommandBuildError, executors::ExecutorError};
@@ -45,6 +48,24 @@ pub enum ApiError {
     #[error(transparent)]
     Webhook(#[from] WebhookError),
     #[error(transparent)]
+    QueuedMessage(#[from] QueuedMessageError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
+    ApprovalRule(#[from] ApprovalRuleError),
+    #[error(transparent)]
+    Race(#[from] RaceError),
+    #[error(transparent)]
+    MergeQueue(#[from] MergeQueueError),
+    #[error(transparent)]
+    ExecutionStartQueue(#[from] ExecutionStartQueueError),
+    #[error(transparent)]
+    Verification(#[from] VerificationError),
+    #[error(transparent)]
+    ModelPrice(#[from] ModelPriceError),
+    #[error(transparent)]
+    WorkspaceBudget(#[from] WorkspaceBudgetError),
+    #[error(transparent)]
     ScratchError(#[from] ScratchError),
     #[error(transparent)]
     ExecutionProcess(#[from] ExecutionProcessError),
@@ -376,6 +397,96 @@ impl IntoResponse for ApiError {
             ApiError::Webhook(WebhookError::NotFound) => {
                 ErrorInfo::not_found("WebhookError", "Webhook not found.")
             }
+            ApiError::Webhook(WebhookError::DeliveryNotFound) => {
+                ErrorInfo::not_found("WebhookError", "Webhook delivery not found.")
+            }
+            ApiError::Webhook(WebhookError::InvalidFilter(msg)) => {
+                ErrorInfo::bad_request("WebhookError", msg.clone())
+            }
+
+            ApiError::QueuedMessage(QueuedMessageError::Database(_)) => {
+                ErrorInfo::internal("QueuedMessageError")
+            }
+            ApiError::QueuedMessage(QueuedMessageError::NotFound) => {
+                ErrorInfo::not_found("QueuedMessageError", "Queued message not found.")
+            }
+            ApiError::QueuedMessage(QueuedMessageError::InvalidOrder) => ErrorInfo::bad_request(
+                "QueuedMessageError",
+                "Reorder must list every queued message exactly once.",
+            ),
+
+            ApiError::Schedule(ScheduleError::Database(_)) => ErrorInfo::internal("ScheduleError"),
+            ApiError::Schedule(ScheduleError::NotFound) => {
+                ErrorInfo::not_found("ScheduleError", "Schedule not found.")
+            }
+            ApiError::Schedule(
+                err @ (ScheduleError::InvalidCron(_) | ScheduleError::Invalid(_)),
+            ) => ErrorInfo::bad_request("ScheduleError", err.to_string()),
+
+            ApiError::ApprovalRule(ApprovalRuleError::Database(_)) => {
+                ErrorInfo::internal("ApprovalRuleError")
+            }
+            ApiError::ApprovalRule(err @ ApprovalRuleError::Invalid(_)) => {
+                ErrorInfo::bad_request("ApprovalRuleError", err.to_string())
+            }
+
+            ApiError::Race(RaceError::Database(_)) => ErrorInfo::internal("RaceError"),
+            ApiError::Race(RaceError::NotFound) => {
+                ErrorInfo::not_found("RaceError", "Race not found.")
+            }
+            ApiError::Race(err @ RaceError::Invalid(_)) => {
+                ErrorInfo::bad_request("RaceError", err.to_string())
+            }
+
+            ApiError::MergeQueue(MergeQueueError::Database(_)) => {
+                ErrorInfo::internal("MergeQueueError")
+            }
+            ApiError::MergeQueue(MergeQueueError::NotFound) => {
+                ErrorInfo::not_found("MergeQueueError", "Merge queue entry not found.")
+            }
+            ApiError::MergeQueue(err @ MergeQueueError::Invalid(_)) => {
+                ErrorInfo::bad_request("MergeQueueError", err.to_string())
+            }
+
+            ApiError::ExecutionStartQueue(ExecutionStartQueueError::Database(_)) => {
+                ErrorInfo::internal("ExecutionStartQueueError")
+            }
+            ApiError::ExecutionStartQueue(ExecutionStartQueueError::InvalidOrder) => {
+                ErrorInfo::bad_request(
+                    "ExecutionStartQueueError",
+                    "Reorder must list every queued execution exactly once.",
+                )
+            }
+
+            ApiError::Verification(VerificationError::Database(_)) => {
+                ErrorInfo::internal("VerificationError")
+            }
+            ApiError::Verification(VerificationError::NotFound) => {
+                ErrorInfo::not_found("VerificationError", "Verification run not found.")
+            }
+            ApiError::Verification(err @ VerificationError::Invalid(_)) => {
+                ErrorInfo::bad_request("VerificationError", err.to_string())
+            }
+            ApiError::Verification(err @ VerificationError::Required(_)) => {
+                ErrorInfo::conflict("VerificationError", err.to_string())
+            }
+
+            ApiError::ModelPrice(ModelPriceError::Database(_)) => {
+                ErrorInfo::internal("ModelPriceError")
+            }
+            ApiError::ModelPrice(ModelPriceError::NotFound) => {
+                ErrorInfo::not_found("ModelPriceError", "Model price not found.")
+            }
+            ApiError::ModelPrice(err @ ModelPriceError::Invalid(_)) => {
+                ErrorInfo::bad_request("ModelPriceError", err.to_string())
+            }
+
+            ApiError::WorkspaceBudget(WorkspaceBudgetError::Database(_)) => {
+                ErrorInfo::internal("WorkspaceBudgetError")
+            }
+            ApiError::WorkspaceBudget(err @ WorkspaceBudgetError::Invalid(_)) => {
+                ErrorInfo::bad_request("WorkspaceBudgetError", err.to_string())
+            }
 
             ApiError::ScratchError(ScratchError::Database(_)) => {
                 ErrorInfo::internal("ScratchError")
@@ -428,6 +539,12 @@ impl IntoResponse for ApiError {
                     ),
                 )
             }
+            ApiError::GitService(e @ GitServiceError::InvalidPath(_)) => {
+                ErrorInfo::bad_request("GitServiceError", e.to_string())
+            }
+            ApiError::GitService(e @ GitServiceError::HunkNotFound { .. }) => {
+                ErrorInfo::conflict("GitServiceError", e.to_string())
+            }
             ApiError::GitService(GitServiceError::GitCLI(git::GitCliError::AuthFailed(msg))) => {
                 ErrorInfo::with_status(
                     StatusCode::UNAUTHORIZED,
@@ -443,6 +560,9 @@ impl IntoResponse for ApiError {
                 "GitServiceError",
                 format!("Git operation failed: {}", e),
             ),
+            ApiError::GitHost(GitHostError::RateLimited(msg)) => {
+                ErrorInfo::with_status(StatusCode::TOO_MANY_REQUESTS, "GitHostError", msg.clone())
+            }
             ApiError::GitHost(_) => ErrorInfo::internal("GitHostError"),
 
             ApiError::File(FileError::TooLarge(size, max)) => ErrorInfo::with_status(
@@ -1,31 +1,86 @@
 use axum::{
-    Router,
+    Extension, Router,
     extract::{State, ws::Message},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use db::models::approval::ApprovalResponder;
 use deployment::Deployment;
 use futures_util::StreamExt;
 use utils::{
-    approvals::{ApprovalOutcome, ApprovalResponse},
+    approvals::{APPROVAL_CLIENT_HEADER, ApprovalOutcome, ApprovalResponse},
     log_msg::LogMsg,
     response::ApiResponse,
 };
+use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
-    middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    middleware::{
+        RelayRequestSignatureContext,
+        signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
+    },
 };
 
+/// The paired client behind a relay signing session, if it is still trusted.
+async fn relay_client_id(deployment: &DeploymentImpl, signing_session_id: Uuid) -> Option<Uuid> {
+    let peer_key = deployment
+        .relay_signing()
+        .get_session_peer_key(signing_session_id)
+        .await?;
+    let peer_key_b64 = BASE64_STANDARD.encode(peer_key.as_bytes());
+    match deployment.trusted_key_auth().list_trusted_clients().await {
+        Ok(clients) => clients
+            .into_iter()
+            .find(|client| client.public_key_b64 == peer_key_b64)
+            .map(|client| client.client_id),
+        Err(e) => {
+            tracing::warn!("Failed to list trusted relay clients: {}", e);
+            None
+        }
+    }
+}
+
+/// Who is answering, for the approval history: a relay client when the request
+/// came through the relay, an MCP client when it says so, otherwise the local UI.
+async fn responder(
+    deployment: &DeploymentImpl,
+    relay_ctx: Option<&RelayRequestSignatureContext>,
+    headers: &HeaderMap,
+) -> (ApprovalResponder, Option<Uuid>) {
+    if let Some(ctx) = relay_ctx {
+        return (
+            ApprovalResponder::Relay,
+            relay_client_id(deployment, ctx.signing_session_id).await,
+        );
+    }
+    let is_mcp = headers
+        .get(APPROVAL_CLIENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("mcp"));
+    if is_mcp {
+        (ApprovalResponder::Mcp, None)
+    } else {
+        (ApprovalResponder::Local, None)
+    }
+}
+
 async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
     axum::extract::Path(id): axum::extract::Path<String>,
+    relay_ctx: Option<Extension<RelayRequestSignatureContext>>,
+    headers: HeaderMap,
     ResponseJson(request): ResponseJson<ApprovalResponse>,
 ) -> Result<ResponseJson<ApiResponse<ApprovalOutcome>>, StatusCode> {
     let service = deployment.approvals();
+    let (responder, relay_client_id) = responder(&deployment, relay_ctx.as_deref(), &headers).await;
 
-    match service.respond(&id, request).await {
+    match service
+        .respond(&id, request, responder, relay_client_id)
+        .await
+    {
         Ok((outcome, context)) => {
             deployment
                 .track_if_analytics_allowed(
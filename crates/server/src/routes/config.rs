@@ -256,6 +256,13 @@ async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &C
         }
         (false, false) => (),
     }
+
+    // Raised limits free slots for queued coding agents
+    if old.concurrency != new.concurrency
+        && let Err(e) = deployment.container().start_queued_executions().await
+    {
+        tracing::error!("Failed to start queued executions: {}", e);
+    }
 }
 
 async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
@@ -9,11 +9,14 @@ use axum::{
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
+    execution_start_queue::QueuedExecution,
+    token_usage::ExecutionTokenUsage,
 };
 use deployment::Deployment;
 use futures_util::{StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::container::ContainerService;
+use ts_rs::TS;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -34,6 +37,13 @@ struct SessionExecutionProcessQuery {
     pub show_soft_deleted: Option<bool>,
 }
 
+/// Request body for reordering the start queue
+#[derive(Debug, Deserialize, TS)]
+struct ReorderExecutionQueueRequest {
+    /// Every queued execution process id, in the desired start order
+    pub execution_process_ids: Vec<Uuid>,
+}
+
 async fn get_execution_process_by_id(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(_deployment): State<DeploymentImpl>,
@@ -204,14 +214,42 @@ async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
-    deployment
-        .container()
+    let container = deployment.container();
+    if execution_process.status == ExecutionProcessStatus::Queued {
+        container
+            .cancel_queued_execution(&execution_process)
+            .await?;
+        return Ok(ResponseJson(ApiResponse::success(())));
+    }
+
+    container
         .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
         .await?;
+    if let Err(e) = container.start_queued_executions().await {
+        tracing::error!("Failed to start queued executions: {}", e);
+    }
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Coding agents waiting for a free slot, in start order
+async fn get_start_queue(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedExecution>>>, ApiError> {
+    let queue = QueuedExecution::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
+/// Change the order in which queued coding agents start
+async fn reorder_start_queue(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderExecutionQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<QueuedExecution>>>, ApiError> {
+    let queue =
+        QueuedExecution::reorder(&deployment.db().pool, &payload.execution_process_ids).await?;
+    Ok(ResponseJson(ApiResponse::success(queue)))
+}
+
 #[derive(Debug, serde::Deserialize)]
 struct InjectMessageRequest {
     content: String,
@@ -316,12 +354,26 @@ async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Tokens used by the execution; `None` if its agent reported no usage
+async fn get_execution_process_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionTokenUsage>>>, ApiError> {
+    let usage = ExecutionTokenUsage::find_by_execution_process_id(
+        &deployment.db().pool,
+        execution_process.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
 pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/inject-message", post(inject_message_into_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/usage", get(get_execution_process_usage))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -334,6 +386,8 @@ pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/stream/session/ws",
             get(stream_execution_processes_by_session_ws),
         )
+        .route("/queue", get(get_start_queue))
+        .route("/queue/reorder", post(reorder_start_queue))
         .nest("/{id}", workspace_id_router);
 
     Router::new().nest("/execution-processes", workspaces_router)
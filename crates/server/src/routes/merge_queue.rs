@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+};
+use db::models::merge_queue::{MergeQueueEntry, MergeQueueError, MergeQueueStatus};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// The pending entries of one repo and target branch, in merge order
+#[derive(Debug, Serialize, TS)]
+pub struct MergeQueue {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Set while an entry has a conflict or failed; nothing behind it merges
+    /// until it is retried or cancelled
+    pub paused: bool,
+    pub entries: Vec<MergeQueueEntry>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(list_merge_queues))
+        .route("/{id}", delete(cancel_merge_queue_entry))
+        .route("/{id}/retry", post(retry_merge_queue_entry))
+}
+
+async fn load_entry(deployment: &DeploymentImpl, id: Uuid) -> Result<MergeQueueEntry, ApiError> {
+    Ok(MergeQueueEntry::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(MergeQueueError::NotFound)?)
+}
+
+pub async fn list_merge_queues(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueue>>>, ApiError> {
+    let mut queues: BTreeMap<(Uuid, String), Vec<MergeQueueEntry>> = BTreeMap::new();
+    for entry in MergeQueueEntry::find_pending(&deployment.db().pool).await? {
+        queues
+            .entry((entry.repo_id, entry.target_branch.clone()))
+            .or_default()
+            .push(entry);
+    }
+    let queues = queues
+        .into_iter()
+        .map(|((repo_id, target_branch), entries)| MergeQueue {
+            repo_id,
+            target_branch,
+            paused: entries.iter().any(|entry| {
+                matches!(
+                    entry.status,
+                    MergeQueueStatus::Conflict | MergeQueueStatus::Failed
+                )
+            }),
+            entries,
+        })
+        .collect();
+    Ok(ResponseJson(ApiResponse::success(queues)))
+}
+
+/// Retry a conflicted or failed entry, which resumes its queue. Resolve the
+/// conflict or fix the failing check in the workspace first.
+pub async fn retry_merge_queue_entry(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    load_entry(&deployment, id).await?;
+    if !MergeQueueEntry::retry(&deployment.db().pool, id).await? {
+        return Err(MergeQueueError::Invalid(
+            "only conflicted or failed entries can be retried".to_string(),
+        )
+        .into());
+    }
+    deployment.trigger_merge_queue();
+    Ok(ResponseJson(ApiResponse::success(
+        load_entry(&deployment, id).await?,
+    )))
+}
+
+/// Take an entry out of its queue. Cancelling the entry that paused a queue
+/// resumes it.
+pub async fn cancel_merge_queue_entry(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    load_entry(&deployment, id).await?;
+    if !MergeQueueEntry::cancel(&deployment.db().pool, id).await? {
+        return Err(MergeQueueError::Invalid(
+            "only queued, conflicted or failed entries can be cancelled".to_string(),
+        )
+        .into());
+    }
+    deployment.trigger_merge_queue();
+    Ok(ResponseJson(ApiResponse::success(
+        load_entry(&deployment, id).await?,
+    )))
+}
@@ -21,19 +21,23 @@ pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod host_relay;
+pub mod merge_queue;
 pub mod oauth;
 pub mod organizations;
 pub mod preview;
+pub mod races;
 pub mod relay_auth;
 pub mod releases;
 pub mod remote;
 pub mod repo;
+pub mod schedules;
 pub mod scratch;
 pub mod search;
 pub mod sessions;
 pub mod ssh_session;
 pub mod tags;
 pub mod terminal;
+pub mod usage;
 pub mod webrtc;
 pub mod workspaces;
 
@@ -60,6 +64,10 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(sessions::router(&deployment))
         .nest("/sessions/external", external_sessions::router(&deployment))
         .nest("/webhooks", webhooks::router(&deployment))
+        .nest("/schedules", schedules::router())
+        .nest("/races", races::router())
+        .nest("/merge-queue", merge_queue::router())
+        .nest("/usage", usage::router())
         .merge(terminal::router())
         .route("/ssh-session", get(ssh_session::ssh_session_ws))
         .nest("/remote", remote::router())
@@ -0,0 +1,345 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    race::{Race, RaceAttempt, RaceError, RaceWithAttempts},
+    session::{CreateSession, Session},
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    },
+    logs::{
+        NormalizedEntryType, TokenUsageInfo, utils::patch::extract_normalized_entry_from_patch,
+    },
+    profile::ExecutorProfileId,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::{log_msg::LogMsg, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::workspaces::workspace_summary::{DiffStats, compute_workspace_diff_stats},
+};
+
+/// One attempt of a race, side by side with the others
+#[derive(Debug, Serialize, TS)]
+pub struct RaceAttemptComparison {
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    #[ts(type = "ExecutorProfileId")]
+    pub executor_profile_id: ExecutorProfileId,
+    pub archived: bool,
+    pub is_winner: bool,
+    /// Status of the latest coding agent run; `None` if the agent never started
+    pub agent_status: Option<ExecutionProcessStatus>,
+    /// Time spent in coding agent runs, including the one still running
+    pub duration_seconds: i64,
+    pub diff_stats: Option<DiffStats>,
+    /// Last token usage reported by the latest coding agent run
+    pub token_usage: Option<TokenUsageInfo>,
+    pub test_status: Option<ExecutionProcessStatus>,
+    pub test_exit_code: Option<i64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RaceComparison {
+    pub race: Race,
+    pub attempts: Vec<RaceAttemptComparison>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PickRaceWinnerRequest {
+    pub workspace_id: Uuid,
+}
+
+async fn load_race(deployment: &DeploymentImpl, id: Uuid) -> Result<Race, ApiError> {
+    Ok(Race::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(RaceError::NotFound)?)
+}
+
+async fn load_attempt_workspace(
+    deployment: &DeploymentImpl,
+    attempt: &RaceAttempt,
+) -> Result<Workspace, ApiError> {
+    Ok(
+        Workspace::find_by_id(&deployment.db().pool, attempt.workspace_id)
+            .await?
+            .ok_or(WorkspaceError::WorkspaceNotFound)?,
+    )
+}
+
+pub async fn get_race(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RaceWithAttempts>>, ApiError> {
+    let race = load_race(&deployment, id).await?;
+    let attempts = RaceAttempt::find_by_race_id(&deployment.db().pool, id).await?;
+    Ok(ResponseJson(ApiResponse::success(RaceWithAttempts {
+        race,
+        attempts,
+    })))
+}
+
+/// The last token usage entry logged by an execution process. Live processes
+/// are read from their in-memory store; finished ones are re-normalized from
+/// the stored logs.
+async fn last_token_usage(
+    deployment: &DeploymentImpl,
+    process: &ExecutionProcess,
+) -> Option<TokenUsageInfo> {
+    let container = deployment.container();
+    let messages = if process.status == ExecutionProcessStatus::Running {
+        container
+            .get_msg_store_by_id(&process.id)
+            .await?
+            .get_history()
+    } else {
+        container
+            .stream_normalized_logs(&process.id)
+            .await?
+            .filter_map(|msg| async move { msg.ok() })
+            .collect::<Vec<_>>()
+            .await
+    };
+
+    messages.iter().rev().find_map(|msg| match msg {
+        LogMsg::JsonPatch(patch) => {
+            match extract_normalized_entry_from_patch(patch)?.1.entry_type {
+                NormalizedEntryType::TokenUsageInfo(usage) => Some(usage),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+async fn compare_attempt(
+    deployment: &DeploymentImpl,
+    race: &Race,
+    attempt: RaceAttempt,
+) -> Result<RaceAttemptComparison, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = load_attempt_workspace(deployment, &attempt).await?;
+
+    let mut agent_processes = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+        agent_processes.extend(
+            ExecutionProcess::find_by_session_id(pool, session.id, false)
+                .await?
+                .into_iter()
+                .filter(|process| process.run_reason == ExecutionProcessRunReason::CodingAgent),
+        );
+    }
+    agent_processes.sort_by_key(|process| process.started_at);
+
+    let now = Utc::now();
+    let duration_seconds = agent_processes
+        .iter()
+        .map(|process| {
+            (process.completed_at.unwrap_or(now) - process.started_at)
+                .num_seconds()
+                .max(0)
+        })
+        .sum();
+    let latest_agent_process = agent_processes.last();
+    let token_usage = match latest_agent_process {
+        Some(process) => last_token_usage(deployment, process).await,
+        None => None,
+    };
+
+    let test_process = match attempt.test_execution_process_id {
+        Some(id) => ExecutionProcess::find_by_id(pool, id).await?,
+        None => None,
+    };
+
+    Ok(RaceAttemptComparison {
+        workspace_id: workspace.id,
+        workspace_name: workspace.name.clone(),
+        executor_profile_id: attempt.executor_profile_id.0,
+        archived: workspace.archived,
+        is_winner: race.winner_workspace_id == Some(workspace.id),
+        agent_status: latest_agent_process.map(|process| process.status.clone()),
+        duration_seconds,
+        diff_stats: compute_workspace_diff_stats(deployment, &workspace).await,
+        token_usage,
+        test_status: test_process.as_ref().map(|process| process.status.clone()),
+        test_exit_code: test_process.and_then(|process| process.exit_code),
+    })
+}
+
+pub async fn get_race_comparison(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RaceComparison>>, ApiError> {
+    let race = load_race(&deployment, id).await?;
+    let mut attempts = Vec::new();
+    for attempt in RaceAttempt::find_by_race_id(&deployment.db().pool, id).await? {
+        attempts.push(compare_attempt(&deployment, &race, attempt).await?);
+    }
+    Ok(ResponseJson(ApiResponse::success(RaceComparison {
+        race,
+        attempts,
+    })))
+}
+
+/// Run the race's test script in every attempt that isn't archived or busy.
+/// Returns the started test processes.
+pub async fn run_race_tests(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionProcess>>>, ApiError> {
+    let race = load_race(&deployment, id).await?;
+    let Some(test_script) = race.test_script.clone() else {
+        return Err(RaceError::Invalid("race has no test script".to_string()).into());
+    };
+    let pool = &deployment.db().pool;
+
+    let mut started = Vec::new();
+    for attempt in RaceAttempt::find_by_race_id(pool, id).await? {
+        let workspace = load_attempt_workspace(&deployment, &attempt).await?;
+        if workspace.archived
+            || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                pool,
+                workspace.id,
+            )
+            .await?
+        {
+            tracing::info!(
+                "Skipping race {} test in workspace {}: archived or busy",
+                race.id,
+                workspace.id
+            );
+            continue;
+        }
+
+        deployment
+            .container()
+            .ensure_container_exists(&workspace)
+            .await?;
+
+        // Run from the first repository's worktree, like the per-repo scripts
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: test_script.clone(),
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::TestScript,
+                working_dir: repos.first().map(|repo| repo.name.clone()),
+            }),
+            None,
+        );
+
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(s) => s,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: None,
+                        name: None,
+                        host_id: None,
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        };
+
+        let execution_process = deployment
+            .container()
+            .start_execution(
+                &workspace,
+                &session,
+                &executor_action,
+                &ExecutionProcessRunReason::TestScript,
+            )
+            .await?;
+        RaceAttempt::set_test_execution_process(pool, workspace.id, execution_process.id).await?;
+        started.push(execution_process);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(started)))
+}
+
+/// Keep one attempt and archive the others, stopping anything still running
+/// in them. The winner is recorded first; archiving a losing attempt is best
+/// effort, so one that fails is logged and can be archived by hand.
+pub async fn pick_race_winner(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<PickRaceWinnerRequest>,
+) -> Result<ResponseJson<ApiResponse<Race>>, ApiError> {
+    load_race(&deployment, id).await?;
+    let pool = &deployment.db().pool;
+    let attempts = RaceAttempt::find_by_race_id(pool, id).await?;
+    if !attempts
+        .iter()
+        .any(|attempt| attempt.workspace_id == payload.workspace_id)
+    {
+        return Err(RaceError::Invalid(format!(
+            "workspace {} is not part of this race",
+            payload.workspace_id
+        ))
+        .into());
+    }
+
+    let race = Race::set_winner(pool, id, payload.workspace_id).await?;
+    for attempt in attempts
+        .iter()
+        .filter(|attempt| attempt.workspace_id != payload.workspace_id)
+    {
+        let workspace = match load_attempt_workspace(&deployment, attempt).await {
+            Ok(workspace) if !workspace.archived => workspace,
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load race attempt {} to archive it: {}",
+                    attempt.workspace_id,
+                    e
+                );
+                continue;
+            }
+        };
+        deployment.container().try_stop(&workspace, true).await;
+        if let Err(e) = deployment.container().archive_workspace(workspace.id).await {
+            tracing::error!("Failed to archive race attempt {}: {}", workspace.id, e);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "race_winner_picked",
+            serde_json::json!({
+                "race_id": race.id.to_string(),
+                "workspace_id": payload.workspace_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(race)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/{id}", get(get_race))
+        .route("/{id}/comparison", get(get_race_comparison))
+        .route("/{id}/test", post(run_race_tests))
+        .route("/{id}/winner", post(pick_race_winner))
+}
@@ -7,12 +7,18 @@ use axum::{
     response::Json as ResponseJson,
     routing::{get, post},
 };
-use db::models::repo::{Repo, SearchResult, UpdateRepo};
+use db::models::{
+    approval_rule::{ApprovalRule, ApprovalRuleInput},
+    repo::{GitHubAccess, Repo, SearchResult, UpdateRepo},
+    verification::{VerificationStep, VerificationStepInput},
+};
 use deployment::Deployment;
 use git::{GitBranch, GitRemote};
 use git_host::{GitHostError, GitHostProvider, GitHostService, ProviderKind, PullRequestDetail};
 use serde::{Deserialize, Serialize};
-use services::services::file_search::SearchQuery;
+use services::services::{
+    approvals::policy::validate_rules, commit_message, file_search::SearchQuery,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -148,10 +154,76 @@ pub async fn update_repo(
     Path(repo_id): Path<Uuid>,
     ResponseJson(payload): ResponseJson<UpdateRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    if let Some(Some(template)) = &payload.commit_message_template {
+        commit_message::validate_template(template).map_err(ApiError::BadRequest)?;
+    }
+    if payload.cpu_limit.flatten().is_some_and(|cpus| cpus <= 0.0)
+        || payload.memory_limit_mb.flatten().is_some_and(|mb| mb <= 0)
+        || payload.pids_limit.flatten().is_some_and(|pids| pids <= 0)
+    {
+        return Err(ApiError::BadRequest(
+            "Resource limits must be positive".to_string(),
+        ));
+    }
     let repo = Repo::update(&deployment.db().pool, repo_id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+pub async fn get_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let rules = ApprovalRule::find_by_repo_id(pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReplaceApprovalRulesRequest {
+    /// The repository's complete rule list, in evaluation order
+    pub rules: Vec<ApprovalRuleInput>,
+}
+
+pub async fn replace_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<ReplaceApprovalRulesRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    validate_rules(&payload.rules)?;
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let rules = ApprovalRule::replace_for_repo(pool, repo_id, &payload.rules).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_verification_steps(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationStep>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let steps = VerificationStep::find_by_repo_id(pool, repo_id).await?;
+    Ok(ResponseJson(ApiResponse::success(steps)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReplaceVerificationStepsRequest {
+    /// The repository's complete step list, in run order
+    pub steps: Vec<VerificationStepInput>,
+}
+
+pub async fn replace_verification_steps(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+    ResponseJson(payload): ResponseJson<ReplaceVerificationStepsRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationStep>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    deployment.repo().get_by_id(pool, repo_id).await?;
+    let steps = VerificationStep::replace_for_repo(pool, repo_id, &payload.steps).await?;
+    Ok(ResponseJson(ApiResponse::success(steps)))
+}
+
 pub async fn open_repo_in_editor(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -267,7 +339,12 @@ pub async fn list_open_prs(
         None => deployment.git().get_default_remote(&repo.path)?,
     };
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(repo.github_access);
+    let git_host = match GitHostService::from_url(&remote.url, &git_host_options) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -304,10 +381,15 @@ pub struct PrInfoQuery {
 }
 
 pub async fn get_pr_info(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Query(query): Query<PrInfoQuery>,
 ) -> Result<ResponseJson<ApiResponse<PullRequestDetail, ListPrsError>>, ApiError> {
-    let git_host = match GitHostService::from_url(&query.url) {
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(GitHubAccess::Auto);
+    let git_host = match GitHostService::from_url(&query.url, &git_host_options) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -387,4 +469,12 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/pr-info", get(get_pr_info))
         .route("/repos/{repo_id}/search", get(search_repo))
         .route("/repos/{repo_id}/open-editor", post(open_repo_in_editor))
+        .route(
+            "/repos/{repo_id}/approval-rules",
+            get(get_approval_rules).put(replace_approval_rules),
+        )
+        .route(
+            "/repos/{repo_id}/verification-steps",
+            get(get_verification_steps).put(replace_verification_steps),
+        )
 }
@@ -0,0 +1,211 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::Utc;
+use db::models::{
+    repo::{Repo, RepoError},
+    schedule::{CreateSchedule, Schedule, ScheduleError, ScheduleRun, UpdateSchedule},
+};
+use deployment::Deployment;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
+use serde::Deserialize;
+use services::services::schedule::{next_run_after, next_run_for_update, validate_schedule};
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+fn validate_executor_profile(profile_id: &ExecutorProfileId) -> Result<(), ScheduleError> {
+    if ExecutorConfigs::get_cached()
+        .get_coding_agent(profile_id)
+        .is_none()
+    {
+        return Err(ScheduleError::Invalid(format!(
+            "unknown executor profile '{profile_id}'"
+        )));
+    }
+    Ok(())
+}
+
+async fn load_schedule(deployment: &DeploymentImpl, id: Uuid) -> Result<Schedule, ApiError> {
+    Ok(Schedule::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ScheduleError::NotFound)?)
+}
+
+pub async fn list_schedules(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Schedule>>>, ApiError> {
+    let schedules = Schedule::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn create_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateSchedule>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    validate_schedule(
+        &payload.name,
+        &payload.cron_expression,
+        &payload.target_branch,
+        &payload.prompt_template,
+    )?;
+    validate_executor_profile(&payload.executor_profile_id)?;
+    let pool = &deployment.db().pool;
+    Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let next_run_at = next_run_after(&payload.cron_expression, Utc::now())?;
+    let schedule = Schedule::create(pool, &payload, next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "executor": payload.executor_profile_id.executor.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn get_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = load_schedule(&deployment, id).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateSchedule>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let existing = load_schedule(&deployment, id).await?;
+    validate_schedule(
+        payload.name.as_deref().unwrap_or(&existing.name),
+        payload
+            .cron_expression
+            .as_deref()
+            .unwrap_or(&existing.cron_expression),
+        payload
+            .target_branch
+            .as_deref()
+            .unwrap_or(&existing.target_branch),
+        payload
+            .prompt_template
+            .as_deref()
+            .unwrap_or(&existing.prompt_template),
+    )?;
+    if let Some(profile_id) = &payload.executor_profile_id {
+        validate_executor_profile(profile_id)?;
+    }
+
+    let next_run_at = next_run_for_update(&existing, &payload, Utc::now())?;
+    let schedule = Schedule::update(&deployment.db().pool, id, &payload, next_run_at).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+async fn set_paused(
+    deployment: &DeploymentImpl,
+    id: Uuid,
+    paused: bool,
+) -> Result<Schedule, ApiError> {
+    let existing = load_schedule(deployment, id).await?;
+    let payload = UpdateSchedule {
+        paused: Some(paused),
+        ..Default::default()
+    };
+    let next_run_at = next_run_for_update(&existing, &payload, Utc::now())?;
+    Ok(Schedule::update(&deployment.db().pool, id, &payload, next_run_at).await?)
+}
+
+pub async fn pause_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = set_paused(&deployment, id, true).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn resume_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let schedule = set_paused(&deployment, id, false).await?;
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+/// Fire the schedule on the scheduler's next pass instead of waiting for its
+/// next cron tick. The regular cadence continues afterwards.
+pub async fn run_schedule_now(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Schedule>>, ApiError> {
+    let existing = load_schedule(&deployment, id).await?;
+    if existing.paused {
+        return Err(ScheduleError::Invalid("schedule is paused".to_string()).into());
+    }
+    let schedule = Schedule::update(
+        &deployment.db().pool,
+        id,
+        &UpdateSchedule::default(),
+        Some(Utc::now()),
+    )
+    .await?;
+    deployment.trigger_schedule_check();
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    Schedule::delete(&deployment.db().pool, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListRunsQuery {
+    pub limit: Option<i64>,
+}
+
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+const MAX_RUNS_LIMIT: i64 = 500;
+
+pub async fn list_schedule_runs(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListRunsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ScheduleRun>>>, ApiError> {
+    load_schedule(&deployment, id).await?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUNS_LIMIT)
+        .clamp(1, MAX_RUNS_LIMIT);
+    let runs = ScheduleRun::find_by_schedule_id(&deployment.db().pool, id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(list_schedules).post(create_schedule))
+        .route(
+            "/{id}",
+            get(get_schedule)
+                .put(update_schedule)
+                .delete(delete_schedule),
+        )
+        .route("/{id}/pause", post(pause_schedule))
+        .route("/{id}/resume", post(resume_schedule))
+        .route("/{id}/run", post(run_schedule_now))
+        .route("/{id}/runs", get(list_schedule_runs))
+}
@@ -0,0 +1,413 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    checkpoint::Checkpoint,
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    requests::{CreateAndStartWorkspaceResponse, WorkspaceRepoInput},
+    session::{CreateSession, Session},
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
+    },
+    profile::ExecutorConfig,
+};
+use serde::{Deserialize, Serialize};
+use services::services::container::ContainerService;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_session_middleware,
+    routes::workspaces::create::{create_workspace_record, normalize_prompt},
+};
+
+/// A coding agent turn of the session with the checkpoints taken after it
+#[derive(Debug, Serialize, TS)]
+pub struct CheckpointTurn {
+    pub execution_process_id: Uuid,
+    pub prompt: Option<String>,
+    pub summary: Option<String>,
+    /// Totals over the repos of the turn
+    pub files_changed: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub created_at: DateTime<Utc>,
+    /// One checkpoint per repo
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+/// Request body for restoring the session to a checkpoint
+#[derive(Debug, Deserialize, TS)]
+pub struct RestoreCheckpointRequest {
+    pub execution_process_id: Uuid,
+    /// Discard changes made after the latest checkpoint
+    #[serde(default)]
+    pub force_when_dirty: Option<bool>,
+    /// Send this prompt as a follow-up from the restored turn
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Defaults to the session's latest executor
+    #[serde(default)]
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+/// Request body for starting a new workspace from a checkpoint
+#[derive(Debug, Deserialize, TS)]
+pub struct BranchFromCheckpointRequest {
+    pub execution_process_id: Uuid,
+    pub prompt: String,
+    pub name: Option<String>,
+    /// Defaults to the session's latest executor
+    #[serde(default)]
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+/// The checkpoints taken after `execution_process_id` in the session, or a
+/// bad request when there are none
+async fn turn_checkpoints(
+    pool: &SqlitePool,
+    session: &Session,
+    execution_process_id: Uuid,
+) -> Result<Vec<Checkpoint>, ApiError> {
+    let checkpoints = Checkpoint::find_by_execution_process_id(pool, execution_process_id).await?;
+    if checkpoints.is_empty() || checkpoints.iter().any(|c| c.session_id != session.id) {
+        return Err(ApiError::BadRequest(
+            "No checkpoint was taken after this process in the session".to_string(),
+        ));
+    }
+    Ok(checkpoints)
+}
+
+async fn resolve_executor_config(
+    pool: &SqlitePool,
+    session: &Session,
+    requested: Option<ExecutorConfig>,
+) -> Result<ExecutorConfig, ApiError> {
+    let latest_profile =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    match (requested, latest_profile) {
+        (Some(config), Some(profile)) if config.executor != profile.executor => {
+            Err(ApiError::BadRequest(format!(
+                "Session runs {}, not {}",
+                profile.executor, config.executor
+            )))
+        }
+        (Some(config), _) => Ok(config),
+        (None, Some(profile)) => Ok(ExecutorConfig::from(profile)),
+        (None, None) => Err(ApiError::BadRequest(
+            "An executor_config is required for a session without coding agent runs".to_string(),
+        )),
+    }
+}
+
+/// Continue the agent's conversation of `turn` with `prompt`, trimming it
+/// back to that turn, or start a new conversation when the turn has none
+fn follow_up_from_turn(
+    turn: Option<CodingAgentTurn>,
+    prompt: String,
+    executor_config: ExecutorConfig,
+    working_dir: Option<String>,
+) -> ExecutorActionType {
+    match turn.and_then(|turn| Some((turn.agent_session_id?, turn.agent_message_id))) {
+        Some((session_id, message_id)) => {
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt,
+                session_id,
+                reset_to_message_id: message_id,
+                executor_config,
+                working_dir,
+            })
+        }
+        None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt,
+            executor_config,
+            working_dir,
+        }),
+    }
+}
+
+/// The session's coding agent turns that have checkpoints, oldest first
+async fn get_checkpoints(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<CheckpointTurn>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut turns: Vec<CheckpointTurn> = Vec::new();
+    for checkpoint in Checkpoint::find_by_session_id(pool, session.id).await? {
+        match turns.last_mut() {
+            Some(turn) if turn.execution_process_id == checkpoint.execution_process_id => {
+                turn.files_changed += checkpoint.files_changed;
+                turn.lines_added += checkpoint.lines_added;
+                turn.lines_removed += checkpoint.lines_removed;
+                turn.checkpoints.push(checkpoint);
+            }
+            _ => {
+                let agent_turn = CodingAgentTurn::find_by_execution_process_id(
+                    pool,
+                    checkpoint.execution_process_id,
+                )
+                .await?;
+                let (prompt, summary) = agent_turn
+                    .map(|turn| (turn.prompt, turn.summary))
+                    .unwrap_or_default();
+                turns.push(CheckpointTurn {
+                    execution_process_id: checkpoint.execution_process_id,
+                    prompt,
+                    summary,
+                    files_changed: checkpoint.files_changed,
+                    lines_added: checkpoint.lines_added,
+                    lines_removed: checkpoint.lines_removed,
+                    created_at: checkpoint.created_at,
+                    checkpoints: vec![checkpoint],
+                });
+            }
+        }
+    }
+    Ok(ResponseJson(ApiResponse::success(turns)))
+}
+
+/// Put the workspace back to a checkpoint and drop the later turns from the
+/// session. With a prompt, also send it as a follow-up from that turn.
+async fn restore_checkpoint(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RestoreCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<Option<ExecutionProcess>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    turn_checkpoints(pool, &session, payload.execution_process_id).await?;
+    let prompt = payload.prompt.as_deref().and_then(normalize_prompt);
+    let executor_config = match prompt {
+        Some(_) => Some(resolve_executor_config(pool, &session, payload.executor_config).await?),
+        None => None,
+    };
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before restoring a checkpoint".to_string(),
+        ));
+    }
+
+    deployment
+        .container()
+        .restore_session_to_checkpoint(
+            session.id,
+            payload.execution_process_id,
+            payload.force_when_dirty.unwrap_or(false),
+        )
+        .await?;
+
+    let execution_process = match (prompt, executor_config) {
+        (Some(prompt), Some(executor_config)) => {
+            let turn =
+                CodingAgentTurn::find_by_execution_process_id(pool, payload.execution_process_id)
+                    .await?;
+            let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+            let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+            let working_dir = session
+                .agent_working_dir
+                .as_ref()
+                .filter(|dir| !dir.is_empty())
+                .cloned();
+            let action = ExecutorAction::new(
+                follow_up_from_turn(turn, prompt, executor_config, working_dir),
+                cleanup_action.map(Box::new),
+            );
+            Some(
+                deployment
+                    .container()
+                    .start_execution(
+                        &workspace,
+                        &session,
+                        &action,
+                        &ExecutionProcessRunReason::CodingAgent,
+                    )
+                    .await?,
+            )
+        }
+        _ => None,
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "checkpoint_restored",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "with_prompt": execution_process.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// Create and start a workspace whose repos start from a checkpoint of this
+/// session, continuing the agent's conversation as it was at that turn.
+/// Agents that keep their history per directory may not find it from the
+/// new worktree, in which case the run offers the usual session recovery.
+async fn branch_from_checkpoint(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<BranchFromCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateAndStartWorkspaceResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let prompt = normalize_prompt(&payload.prompt).ok_or_else(|| {
+        ApiError::BadRequest(
+            "A workspace prompt is required. Provide a non-empty `prompt`.".to_string(),
+        )
+    })?;
+    let checkpoints = turn_checkpoints(pool, &session, payload.execution_process_id).await?;
+    let executor_config = resolve_executor_config(pool, &session, payload.executor_config).await?;
+
+    let repos: Vec<WorkspaceRepoInput> =
+        WorkspaceRepo::find_by_workspace_id(pool, session.workspace_id)
+            .await?
+            .into_iter()
+            .filter(|repo| checkpoints.iter().any(|c| c.repo_id == repo.repo_id))
+            .map(|repo| WorkspaceRepoInput {
+                repo_id: repo.repo_id,
+                target_branch: repo.target_branch,
+            })
+            .collect();
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "None of the checkpoint's repositories are part of the workspace anymore".to_string(),
+        ));
+    }
+
+    let mut managed_workspace = deployment
+        .workspace_manager()
+        .load_managed_workspace(create_workspace_record(&deployment, payload.name).await?)
+        .await?;
+    for repo in &repos {
+        managed_workspace
+            .add_repository(repo, deployment.git())
+            .await
+            .map_err(ApiError::from)?;
+    }
+    let workspace_id = managed_workspace.workspace.id;
+
+    let container_ref = deployment
+        .container()
+        .create(&managed_workspace.workspace)
+        .await?;
+    let workspace = Workspace::find_by_id(pool, workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+    let workspace_repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let workspace_dir = PathBuf::from(container_ref);
+    for repo in &workspace_repos {
+        if let Some(checkpoint) = checkpoints.iter().find(|c| c.repo_id == repo.id) {
+            // Files copied into the new worktree make it dirty; the
+            // checkpoint holds the complete content
+            deployment.git().restore_checkpoint(
+                &workspace_dir.join(&repo.name),
+                &checkpoint.head_commit,
+                &checkpoint.commit_sha,
+                true,
+            )?;
+        }
+    }
+
+    let new_session = Session::create(
+        pool,
+        &CreateSession {
+            executor: Some(executor_config.executor.to_string()),
+            name: session.name.clone(),
+            host_id: None,
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await?;
+    let working_dir = new_session
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+    let turn =
+        CodingAgentTurn::find_by_execution_process_id(pool, payload.execution_process_id).await?;
+    let coding_action = ExecutorAction::new(
+        follow_up_from_turn(turn, prompt, executor_config.clone(), working_dir),
+        deployment
+            .container()
+            .cleanup_actions_for_repos(&workspace_repos)
+            .map(Box::new),
+    );
+    let (action, run_reason) = match deployment
+        .container()
+        .setup_actions_for_repos(&workspace_repos)
+    {
+        Some(setup_action) => (
+            setup_action.append_action(coding_action),
+            ExecutionProcessRunReason::SetupScript,
+        ),
+        None => (coding_action, ExecutionProcessRunReason::CodingAgent),
+    };
+    let execution_process = deployment
+        .container()
+        .start_execution(&workspace, &new_session, &action, &run_reason)
+        .await?;
+
+    tracing::info!(
+        "Created workspace {} from checkpoint of execution process {}",
+        workspace.id,
+        payload.execution_process_id
+    );
+    deployment
+        .track_if_analytics_allowed(
+            "checkpoint_branched",
+            serde_json::json!({
+                "executor": &executor_config.executor,
+                "workspace_id": workspace.id.to_string(),
+                "source_workspace_id": session.workspace_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CreateAndStartWorkspaceResponse {
+            workspace,
+            execution_process,
+            link_warning: None,
+        },
+    )))
+}
+
+pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_checkpoints))
+        .route("/restore", post(restore_checkpoint))
+        .route("/branch", post(branch_from_checkpoint))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_session_middleware,
+        ))
+}
@@ -0,0 +1,165 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::ExecutionProcess,
+    fix_up_loop::{FixUpLoop, FixUpLoopStatus},
+    session::Session,
+    verification::VerificationTrigger,
+    workspace::{Workspace, WorkspaceError},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+
+const DEFAULT_MAX_ITERATIONS: i64 = 3;
+const MAX_ITERATIONS_LIMIT: i64 = 10;
+
+/// Request body for starting a fix-up loop on a session
+#[derive(Debug, Deserialize, TS)]
+pub struct StartFixUpLoopRequest {
+    /// Most follow-ups to send before giving up; 3 when omitted
+    #[serde(default)]
+    pub max_iterations: Option<i64>,
+}
+
+/// The fix-up loops of the session, newest first
+async fn get_fix_up_loops(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<FixUpLoop>>>, ApiError> {
+    let loops = FixUpLoop::find_by_session_id(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(loops)))
+}
+
+/// Verify the workspace now and, while the steps fail, send the failing
+/// output back to the session's coding agent as a follow-up and verify again
+async fn start_fix_up_loop(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<StartFixUpLoopRequest>,
+) -> Result<ResponseJson<ApiResponse<FixUpLoop>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let max_iterations = payload.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+    if !(1..=MAX_ITERATIONS_LIMIT).contains(&max_iterations) {
+        return Err(ApiError::BadRequest(format!(
+            "max_iterations must be between 1 and {MAX_ITERATIONS_LIMIT}"
+        )));
+    }
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    if FixUpLoop::find_running_by_session_id(pool, session.id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(
+            "A fix-up loop is already running in this session".to_string(),
+        ));
+    }
+    if CodingAgentTurn::find_latest_session_info(pool, session.id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "The session has no coding agent turn to follow up on".to_string(),
+        ));
+    }
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before starting a fix-up loop".to_string(),
+        ));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    // The loop exists before its first run so that a step failing right away
+    // is picked up by it
+    let fix_up = FixUpLoop::create(pool, workspace.id, session.id, max_iterations).await?;
+    let started = deployment
+        .container()
+        .start_verification(&workspace, &session, None, VerificationTrigger::Manual)
+        .await;
+    let error = match started {
+        Ok(Some(_)) => None,
+        Ok(None) => Some(ApiError::BadRequest(
+            "No verification steps are configured".to_string(),
+        )),
+        Err(e) => Some(ApiError::from(e)),
+    };
+    if let Some(error) = error {
+        FixUpLoop::update_status(
+            pool,
+            fix_up.id,
+            FixUpLoopStatus::Failed,
+            Some(&error.to_string()),
+        )
+        .await?;
+        return Err(error);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "fix_up_loop_started",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "max_iterations": max_iterations,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(fix_up)))
+}
+
+/// Stop the session's running fix-up loop. A follow-up or verification
+/// already running finishes, but no further follow-up is sent.
+async fn stop_fix_up_loop(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(fix_up) = FixUpLoop::find_running_by_session_id(pool, session.id).await? else {
+        return Err(ApiError::BadRequest(
+            "No fix-up loop is running in this session".to_string(),
+        ));
+    };
+    FixUpLoop::update_status(
+        pool,
+        fix_up.id,
+        FixUpLoopStatus::Cancelled,
+        Some("Stopped by the user"),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            "/",
+            get(get_fix_up_loops)
+                .post(start_fix_up_loop)
+                .delete(stop_fix_up_loop),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_session_middleware,
+        ))
+}
@@ -1,3 +1,5 @@
+pub mod checkpoints;
+pub mod fix_up;
 pub mod queue;
 pub mod review;
 
@@ -15,6 +17,7 @@ use db::models::{
     requests::UpdateSession,
     scratch::{Scratch, ScratchType},
     session::{CreateSession, Session, SessionError},
+    token_usage::{ExecutionTokenUsage, UsageTotals},
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -63,6 +66,15 @@ pub async fn get_session(
     Ok(ResponseJson(ApiResponse::success(session)))
 }
 
+/// Token usage and cost of every execution in the session
+pub async fn get_session_usage(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UsageTotals>>, ApiError> {
+    let totals = ExecutionTokenUsage::totals_for_session(&deployment.db().pool, session.id).await?;
+    Ok(ResponseJson(ApiResponse::success(totals)))
+}
+
 pub async fn create_session(
     State(deployment): State<DeploymentImpl>,
     headers: HeaderMap,
@@ -217,11 +229,12 @@ pub async fn follow_up(
             working_dir: working_dir.clone(),
         })
     } else if let Some(info) = latest_session_info {
-        let is_reset = payload.retry_process_id.is_some();
+        // A retry or restored checkpoint leaves the agent's own history ahead
+        // of the session, so it is trimmed back to the resumed turn
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
             prompt: prompt.clone(),
+            reset_to_message_id: info.reset_to_message_id(),
             session_id: info.session_id,
-            reset_to_message_id: if is_reset { info.message_id } else { None },
             executor_config: payload.executor_config.clone(),
             working_dir: working_dir.clone(),
         })
@@ -344,6 +357,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_session).put(update_session))
         .route("/follow-up", post(follow_up))
         .route("/reset", post(reset_process))
+        .route("/usage", get(get_session_usage))
         .route("/setup", post(run_setup_script))
         .route("/review", post(review::start_review))
         .layer(from_fn_with_state(
@@ -354,7 +368,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let sessions_router = Router::new()
         .route("/", get(get_sessions).post(create_session))
         .nest("/{session_id}", session_id_router)
-        .nest("/{session_id}/queue", queue::router(deployment));
+        .nest("/{session_id}/queue", queue::router(deployment))
+        .nest("/{session_id}/fix-up", fix_up::router(deployment))
+        .nest("/{session_id}/checkpoints", checkpoints::router(deployment));
 
     Router::new().nest("/sessions", sessions_router)
 }
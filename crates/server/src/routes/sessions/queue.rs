@@ -1,6 +1,9 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
@@ -9,17 +12,26 @@ use serde::Deserialize;
 use services::services::queued_message::QueueStatus;
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
 
-/// Request body for queueing a follow-up message
+/// Request body for queueing (or editing a queued) follow-up message
 #[derive(Debug, Deserialize, TS)]
 struct QueueMessageRequest {
     pub message: String,
     pub executor_config: ExecutorConfig,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+struct ReorderQueueRequest {
+    /// Every queued message id of the session, in the desired execution order
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Append a follow-up message to be executed after the queued ones, once the
+/// current execution finishes
 async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
@@ -30,9 +42,10 @@ async fn queue_message(
         executor_config: payload.executor_config,
     };
 
-    let queued = deployment
+    let status = deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -44,19 +57,18 @@ async fn queue_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel every queued follow-up message of the session
 async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    deployment
+    let status = deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .clear(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -68,15 +80,64 @@ async fn cancel_queued_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Empty)))
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Get the current queue status for a session's workspace
+/// Get the current queue status for a session
 async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Change the order in which queued messages run
+async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .reorder(session.id, &payload.message_ids)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Edit a single queued message
+async fn update_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let data = DraftFollowUpData {
+        message: payload.message,
+        executor_config: payload.executor_config,
+    };
+
+    let status = deployment
+        .queued_message_service()
+        .update_message(session_id, message_id, data)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+/// Remove a single queued message
+async fn delete_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let status = deployment
+        .queued_message_service()
+        .delete_message(session_id, message_id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
@@ -89,8 +150,15 @@ pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/reorder", post(reorder_queue))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
         ))
+        // Item routes carry a second path parameter, which the session
+        // loader can't extract; queries are scoped by session id instead.
+        .route(
+            "/{message_id}",
+            put(update_queued_message).delete(delete_queued_message),
+        )
 }
@@ -0,0 +1,105 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use chrono::{DateTime, Duration, Utc};
+use db::models::{
+    model_price::{ModelPrice, ModelPriceError, UpsertModelPrice},
+    token_usage::{DailyUsage, ExecutionTokenUsage, ExecutorUsage, RepoUsage},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageRangeQuery {
+    /// How many days back to look, counting today
+    pub days: Option<i64>,
+}
+
+const DEFAULT_USAGE_DAYS: i64 = 30;
+const MAX_USAGE_DAYS: i64 = 366;
+
+impl UsageRangeQuery {
+    fn since(&self) -> DateTime<Utc> {
+        let days = self
+            .days
+            .unwrap_or(DEFAULT_USAGE_DAYS)
+            .clamp(1, MAX_USAGE_DAYS);
+        let today = Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc();
+        today - Duration::days(days - 1)
+    }
+}
+
+pub async fn get_daily_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageRangeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DailyUsage>>>, ApiError> {
+    let usage = ExecutionTokenUsage::daily_totals(&deployment.db().pool, query.since()).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+pub async fn get_executor_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageRangeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutorUsage>>>, ApiError> {
+    let usage = ExecutionTokenUsage::executor_totals(&deployment.db().pool, query.since()).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+pub async fn get_repo_usage(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageRangeQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<RepoUsage>>>, ApiError> {
+    let usage = ExecutionTokenUsage::repo_totals(&deployment.db().pool, query.since()).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+pub async fn list_model_prices(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ModelPrice>>>, ApiError> {
+    let prices = ModelPrice::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(prices)))
+}
+
+/// Prices apply to usage recorded from now on; stored costs are not repriced
+pub async fn upsert_model_price(
+    State(deployment): State<DeploymentImpl>,
+    Path(model): Path<String>,
+    Json(payload): Json<UpsertModelPrice>,
+) -> Result<ResponseJson<ApiResponse<ModelPrice>>, ApiError> {
+    let price = ModelPrice::upsert(&deployment.db().pool, &model, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(price)))
+}
+
+pub async fn delete_model_price(
+    State(deployment): State<DeploymentImpl>,
+    Path(model): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if ModelPrice::delete(&deployment.db().pool, &model).await? == 0 {
+        return Err(ModelPriceError::NotFound.into());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/daily", get(get_daily_usage))
+        .route("/executors", get(get_executor_usage))
+        .route("/repos", get(get_repo_usage))
+        .route("/prices", get(list_model_prices))
+        // Model ids may contain slashes, e.g. `anthropic/claude-sonnet-4`
+        .route(
+            "/prices/{*model}",
+            put(upsert_model_price).delete(delete_model_price),
+        )
+}
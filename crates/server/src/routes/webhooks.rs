@@ -1,13 +1,17 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json as ResponseJson,
-    routing::get,
+    routing::{get, post},
+};
+use db::models::{
+    webhook::{CreateWebhook, UpdateWebhook, Webhook, WebhookError},
+    webhook_delivery::WebhookDelivery,
 };
-use db::models::webhook::{CreateWebhook, Webhook};
 use deployment::Deployment;
 use serde::Deserialize;
+use services::services::webhook_filter::validate_filters;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -19,6 +23,10 @@ pub struct CreateWebhookRequest {
     pub url: String,
     pub secret: Option<String>,
     pub description: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub repo_ids: Option<Vec<Uuid>>,
+    pub workspace_glob: Option<String>,
+    pub branch_glob: Option<String>,
 }
 
 pub async fn list_webhooks(
@@ -33,6 +41,12 @@ pub async fn create_webhook(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateWebhookRequest>,
 ) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    validate_filters(
+        payload.event_types.as_deref(),
+        payload.repo_ids.as_deref(),
+        payload.workspace_glob.as_deref(),
+        payload.branch_glob.as_deref(),
+    )?;
     let pool = &deployment.db().pool;
     let hook = Webhook::create(
         pool,
@@ -40,9 +54,31 @@ pub async fn create_webhook(
             url: payload.url,
             secret: payload.secret,
             description: payload.description,
+            event_types: payload.event_types,
+            repo_ids: payload.repo_ids,
+            workspace_glob: payload.workspace_glob,
+            branch_glob: payload.branch_glob,
         },
     )
     .await?;
+    deployment.invalidate_webhook_cache().await;
+    Ok(ResponseJson(ApiResponse::success(hook)))
+}
+
+pub async fn update_webhook(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhook>,
+) -> Result<ResponseJson<ApiResponse<Webhook>>, ApiError> {
+    validate_filters(
+        payload.event_types.as_ref().and_then(|v| v.as_deref()),
+        payload.repo_ids.as_ref().and_then(|v| v.as_deref()),
+        payload.workspace_glob.as_ref().and_then(|v| v.as_deref()),
+        payload.branch_glob.as_ref().and_then(|v| v.as_deref()),
+    )?;
+    let pool = &deployment.db().pool;
+    let hook = Webhook::update(pool, id, &payload).await?;
+    deployment.invalidate_webhook_cache().await;
     Ok(ResponseJson(ApiResponse::success(hook)))
 }
 
@@ -52,12 +88,56 @@ pub async fn delete_webhook(
 ) -> Result<StatusCode, ApiError> {
     let pool = &deployment.db().pool;
     Webhook::delete(pool, id).await?;
+    deployment.invalidate_webhook_cache().await;
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    pub limit: Option<i64>,
+}
+
+const DEFAULT_DELIVERIES_LIMIT: i64 = 50;
+const MAX_DELIVERIES_LIMIT: i64 = 500;
+
+pub async fn list_webhook_deliveries(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<WebhookDelivery>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Webhook::find_by_id(pool, id)
+        .await?
+        .ok_or(WebhookError::NotFound)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERIES_LIMIT)
+        .clamp(1, MAX_DELIVERIES_LIMIT);
+    let deliveries = WebhookDelivery::find_by_webhook_id(pool, id, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(deliveries)))
+}
+
+pub async fn redeliver_webhook_delivery(
+    State(deployment): State<DeploymentImpl>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<WebhookDelivery>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let delivery = WebhookDelivery::reset_for_redelivery(pool, id, delivery_id).await?;
+    deployment.trigger_webhook_delivery();
+    Ok(ResponseJson(ApiResponse::success(delivery)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let _ = deployment;
     Router::new()
         .route("/", get(list_webhooks).post(create_webhook))
-        .route("/{id}", axum::routing::delete(delete_webhook))
+        .route(
+            "/{id}",
+            axum::routing::put(update_webhook).delete(delete_webhook),
+        )
+        .route("/{id}/deliveries", get(list_webhook_deliveries))
+        .route(
+            "/{id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver_webhook_delivery),
+        )
 }
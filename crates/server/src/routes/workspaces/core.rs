@@ -5,14 +5,18 @@ use axum::{
     response::Json as ResponseJson,
 };
 use db::models::{
+    approval::Approval,
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    token_usage::{ExecutionTokenUsage, UsageTotals},
     workspace::{Workspace, WorkspaceError},
+    workspace_budget::{SetWorkspaceBudget, WorkspaceBudget},
 };
 use deployment::Deployment;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::{container::ContainerService, diff_stream, remote_sync};
 use sqlx::Error as SqlxError;
+use ts_rs::TS;
 use utils::response::ApiResponse;
 use workspace_manager::WorkspaceManager;
 
@@ -96,6 +100,68 @@ pub async fn get_first_user_message(
     Ok(ResponseJson(ApiResponse::success(message)))
 }
 
+/// Approval and question history of every execution in the workspace
+pub async fn get_workspace_approvals(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Approval>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let approvals = Approval::find_by_workspace_id(pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorkspaceUsage {
+    pub totals: UsageTotals,
+    pub budget: Option<WorkspaceBudget>,
+    /// Why the workspace is over budget; agents will not start while set
+    pub budget_exceeded: Option<String>,
+}
+
+/// Token usage and cost of every execution in the workspace
+pub async fn get_workspace_usage(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceUsage>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let totals = ExecutionTokenUsage::totals_for_workspace(pool, workspace.id).await?;
+    let budget = WorkspaceBudget::find_by_workspace_id(pool, workspace.id).await?;
+    let budget_exceeded = budget
+        .as_ref()
+        .and_then(|budget| budget.exceeded_by(&totals));
+    Ok(ResponseJson(ApiResponse::success(WorkspaceUsage {
+        totals,
+        budget,
+        budget_exceeded,
+    })))
+}
+
+pub async fn get_workspace_budget(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<WorkspaceBudget>>>, ApiError> {
+    let budget = WorkspaceBudget::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+/// Takes effect on the next token usage report of a running agent
+pub async fn set_workspace_budget(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetWorkspaceBudget>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceBudget>>, ApiError> {
+    let budget = WorkspaceBudget::set(&deployment.db().pool, workspace.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(budget)))
+}
+
+pub async fn delete_workspace_budget(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<StatusCode, ApiError> {
+    WorkspaceBudget::delete(&deployment.db().pool, workspace.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn delete_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
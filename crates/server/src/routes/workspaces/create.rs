@@ -2,15 +2,19 @@ use std::collections::HashMap;
 
 use axum::{Json, extract::State, response::Json as ResponseJson};
 use db::models::{
+    race::{Race, RaceAttempt, RaceError},
     requests::{
-        CreateAndStartWorkspaceRequest, CreateAndStartWorkspaceResponse, CreateWorkspaceApiRequest,
+        CreateAndStartWorkspaceRequest, CreateAndStartWorkspaceResponse, CreateRaceRequest,
+        CreateRaceResponse, CreateWorkspaceApiRequest,
     },
     workspace::{CreateWorkspace, Workspace},
 };
 use deployment::Deployment;
+use executors::profile::{ExecutorConfig, ExecutorConfigs};
 use services::services::container::ContainerService;
 use utils::response::ApiResponse;
 use uuid::Uuid;
+use workspace_manager::WorkspaceManager;
 
 use crate::{
     DeploymentImpl,
@@ -66,7 +70,7 @@ pub async fn create_workspace(
     Ok(ResponseJson(ApiResponse::success(workspace)))
 }
 
-fn normalize_prompt(prompt: &str) -> Option<String> {
+pub(crate) fn normalize_prompt(prompt: &str) -> Option<String> {
     let trimmed = prompt.trim();
     if trimmed.is_empty() {
         None
@@ -364,6 +368,177 @@ pub async fn create_and_start_workspace(
     )))
 }
 
+/// Name of a race: the requested name, or the first line of the prompt.
+fn race_name(name: Option<&str>, prompt: &str) -> String {
+    const MAX_LEN: usize = 60;
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| prompt.lines().next().unwrap_or_default().trim());
+    name.chars().take(MAX_LEN).collect()
+}
+
+/// Stop and delete the workspaces of a race that failed to start, then the
+/// race itself.
+async fn discard_race(deployment: &DeploymentImpl, race_id: Uuid, workspace_ids: &[Uuid]) {
+    let pool = &deployment.db().pool;
+    for workspace_id in workspace_ids {
+        let workspace = match Workspace::find_by_id(pool, *workspace_id).await {
+            Ok(Some(workspace)) => workspace,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("Failed to load race attempt {workspace_id} to discard it: {e}");
+                continue;
+            }
+        };
+        deployment.container().try_stop(&workspace, true).await;
+        // Starting may have created the worktrees since `workspace` was loaded
+        let workspace = match Workspace::find_by_id(pool, *workspace_id).await {
+            Ok(Some(workspace)) => workspace,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("Failed to reload workspace {workspace_id}: {e}");
+                workspace
+            }
+        };
+        let result = async {
+            let managed_workspace = deployment
+                .workspace_manager()
+                .load_managed_workspace(workspace)
+                .await?;
+            let deletion_context = managed_workspace.prepare_deletion_context().await?;
+            managed_workspace.delete_record().await?;
+            Ok::<_, sqlx::Error>(deletion_context)
+        }
+        .await;
+        match result {
+            Ok(deletion_context) => {
+                WorkspaceManager::spawn_workspace_deletion_cleanup(deletion_context, true)
+            }
+            Err(e) => tracing::error!(
+                "Failed to delete workspace {workspace_id} of a race that failed to start: {e}"
+            ),
+        }
+    }
+    if let Err(e) = Race::delete(pool, race_id).await {
+        tracing::error!("Failed to delete race {race_id} that failed to start: {e}");
+    }
+}
+
+/// Fan one prompt out to several executors. Every executor gets its own
+/// workspace (and so its own worktrees) on the same repositories; the
+/// workspaces are grouped as a race so they can be compared.
+pub async fn create_race(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateRaceRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateRaceResponse>>, ApiError> {
+    let CreateRaceRequest {
+        name,
+        repos,
+        prompt,
+        executor_profile_ids,
+        test_script,
+    } = payload;
+
+    let prompt = normalize_prompt(&prompt).ok_or_else(|| {
+        ApiError::BadRequest("A race prompt is required. Provide a non-empty `prompt`.".to_string())
+    })?;
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+    if executor_profile_ids.len() < 2 {
+        return Err(
+            RaceError::Invalid("a race needs at least two executor profiles".to_string()).into(),
+        );
+    }
+    let executor_configs = ExecutorConfigs::get_cached();
+    for profile_id in &executor_profile_ids {
+        if executor_configs.get_coding_agent(profile_id).is_none() {
+            return Err(
+                RaceError::Invalid(format!("unknown executor profile '{profile_id}'")).into(),
+            );
+        }
+    }
+    let test_script = test_script
+        .map(|script| script.trim().to_string())
+        .filter(|script| !script.is_empty());
+
+    let pool = &deployment.db().pool;
+    let name = race_name(name.as_deref(), &prompt);
+    let race = Race::create(pool, &name, &prompt, test_script.as_deref()).await?;
+
+    // Every attempt is created before any of them starts, and a failure
+    // discards the whole race instead of leaving some attempts running
+    let mut attempts = Vec::with_capacity(executor_profile_ids.len());
+    let mut workspaces = Vec::with_capacity(executor_profile_ids.len());
+    let mut workspace_ids = Vec::with_capacity(executor_profile_ids.len());
+    let started = async {
+        for (position, profile_id) in executor_profile_ids.into_iter().enumerate() {
+            let workspace_name = format!("{name} ({profile_id})");
+            let workspace = create_workspace_record(&deployment, Some(workspace_name)).await?;
+            workspace_ids.push(workspace.id);
+            let mut managed_workspace = deployment
+                .workspace_manager()
+                .load_managed_workspace(workspace)
+                .await?;
+            for repo in &repos {
+                managed_workspace
+                    .add_repository(repo, deployment.git())
+                    .await
+                    .map_err(ApiError::from)?;
+            }
+            let workspace = managed_workspace.workspace.clone();
+            attempts.push(
+                RaceAttempt::create(pool, race.id, workspace.id, position as i64, &profile_id)
+                    .await?,
+            );
+            workspaces.push(workspace);
+        }
+        for (workspace, attempt) in workspaces.iter().zip(&attempts) {
+            deployment
+                .container()
+                .start_workspace(
+                    workspace,
+                    ExecutorConfig::from(attempt.executor_profile_id.0.clone()),
+                    prompt.clone(),
+                )
+                .await?;
+            tracing::info!(
+                "Started race {} attempt in workspace {}",
+                race.id,
+                workspace.id
+            );
+        }
+        Ok::<_, ApiError>(())
+    }
+    .await;
+    if let Err(e) = started {
+        discard_race(&deployment, race.id, &workspace_ids).await;
+        return Err(e);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "race_created",
+            serde_json::json!({
+                "race_id": race.id.to_string(),
+                "executors": attempts
+                    .iter()
+                    .map(|attempt| attempt.executor_profile_id.0.to_string())
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(CreateRaceResponse {
+        race,
+        attempts,
+        workspaces,
+    })))
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
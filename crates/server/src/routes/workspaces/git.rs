@@ -5,25 +5,44 @@ use std::{
 
 use axum::{
     Extension, Json, Router,
-    extract::State,
+    extract::{Query, State},
     response::{IntoResponse, Json as ResponseJson},
     routing::{get, post},
 };
 use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    conflict_resolution::ConflictResolution,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     repo::{Repo, RepoError},
+    session::{CreateSession, Session},
     workspace::Workspace,
     workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use git::{ConflictOp, GitCliError, GitServiceError, MergeStrategy};
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
+    },
+    profile::ExecutorConfig,
+};
+use git::{
+    CommitSignature, ConflictCommits, ConflictOp, DiffHunk, DiffSelection, GitCliError,
+    GitServiceError, MergeStrategy,
+};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, diff_stream, remote_sync};
+use services::services::{
+    container::ContainerService, diff_stream, remote_sync, repo::commit_signing,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use super::streams::{DiffStreamQuery, stream_workspace_diff_ws};
+use super::{
+    streams::{DiffStreamQuery, stream_workspace_diff_ws},
+    verification,
+};
 use crate::{DeploymentImpl, error::ApiError, middleware::signed_ws::SignedWsUpgrade};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -43,6 +62,54 @@ pub struct ContinueRebaseRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+    /// Session to run the agent in. Defaults to the workspace's latest session.
+    pub session_id: Option<Uuid>,
+    /// Defaults to the executor the session last ran with
+    pub executor_config: Option<ExecutorConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct FileHunksQuery {
+    pub repo_id: Uuid,
+    pub path: String,
+    /// Hunks of the unstaged changes (worktree against the index) instead of
+    /// the changes against the target branch shown in the diff stream
+    #[serde(default)]
+    pub unstaged: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct RevertChangesRequest {
+    pub repo_id: Uuid,
+    /// Files and hunks of the diff against the target branch to undo
+    pub files: Vec<DiffSelection>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct StageChangesRequest {
+    pub repo_id: Uuid,
+    /// Files and hunks of the unstaged changes to stage
+    pub files: Vec<DiffSelection>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CommitChangesRequest {
+    pub repo_id: Uuid,
+    pub message: String,
+    /// Files and hunks of the unstaged changes to stage before committing.
+    /// Only what is already staged is committed when omitted.
+    #[serde(default)]
+    pub files: Option<Vec<DiffSelection>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CommitChangesResponse {
+    pub commit_sha: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -92,6 +159,10 @@ pub struct BranchStatus {
     pub conflict_op: Option<ConflictOp>,
     pub conflicted_files: Vec<String>,
     pub is_target_remote: bool,
+    /// How the signature of the HEAD commit verifies locally
+    pub head_signature: Option<CommitSignature>,
+    /// Commits ahead of the target branch that carry no signature
+    pub unsigned_commits: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -147,25 +218,14 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/rebase", post(rebase_workspace))
         .route("/rebase/continue", post(continue_workspace_rebase))
         .route("/conflicts/abort", post(abort_workspace_conflicts))
+        .route("/conflicts/resolve", post(resolve_workspace_conflicts))
+        .route("/conflicts/resolutions", get(get_conflict_resolutions))
         .route("/target-branch", axum::routing::put(change_target_branch))
         .route("/branch", axum::routing::put(rename_branch))
-}
-
-async fn resolve_vibe_kanban_identifier(
-    deployment: &DeploymentImpl,
-    local_workspace_id: Uuid,
-) -> String {
-    if let Ok(client) = deployment.remote_client()
-        && let Ok(remote_ws) = client.get_workspace_by_local_id(local_workspace_id).await
-        && let Some(issue_id) = remote_ws.issue_id
-        && let Ok(issue) = client.get_issue(issue_id).await
-    {
-        if !issue.simple_id.is_empty() {
-            return issue.simple_id;
-        }
-        return issue_id.to_string();
-    }
-    local_workspace_id.to_string()
+        .route("/changes/hunks", get(get_file_hunks))
+        .route("/changes/revert", post(revert_changes))
+        .route("/changes/stage", post(stage_changes))
+        .route("/changes/commit", post(commit_changes))
 }
 
 #[axum::debug_handler]
@@ -231,10 +291,13 @@ pub async fn merge_workspace(
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
+    verification::ensure_verified(&deployment, &workspace, &repo, &worktree_path).await?;
 
     let workspace_label = workspace.name.as_deref().unwrap_or(&workspace.branch);
-    let vk_id = resolve_vibe_kanban_identifier(&deployment, workspace.id).await;
+    let vk_id =
+        remote_sync::vibe_kanban_identifier(deployment.remote_client().ok().as_ref(), workspace.id)
+            .await;
     let commit_message = format!("{} (vibe-kanban {})", workspace_label, vk_id);
 
     let strategy = request.strategy.unwrap_or_default();
@@ -249,6 +312,7 @@ pub async fn merge_workspace(
         let branch = workspace.branch.clone();
         let target_branch = workspace_repo.target_branch.clone();
         let commit_message = commit_message.clone();
+        let signing = commit_signing(&repo);
         tokio::task::spawn_blocking(move || {
             git.merge_changes(
                 &repo_path,
@@ -257,6 +321,7 @@ pub async fn merge_workspace(
                 &target_branch,
                 &commit_message,
                 strategy,
+                signing.as_ref(),
             )
         })
         .await
@@ -508,6 +573,16 @@ pub async fn get_workspace_branch_status(
             (Some(a), Some(b))
         };
 
+        let head_signature = deployment
+            .git()
+            .get_head_signature(&worktree_path)
+            .ok()
+            .flatten();
+        let unsigned_commits = deployment
+            .git()
+            .count_unsigned_commits(&worktree_path, &target_branch)
+            .ok();
+
         let (remote_ahead, remote_behind) = if let Some(Merge::Pr(PrMerge {
             pr_info:
                 PullRequestInfo {
@@ -546,6 +621,8 @@ pub async fn get_workspace_branch_status(
                 conflict_op,
                 conflicted_files,
                 is_target_remote,
+                head_signature,
+                unsigned_commits,
             },
         });
     }
@@ -804,6 +881,7 @@ pub async fn rebase_workspace(
         &new_base_branch,
         &old_base_branch,
         &workspace.branch.clone(),
+        commit_signing(&repo).as_ref(),
     );
     if let Err(e) = result {
         return match e {
@@ -867,6 +945,230 @@ pub async fn abort_workspace_conflicts(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Instructions for an agent resolving the conflicts of a stuck operation
+fn resolve_conflicts_prompt(
+    repo_name: &str,
+    branch: &str,
+    target_branch: &str,
+    op: &ConflictOp,
+    commits: &ConflictCommits,
+    conflicted_files: &[String],
+) -> String {
+    let (operation, ours, theirs) = match op {
+        ConflictOp::Rebase => (
+            format!("A rebase of `{branch}` onto `{target_branch}`"),
+            format!("the `{target_branch}` commits being rebased onto"),
+            format!("the `{branch}` commit being replayed"),
+        ),
+        ConflictOp::Merge => (
+            format!("A merge into `{branch}`"),
+            format!("the `{branch}` branch"),
+            "the commit being merged in".to_string(),
+        ),
+        ConflictOp::CherryPick => (
+            format!("A cherry-pick onto `{branch}`"),
+            format!("the `{branch}` branch"),
+            "the commit being cherry-picked".to_string(),
+        ),
+        ConflictOp::Revert => (
+            format!("A revert on `{branch}`"),
+            format!("the `{branch}` branch"),
+            "the commit being reverted".to_string(),
+        ),
+    };
+    let subject = |subject: &Option<String>| {
+        subject
+            .as_deref()
+            .map(|subject| format!("\"{subject}\""))
+            .unwrap_or_else(|| "unknown commit".to_string())
+    };
+    let files = conflicted_files
+        .iter()
+        .map(|file| format!("- {file}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{operation} stopped with conflicts in the `{repo_name}` repository.\n\n\
+         Ours (HEAD, {ours}): {}\n\
+         Theirs ({theirs}): {}\n\n\
+         Files with conflicts:\n{files}\n\n\
+         Resolve each file by keeping the intent of both sides, and remove every conflict \
+         marker (`<<<<<<<`, `=======`, `>>>>>>>`). Do not continue, commit or abort the \
+         operation yourself; it is continued automatically once no conflict markers remain.",
+        subject(&commits.ours),
+        subject(&commits.theirs),
+    )
+}
+
+/// Start a coding agent run that resolves the conflicts of the operation in
+/// progress in one repo. Once the run finishes without leaving conflict
+/// markers, the files are staged and the operation is continued.
+#[axum::debug_handler]
+pub async fn resolve_workspace_conflicts(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ConflictResolution>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+
+    let git = deployment.git();
+    let Some(op) = git.detect_conflict_op(&worktree_path)? else {
+        return Err(ApiError::BadRequest(
+            "No rebase, merge, cherry-pick or revert is in progress".to_string(),
+        ));
+    };
+    let conflicted_files = git.get_conflicted_files(&worktree_path)?;
+    if conflicted_files.is_empty() {
+        return Err(ApiError::BadRequest(
+            "There are no conflicted files to resolve".to_string(),
+        ));
+    }
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before resolving conflicts".to_string(),
+        ));
+    }
+
+    let session = match payload.session_id {
+        Some(session_id) => Session::find_by_id(pool, session_id)
+            .await?
+            .filter(|session| session.workspace_id == workspace.id)
+            .ok_or_else(|| {
+                ApiError::BadRequest("Session not found in this workspace".to_string())
+            })?,
+        None => match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: None,
+                        name: Some("Resolve conflicts".to_string()),
+                        host_id: None,
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        },
+    };
+
+    let latest_profile =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+    let executor_config = match (payload.executor_config, latest_profile) {
+        (Some(config), Some(profile)) if config.executor != profile.executor => {
+            return Err(ApiError::BadRequest(format!(
+                "Session runs {}, not {}",
+                profile.executor, config.executor
+            )));
+        }
+        (Some(config), _) => config,
+        (None, Some(profile)) => ExecutorConfig::from(profile),
+        (None, None) => {
+            return Err(ApiError::BadRequest(
+                "An executor_config is required for a session without coding agent runs"
+                    .to_string(),
+            ));
+        }
+    };
+    if session.executor.is_none() {
+        Session::update_executor(pool, session.id, &executor_config.executor.to_string()).await?;
+    }
+
+    let commits = git.conflict_commits(&worktree_path, &op);
+    let prompt = resolve_conflicts_prompt(
+        &repo.name,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+        &op,
+        &commits,
+        &conflicted_files,
+    );
+
+    let working_dir = session
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+    let action_type = match CodingAgentTurn::find_latest_session_info(pool, session.id).await? {
+        Some(info) => ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+            prompt,
+            reset_to_message_id: info.reset_to_message_id(),
+            session_id: info.session_id,
+            executor_config,
+            working_dir,
+        }),
+        None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt,
+            executor_config,
+            working_dir,
+        }),
+    };
+    // No cleanup script: it would run while the operation is still stopped
+    let action = ExecutorAction::new(action_type, None);
+
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+    let resolution = ConflictResolution::create(
+        pool,
+        workspace.id,
+        repo.id,
+        execution_process.id,
+        op.as_str(),
+        &conflicted_files,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_conflicts_resolve_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "op": op.as_str(),
+                "conflicted_files": conflicted_files.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(resolution)))
+}
+
+pub async fn get_conflict_resolutions(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ConflictResolution>>>, ApiError> {
+    let resolutions =
+        ConflictResolution::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(resolutions)))
+}
+
 #[axum::debug_handler]
 pub async fn continue_workspace_rebase(
     Extension(workspace): Extension<Workspace>,
@@ -886,7 +1188,200 @@ pub async fn continue_workspace_rebase(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    deployment.git().continue_rebase(&worktree_path)?;
+    deployment
+        .git()
+        .continue_rebase(&worktree_path, commit_signing(&repo).as_ref())?;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
+
+/// The repo, its link to the workspace and its worktree. Repositories that
+/// aren't part of the workspace are not found.
+async fn workspace_worktree(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_id: Uuid,
+) -> Result<(Repo, WorkspaceRepo, PathBuf), ApiError> {
+    let pool = &deployment.db().pool;
+    let workspace_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    Ok((repo, workspace_repo, worktree_path))
+}
+
+/// Reject an empty selection, including a file with an empty list of hunks,
+/// which would otherwise act on the whole file
+fn validate_selection(files: &[DiffSelection]) -> Result<(), ApiError> {
+    if files.is_empty() {
+        return Err(ApiError::BadRequest("No files selected".to_string()));
+    }
+    if let Some(file) = files
+        .iter()
+        .find(|file| file.hunks.as_ref().is_some_and(Vec::is_empty))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "No hunks selected in {}",
+            file.path
+        )));
+    }
+    Ok(())
+}
+
+/// Reject changes to the worktree while an agent or script may be writing to it
+async fn ensure_worktree_idle(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<(), ApiError> {
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before changing the worktree".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_file_hunks(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<FileHunksQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DiffHunk>>>, ApiError> {
+    let (repo, workspace_repo, worktree_path) =
+        workspace_worktree(&deployment, &workspace, query.repo_id).await?;
+    let git = deployment.git();
+
+    let hunks = if query.unstaged {
+        git.get_file_hunks(&worktree_path, None, &query.path)?
+    } else {
+        let base_commit =
+            git.get_base_commit(&repo.path, &workspace.branch, &workspace_repo.target_branch)?;
+        git.get_file_hunks(&worktree_path, Some(&base_commit), &query.path)?
+    };
+
+    Ok(ResponseJson(ApiResponse::success(hunks)))
+}
+
+/// Undo files or hunks of the diff against the target branch. The worktree is
+/// changed and the index left alone, so reverting committed changes leaves
+/// uncommitted changes to commit.
+pub async fn revert_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertChangesRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    validate_selection(&payload.files)?;
+    ensure_worktree_idle(&deployment, &workspace).await?;
+    let (repo, workspace_repo, worktree_path) =
+        workspace_worktree(&deployment, &workspace, payload.repo_id).await?;
+
+    let git = deployment.git();
+    let base_commit =
+        git.get_base_commit(&repo.path, &workspace.branch, &workspace_repo.target_branch)?;
+    git.revert_changes(&worktree_path, &base_commit, &payload.files)?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn stage_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<StageChangesRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    validate_selection(&payload.files)?;
+    ensure_worktree_idle(&deployment, &workspace).await?;
+    let (_, _, worktree_path) =
+        workspace_worktree(&deployment, &workspace, payload.repo_id).await?;
+
+    deployment
+        .git()
+        .stage_changes(&worktree_path, &payload.files)?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Commit a selection of the unstaged changes, or what is already staged,
+/// with a custom message
+pub async fn commit_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CommitChangesRequest>,
+) -> Result<ResponseJson<ApiResponse<CommitChangesResponse>>, ApiError> {
+    let message = payload.message.trim();
+    if message.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Commit message cannot be empty".to_string(),
+        ));
+    }
+    if let Some(files) = payload.files.as_deref().filter(|files| !files.is_empty()) {
+        validate_selection(files)?;
+    }
+    ensure_worktree_idle(&deployment, &workspace).await?;
+    let (repo, _, worktree_path) =
+        workspace_worktree(&deployment, &workspace, payload.repo_id).await?;
+
+    let git = deployment.git();
+    if let Some(op) = git.detect_conflict_op(&worktree_path)? {
+        return Err(ApiError::Conflict(format!(
+            "A {} is in progress; continue or abort it before committing",
+            op.as_str().replace('_', "-")
+        )));
+    }
+    if let Some(files) = payload.files.as_deref().filter(|files| !files.is_empty()) {
+        git.stage_changes(&worktree_path, files)?;
+    }
+    if !git.commit_staged(&worktree_path, message, commit_signing(&repo).as_ref())? {
+        return Err(ApiError::BadRequest(
+            "No staged changes to commit".to_string(),
+        ));
+    }
+    let commit_sha = git.get_head_info(&worktree_path)?.oid;
+
+    Ok(ResponseJson(ApiResponse::success(CommitChangesResponse {
+        commit_sha,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use git::DiffHunkId;
+
+    use super::*;
+
+    fn selection(path: &str, hunks: Option<Vec<DiffHunkId>>) -> DiffSelection {
+        DiffSelection {
+            path: path.to_string(),
+            hunks,
+        }
+    }
+
+    #[test]
+    fn validate_selection_rejects_an_empty_list_of_hunks() {
+        let hunk = DiffHunkId {
+            old_start: 1,
+            new_start: 1,
+        };
+        assert!(validate_selection(&[selection("a.rs", None)]).is_ok());
+        assert!(validate_selection(&[selection("a.rs", Some(vec![hunk]))]).is_ok());
+
+        assert!(matches!(
+            validate_selection(&[]),
+            Err(ApiError::BadRequest(_))
+        ));
+        assert!(matches!(
+            validate_selection(&[selection("a.rs", None), selection("b.rs", Some(vec![]))]),
+            Err(ApiError::BadRequest(message)) if message.contains("b.rs")
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{Extension, Json, Router, extract::State, response::Json as ResponseJson, routing::get};
+use db::models::{
+    merge::{Merge, MergeStatus},
+    merge_queue::{MergeQueueEntry, MergeQueueError, MergeQueueStatus},
+    repo::{Repo, RepoError},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use super::git::MergeWorkspaceRequest;
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/",
+        get(get_workspace_merge_queue_entries).post(enqueue_workspace_merge),
+    )
+}
+
+pub async fn get_workspace_merge_queue_entries(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<MergeQueueEntry>>>, ApiError> {
+    let entries =
+        MergeQueueEntry::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Queue a repository of the workspace for merging into its target branch.
+/// The merge queue merges it once everything queued ahead of it is done.
+pub async fn enqueue_workspace_merge(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<MergeWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<MergeQueueEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, request.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?;
+    if merges
+        .iter()
+        .any(|m| matches!(m, Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open)))
+    {
+        return Err(MergeQueueError::Invalid(
+            "a pull request is open for this repository".to_string(),
+        )
+        .into());
+    }
+    if merges.iter().any(|m| matches!(m, Merge::Direct(_))) {
+        return Err(MergeQueueError::Invalid(
+            "this workspace has already been merged into the target branch".to_string(),
+        )
+        .into());
+    }
+    if deployment
+        .git()
+        .is_remote_branch(&repo.path, &workspace_repo.target_branch)?
+    {
+        return Err(MergeQueueError::Invalid(
+            "the target is a remote branch; create a pull request instead".to_string(),
+        )
+        .into());
+    }
+    let already_queued = MergeQueueEntry::find_by_workspace_id(pool, workspace.id)
+        .await?
+        .iter()
+        .any(|entry| {
+            entry.repo_id == repo.id
+                && !matches!(
+                    entry.status,
+                    MergeQueueStatus::Merged | MergeQueueStatus::Cancelled
+                )
+        });
+    if already_queued {
+        return Err(MergeQueueError::Invalid(
+            "this repository is already in the merge queue".to_string(),
+        )
+        .into());
+    }
+
+    let strategy = request.strategy.unwrap_or_default();
+    let entry = MergeQueueEntry::create(
+        pool,
+        workspace.id,
+        repo.id,
+        &workspace_repo.target_branch,
+        strategy.as_str(),
+    )
+    .await?;
+    deployment.trigger_merge_queue();
+
+    deployment
+        .track_if_analytics_allowed(
+            "merge_queued",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "strategy": strategy.as_str(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
@@ -8,9 +8,12 @@ pub mod gh_cli_setup;
 pub mod git;
 pub mod integration;
 pub mod links;
+pub mod merge_queue;
 pub mod pr;
 pub mod repos;
+pub mod stack;
 pub mod streams;
+pub mod verification;
 pub mod workspace_summary;
 
 use axum::{
@@ -30,12 +33,23 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
                 .delete(core::delete_workspace),
         )
         .route("/messages/first", get(core::get_first_user_message))
+        .route("/approvals", get(core::get_workspace_approvals))
+        .route("/usage", get(core::get_workspace_usage))
+        .route(
+            "/budget",
+            get(core::get_workspace_budget)
+                .put(core::set_workspace_budget)
+                .delete(core::delete_workspace_budget),
+        )
         .route("/seen", axum::routing::put(core::mark_seen))
         .nest("/git", git::router())
         .nest("/execution", execution::router())
         .nest("/integration", integration::router())
         .nest("/repos", repos::router())
         .nest("/pull-requests", pr::router())
+        .nest("/stack", stack::router())
+        .nest("/merge-queue", merge_queue::router())
+        .nest("/verification", verification::router())
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -47,6 +61,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             get(core::get_workspaces).post(create::create_workspace),
         )
         .route("/start", post(create::create_and_start_workspace))
+        .route("/race", post(create::create_race))
         .route("/from-pr", post(pr::create_workspace_from_pr))
         .route("/streams/ws", get(streams::stream_workspaces_ws))
         .route(
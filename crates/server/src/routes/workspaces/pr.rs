@@ -11,16 +11,21 @@ use db::models::{
     coding_agent_turn::CodingAgentTurn,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason},
     merge::{Merge, MergeStatus, PrMerge},
+    pr_comment_fix::PrCommentFix,
     pull_request::PullRequest,
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+    workspace_stack::WorkspaceStack,
 };
 use deployment::Deployment;
-use executors::actions::{
-    ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
-    coding_agent_initial::CodingAgentInitialRequest,
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
+    },
+    profile::ExecutorConfig,
 };
 use git::{GitCliError, GitRemote, GitServiceError};
 use git_host::{
@@ -29,13 +34,14 @@ use git_host::{
 };
 use serde::{Deserialize, Serialize};
 use services::services::{
-    config::DEFAULT_PR_DESCRIPTION_PROMPT, container::ContainerService, remote_sync,
+    config::DEFAULT_PR_DESCRIPTION_PROMPT, container::ContainerService, pr_comments, remote_sync,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 use workspace_manager::WorkspaceManager;
 
+use super::verification;
 use crate::{DeploymentImpl, error::ApiError};
 
 /// Returns the first open PR merge for the given list of merges, or `None` if
@@ -103,6 +109,21 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct AddressPrCommentsRequest {
+    pub repo_id: Uuid,
+    /// Inline review comments to address; when omitted, the first comment of
+    /// every unresolved thread that no fix addressed yet
+    #[serde(default)]
+    pub comment_ids: Option<Vec<i64>>,
+    /// Session to send the follow-up in; the latest session when omitted
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+    /// Also mark each replied thread resolved, where the host supports it
+    #[serde(default)]
+    pub resolve_threads: bool,
+}
+
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
@@ -168,8 +189,8 @@ async fn trigger_pr_description_follow_up(
     let action_type = if let Some(info) = latest_session_info {
         ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
             prompt,
+            reset_to_message_id: info.reset_to_message_id(),
             session_id: info.session_id,
-            reset_to_message_id: None,
             executor_config: executors::profile::ExecutorConfig::from(executor_profile_id.clone()),
             working_dir: working_dir.clone(),
         })
@@ -225,10 +246,25 @@ pub async fn create_pr(
         .await?;
     let workspace_path = PathBuf::from(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
+    verification::ensure_verified(&deployment, &workspace, &repo, &worktree_path).await?;
 
     let git = deployment.git();
     let push_remote = git.resolve_remote_for_branch(&repo_path, &workspace.branch)?;
 
+    // A stacked workspace targets its parent's branch, which has to be on the
+    // remote before a PR can be opened against it
+    if let Some(parent_id) = WorkspaceStack::find_parent_id(pool, workspace.id).await?
+        && let Some(parent) = Workspace::find_by_id(pool, parent_id).await?
+        && parent.branch == target_branch
+        && let Err(e) = git.push_to_remote(&worktree_path, &parent.branch, false)
+    {
+        tracing::warn!(
+            "Failed to push parent branch '{}' of stacked workspace: {}",
+            parent.branch,
+            e
+        );
+    }
+
     // Try to get the remote from the branch name (works for remote-tracking branches like "upstream/main").
     // Fall back to push_remote if the branch doesn't exist locally or isn't a remote-tracking branch.
     let (target_remote, base_branch) =
@@ -281,7 +317,12 @@ pub async fn create_pr(
         }
     }
 
-    let git_host = match GitHostService::from_url(&target_remote.url) {
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(repo.github_access);
+    let git_host = match GitHostService::from_url(&target_remote.url, &git_host_options) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -429,7 +470,12 @@ pub async fn attach_existing_pr(
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(repo.github_access);
+    let git_host = match GitHostService::from_url(&remote.url, &git_host_options) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -586,7 +632,12 @@ pub async fn get_pr_comments(
     let git = deployment.git();
     let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(repo.github_access);
+    let git_host = match GitHostService::from_url(&remote.url, &git_host_options) {
         Ok(host) => host,
         Err(GitHostError::CliNotInstalled { provider }) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -628,6 +679,193 @@ pub async fn get_pr_comments(
     }
 }
 
+/// Send inline review comments of the repo's open pull request to the
+/// session's coding agent. Once the follow-up and the cleanup scripts finish,
+/// the branch is pushed and each comment is replied to on the host. Without
+/// `comment_ids`, the first comment of every unresolved thread is sent unless
+/// a fix already addressed it.
+pub async fn address_pr_comments(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AddressPrCommentsRequest>,
+) -> Result<ResponseJson<ApiResponse<PrCommentFix>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, repo.id).await?;
+    let Some(pr_merge) = find_open_pr_merge(merges) else {
+        return Err(ApiError::BadRequest(
+            "No open pull request is attached to this repo".to_string(),
+        ));
+    };
+    let pr_number = pr_merge.pr_info.number;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before addressing review comments".to_string(),
+        ));
+    }
+
+    let session = match payload.session_id {
+        Some(session_id) => Session::find_by_id(pool, session_id)
+            .await?
+            .filter(|session| session.workspace_id == workspace.id)
+            .ok_or_else(|| {
+                ApiError::BadRequest("Session not found in this workspace".to_string())
+            })?,
+        None => Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest("The workspace has no session to follow up in".to_string())
+            })?,
+    };
+    let Some(info) = CodingAgentTurn::find_latest_session_info(pool, session.id).await? else {
+        return Err(ApiError::BadRequest(
+            "The session has no coding agent turn to follow up on".to_string(),
+        ));
+    };
+    let Some(profile) =
+        ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?
+    else {
+        return Err(ApiError::BadRequest(
+            "The session has no coding agent run to follow up on".to_string(),
+        ));
+    };
+
+    let git = deployment.git();
+    let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
+    let git_host_options = deployment
+        .config()
+        .read()
+        .await
+        .git_host_options(repo.github_access);
+    let git_host = GitHostService::from_url(&remote.url, &git_host_options)?;
+    // Inline review comments with their ids
+    let review_comments: Vec<(i64, UnifiedPrComment)> = git_host
+        .get_pr_comments(&repo.path, &remote.url, pr_number)
+        .await?
+        .into_iter()
+        .filter_map(|comment| match comment {
+            UnifiedPrComment::Review { id, .. } => Some((id, comment)),
+            UnifiedPrComment::General { .. } => None,
+        })
+        .collect();
+
+    let selected: Vec<(i64, UnifiedPrComment)> = match payload.comment_ids {
+        Some(ids) => {
+            if let Some(missing) = ids.iter().find(|id| {
+                !review_comments
+                    .iter()
+                    .any(|(comment_id, _)| comment_id == *id)
+            }) {
+                return Err(ApiError::BadRequest(format!(
+                    "Review comment {missing} was not found on pull request #{pr_number}"
+                )));
+            }
+            review_comments
+                .into_iter()
+                .filter(|(comment_id, _)| ids.contains(comment_id))
+                .collect()
+        }
+        None => {
+            let addressed =
+                PrCommentFix::find_addressed_comment_ids(pool, workspace.id, repo.id, pr_number)
+                    .await?;
+            // Replies, including ours, and resolved threads need no fix
+            let open_threads = git_host
+                .get_open_review_threads(&repo.path, &remote.url, pr_number)
+                .await?;
+            pr_comments::unaddressed_review_comments(
+                review_comments,
+                open_threads.as_deref(),
+                &addressed,
+            )
+        }
+    };
+    if selected.is_empty() {
+        return Err(ApiError::BadRequest(
+            "There are no unresolved review comments to address".to_string(),
+        ));
+    }
+    let (comment_ids, comments): (Vec<i64>, Vec<UnifiedPrComment>) = selected.into_iter().unzip();
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let prompt = pr_comments::review_comments_prompt(&repo.name, pr_number, &comments);
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let working_dir = session
+        .agent_working_dir
+        .as_ref()
+        .filter(|dir| !dir.is_empty())
+        .cloned();
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+            prompt,
+            reset_to_message_id: info.reset_to_message_id(),
+            session_id: info.session_id,
+            executor_config: ExecutorConfig::from(profile),
+            working_dir,
+        }),
+        cleanup_action.map(Box::new),
+    );
+
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+    let fix = PrCommentFix::create(
+        pool,
+        workspace.id,
+        repo.id,
+        session.id,
+        execution_process.id,
+        pr_number,
+        &comment_ids,
+        payload.resolve_threads,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_comments_address_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "comments": comment_ids.len(),
+                "resolve_threads": payload.resolve_threads,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(fix)))
+}
+
+/// The workspace's review comment fixes, newest first
+pub async fn get_pr_comment_fixes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PrCommentFix>>>, ApiError> {
+    let fixes = PrCommentFix::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(fixes)))
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateWorkspaceFromPrBody {
     pub repo_id: Uuid,
@@ -863,6 +1101,8 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/", post(create_pr))
         .route("/attach", post(attach_existing_pr))
         .route("/comments", get(get_pr_comments))
+        .route("/comments/address", post(address_pr_comments))
+        .route("/comments/fixes", get(get_pr_comment_fixes))
 }
 
 #[cfg(test)]
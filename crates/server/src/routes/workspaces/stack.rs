@@ -0,0 +1,378 @@
+use std::{collections::HashMap, path::Path};
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    requests::{CreateAndStartWorkspaceResponse, WorkspaceRepoInput},
+    workspace::Workspace,
+    workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+    workspace_stack::WorkspaceStack,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use git::{ConflictOp, GitServiceError};
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, repo::commit_signing};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use super::create::{create_workspace_record, normalize_prompt};
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Start a workspace on top of this workspace's branch
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct CreateStackedWorkspaceRequest {
+    pub name: Option<String>,
+    pub executor_config: ExecutorConfig,
+    pub prompt: String,
+    /// Repositories of the parent to include. Defaults to all of them.
+    pub repo_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct StackedWorkspace {
+    pub workspace: Workspace,
+    pub parent_workspace_id: Option<Uuid>,
+    /// 0 for the bottom of the stack, which branches off a repository branch
+    pub depth: u32,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResponse {
+    /// Workspaces that were rebased, bottom of the stack first
+    pub restacked_workspace_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum RestackError {
+    MergeConflicts {
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        message: String,
+        op: ConflictOp,
+        conflicted_files: Vec<String>,
+        target_branch: String,
+    },
+    RebaseInProgress {
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    },
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_workspace_stack).post(create_stacked_workspace))
+        .route("/restack", post(restack_workspace))
+}
+
+/// Every workspace in the stack containing `workspace_id`, parents before
+/// their children
+async fn load_stack(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Vec<StackedWorkspace>, sqlx::Error> {
+    let mut stack = Vec::new();
+    for entry in WorkspaceStack::find_stack(pool, workspace_id).await? {
+        let Some(workspace) = Workspace::find_by_id(pool, entry.workspace_id).await? else {
+            continue;
+        };
+        stack.push(StackedWorkspace {
+            workspace,
+            parent_workspace_id: entry.parent_workspace_id,
+            depth: entry.depth,
+        });
+    }
+    Ok(stack)
+}
+
+/// The commit a stacked branch is replayed from when it's rebased onto
+/// `target_branch`. `parent` is the parent workspace's branch and its tip
+/// before this restack rebased it, if it was.
+fn restack_old_base(
+    parent: Option<(&str, Option<&str>)>,
+    target_branch: &str,
+    parent_branch_exists: impl FnOnce(&str) -> Result<bool, GitServiceError>,
+) -> Result<String, GitServiceError> {
+    Ok(match parent {
+        Some((parent_branch, old_tip)) if parent_branch == target_branch => {
+            old_tip.unwrap_or(parent_branch).to_string()
+        }
+        // The parent merged and this branch now targets what it merged into;
+        // only the commits on top of the parent are replayed
+        Some((parent_branch, _)) if parent_branch_exists(parent_branch)? => {
+            parent_branch.to_string()
+        }
+        _ => target_branch.to_string(),
+    })
+}
+
+pub async fn get_workspace_stack(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<StackedWorkspace>>>, ApiError> {
+    let stack = load_stack(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(stack)))
+}
+
+/// Create and start a workspace whose repositories target this workspace's
+/// branch instead of a repository branch.
+pub async fn create_stacked_workspace(
+    Extension(parent): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateStackedWorkspaceRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateAndStartWorkspaceResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let prompt = normalize_prompt(&payload.prompt).ok_or_else(|| {
+        ApiError::BadRequest(
+            "A workspace prompt is required. Provide a non-empty `prompt`.".to_string(),
+        )
+    })?;
+
+    let parent_repos = WorkspaceRepo::find_by_workspace_id(pool, parent.id).await?;
+    if let Some(repo_ids) = &payload.repo_ids
+        && let Some(missing) = repo_ids
+            .iter()
+            .find(|id| !parent_repos.iter().any(|repo| repo.repo_id == **id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {missing} is not part of the parent workspace"
+        )));
+    }
+    let repos: Vec<WorkspaceRepoInput> = parent_repos
+        .iter()
+        .filter(|repo| {
+            payload
+                .repo_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&repo.repo_id))
+        })
+        .map(|repo| WorkspaceRepoInput {
+            repo_id: repo.repo_id,
+            target_branch: parent.branch.clone(),
+        })
+        .collect();
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let mut managed_workspace = deployment
+        .workspace_manager()
+        .load_managed_workspace(create_workspace_record(&deployment, payload.name).await?)
+        .await?;
+    for repo in &repos {
+        managed_workspace
+            .add_repository(repo, deployment.git())
+            .await
+            .map_err(ApiError::from)?;
+    }
+    let workspace = managed_workspace.workspace.clone();
+    WorkspaceStack::create(pool, workspace.id, parent.id).await?;
+    tracing::info!(
+        "Created workspace {} stacked on workspace {}",
+        workspace.id,
+        parent.id
+    );
+
+    let execution_process = deployment
+        .container()
+        .start_workspace(&workspace, payload.executor_config.clone(), prompt)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "stacked_workspace_created",
+            serde_json::json!({
+                "executor": &payload.executor_config.executor,
+                "workspace_id": workspace.id.to_string(),
+                "parent_workspace_id": parent.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CreateAndStartWorkspaceResponse {
+            workspace,
+            execution_process,
+            link_warning: None,
+        },
+    )))
+}
+
+/// Rebase every workspace in the stack onto its target, bottom first, so each
+/// branch sits on the latest commit of the one below it. Stops at the first
+/// conflict, leaving that worktree mid-rebase.
+pub async fn restack_workspace(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RestackResponse, RestackError>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let git = deployment.git();
+    let stack = load_stack(pool, workspace.id).await?;
+    let branches: HashMap<Uuid, &str> = stack
+        .iter()
+        .map(|entry| (entry.workspace.id, entry.workspace.branch.as_str()))
+        .collect();
+
+    // Tip of each (workspace, repo) branch before it was rebased. A child is
+    // replayed from there so its parent's old commits aren't applied twice.
+    let mut old_tips: HashMap<(Uuid, Uuid), String> = HashMap::new();
+    let mut restacked_workspace_ids = Vec::new();
+
+    for entry in &stack {
+        let stacked = &entry.workspace;
+        if stacked.archived {
+            continue;
+        }
+        let container_ref = deployment
+            .container()
+            .ensure_container_exists(stacked)
+            .await?;
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, stacked.id).await?;
+
+        for RepoWithTargetBranch {
+            repo,
+            target_branch,
+        } in repos
+        {
+            let parent = entry
+                .parent_workspace_id
+                .and_then(|id| branches.get(&id).map(|branch| (id, *branch)));
+            let old_base = restack_old_base(
+                parent.map(|(parent_id, parent_branch)| {
+                    (
+                        parent_branch,
+                        old_tips.get(&(parent_id, repo.id)).map(String::as_str),
+                    )
+                }),
+                &target_branch,
+                |branch| git.check_branch_exists(&repo.path, branch),
+            )?;
+
+            let worktree_path = Path::new(&container_ref).join(&repo.name);
+            old_tips.insert(
+                (stacked.id, repo.id),
+                git.get_branch_oid(&repo.path, &stacked.branch)?,
+            );
+
+            match git.rebase_branch(
+                &repo.path,
+                &worktree_path,
+                &target_branch,
+                &old_base,
+                &stacked.branch,
+                commit_signing(&repo).as_ref(),
+            ) {
+                Ok(_) => {}
+                Err(GitServiceError::MergeConflicts {
+                    message,
+                    conflicted_files,
+                }) => {
+                    let op = git
+                        .detect_conflict_op(&worktree_path)
+                        .ok()
+                        .flatten()
+                        .unwrap_or(ConflictOp::Rebase);
+                    return Ok(ResponseJson(ApiResponse::error_with_data(
+                        RestackError::MergeConflicts {
+                            workspace_id: stacked.id,
+                            repo_id: repo.id,
+                            message,
+                            op,
+                            conflicted_files,
+                            target_branch,
+                        },
+                    )));
+                }
+                Err(GitServiceError::RebaseInProgress) => {
+                    return Ok(ResponseJson(ApiResponse::error_with_data(
+                        RestackError::RebaseInProgress {
+                            workspace_id: stacked.id,
+                            repo_id: repo.id,
+                        },
+                    )));
+                }
+                Err(e) => return Err(ApiError::GitService(e)),
+            }
+        }
+        restacked_workspace_ids.push(stacked.id);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_stack_restacked",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "restacked": restacked_workspace_ids.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RestackResponse {
+        restacked_workspace_ids,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use git::GitServiceError;
+
+    use super::restack_old_base;
+
+    fn branch_exists(found: bool) -> impl FnOnce(&str) -> Result<bool, GitServiceError> {
+        move |_| Ok(found)
+    }
+
+    #[test]
+    fn restack_old_base_replays_from_the_parents_old_tip() {
+        let old_base = restack_old_base(Some(("vk/parent", Some("abc123"))), "vk/parent", |_| {
+            panic!("the parent branch isn't looked up while it's the target")
+        })
+        .unwrap();
+        assert_eq!(old_base, "abc123");
+
+        // The parent wasn't rebased, e.g. it's archived
+        let old_base =
+            restack_old_base(Some(("vk/parent", None)), "vk/parent", branch_exists(true)).unwrap();
+        assert_eq!(old_base, "vk/parent");
+    }
+
+    #[test]
+    fn restack_old_base_skips_the_commits_of_a_merged_parent() {
+        let old_base = restack_old_base(
+            Some(("vk/parent", Some("abc123"))),
+            "main",
+            branch_exists(true),
+        )
+        .unwrap();
+        assert_eq!(old_base, "vk/parent");
+
+        // The merged parent's branch was deleted
+        let old_base = restack_old_base(
+            Some(("vk/parent", Some("abc123"))),
+            "main",
+            branch_exists(false),
+        )
+        .unwrap();
+        assert_eq!(old_base, "main");
+    }
+
+    #[test]
+    fn restack_old_base_uses_the_target_at_the_bottom_of_the_stack() {
+        let old_base = restack_old_base(None, "main", |_| {
+            panic!("there's no parent branch to look up")
+        })
+        .unwrap();
+        assert_eq!(old_base, "main");
+    }
+}
@@ -0,0 +1,291 @@
+use std::path::Path;
+
+use axum::{Extension, Json, Router, extract::State, response::Json as ResponseJson, routing::get};
+use db::models::{
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::Repo,
+    session::{CreateSession, Session},
+    verification::{
+        VerificationError, VerificationResult, VerificationResultStatus, VerificationRun,
+        VerificationRunWithResults, VerificationStep, VerificationTrigger,
+    },
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// How many runs the workspace's verification history returns
+const RECENT_RUNS_LIMIT: i64 = 20;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RunVerificationRequest {
+    /// Verify only these repos of the workspace; all of them when omitted
+    #[serde(default)]
+    pub repo_ids: Option<Vec<Uuid>>,
+    /// Session to run the steps in; the latest session when omitted
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(
+        "/",
+        get(get_verification_runs).post(run_workspace_verification),
+    )
+}
+
+/// The workspace's latest verification runs with their results, newest first
+pub async fn get_verification_runs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<VerificationRunWithResults>>>, ApiError> {
+    let runs = VerificationRun::find_recent_by_workspace_id(
+        &deployment.db().pool,
+        workspace.id,
+        RECENT_RUNS_LIMIT,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Run the verification steps of the workspace's repos now
+pub async fn run_workspace_verification(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RunVerificationRequest>,
+) -> Result<ResponseJson<ApiResponse<VerificationRunWithResults>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Wait for the running process to finish before verifying".to_string(),
+        ));
+    }
+    if VerificationRun::find_running_by_workspace_id(pool, workspace.id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::Conflict(
+            "A verification run is already in progress".to_string(),
+        ));
+    }
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let session = match payload.session_id {
+        Some(session_id) => Session::find_by_id(pool, session_id)
+            .await?
+            .filter(|session| session.workspace_id == workspace.id)
+            .ok_or_else(|| {
+                ApiError::BadRequest("Session not found in this workspace".to_string())
+            })?,
+        None => match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(session) => session,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: None,
+                        name: None,
+                        host_id: None,
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        },
+    };
+
+    let run = deployment
+        .container()
+        .start_verification(
+            &workspace,
+            &session,
+            payload.repo_ids.as_deref(),
+            VerificationTrigger::Manual,
+        )
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("No verification steps are configured".to_string()))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "verification_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "steps": run.results.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(run)))
+}
+
+/// Refuse to go on unless every current verification step of `repo` passed
+/// on the commit the repo's worktree is at. Repos without
+/// `require_verification`, or without steps, always pass.
+pub async fn ensure_verified(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    worktree_path: &Path,
+) -> Result<(), ApiError> {
+    if !repo.require_verification {
+        return Ok(());
+    }
+    let pool = &deployment.db().pool;
+    let steps = VerificationStep::find_by_repo_id(pool, repo.id).await?;
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    let results = VerificationResult::find_latest_for_repo(pool, workspace.id, repo.id).await?;
+    let passed = passed_results(&repo.name, &steps, &results)?;
+
+    let head = deployment.git().get_head_info(worktree_path)?.oid;
+    for result in passed {
+        let Some(execution_process_id) = result.execution_process_id else {
+            continue;
+        };
+        let verified_commit =
+            ExecutionProcessRepoState::find_by_execution_process_id(pool, execution_process_id)
+                .await?
+                .into_iter()
+                .find(|state| state.repo_id == repo.id)
+                .and_then(|state| state.before_head_commit);
+        if verified_commit.as_deref() != Some(head.as_str()) {
+            return Err(VerificationError::Required(format!(
+                "{} has changed since it was last verified",
+                repo.name
+            ))
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// The passed result of each of `steps` in `results`, the latest run of the
+/// repo. Steps added or edited since that run have no result there, so they
+/// count as unverified.
+fn passed_results<'a>(
+    repo_name: &str,
+    steps: &[VerificationStep],
+    results: &'a [VerificationResult],
+) -> Result<Vec<&'a VerificationResult>, VerificationError> {
+    if results.is_empty() {
+        return Err(VerificationError::Required(format!(
+            "{repo_name} has not been verified yet"
+        )));
+    }
+    steps
+        .iter()
+        .map(|step| {
+            let result = results
+                .iter()
+                .find(|result| result.step_id == Some(step.id))
+                .ok_or_else(|| {
+                    VerificationError::Required(format!(
+                        "'{}' of {repo_name} has not been verified yet",
+                        step.name
+                    ))
+                })?;
+            if result.status != VerificationResultStatus::Passed {
+                return Err(VerificationError::Required(format!(
+                    "'{}' of {repo_name} did not pass",
+                    result.name
+                )));
+            }
+            Ok(result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn step(name: &str) -> VerificationStep {
+        VerificationStep {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::nil(),
+            name: name.to_string(),
+            script: format!("npm run {name}"),
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn result(step: &VerificationStep, status: VerificationResultStatus) -> VerificationResult {
+        VerificationResult {
+            id: Uuid::new_v4(),
+            run_id: Uuid::nil(),
+            repo_id: Uuid::nil(),
+            step_id: Some(step.id),
+            name: step.name.clone(),
+            position: step.position,
+            execution_process_id: None,
+            status,
+            exit_code: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn every_current_step_must_have_passed() {
+        let lint = step("lint");
+        let test = step("test");
+        let results = [
+            result(&lint, VerificationResultStatus::Passed),
+            result(&test, VerificationResultStatus::Passed),
+        ];
+        let passed = passed_results("app", &[lint.clone(), test.clone()], &results).unwrap();
+        assert_eq!(passed.len(), 2);
+
+        let results = [
+            result(&lint, VerificationResultStatus::Passed),
+            result(&test, VerificationResultStatus::Failed),
+        ];
+        assert!(passed_results("app", &[lint, test], &results).is_err());
+    }
+
+    #[test]
+    fn step_added_after_the_latest_run_is_unverified() {
+        let lint = step("lint");
+        let results = [result(&lint, VerificationResultStatus::Passed)];
+
+        let err = passed_results("app", &[lint, step("typecheck")], &results).unwrap_err();
+        assert!(err.to_string().contains("'typecheck' of app"));
+    }
+
+    #[test]
+    fn results_of_removed_steps_are_ignored() {
+        let lint = step("lint");
+        let removed = step("e2e");
+        let results = [
+            result(&lint, VerificationResultStatus::Passed),
+            VerificationResult {
+                step_id: None,
+                ..result(&removed, VerificationResultStatus::Failed)
+            },
+        ];
+
+        assert!(passed_results("app", &[lint], &results).is_ok());
+    }
+}
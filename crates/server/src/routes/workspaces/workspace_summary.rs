@@ -6,6 +6,7 @@ use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessStatus},
     merge::MergeStatus,
     pull_request::PullRequest,
+    verification::{VerificationRun, VerificationRunStatus},
     workspace::Workspace,
 };
 use deployment::Deployment;
@@ -53,6 +54,8 @@ pub struct WorkspaceSummary {
     pub pr_url: Option<String>,
     /// Host ID of the session that ran the latest execution process
     pub latest_host_id: Option<String>,
+    /// Status of the latest verification run (if the workspace was verified)
+    pub verification_status: Option<VerificationRunStatus>,
 }
 
 /// Response containing summaries for requested workspaces
@@ -114,7 +117,11 @@ pub async fn get_workspace_summaries(
     // 6. Get PR status for each workspace
     let pr_statuses = PullRequest::get_latest_for_workspaces(pool, archived).await?;
 
-    // 7. Compute diff stats for each workspace (in parallel)
+    // 7. Get the latest verification status for each workspace
+    let verification_statuses =
+        VerificationRun::latest_status_for_workspaces(pool, archived).await?;
+
+    // 8. Compute diff stats for each workspace (in parallel)
     let diff_futures: Vec<_> = workspaces
         .iter()
         .map(|ws| {
@@ -136,7 +143,7 @@ pub async fn get_workspace_summaries(
         futures_util::future::join_all(diff_futures).await;
     let diff_stats: HashMap<Uuid, DiffStats> = diff_results.into_iter().flatten().collect();
 
-    // 8. Assemble response
+    // 9. Assemble response
     let summaries: Vec<WorkspaceSummary> = workspaces
         .iter()
         .map(|ws| {
@@ -162,6 +169,7 @@ pub async fn get_workspace_summaries(
                 pr_number: pr_statuses.get(&id).map(|pr| pr.pr_number),
                 pr_url: pr_statuses.get(&id).map(|pr| pr.pr_url.clone()),
                 latest_host_id: latest.and_then(|p| p.host_id.clone()),
+                verification_status: verification_statuses.get(&id).copied(),
             }
         })
         .collect();
@@ -199,6 +199,10 @@ pub async fn initialize_deployment(
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    // Coding agents queued before the last shutdown
+    if let Err(e) = deployment.container().start_queued_executions().await {
+        tracing::error!("Failed to start queued executions: {}", e);
+    }
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -1,9 +1,14 @@
 pub mod executor_approvals;
+pub mod policy;
 
 use std::{collections::HashSet, sync::Arc, time::Duration as StdDuration};
 
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use db::{
+    DBService,
+    models::approval::{Approval, ApprovalResponder, CreateApproval},
+};
 use executors::logs::AskUserQuestionItem;
 use futures::{
     StreamExt,
@@ -54,6 +59,7 @@ pub struct Approvals {
     pending: Arc<DashMap<String, PendingApproval>>,
     completed: Arc<DashMap<String, ApprovalOutcome>>,
     patches_tx: broadcast::Sender<Patch>,
+    db: DBService,
 }
 
 #[derive(Debug, Error)]
@@ -70,22 +76,67 @@ pub enum ApprovalError {
     Custom(#[from] anyhow::Error),
 }
 
-impl Default for Approvals {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl Approvals {
-    pub fn new() -> Self {
+    pub fn new(db: DBService) -> Self {
         let (patches_tx, _) = broadcast::channel(64);
         Self {
             pending: Arc::new(DashMap::new()),
             completed: Arc::new(DashMap::new()),
             patches_tx,
+            db,
+        }
+    }
+
+    /// Write the request to the approval history. History is best-effort:
+    /// a failed write is logged and never blocks the executor.
+    async fn record_request(
+        &self,
+        request: &ApprovalRequest,
+        is_question: bool,
+        questions: Option<Vec<AskUserQuestionItem>>,
+    ) {
+        let data = CreateApproval {
+            id: request.id.clone(),
+            execution_process_id: request.execution_process_id,
+            tool_name: request.tool_name.clone(),
+            is_question,
+            questions,
+            requested_at: request.created_at,
+        };
+        if let Err(e) = Approval::create(&self.db.pool, &data).await {
+            tracing::warn!("Failed to record approval '{}': {}", request.id, e);
         }
     }
 
+    async fn record_resolution(
+        db: &DBService,
+        id: &str,
+        outcome: &ApprovalOutcome,
+        responder: Option<ApprovalResponder>,
+        relay_client_id: Option<Uuid>,
+    ) {
+        if let Err(e) = Approval::resolve(&db.pool, id, outcome, responder, relay_client_id).await {
+            tracing::warn!("Failed to record outcome of approval '{}': {}", id, e);
+        }
+    }
+
+    /// Record a request that an approval rule settled without asking anyone.
+    pub(crate) async fn record_settled(
+        &self,
+        request: &ApprovalRequest,
+        outcome: &ApprovalOutcome,
+    ) {
+        self.record_request(request, false, None).await;
+        Self::record_resolution(
+            &self.db,
+            &request.id,
+            outcome,
+            Some(ApprovalResponder::Policy),
+            None,
+        )
+        .await;
+    }
+
     pub(crate) async fn create_with_waiter(
         &self,
         request: ApprovalRequest,
@@ -120,6 +171,8 @@ impl Approvals {
             questions,
         };
 
+        self.record_request(&request, is_question, pending_approval.questions.clone())
+            .await;
         self.pending.insert(req_id.clone(), pending_approval);
 
         let _ = self
@@ -145,11 +198,15 @@ impl Approvals {
         }
     }
 
+    /// Resolve a pending request. `responder` and `relay_client_id` identify
+    /// the client that answered and are kept in the approval history.
     #[tracing::instrument(skip(self, id, req))]
     pub async fn respond(
         &self,
         id: &str,
         req: ApprovalResponse,
+        responder: ApprovalResponder,
+        relay_client_id: Option<Uuid>,
     ) -> Result<(ApprovalOutcome, ToolContext), ApprovalError> {
         if let Some((_, p)) = self.pending.remove(id) {
             if let Err(e) = Self::validate_approval_response(&req.status, p.is_question) {
@@ -160,6 +217,7 @@ impl Approvals {
             let outcome = req.status.clone();
             self.completed.insert(id.to_string(), outcome.clone());
             let _ = p.response_tx.send(outcome.clone());
+            Self::record_resolution(&self.db, id, &outcome, Some(responder), relay_client_id).await;
 
             let _ =
                 self.patches_tx
@@ -176,7 +234,11 @@ impl Approvals {
         } else if self.completed.contains_key(id) {
             Err(ApprovalError::AlreadyCompleted)
         } else {
-            Err(ApprovalError::NotFound)
+            // Requests resolved before a restart are only in the history.
+            match Approval::find_by_id(&self.db.pool, id).await {
+                Ok(Some(_)) => Err(ApprovalError::AlreadyCompleted),
+                _ => Err(ApprovalError::NotFound),
+            }
         }
     }
 
@@ -190,6 +252,7 @@ impl Approvals {
         let pending = self.pending.clone();
         let completed = self.completed.clone();
         let patches_tx = self.patches_tx.clone();
+        let db = self.db.clone();
 
         let timeout_outcome = ApprovalOutcome::TimedOut;
 
@@ -211,6 +274,7 @@ impl Approvals {
             completed.insert(id.clone(), outcome.clone());
 
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
+                Self::record_resolution(&db, &id, &outcome, None, None).await;
                 let _ = patches_tx.send(
                     crate::services::events::patches::approvals_patch::resolved(&id),
                 );
@@ -226,6 +290,7 @@ impl Approvals {
             let outcome = ApprovalOutcome::Denied {
                 reason: Some("Cancelled".to_string()),
             };
+            Self::record_resolution(&self.db, id, &outcome, None, None).await;
             self.completed.insert(id.to_string(), outcome);
             let _ =
                 self.patches_tx
@@ -299,15 +364,35 @@ impl Approvals {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use executors::logs::{AskUserQuestionItem, AskUserQuestionOption};
+    use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
     use utils::approvals::ApprovalRequest;
     use uuid::Uuid;
 
     use super::*;
 
+    /// Approvals backed by a migrated in-memory database. Foreign keys are off
+    /// so requests can reference execution processes that were never created.
+    async fn make_approvals() -> (Approvals, SqlitePool) {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .expect("in-memory sqlite")
+            .foreign_keys(false);
+        let pool = SqlitePool::connect_with(options)
+            .await
+            .expect("in-memory sqlite");
+        sqlx::migrate!("../db/migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let db = DBService { pool: pool.clone() };
+        (Approvals::new(db), pool)
+    }
+
     #[tokio::test]
     async fn approval_info_carries_questions() {
-        let svc = Approvals::new();
+        let (svc, _pool) = make_approvals().await;
         let request = ApprovalRequest::new("AskUserQuestion".to_string(), Uuid::new_v4());
         let questions = vec![AskUserQuestionItem {
             question: "Pick one".to_string(),
@@ -328,4 +413,72 @@ mod tests {
         let q = infos[0].questions.as_ref().unwrap();
         assert_eq!(q[0].header, "pick");
     }
+
+    #[tokio::test]
+    async fn responses_are_recorded_with_their_responder() {
+        let (svc, pool) = make_approvals().await;
+        let execution_process_id = Uuid::new_v4();
+        let request = ApprovalRequest::new("Bash".to_string(), execution_process_id);
+        let id = request.id.clone();
+        let (_, waiter) = svc.create_with_waiter(request, false, None).await.unwrap();
+
+        let pending = Approval::find_by_id(&pool, &id).await.unwrap().unwrap();
+        assert!(pending.outcome.is_none());
+        assert!(pending.resolved_at.is_none());
+
+        let relay_client_id = Uuid::new_v4();
+        svc.respond(
+            &id,
+            ApprovalResponse {
+                execution_process_id,
+                status: ApprovalOutcome::Approved,
+            },
+            ApprovalResponder::Relay,
+            Some(relay_client_id),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(waiter.await, ApprovalOutcome::Approved));
+
+        let resolved = Approval::find_by_id(&pool, &id).await.unwrap().unwrap();
+        assert!(matches!(
+            resolved.outcome.map(|o| o.0),
+            Some(ApprovalOutcome::Approved)
+        ));
+        assert_eq!(resolved.responder, Some(ApprovalResponder::Relay));
+        assert_eq!(resolved.relay_client_id, Some(relay_client_id));
+        assert!(resolved.resolved_at.is_some());
+
+        // A fresh service (as after a restart) still knows it was answered.
+        let restarted = Approvals::new(DBService { pool });
+        let again = restarted
+            .respond(
+                &id,
+                ApprovalResponse {
+                    execution_process_id,
+                    status: ApprovalOutcome::Approved,
+                },
+                ApprovalResponder::Local,
+                None,
+            )
+            .await;
+        assert!(matches!(again, Err(ApprovalError::AlreadyCompleted)));
+    }
+
+    #[tokio::test]
+    async fn cancelled_requests_are_recorded_without_responder() {
+        let (svc, pool) = make_approvals().await;
+        let request = ApprovalRequest::new("Edit".to_string(), Uuid::new_v4());
+        let id = request.id.clone();
+        let (_, _waiter) = svc.create_with_waiter(request, false, None).await.unwrap();
+
+        svc.cancel(&id).await;
+
+        let cancelled = Approval::find_by_id(&pool, &id).await.unwrap().unwrap();
+        assert!(matches!(
+            cancelled.outcome.map(|o| o.0),
+            Some(ApprovalOutcome::Denied { .. })
+        ));
+        assert_eq!(cancelled.responder, None);
+    }
 }
@@ -1,17 +1,24 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use async_trait::async_trait;
-use db::{self, DBService, models::execution_process::ExecutionProcess};
+use db::{
+    self, DBService,
+    models::{approval_rule::ApprovalRule, execution_process::ExecutionProcess},
+};
 use executors::{
-    approvals::{ExecutorApprovalError, ExecutorApprovalService},
-    logs::AskUserQuestionItem,
+    approvals::{ExecutorApprovalError, ExecutorApprovalService, PolicyDecision, ToolApproval},
+    logs::{ActionType, AskUserQuestionItem},
 };
+use futures::FutureExt;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalOutcome, ApprovalRequest, ApprovalStatus, QuestionStatus};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::{Approvals, policy::ApprovalPolicy},
+    notification::NotificationService,
+};
 
 type ApprovalWaiter = futures::future::Shared<futures::future::BoxFuture<'static, ApprovalOutcome>>;
 
@@ -101,6 +108,38 @@ impl ExecutorApprovalBridge {
         Ok(approval_id)
     }
 
+    /// The approval rules of the repositories in this execution's workspace.
+    async fn load_policy(&self) -> Result<ApprovalPolicy, sqlx::Error> {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id).await?;
+        let mut repos = Vec::with_capacity(ctx.repos.len());
+        for repo in ctx.repos {
+            let rules = ApprovalRule::find_by_repo_id(&self.db.pool, repo.id).await?;
+            repos.push((repo.name, rules));
+        }
+        Ok(ApprovalPolicy::new(
+            repos,
+            ctx.workspace.container_ref.as_deref().map(Path::new),
+            ctx.session.agent_working_dir.as_deref(),
+        ))
+    }
+
+    async fn policy_decision(
+        &self,
+        tool_name: &str,
+        actions: &[ActionType],
+    ) -> Option<PolicyDecision> {
+        match self.load_policy().await {
+            Ok(policy) => policy.evaluate_all(tool_name, actions),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load approval rules for execution {}: {e}; asking the user",
+                    self.execution_process_id
+                );
+                None
+            }
+        }
+    }
+
     async fn wait_internal(
         &self,
         approval_id: &str,
@@ -133,8 +172,45 @@ impl ExecutorApprovalBridge {
 
 #[async_trait]
 impl ExecutorApprovalService for ExecutorApprovalBridge {
-    async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError> {
-        self.create_internal(tool_name, false, None).await
+    async fn create_tool_approval(
+        &self,
+        tool_name: &str,
+        actions: &[ActionType],
+    ) -> Result<ToolApproval, ExecutorApprovalError> {
+        if let Some(decision) = self.policy_decision(tool_name, actions).await {
+            tracing::info!(
+                "Approval for '{}' settled by rule for execution {}: {}",
+                tool_name,
+                self.execution_process_id,
+                decision.reason
+            );
+            // Settled without a pending request, so nothing is shown to the
+            // user; the waiter resolves as soon as the executor waits on it.
+            let request = ApprovalRequest::new(tool_name.to_string(), self.execution_process_id);
+            let approval_id = request.id.clone();
+            let outcome = if decision.approved {
+                ApprovalOutcome::Approved
+            } else {
+                ApprovalOutcome::Denied {
+                    reason: Some(decision.reason.clone()),
+                }
+            };
+            self.approvals.record_settled(&request, &outcome).await;
+            self.waiters.lock().await.insert(
+                approval_id.clone(),
+                futures::future::ready(outcome).boxed().shared(),
+            );
+            return Ok(ToolApproval {
+                approval_id,
+                policy_decision: Some(decision),
+            });
+        }
+
+        let approval_id = self.create_internal(tool_name, false, None).await?;
+        Ok(ToolApproval {
+            approval_id,
+            policy_decision: None,
+        })
     }
 
     async fn create_question_approval(
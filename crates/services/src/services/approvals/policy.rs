@@ -0,0 +1,504 @@
+//! Repository approval rules: settle executor tool approvals by tool name,
+//! command regex or edited path before they are forwarded to the user.
+
+use std::path::{Component, Path, PathBuf};
+
+use db::models::approval_rule::{
+    ApprovalDecision, ApprovalRule, ApprovalRuleError, ApprovalRuleInput,
+};
+use executors::{approvals::PolicyDecision, logs::ActionType};
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+
+/// A rule with its command regex and path glob compiled.
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: ApprovalRule,
+    command: Option<Regex>,
+    path: Option<GlobMatcher>,
+}
+
+/// The rules of one repository of the workspace.
+#[derive(Debug, Clone)]
+struct RepoRules {
+    repo_name: String,
+    /// The repository's checkout inside the workspace, used to make edited
+    /// paths repository-relative. `None` when the workspace has no directory.
+    repo_dir: Option<PathBuf>,
+    rules: Vec<CompiledRule>,
+}
+
+/// The approval rules that apply to one execution: those of every repository
+/// in its workspace.
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalPolicy {
+    repos: Vec<RepoRules>,
+    /// Directory the agent runs in; relative tool paths are resolved against it.
+    agent_dir: Option<PathBuf>,
+}
+
+impl ApprovalPolicy {
+    /// `repos` pairs each repository's name with its rules. `workspace_dir`
+    /// is the workspace checkout and `agent_working_dir` the agent's directory
+    /// relative to it.
+    pub fn new(
+        repos: Vec<(String, Vec<ApprovalRule>)>,
+        workspace_dir: Option<&Path>,
+        agent_working_dir: Option<&str>,
+    ) -> Self {
+        let repos = repos
+            .into_iter()
+            .map(|(repo_name, rules)| RepoRules {
+                repo_dir: workspace_dir.map(|dir| dir.join(&repo_name)),
+                rules: rules
+                    .into_iter()
+                    .filter_map(|rule| {
+                        CompiledRule::compile(rule)
+                            .inspect_err(|e| tracing::warn!("Skipping approval rule: {e}"))
+                            .ok()
+                    })
+                    .collect(),
+                repo_name,
+            })
+            .filter(|repo| !repo.rules.is_empty())
+            .collect();
+        let agent_dir = workspace_dir.map(|dir| match agent_working_dir {
+            Some(wd) if !wd.is_empty() => dir.join(wd),
+            _ => dir.to_path_buf(),
+        });
+        Self { repos, agent_dir }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.repos.is_empty()
+    }
+
+    /// Settle a tool approval, or `None` when it should go to the user. Each
+    /// repository's first matching rule votes; deny beats ask beats allow.
+    pub fn evaluate(&self, tool_name: &str, action: Option<&ActionType>) -> Option<PolicyDecision> {
+        let mut allowed = None;
+        let mut ask = false;
+        for repo in &self.repos {
+            let path = action_path(action).and_then(|path| self.repo_relative(path, repo));
+            let Some((index, rule)) = repo
+                .rules
+                .iter()
+                .enumerate()
+                .find(|(_, rule)| rule.matches(tool_name, action, path.as_deref()))
+            else {
+                continue;
+            };
+            let reason = |verb: &str| {
+                format!(
+                    "{verb} `{tool_name}` by approval rule #{} of {} ({})",
+                    index + 1,
+                    repo.repo_name,
+                    rule.describe()
+                )
+            };
+            match rule.rule.decision {
+                ApprovalDecision::Deny => {
+                    return Some(PolicyDecision {
+                        approved: false,
+                        reason: reason("Denied"),
+                    });
+                }
+                ApprovalDecision::Ask => ask = true,
+                ApprovalDecision::Allow if allowed.is_none() => {
+                    allowed = Some(PolicyDecision {
+                        approved: true,
+                        reason: reason("Allowed"),
+                    });
+                }
+                ApprovalDecision::Allow => {}
+            }
+        }
+        if ask { None } else { allowed }
+    }
+
+    /// Settle a tool call that does several things, such as a patch editing
+    /// several files: denied if any of them is, allowed only if all of them
+    /// are.
+    pub fn evaluate_all(&self, tool_name: &str, actions: &[ActionType]) -> Option<PolicyDecision> {
+        if actions.is_empty() {
+            return self.evaluate(tool_name, None);
+        }
+        let mut allowed = None;
+        let mut ask = false;
+        for action in actions {
+            match self.evaluate(tool_name, Some(action)) {
+                Some(decision) if !decision.approved => return Some(decision),
+                Some(decision) => {
+                    allowed.get_or_insert(decision);
+                }
+                None => ask = true,
+            }
+        }
+        if ask { None } else { allowed }
+    }
+
+    /// `path` relative to the repository's checkout, or `None` if it lies
+    /// outside of it.
+    fn repo_relative(&self, path: &str, repo: &RepoRules) -> Option<String> {
+        let path = Path::new(path);
+        let Some(repo_dir) = &repo.repo_dir else {
+            return path
+                .is_relative()
+                .then(|| path.to_string_lossy().to_string());
+        };
+        let absolute = match &self.agent_dir {
+            Some(agent_dir) if path.is_relative() => normalize(&agent_dir.join(path)),
+            _ => normalize(path),
+        };
+        absolute
+            .strip_prefix(repo_dir)
+            .ok()
+            .map(|relative| relative.to_string_lossy().to_string())
+    }
+}
+
+impl CompiledRule {
+    fn compile(rule: ApprovalRule) -> Result<Self, ApprovalRuleError> {
+        let command = rule
+            .command_pattern
+            .as_deref()
+            .map(compile_regex)
+            .transpose()?;
+        let path = rule.path_glob.as_deref().map(compile_glob).transpose()?;
+        Ok(Self {
+            rule,
+            command,
+            path,
+        })
+    }
+
+    /// Every set criterion must match. Command and path criteria only match
+    /// tool calls whose command or edited path the executor reported.
+    fn matches(&self, tool_name: &str, action: Option<&ActionType>, path: Option<&str>) -> bool {
+        if let Some(name) = &self.rule.tool_name
+            && !name.eq_ignore_ascii_case(tool_name)
+        {
+            return false;
+        }
+        if let Some(command_re) = &self.command {
+            match action {
+                Some(ActionType::CommandRun { command, .. }) if command_re.is_match(command) => {}
+                _ => return false,
+            }
+        }
+        if let Some(glob) = &self.path {
+            match (action, path) {
+                (Some(ActionType::FileEdit { .. }), Some(path)) if glob.is_match(path) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn describe(&self) -> String {
+        let mut criteria = Vec::new();
+        if let Some(name) = &self.rule.tool_name {
+            criteria.push(format!("tool is `{name}`"));
+        }
+        if let Some(pattern) = &self.rule.command_pattern {
+            criteria.push(format!("command matches `{pattern}`"));
+        }
+        if let Some(glob) = &self.rule.path_glob {
+            criteria.push(format!("path matches `{glob}`"));
+        }
+        if criteria.is_empty() {
+            "matches every tool call".to_string()
+        } else {
+            criteria.join(", ")
+        }
+    }
+}
+
+/// Reject rules whose regex or glob doesn't compile before they're stored.
+pub fn validate_rules(rules: &[ApprovalRuleInput]) -> Result<(), ApprovalRuleError> {
+    for rule in rules {
+        if let Some(name) = &rule.tool_name
+            && name.trim().is_empty()
+        {
+            return Err(ApprovalRuleError::Invalid(
+                "tool_name must not be empty".to_string(),
+            ));
+        }
+        if let Some(pattern) = &rule.command_pattern {
+            compile_regex(pattern)?;
+        }
+        if let Some(glob) = &rule.path_glob {
+            compile_glob(glob)?;
+        }
+    }
+    Ok(())
+}
+
+fn action_path(action: Option<&ActionType>) -> Option<&str> {
+    match action {
+        Some(ActionType::FileEdit { path, .. }) => Some(path),
+        _ => None,
+    }
+}
+
+/// Resolve `.` and `..` lexically so `src/../.env` can't dodge a glob.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, ApprovalRuleError> {
+    Regex::new(pattern).map_err(|e| {
+        ApprovalRuleError::Invalid(format!("invalid command pattern '{pattern}': {e}"))
+    })
+}
+
+fn compile_glob(pattern: &str) -> Result<GlobMatcher, ApprovalRuleError> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| ApprovalRuleError::Invalid(format!("invalid path glob '{pattern}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::logs::CommandCategory;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn rule(
+        decision: ApprovalDecision,
+        tool_name: Option<&str>,
+        command_pattern: Option<&str>,
+        path_glob: Option<&str>,
+    ) -> ApprovalRule {
+        ApprovalRule {
+            id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            position: 0,
+            decision,
+            tool_name: tool_name.map(str::to_string),
+            command_pattern: command_pattern.map(str::to_string),
+            path_glob: path_glob.map(str::to_string),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn command(command: &str) -> ActionType {
+        ActionType::CommandRun {
+            command: command.to_string(),
+            result: None,
+            category: CommandCategory::from_command(command),
+        }
+    }
+
+    fn edit(path: &str) -> ActionType {
+        ActionType::FileEdit {
+            path: path.to_string(),
+            changes: vec![],
+        }
+    }
+
+    fn policy(repos: Vec<(&str, Vec<ApprovalRule>)>) -> ApprovalPolicy {
+        ApprovalPolicy::new(
+            repos
+                .into_iter()
+                .map(|(name, rules)| (name.to_string(), rules))
+                .collect(),
+            Some(Path::new("/ws")),
+            None,
+        )
+    }
+
+    #[test]
+    fn first_matching_rule_decides() {
+        let policy = policy(vec![(
+            "app",
+            vec![
+                rule(ApprovalDecision::Deny, None, Some(r"\brm\s+-rf\b"), None),
+                rule(
+                    ApprovalDecision::Allow,
+                    Some("bash"),
+                    Some(r"^cargo (test|check)"),
+                    None,
+                ),
+                rule(ApprovalDecision::Ask, Some("bash"), None, None),
+            ],
+        )]);
+
+        let allowed = policy
+            .evaluate("Bash", Some(&command("cargo test --workspace")))
+            .unwrap();
+        assert!(allowed.approved);
+        assert!(allowed.reason.contains("rule #2 of app"));
+
+        let denied = policy
+            .evaluate("Bash", Some(&command("cargo test && rm -rf /")))
+            .unwrap();
+        assert!(!denied.approved);
+
+        assert!(
+            policy
+                .evaluate("Bash", Some(&command("npm publish")))
+                .is_none()
+        );
+        assert!(policy.evaluate("Bash", None).is_none());
+    }
+
+    #[test]
+    fn path_globs_match_repo_relative_paths() {
+        let policy = policy(vec![(
+            "app",
+            vec![
+                rule(ApprovalDecision::Deny, None, None, Some("**/.env*")),
+                rule(ApprovalDecision::Allow, None, None, Some("src/**")),
+            ],
+        )]);
+
+        assert!(
+            policy
+                .evaluate("Edit", Some(&edit("/ws/app/src/lib.rs")))
+                .unwrap()
+                .approved
+        );
+        assert!(
+            !policy
+                .evaluate("Write", Some(&edit("/ws/app/src/../.env")))
+                .unwrap()
+                .approved
+        );
+        assert!(
+            policy
+                .evaluate("Edit", Some(&edit("/ws/app/Cargo.toml")))
+                .is_none()
+        );
+        assert!(
+            policy
+                .evaluate("Edit", Some(&edit("/elsewhere/src/lib.rs")))
+                .is_none()
+        );
+        assert!(policy.evaluate("Bash", Some(&command("ls src/"))).is_none());
+    }
+
+    #[test]
+    fn codex_patches_are_checked_against_every_edited_path() {
+        let policy = policy(vec![(
+            "app",
+            vec![
+                rule(ApprovalDecision::Deny, Some("edit"), None, Some("**/.env*")),
+                rule(ApprovalDecision::Allow, Some("edit"), None, Some("src/**")),
+            ],
+        )]);
+
+        let allowed = policy
+            .evaluate_all(
+                "edit",
+                &[edit("/ws/app/src/lib.rs"), edit("/ws/app/src/main.rs")],
+            )
+            .unwrap();
+        assert!(allowed.approved);
+        assert!(allowed.reason.contains("rule #2 of app"));
+
+        let denied = policy
+            .evaluate_all("edit", &[edit("/ws/app/src/lib.rs"), edit("/ws/app/.env")])
+            .unwrap();
+        assert!(!denied.approved);
+        assert!(denied.reason.contains("rule #1 of app"));
+
+        // One file outside the allowed paths sends the whole patch to the user
+        assert!(
+            policy
+                .evaluate_all(
+                    "edit",
+                    &[edit("/ws/app/src/lib.rs"), edit("/ws/app/README.md")]
+                )
+                .is_none()
+        );
+        // Path rules can't match a patch whose files weren't reported
+        assert!(policy.evaluate_all("edit", &[]).is_none());
+    }
+
+    #[test]
+    fn deny_from_any_repository_wins() {
+        let policy = policy(vec![
+            (
+                "app",
+                vec![rule(ApprovalDecision::Allow, Some("bash"), None, None)],
+            ),
+            (
+                "infra",
+                vec![rule(
+                    ApprovalDecision::Deny,
+                    None,
+                    Some("terraform apply"),
+                    None,
+                )],
+            ),
+        ]);
+
+        assert!(
+            policy
+                .evaluate("bash", Some(&command("ls")))
+                .unwrap()
+                .approved
+        );
+        assert!(
+            !policy
+                .evaluate("bash", Some(&command("terraform apply")))
+                .unwrap()
+                .approved
+        );
+    }
+
+    #[test]
+    fn deny_wins_over_an_earlier_repositorys_ask() {
+        let policy = policy(vec![
+            (
+                "app",
+                vec![rule(ApprovalDecision::Ask, Some("bash"), None, None)],
+            ),
+            (
+                "infra",
+                vec![rule(
+                    ApprovalDecision::Deny,
+                    None,
+                    Some("terraform apply"),
+                    None,
+                )],
+            ),
+        ]);
+
+        assert!(
+            !policy
+                .evaluate("bash", Some(&command("terraform apply")))
+                .unwrap()
+                .approved
+        );
+        assert!(policy.evaluate("bash", Some(&command("ls"))).is_none());
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let input = |command_pattern: Option<&str>, path_glob: Option<&str>| ApprovalRuleInput {
+            decision: ApprovalDecision::Allow,
+            tool_name: None,
+            command_pattern: command_pattern.map(str::to_string),
+            path_glob: path_glob.map(str::to_string),
+        };
+        assert!(validate_rules(&[input(Some("^cargo "), Some("src/**"))]).is_ok());
+        assert!(validate_rules(&[input(Some("(unclosed"), None)]).is_err());
+        assert!(validate_rules(&[input(None, Some("src/[a"))]).is_err());
+    }
+}
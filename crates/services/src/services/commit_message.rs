@@ -0,0 +1,292 @@
+//! Messages for the commits made on behalf of coding agents: a per-repo
+//! template filled from the turn, an optional inferred conventional-commit
+//! type, and trailers naming the agent and session.
+
+/// Variables a commit message template may use
+pub const TEMPLATE_VARIABLES: &[&str] = &["summary", "prompt", "executor", "workspace", "issue"];
+
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Words in a subject line that suggest a conventional-commit type, checked
+/// in order. Anything else is a `feat`.
+const TYPE_KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "fix",
+        &[
+            "fix",
+            "fixes",
+            "fixed",
+            "bug",
+            "bugfix",
+            "crash",
+            "regression",
+        ],
+    ),
+    ("revert", &["revert", "reverts"]),
+    (
+        "docs",
+        &["doc", "docs", "documentation", "readme", "changelog"],
+    ),
+    ("test", &["test", "tests", "testing", "spec", "specs"]),
+    (
+        "refactor",
+        &[
+            "refactor",
+            "refactors",
+            "refactored",
+            "restructure",
+            "cleanup",
+        ],
+    ),
+    (
+        "perf",
+        &["perf", "performance", "optimize", "optimise", "faster"],
+    ),
+    ("ci", &["ci", "workflow", "pipeline"]),
+    (
+        "build",
+        &[
+            "build",
+            "dependency",
+            "dependencies",
+            "deps",
+            "bump",
+            "upgrade",
+        ],
+    ),
+    ("style", &["format", "formatting", "lint", "whitespace"]),
+    ("chore", &["chore", "rename", "config"]),
+];
+
+/// Identity used in `Co-authored-by` trailers; matches the fallback commit
+/// identity of the git service
+const AGENT_EMAIL: &str = "noreply@vibekanban.com";
+
+/// What a commit message template is filled from
+#[derive(Debug, Clone, Default)]
+pub struct CommitMessageContext {
+    /// The agent's final message for the turn
+    pub summary: Option<String>,
+    /// The prompt of the turn
+    pub prompt: Option<String>,
+    /// The coding agent, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    pub workspace_name: Option<String>,
+    /// Simple id of the issue the workspace is linked to
+    pub issue: Option<String>,
+}
+
+impl CommitMessageContext {
+    fn value(&self, variable: &str) -> Option<&str> {
+        let value = match variable {
+            "summary" => self.summary.as_deref(),
+            "prompt" => self.prompt.as_deref().and_then(|p| p.trim().lines().next()),
+            "executor" => self.executor.as_deref(),
+            "workspace" => self.workspace_name.as_deref(),
+            "issue" => self.issue.as_deref(),
+            _ => None,
+        };
+        value.map(str::trim).filter(|v| !v.is_empty())
+    }
+}
+
+/// Check that a template only uses known variables
+pub fn validate_template(template: &str) -> Result<(), String> {
+    for variable in placeholders(template) {
+        if !TEMPLATE_VARIABLES.contains(&variable) {
+            return Err(format!(
+                "Unknown commit message template variable {{{variable}}}. Available: {}",
+                TEMPLATE_VARIABLES
+                    .iter()
+                    .map(|v| format!("{{{v}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Fill `{variable}` placeholders from `ctx`. Lines whose variables all lack
+/// a value are dropped, so optional parts like `Refs: {issue}` disappear.
+pub fn render_template(template: &str, ctx: &CommitMessageContext) -> String {
+    let mut lines = Vec::new();
+    for line in template.lines() {
+        let mut rendered = String::with_capacity(line.len());
+        let mut rest = line;
+        let mut placeholders = 0;
+        let mut filled = 0;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let variable = &rest[start + 1..start + len];
+            rendered.push_str(&rest[..start]);
+            if TEMPLATE_VARIABLES.contains(&variable) {
+                placeholders += 1;
+                if let Some(value) = ctx.value(variable) {
+                    filled += 1;
+                    rendered.push_str(value);
+                }
+            } else {
+                rendered.push_str(&rest[start..=start + len]);
+            }
+            rest = &rest[start + len + 1..];
+        }
+        rendered.push_str(rest);
+        if placeholders > 0 && filled == 0 {
+            continue;
+        }
+        lines.push(rendered.trim_end().to_string());
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Prefix the subject line with a conventional-commit type inferred from it,
+/// unless it already has one
+pub fn apply_conventional_prefix(message: &str) -> String {
+    let subject = message.lines().next().unwrap_or_default();
+    if has_conventional_prefix(subject) {
+        return message.to_string();
+    }
+    with_conventional_type(message, infer_commit_type(message))
+}
+
+/// Prefix the subject line with `commit_type`, lowercasing its first letter
+pub fn with_conventional_type(message: &str, commit_type: &str) -> String {
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    let mut chars = subject.trim().chars();
+    let subject = match chars.next() {
+        // Keep acronyms like "API" as they are
+        Some(first) if !chars.clone().next().is_some_and(char::is_uppercase) => {
+            format!("{}{}", first.to_lowercase(), chars.as_str())
+        }
+        _ => subject.trim().to_string(),
+    };
+    if body.is_empty() {
+        format!("{commit_type}: {subject}")
+    } else {
+        format!("{commit_type}: {subject}\n{body}")
+    }
+}
+
+/// Append `Co-authored-by` and `X-Agent` trailers for the coding agent that
+/// wrote the changes and the session it ran in
+pub fn append_agent_trailers(message: &str, executor: &str, session_id: &str) -> String {
+    format!(
+        "{}\n\nCo-authored-by: {} <{AGENT_EMAIL}>\nX-Agent: {executor}\nX-Agent-Session: {session_id}",
+        message.trim_end(),
+        executor_display_name(executor),
+    )
+}
+
+/// `CLAUDE_CODE` -> `Claude Code`
+fn executor_display_name(executor: &str) -> String {
+    executor
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => format!("{first}{}", chars.as_str().to_lowercase()),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| {
+        let (variable, _) = part.split_once('}')?;
+        (!variable.is_empty()
+            && variable
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .then_some(variable)
+    })
+}
+
+fn has_conventional_prefix(subject: &str) -> bool {
+    let Some((head, _)) = subject.split_once(": ") else {
+        return false;
+    };
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let commit_type = match head.split_once('(') {
+        Some((commit_type, scope)) if scope.ends_with(')') => commit_type,
+        Some(_) => return false,
+        None => head,
+    };
+    CONVENTIONAL_TYPES.contains(&commit_type.to_ascii_lowercase().as_str())
+}
+
+fn infer_commit_type(message: &str) -> &'static str {
+    let subject = message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let words: Vec<&str> = subject
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    TYPE_KEYWORDS
+        .iter()
+        .find(|(_, keywords)| words.iter().any(|w| keywords.contains(w)))
+        .map_or("feat", |(commit_type, _)| commit_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> CommitMessageContext {
+        CommitMessageContext {
+            summary: Some("Add retry to the webhook dispatcher".to_string()),
+            prompt: Some("Make webhooks retry\n\nUse exponential backoff".to_string()),
+            executor: Some("CLAUDE_CODE".to_string()),
+            workspace_name: Some("webhook retries".to_string()),
+            issue: None,
+        }
+    }
+
+    #[test]
+    fn renders_variables_and_drops_lines_left_empty() {
+        let template = "{summary}\n\nPrompt: {prompt}\nRefs: {issue}\nBy {executor} in {workspace}";
+        assert_eq!(
+            render_template(template, &ctx()),
+            "Add retry to the webhook dispatcher\n\nPrompt: Make webhooks retry\n\
+             By CLAUDE_CODE in webhook retries"
+        );
+        assert!(validate_template(template).is_ok());
+        assert!(validate_template("{summary} {author}").is_err());
+    }
+
+    #[test]
+    fn infers_conventional_types_unless_present() {
+        assert_eq!(
+            apply_conventional_prefix("Handle crash when the queue is empty"),
+            "fix: handle crash when the queue is empty"
+        );
+        assert_eq!(
+            apply_conventional_prefix("Add retry to webhooks\n\nDetails"),
+            "feat: add retry to webhooks\n\nDetails"
+        );
+        assert_eq!(apply_conventional_prefix("API docs"), "docs: API docs");
+        assert_eq!(
+            apply_conventional_prefix("refactor(git)!: drop libgit2 merges"),
+            "refactor(git)!: drop libgit2 merges"
+        );
+    }
+
+    #[test]
+    fn appends_agent_trailers() {
+        assert_eq!(
+            append_agent_trailers("feat: add retry\n", "CLAUDE_CODE", "s-1"),
+            "feat: add retry\n\nCo-authored-by: Claude Code <noreply@vibekanban.com>\n\
+             X-Agent: CLAUDE_CODE\nX-Agent-Session: s-1"
+        );
+    }
+}
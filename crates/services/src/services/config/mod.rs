@@ -50,6 +50,7 @@ pub type AppearanceConfig = versions::v9::AppearanceConfig;
 pub type HostBannerConfig = versions::v9::HostBannerConfig;
 pub type LinksConfig = versions::v9::LinksConfig;
 pub type InputEditorMode = versions::v9::InputEditorMode;
+pub type ConcurrencyConfig = versions::v9::ConcurrencyConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
-use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use db::models::repo::GitHubAccess;
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId, sandbox::SandboxConfig};
+use git_host::{GitHostConfig, GitHostOptions};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub use v8::{
@@ -131,6 +135,35 @@ pub struct AppearanceConfig {
     pub links: LinksConfig,
 }
 
+/// Limits on coding agents running at once. Starts beyond them wait in a
+/// queue until a running agent finishes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct ConcurrencyConfig {
+    /// Coding agents running at once across all executors; unlimited when
+    /// unset
+    #[serde(default)]
+    pub max_coding_agents: Option<u32>,
+    /// Coding agents of one executor running at once
+    #[serde(default)]
+    pub max_per_executor: HashMap<BaseCodingAgent, u32>,
+}
+
+impl ConcurrencyConfig {
+    /// Whether an agent of `executor` may start next to the `running` ones
+    pub fn has_capacity(&self, running: &[BaseCodingAgent], executor: BaseCodingAgent) -> bool {
+        if self
+            .max_coding_agents
+            .is_some_and(|max| running.len() >= max as usize)
+        {
+            return false;
+        }
+        self.max_per_executor.get(&executor).is_none_or(|&max| {
+            running.iter().filter(|agent| **agent == executor).count() < max as usize
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -171,6 +204,15 @@ pub struct Config {
     pub appearance: AppearanceConfig,
     #[serde(default)]
     pub input_editor_mode: InputEditorMode,
+    /// Self-hosted GitLab and Gitea instances, keyed by the host in remote URLs
+    #[serde(default)]
+    pub git_hosts: Vec<GitHostConfig>,
+    /// Bubblewrap jail for coding agents and repository scripts (Linux only)
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    /// Caps on concurrently running coding agents
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
 }
 
 impl Config {
@@ -201,6 +243,9 @@ impl Config {
             host_nickname: old_config.host_nickname,
             appearance: AppearanceConfig::default(),
             input_editor_mode: InputEditorMode::default(),
+            git_hosts: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
         }
     }
 
@@ -208,6 +253,15 @@ impl Config {
         let old_config = v8::Config::from(raw_config.to_string());
         Ok(Self::from_v8_config(old_config))
     }
+
+    /// Provider settings for remotes of a repo with the given GitHub access mode
+    pub fn git_host_options(&self, github_access: GitHubAccess) -> GitHostOptions {
+        GitHostOptions {
+            hosts: self.git_hosts.clone(),
+            github_token: self.github.token(),
+            github_access,
+        }
+    }
 }
 
 impl From<String> for Config {
@@ -265,6 +319,9 @@ impl Default for Config {
             host_nickname: None,
             appearance: AppearanceConfig::default(),
             input_editor_mode: InputEditorMode::default(),
+            git_hosts: Vec::new(),
+            sandbox: SandboxConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
         }
     }
 }
@@ -329,4 +386,25 @@ mod tests {
         assert_eq!(config.config_version, "v9");
         assert_eq!(config.input_editor_mode, InputEditorMode::Wysiwyg);
     }
+
+    #[test]
+    fn test_concurrency_limits_global_and_per_executor() {
+        let concurrency = ConcurrencyConfig {
+            max_coding_agents: Some(3),
+            max_per_executor: HashMap::from([(BaseCodingAgent::ClaudeCode, 1)]),
+        };
+        let running = [BaseCodingAgent::ClaudeCode, BaseCodingAgent::Codex];
+
+        assert!(!concurrency.has_capacity(&running, BaseCodingAgent::ClaudeCode));
+        assert!(concurrency.has_capacity(&running, BaseCodingAgent::Codex));
+        assert!(!concurrency.has_capacity(
+            &[
+                BaseCodingAgent::Codex,
+                BaseCodingAgent::Codex,
+                BaseCodingAgent::Gemini
+            ],
+            BaseCodingAgent::Amp
+        ));
+        assert!(ConcurrencyConfig::default().has_capacity(&running, BaseCodingAgent::ClaudeCode));
+    }
 }
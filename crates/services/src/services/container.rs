@@ -9,6 +9,8 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        approval::Approval,
+        checkpoint::Checkpoint,
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
@@ -17,8 +19,13 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        execution_start_queue::QueuedExecution,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
+        verification::{
+            VerificationResult, VerificationResultStatus, VerificationRun, VerificationRunStatus,
+            VerificationRunWithResults, VerificationStep, VerificationTrigger,
+        },
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
@@ -48,8 +55,12 @@ use git::{GitService, GitServiceError};
 use json_patch::Patch;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinHandle,
+};
 use utils::{
+    approvals::ApprovalOutcome,
     log_msg::LogMsg,
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
@@ -57,7 +68,12 @@ use utils::{
 use uuid::Uuid;
 use worktree_manager::WorktreeError;
 
-use crate::services::{execution_process, notification::NotificationService};
+use crate::services::{
+    config::ConcurrencyConfig,
+    execution_process,
+    notification::NotificationService,
+    start_queue::{self, ClaimedExecution},
+};
 pub type ContainerRef = String;
 
 #[derive(Debug, Error)]
@@ -330,6 +346,15 @@ pub trait ContainerService {
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
         }
+        // Pending approvals died with their executors; close them in the history.
+        let cancelled = ApprovalOutcome::Denied {
+            reason: Some("Cancelled".to_string()),
+        };
+        match Approval::resolve_all_pending(&self.db().pool, &cancelled).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("Marked {} orphaned approvals as cancelled", count),
+            Err(e) => tracing::error!("Failed to close orphaned approvals: {}", e),
+        }
         Ok(())
     }
 
@@ -638,6 +663,83 @@ pub trait ContainerService {
         chained
     }
 
+    /// Chain the verification steps of `repos` into script actions, in repo
+    /// order and then step order, returning the steps with the action.
+    async fn verification_action_for_repos(
+        &self,
+        repos: &[Repo],
+    ) -> Result<Option<(Vec<VerificationStep>, ExecutorAction)>, ContainerError> {
+        let mut steps = Vec::new();
+        for repo in repos {
+            steps.extend(VerificationStep::find_by_repo_id(&self.db().pool, repo.id).await?);
+        }
+
+        let mut chained: Option<ExecutorAction> = None;
+        for step in steps.iter().rev() {
+            let Some(repo) = repos.iter().find(|r| r.id == step.repo_id) else {
+                continue;
+            };
+            chained = Some(ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: step.script.clone(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::VerificationScript,
+                    working_dir: Some(repo.name.clone()),
+                }),
+                chained.map(Box::new),
+            ));
+        }
+
+        Ok(chained.map(|action| (steps, action)))
+    }
+
+    /// Run the verification steps of the workspace's repos, or only of
+    /// `repo_ids` when given, as a chain of scripts in `session`. Returns
+    /// `None` when none of the repos has steps.
+    async fn start_verification(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        repo_ids: Option<&[Uuid]>,
+        trigger: VerificationTrigger,
+    ) -> Result<Option<VerificationRunWithResults>, ContainerError> {
+        let pool = &self.db().pool;
+        if VerificationRun::find_running_by_workspace_id(pool, workspace.id)
+            .await?
+            .is_some()
+        {
+            return Err(ContainerError::Other(anyhow!(
+                "A verification run is already in progress for this workspace"
+            )));
+        }
+
+        let repos: Vec<Repo> = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id)
+            .await?
+            .into_iter()
+            .filter(|repo| repo_ids.is_none_or(|ids| ids.contains(&repo.id)))
+            .collect();
+        let Some((steps, action)) = self.verification_action_for_repos(&repos).await? else {
+            return Ok(None);
+        };
+
+        let run = VerificationRun::create(pool, workspace.id, session.id, trigger, &steps).await?;
+        if let Err(e) = self
+            .start_execution(
+                workspace,
+                session,
+                &action,
+                &ExecutionProcessRunReason::TestScript,
+            )
+            .await
+        {
+            VerificationResult::abandon_run(pool, run.run.id, VerificationRunStatus::Failed)
+                .await?;
+            return Err(e);
+        }
+
+        Ok(Some(run))
+    }
+
     /// Reset a session to a specific process: restore worktrees, stop processes, drop later processes.
     async fn reset_session_to_process(
         &self,
@@ -709,10 +811,112 @@ pub trait ContainerService {
 
         self.try_stop(&workspace, false).await;
         ExecutionProcess::drop_at_and_after(pool, session_id, target_process_id).await?;
+        self.prune_dropped_checkpoints(session_id, &repos, &workspace_dir)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Put every repo of the session's workspace back to the checkpoints taken
+    /// after `target_process_id` and drop the later processes, so the next
+    /// follow-up continues from that turn. If `force_when_dirty` is false and
+    /// any worktree is dirty, nothing is touched and WorktreeDirty is returned.
+    async fn restore_session_to_checkpoint(
+        &self,
+        session_id: Uuid,
+        target_process_id: Uuid,
+        force_when_dirty: bool,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+
+        let checkpoints = Checkpoint::find_by_execution_process_id(pool, target_process_id).await?;
+        if checkpoints.is_empty() || checkpoints.iter().any(|c| c.session_id != session_id) {
+            return Err(ContainerError::Other(anyhow!(
+                "No checkpoint was taken after this process in the session"
+            )));
+        }
+        let session = Session::find_by_id(pool, session_id)
+            .await?
+            .ok_or_else(|| ContainerError::Other(anyhow!("Session not found")))?;
+        let workspace = Workspace::find_by_id(pool, session.workspace_id)
+            .await?
+            .ok_or_else(|| ContainerError::Other(anyhow!("Workspace not found")))?;
+        let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+        let workspace_dir = PathBuf::from(self.ensure_container_exists(&workspace).await?);
+
+        let targets: Vec<(PathBuf, &Checkpoint)> = repos
+            .iter()
+            .filter_map(|repo| {
+                let checkpoint = checkpoints.iter().find(|c| c.repo_id == repo.id)?;
+                Some((workspace_dir.join(&repo.name), checkpoint))
+            })
+            .collect();
+        if !force_when_dirty {
+            // Changes the latest checkpoint already holds are not lost
+            for (worktree_path, checkpoint) in &targets {
+                let latest =
+                    Checkpoint::find_latest_commit(pool, session_id, checkpoint.repo_id).await?;
+                let unsaved = match latest {
+                    Some(commit) => !self.git().worktree_matches_commit(worktree_path, &commit)?,
+                    None => !self.git().is_worktree_clean(worktree_path)?,
+                };
+                if unsaved {
+                    let branch = self
+                        .git()
+                        .get_current_branch(worktree_path)
+                        .unwrap_or_else(|_| workspace.branch.clone());
+                    return Err(GitServiceError::WorktreeDirty(
+                        branch,
+                        "changes made after the checkpoint".to_string(),
+                    )
+                    .into());
+                }
+            }
+        }
+
+        self.try_stop(&workspace, false).await;
+        for (worktree_path, checkpoint) in &targets {
+            self.git().restore_checkpoint(
+                worktree_path,
+                &checkpoint.head_commit,
+                &checkpoint.commit_sha,
+                true,
+            )?;
+        }
+        ExecutionProcess::drop_after(pool, session_id, target_process_id).await?;
+        self.prune_dropped_checkpoints(session_id, &repos, &workspace_dir)
+            .await?;
 
         Ok(())
     }
 
+    /// Delete the checkpoints of turns dropped from a session together with
+    /// their hidden refs, which would otherwise keep the snapshots alive
+    async fn prune_dropped_checkpoints(
+        &self,
+        session_id: Uuid,
+        repos: &[Repo],
+        workspace_dir: &Path,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        for checkpoint in Checkpoint::find_dropped_by_session_id(pool, session_id).await? {
+            if let Some(repo) = repos.iter().find(|r| r.id == checkpoint.repo_id)
+                && let Err(e) = self
+                    .git()
+                    .delete_ref(&workspace_dir.join(&repo.name), &checkpoint.ref_name)
+            {
+                tracing::debug!(
+                    "Failed to delete checkpoint ref {} in repo '{}': {}",
+                    checkpoint.ref_name,
+                    repo.name,
+                    e
+                );
+            }
+            Checkpoint::delete(pool, checkpoint.id).await?;
+        }
+        Ok(())
+    }
+
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
@@ -742,10 +946,78 @@ pub trait ContainerService {
                                     e
                                 );
                             });
+                    } else if process.status == ExecutionProcessStatus::Queued {
+                        self.cancel_queued_execution(&process)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::debug!(
+                                    "Failed to cancel queued execution process {} for workspace {}: {}",
+                                    process.id,
+                                    workspace.id,
+                                    e
+                                );
+                            });
                     }
                 }
             }
         }
+
+        if let Err(e) = self.start_queued_executions().await {
+            tracing::error!("Failed to start queued executions: {}", e);
+        }
+    }
+
+    /// Start queued coding agents while the concurrency limits allow. The
+    /// start queue lock covers claiming an agent, not spawning it.
+    async fn start_queued_executions(&self) -> Result<(), ContainerError> {
+        loop {
+            let claimed = {
+                let _guard = self.start_queue_lock().lock().await;
+                let concurrency = self.concurrency_config().await;
+                start_queue::claim_next(&self.db().pool, &concurrency).await?
+            };
+            let Some(ClaimedExecution {
+                ctx,
+                executor_action,
+            }) = claimed
+            else {
+                return Ok(());
+            };
+
+            let id = ctx.execution_process.id;
+            tracing::info!("Starting queued execution {}", id);
+            // A failed start is recorded on the execution and in its logs.
+            // Only coding agents are queued, never verification steps.
+            if let Err(e) = self
+                .launch_execution(
+                    &ctx.workspace,
+                    &ctx.session,
+                    &ctx.execution_process,
+                    &executor_action,
+                    false,
+                )
+                .await
+            {
+                tracing::error!("Failed to start queued execution {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Take a coding agent out of the start queue before it runs
+    async fn cancel_queued_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError> {
+        let _guard = self.start_queue_lock().lock().await;
+        let pool = &self.db().pool;
+        if !ExecutionProcess::cancel_queued(pool, execution_process.id).await? {
+            return Err(ContainerError::Other(anyhow!(
+                "Execution process {} is not queued",
+                execution_process.id
+            )));
+        }
+        QueuedExecution::delete(pool, execution_process.id).await?;
+        Ok(())
     }
 
     async fn ensure_container_exists(
@@ -792,6 +1064,12 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    async fn concurrency_config(&self) -> ConcurrencyConfig;
+
+    /// Held while deciding whether coding agents start or wait in the start
+    /// queue, so two starts can't both take the last free slot
+    fn start_queue_lock(&self) -> &Mutex<()>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id(task_title);
         let prefix = self.git_branch_prefix().await;
@@ -1174,10 +1452,28 @@ pub trait ContainerService {
                 merge_commit: None,
             });
         }
+
+        // Coding agents beyond the concurrency limits wait in the start queue
+        let start_queue_guard = match run_reason {
+            ExecutionProcessRunReason::CodingAgent => Some(self.start_queue_lock().lock().await),
+            _ => None,
+        };
+        let queued = match (&start_queue_guard, executor_action.base_executor()) {
+            (Some(_), Some(executor)) => !self.concurrency_config().await.has_capacity(
+                &start_queue::running_coding_agents(&self.db().pool, session.id).await?,
+                executor,
+            ),
+            _ => false,
+        };
         let create_execution_process = CreateExecutionProcess {
             session_id: session.id,
             executor_action: executor_action.clone(),
             run_reason: run_reason.clone(),
+            status: if queued {
+                ExecutionProcessStatus::Queued
+            } else {
+                ExecutionProcessStatus::Running
+            },
         };
 
         let execution_process = ExecutionProcess::create(
@@ -1187,20 +1483,8 @@ pub trait ContainerService {
             &repo_states,
         )
         .await?;
-        self.msg_stores()
-            .write()
-            .await
-            .insert(execution_process.id, Arc::new(MsgStore::new()));
-        if *run_reason != ExecutionProcessRunReason::ArchiveScript
-            && let Err(e) = Workspace::set_archived(&self.db().pool, workspace.id, false).await
-        {
-            self.msg_stores()
-                .write()
-                .await
-                .remove(&execution_process.id);
-            return Err(e.into());
-        }
-
+        // The turn must exist before the queue lock is released: the queue
+        // may start the execution as soon as it's enqueued
         if let Some(prompt) = match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(coding_agent_request) => {
                 Some(coding_agent_request.prompt.clone())
@@ -1220,29 +1504,95 @@ pub trait ContainerService {
 
             let coding_agent_turn_id = Uuid::new_v4();
 
-            if let Err(e) = CodingAgentTurn::create(
+            CodingAgentTurn::create(
                 &self.db().pool,
                 &create_coding_agent_turn,
                 coding_agent_turn_id,
             )
-            .await
-            {
-                self.msg_stores()
-                    .write()
+            .await?;
+        }
+
+        if queued {
+            QueuedExecution::create(&self.db().pool, execution_process.id).await?;
+        }
+        drop(start_queue_guard);
+
+        let is_verification_step = matches!(
+            executor_action.typ(),
+            ExecutorActionType::ScriptRequest(request)
+                if request.context == ScriptContext::VerificationScript
+        );
+        if is_verification_step
+            && let Err(e) =
+                VerificationResult::claim_next(&self.db().pool, session.id, execution_process.id)
                     .await
-                    .remove(&execution_process.id);
-                return Err(e.into());
-            }
+        {
+            tracing::error!(
+                "Failed to attach execution {} to its verification step: {}",
+                execution_process.id,
+                e
+            );
+        }
+        if *run_reason != ExecutionProcessRunReason::ArchiveScript {
+            Workspace::set_archived(&self.db().pool, workspace.id, false).await?;
+        }
+
+        if queued {
+            tracing::info!(
+                "Queued execution {} until a coding agent slot is free",
+                execution_process.id
+            );
+            return Ok(execution_process);
         }
 
+        self.launch_execution(
+            workspace,
+            session,
+            &execution_process,
+            executor_action,
+            is_verification_step,
+        )
+        .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn a created execution and stream its logs
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        session: &Session,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        is_verification_step: bool,
+    ) -> Result<(), ContainerError> {
+        self.msg_stores()
+            .write()
+            .await
+            .insert(execution_process.id, Arc::new(MsgStore::new()));
+
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(workspace, execution_process, executor_action)
             .await
         {
             self.msg_stores()
                 .write()
                 .await
                 .remove(&execution_process.id);
+            if is_verification_step
+                && let Err(e) = VerificationResult::finish(
+                    &self.db().pool,
+                    execution_process.id,
+                    VerificationResultStatus::Failed,
+                    None,
+                )
+                .await
+            {
+                tracing::error!(
+                    "Failed to record verification failure for execution {}: {}",
+                    execution_process.id,
+                    e
+                );
+            }
             // Mark process as failed
             if let Err(update_error) = ExecutionProcess::update_completion(
                 &self.db().pool,
@@ -1362,7 +1712,7 @@ pub trait ContainerService {
             execution_process.id,
             session.id,
         );
-        Ok(execution_process)
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1376,6 +1726,11 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            (_, ExecutorActionType::ScriptRequest(request))
+                if request.context == ScriptContext::VerificationScript =>
+            {
+                ExecutionProcessRunReason::TestScript
+            }
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1,5 +1,6 @@
 use db::models::{
-    execution_process::ExecutionProcess, scratch::Scratch, workspace::WorkspaceWithStatus,
+    execution_process::ExecutionProcess, queued_message::QueuedMessage, scratch::Scratch,
+    workspace::WorkspaceWithStatus,
 };
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
@@ -182,3 +183,49 @@ pub mod approvals_patch {
         })])
     }
 }
+
+/// Helper functions for creating follow-up queue patches.
+/// The global event stream carries the whole queue of a session at
+/// `/queued_messages/{session_id}`; per-session streams rewrite it to a
+/// `/queued_messages` snapshot.
+pub mod queued_messages_patch {
+    use super::*;
+
+    const QUEUED_MESSAGES_PATH: &str = "/queued_messages";
+
+    pub fn session_path(session_id: Uuid) -> String {
+        format!(
+            "{}/{}",
+            QUEUED_MESSAGES_PATH,
+            escape_pointer_segment(&session_id.to_string())
+        )
+    }
+
+    fn to_value(messages: &[QueuedMessage]) -> serde_json::Value {
+        serde_json::to_value(messages).expect("Queued message serialization should not fail")
+    }
+
+    /// Full queue of one session, for the global event stream
+    pub fn replace(session_id: Uuid, messages: &[QueuedMessage]) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: session_path(session_id)
+                .try_into()
+                .expect("Queued messages path should be valid"),
+            value: to_value(messages),
+        })])
+    }
+
+    /// Full queue of the streamed session, for per-session streams
+    pub fn snapshot(messages: &[QueuedMessage]) -> Patch {
+        snapshot_from_value(to_value(messages))
+    }
+
+    pub(crate) fn snapshot_from_value(value: serde_json::Value) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: QUEUED_MESSAGES_PATH
+                .try_into()
+                .expect("Queued messages path should be valid"),
+            value,
+        })])
+    }
+}
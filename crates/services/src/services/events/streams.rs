@@ -1,4 +1,7 @@
-use db::models::{execution_process::ExecutionProcess, scratch::Scratch, workspace::Workspace};
+use db::models::{
+    execution_process::ExecutionProcess, queued_message::QueuedMessage, scratch::Scratch,
+    workspace::Workspace,
+};
 use futures::StreamExt;
 use serde_json::json;
 use tokio_stream::wrappers::BroadcastStream;
@@ -7,7 +10,7 @@ use uuid::Uuid;
 
 use super::{
     EventService,
-    patches::execution_process_patch,
+    patches::{execution_process_patch, queued_messages_patch},
     types::{EventPatch, RecordTypes},
 };
 
@@ -22,6 +25,9 @@ impl EventService {
     /// On `BroadcastStreamRecvError::Lagged`, re-emits a fresh snapshot patch
     /// rather than silently dropping it; the client's `applyUpsertPatch`
     /// treats `replace /execution_processes` as a full state reset.
+    ///
+    /// The session's follow-up queue is carried alongside as a
+    /// `replace /queued_messages` array, re-sent whenever the queue changes.
     pub async fn stream_execution_processes_for_session_raw(
         &self,
         session_id: Uuid,
@@ -52,10 +58,13 @@ impl EventService {
             "path": "/execution_processes",
             "value": processes_map
         }]);
-        let initial_msg = LogMsg::JsonPatch(
-            serde_json::from_value(initial_patch)
-                .expect("hardcoded execution-processes patch structure is valid JSON Patch"),
-        );
+        let mut initial_patch: json_patch::Patch = serde_json::from_value(initial_patch)
+            .expect("hardcoded execution-processes patch structure is valid JSON Patch");
+        let queued = QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?;
+        initial_patch
+            .0
+            .extend(queued_messages_patch::snapshot(&queued).0);
+        let initial_msg = LogMsg::JsonPatch(initial_patch);
 
         /// Returns `None` on DB error so a transient DB hiccup during lag-recovery
         /// does not wipe the client's in-memory state to `{}`.
@@ -95,15 +104,27 @@ impl EventService {
                 "path": "/execution_processes",
                 "value": processes_map
             }]);
-            Some(LogMsg::JsonPatch(serde_json::from_value(snapshot).expect(
-                "hardcoded execution-processes patch structure is valid JSON Patch",
-            )))
+            let mut snapshot: json_patch::Patch = serde_json::from_value(snapshot)
+                .expect("hardcoded execution-processes patch structure is valid JSON Patch");
+            match QueuedMessage::find_by_session_id(pool, session_id).await {
+                Ok(queued) => snapshot
+                    .0
+                    .extend(queued_messages_patch::snapshot(&queued).0),
+                Err(e) => tracing::warn!(
+                    session_id = %session_id,
+                    error = %e,
+                    "Failed to load queued messages for resync snapshot"
+                ),
+            }
+            Some(LogMsg::JsonPatch(snapshot))
         }
 
+        let queued_messages_path = queued_messages_patch::session_path(session_id);
         let live_pool = self.db.pool.clone();
         let live = BroadcastStream::new(receiver)
             .then(move |msg_result| {
                 let live_pool = live_pool.clone();
+                let queued_messages_path = queued_messages_path.clone();
                 async move {
                     match msg_result {
                         Ok(LogMsg::JsonPatch(patch)) => {
@@ -150,6 +171,15 @@ impl EventService {
                                         _ => {}
                                     }
                                 }
+                                // Follow-up queue of this session.
+                                else if patch_op.path().as_str() == queued_messages_path
+                                    && let json_patch::PatchOperation::Replace(op) = patch_op
+                                {
+                                    let snapshot = queued_messages_patch::snapshot_from_value(
+                                        op.value.clone(),
+                                    );
+                                    return Some(Ok(LogMsg::JsonPatch(snapshot)));
+                                }
                                 // Legacy EventPatch fallback.
                                 else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
                                     && let Ok(event_patch) =
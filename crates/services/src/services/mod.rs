@@ -1,6 +1,7 @@
 pub mod analytics;
 pub mod approvals;
 pub mod auth;
+pub mod commit_message;
 pub mod config;
 pub mod container;
 pub mod diff_stream;
@@ -13,6 +14,7 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod notification;
 pub mod oauth_credentials;
+pub mod pr_comments;
 pub mod pr_monitor;
 
 #[cfg(feature = "qa-mode")]
@@ -21,4 +23,8 @@ pub mod queued_message;
 pub mod remote_client;
 pub mod remote_sync;
 pub mod repo;
+pub mod schedule;
+pub mod start_queue;
 pub mod webhook_dispatcher;
+pub mod webhook_event;
+pub mod webhook_filter;
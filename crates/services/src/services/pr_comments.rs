@@ -0,0 +1,213 @@
+//! Addressing inline review comments of a pull request with a coding agent
+//! follow-up, then answering them on the git host.
+
+use std::path::Path;
+
+use db::models::{pr_comment_fix::PrCommentFix, repo::Repo};
+use git::{GitService, GitServiceError};
+use git_host::{GitHostError, GitHostOptions, GitHostProvider, GitHostService, UnifiedPrComment};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PrCommentsError {
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error(transparent)]
+    GitHost(#[from] GitHostError),
+}
+
+/// What came of answering the comments of a fix
+#[derive(Debug)]
+pub struct RepliesOutcome {
+    pub replied: i64,
+    pub resolved: i64,
+    /// One message per comment that could not be replied to
+    pub failures: Vec<String>,
+}
+
+/// The inline review comments still to address, by id: those not in
+/// `addressed` that start a thread which isn't resolved. With no thread
+/// information from the host (`open_threads` is `None`) every comment counts.
+pub fn unaddressed_review_comments(
+    review_comments: Vec<(i64, UnifiedPrComment)>,
+    open_threads: Option<&[i64]>,
+    addressed: &[i64],
+) -> Vec<(i64, UnifiedPrComment)> {
+    review_comments
+        .into_iter()
+        .filter(|(id, _)| {
+            !addressed.contains(id) && open_threads.is_none_or(|roots| roots.contains(id))
+        })
+        .collect()
+}
+
+/// A follow-up prompt asking the coding agent to address `comments`, the
+/// inline review comments of pull request `pr_number` on `repo_name`
+pub fn review_comments_prompt(
+    repo_name: &str,
+    pr_number: i64,
+    comments: &[UnifiedPrComment],
+) -> String {
+    let mut prompt = format!(
+        "Reviewers left the following comments on pull request #{pr_number} of the \
+         `{repo_name}` repository. Address each of them with a code change. Paths are \
+         relative to the `{repo_name}` repository.\n"
+    );
+    for (index, comment) in comments.iter().enumerate() {
+        let UnifiedPrComment::Review {
+            author,
+            body,
+            path,
+            line,
+            diff_hunk,
+            ..
+        } = comment
+        else {
+            continue;
+        };
+        let location = match line {
+            Some(line) => format!("{path}:{line}"),
+            None => path.clone(),
+        };
+        prompt.push_str(&format!(
+            "\n## Comment {} — `{location}` (by {author})\n\n{}\n",
+            index + 1,
+            body.trim()
+        ));
+        if let Some(diff_hunk) = diff_hunk.as_deref().filter(|hunk| !hunk.is_empty()) {
+            prompt.push_str(&format!(
+                "\nCode under review:\n```diff\n{diff_hunk}\n```\n"
+            ));
+        }
+    }
+    prompt.push_str(
+        "\nIf a comment asks for something that should not be done, leave the code as it \
+         is for that comment. Do not push; the changes are pushed and each comment is \
+         answered automatically once you are done.",
+    );
+    prompt
+}
+
+/// Push the workspace branch of `repo`, then reply to each review comment of
+/// `fix` with the commit that addressed it and, if the fix asks for it,
+/// resolve the comment's thread
+pub async fn push_and_reply(
+    git: &GitService,
+    git_host_options: &GitHostOptions,
+    fix: &PrCommentFix,
+    repo: &Repo,
+    worktree_path: &Path,
+    branch: &str,
+    target_branch: &str,
+) -> Result<RepliesOutcome, PrCommentsError> {
+    git.push_to_remote(worktree_path, branch, false)?;
+    let head = git.get_head_info(worktree_path)?.oid;
+    let short_head = head.get(..7).unwrap_or(&head);
+
+    let remote = git.resolve_remote_for_branch(&repo.path, target_branch)?;
+    let git_host = GitHostService::from_url(&remote.url, git_host_options)?;
+    let body = format!("Addressed in {short_head}.");
+
+    let mut outcome = RepliesOutcome {
+        replied: 0,
+        resolved: 0,
+        failures: Vec::new(),
+    };
+    for &comment_id in fix.comment_ids.iter() {
+        match git_host
+            .reply_to_review_comment(
+                &repo.path,
+                &remote.url,
+                fix.pr_number,
+                comment_id,
+                &body,
+                fix.resolve_threads,
+            )
+            .await
+        {
+            Ok(resolved) => {
+                outcome.replied += 1;
+                if resolved {
+                    outcome.resolved += 1;
+                }
+            }
+            Err(e) => outcome.failures.push(format!("comment {comment_id}: {e}")),
+        }
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn review_comment(path: &str, line: Option<i64>, diff_hunk: Option<&str>) -> UnifiedPrComment {
+        review_comment_with_id(1, path, line, diff_hunk)
+    }
+
+    fn review_comment_with_id(
+        id: i64,
+        path: &str,
+        line: Option<i64>,
+        diff_hunk: Option<&str>,
+    ) -> UnifiedPrComment {
+        UnifiedPrComment::Review {
+            id,
+            author: "alice".to_string(),
+            author_association: None,
+            body: "Handle the error here\n".to_string(),
+            created_at: Utc::now(),
+            url: None,
+            path: path.to_string(),
+            line,
+            side: None,
+            diff_hunk: diff_hunk.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn prompt_lists_each_comment_with_its_location_and_code() {
+        let prompt = review_comments_prompt(
+            "api",
+            12,
+            &[
+                review_comment("src/lib.rs", Some(40), Some("@@ -1 +1 @@\n-a\n+b")),
+                review_comment("README.md", None, None),
+            ],
+        );
+
+        assert!(prompt.contains("pull request #12 of the `api` repository"));
+        assert!(prompt.contains("## Comment 1 — `src/lib.rs:40` (by alice)"));
+        assert!(prompt.contains("```diff\n@@ -1 +1 @@\n-a\n+b\n```"));
+        assert!(prompt.contains("## Comment 2 — `README.md` (by alice)"));
+        assert_eq!(prompt.matches("```diff").count(), 1);
+    }
+
+    #[test]
+    fn only_unaddressed_roots_of_open_threads_are_addressed() {
+        // 1 opens a thread whose replies are 2 (a reviewer) and 3 (our own
+        // "Addressed in" reply), 4 opens a resolved thread, 5 was addressed
+        let comments: Vec<(i64, UnifiedPrComment)> = (1..=6)
+            .map(|id| (id, review_comment_with_id(id, "src/lib.rs", Some(id), None)))
+            .collect();
+
+        let ids = |selected: Vec<(i64, UnifiedPrComment)>| -> Vec<i64> {
+            selected.into_iter().map(|(id, _)| id).collect()
+        };
+        assert_eq!(
+            ids(unaddressed_review_comments(
+                comments.clone(),
+                Some(&[1, 5, 6]),
+                &[5]
+            )),
+            [1, 6]
+        );
+        // Hosts without threads leave only the addressed comments out
+        assert_eq!(
+            ids(unaddressed_review_comments(comments, None, &[5])),
+            [1, 2, 3, 4, 6]
+        );
+    }
+}
@@ -7,18 +7,24 @@ use db::{
     models::{
         merge::MergeStatus,
         pull_request::PullRequest,
+        repo::Repo,
         workspace::{Workspace, WorkspaceError},
+        workspace_repo::WorkspaceRepo,
     },
 };
 use git_host::{GitHostError, GitHostProvider, GitHostService};
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
-use tokio::{sync::Notify, time::interval};
+use tokio::{
+    sync::{Notify, RwLock},
+    time::interval,
+};
 use tracing::{debug, error, info, warn};
 
 use crate::services::{
     analytics::AnalyticsContext,
+    config::Config,
     container::ContainerService,
     remote_client::{RemoteClient, RemoteClientError},
     remote_sync,
@@ -39,7 +45,9 @@ impl PrMonitorError {
         matches!(
             self,
             PrMonitorError::GitHostError(
-                GitHostError::CliNotInstalled { .. } | GitHostError::NotAGitRepository(_)
+                GitHostError::CliNotInstalled { .. }
+                    | GitHostError::NotAGitRepository(_)
+                    | GitHostError::RateLimited(_)
             )
         )
     }
@@ -48,6 +56,7 @@ impl PrMonitorError {
 /// Service to monitor PRs and update task status when they are merged
 pub struct PrMonitorService<C: ContainerService> {
     db: DBService,
+    config: Arc<RwLock<Config>>,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
     container: C,
@@ -58,6 +67,7 @@ pub struct PrMonitorService<C: ContainerService> {
 impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        config: Arc<RwLock<Config>>,
         analytics: Option<AnalyticsContext>,
         container: C,
         remote_client: Option<RemoteClient>,
@@ -65,6 +75,7 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            config,
             poll_interval: Duration::from_secs(60),
             analytics,
             container,
@@ -127,7 +138,15 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
 
     /// Check the status of a single open PR and handle state changes.
     async fn check_open_pr(&self, pr: &PullRequest) -> Result<(), PrMonitorError> {
-        let git_host = GitHostService::from_url(&pr.pr_url)?;
+        let github_access = match pr.repo_id {
+            Some(repo_id) => Repo::find_by_id(&self.db.pool, repo_id)
+                .await?
+                .map(|repo| repo.github_access)
+                .unwrap_or_default(),
+            None => Default::default(),
+        };
+        let git_host_options = self.config.read().await.git_host_options(github_access);
+        let git_host = GitHostService::from_url(&pr.pr_url, &git_host_options)?;
         let status = git_host.get_pr_status(&pr.pr_url).await?;
 
         debug!(
@@ -154,10 +173,20 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         )
         .await?;
 
-        // If this is a workspace PR and it was merged, try to archive
+        // If this is a workspace PR and it was merged, move stacked PRs off its
+        // branch and try to archive
         if matches!(&status.status, MergeStatus::Merged)
             && let Some(workspace_id) = pr.workspace_id
         {
+            if let Err(e) = self
+                .retarget_stacked_children(workspace_id, pr, &git_host)
+                .await
+            {
+                warn!(
+                    "Failed to retarget workspaces stacked on workspace {}: {}",
+                    workspace_id, e
+                );
+            }
             self.try_archive_workspace(workspace_id, pr.pr_number)
                 .await?;
         }
@@ -167,6 +196,66 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Point workspaces stacked on `workspace_id`, and their open PRs, at the
+    /// branch its PR was merged into
+    async fn retarget_stacked_children(
+        &self,
+        workspace_id: uuid::Uuid,
+        pr: &PullRequest,
+        git_host: &GitHostService,
+    ) -> Result<(), PrMonitorError> {
+        let pool = &self.db.pool;
+        let Some(repo_id) = pr.repo_id else {
+            return Ok(());
+        };
+        let Some(parent) = Workspace::find_by_id(pool, workspace_id).await? else {
+            return Ok(());
+        };
+
+        let child_ids = WorkspaceRepo::retarget_stacked_children(
+            pool,
+            workspace_id,
+            repo_id,
+            &parent.branch,
+            &pr.target_branch_name,
+        )
+        .await?;
+        for child_id in child_ids {
+            let child_prs = PullRequest::find_by_workspace_and_repo_id(pool, child_id, repo_id)
+                .await?
+                .into_iter()
+                .filter(|child_pr| {
+                    matches!(child_pr.pr_status, MergeStatus::Open)
+                        && child_pr.target_branch_name == parent.branch
+                });
+            for child_pr in child_prs {
+                match git_host
+                    .update_pr_base(&child_pr.pr_url, &pr.target_branch_name)
+                    .await
+                {
+                    Ok(_) => {
+                        PullRequest::update_target_branch(
+                            pool,
+                            &child_pr.pr_url,
+                            &pr.target_branch_name,
+                        )
+                        .await?;
+                        info!(
+                            "Retargeted stacked PR #{} from {} to {}",
+                            child_pr.pr_number, parent.branch, pr.target_branch_name
+                        );
+                    }
+                    Err(e) => warn!(
+                        "Failed to retarget stacked PR #{} onto {}: {}",
+                        child_pr.pr_number, pr.target_branch_name, e
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Archive workspace if all its PRs are merged/closed
     async fn try_archive_workspace(
         &self,
@@ -1,22 +1,17 @@
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+use db::models::{
+    execution_process::ExecutionProcessStatus,
+    queued_message::{QueuedMessage, QueuedMessageError},
+    scratch::DraftFollowUpData,
+};
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 use ts_rs::TS;
+use utils::msg_store::MsgStore;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
+use crate::services::events::queued_messages_patch;
 
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -24,67 +19,278 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and waiting for execution to complete, in the
+    /// order they will run
+    Queued { messages: Vec<QueuedMessage> },
+}
+
+impl From<Vec<QueuedMessage>> for QueueStatus {
+    fn from(messages: Vec<QueuedMessage>) -> Self {
+        if messages.is_empty() {
+            QueueStatus::Empty
+        } else {
+            QueueStatus::Queued { messages }
+        }
+    }
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+/// DB-backed, ordered queue of follow-up messages per session. Every change
+/// is pushed to the events stream so session websockets see the new queue.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    pool: SqlitePool,
+    msg_store: Arc<MsgStore>,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
-        }
+    pub fn new(pool: SqlitePool, msg_store: Arc<MsgStore>) -> Self {
+        Self { pool, msg_store }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Reload the session's queue and broadcast it.
+    async fn publish(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        let messages = QueuedMessage::find_by_session_id(&self.pool, session_id).await?;
+        self.msg_store
+            .push_patch(queued_messages_patch::replace(session_id, &messages));
+        Ok(messages.into())
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Append a message to the end of a session's queue.
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::create(&self.pool, session_id, &data).await?;
+        self.publish(session_id).await
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Replace the content of a single queued message.
+    pub async fn update_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::update_data(&self.pool, session_id, message_id, &data).await?;
+        self.publish(session_id).await
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Remove a single queued message.
+    pub async fn delete_message(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::delete(&self.pool, session_id, message_id).await?;
+        self.publish(session_id).await
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Set the execution order; `ordered_ids` must list every queued message.
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<QueueStatus, QueuedMessageError> {
+        QueuedMessage::reorder(&self.pool, session_id, ordered_ids).await?;
+        self.publish(session_id).await
     }
 
-    /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
+    /// Remove every queued message for a session.
+    pub async fn clear(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        if QueuedMessage::delete_by_session_id(&self.pool, session_id).await? > 0 {
+            return self.publish(session_id).await;
         }
+        Ok(QueueStatus::Empty)
+    }
+
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume one message per finished turn.
+    pub async fn take_next(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        let next = QueuedMessage::take_next(&self.pool, session_id).await?;
+        if next.is_some() {
+            self.publish(session_id).await?;
+        }
+        Ok(next)
+    }
+
+    /// The message to run after a turn that finished with `status`. After a
+    /// failed or killed turn the whole queue is kept, rather than discarded
+    /// as it was before the queue was persisted, so the user can edit, clear
+    /// or resume it with their next message.
+    pub async fn take_next_after_turn(
+        &self,
+        session_id: Uuid,
+        status: &ExecutionProcessStatus,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        if matches!(
+            status,
+            ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
+        ) {
+            tracing::info!(
+                "Not draining follow-up queue for session {} due to execution status {:?}",
+                session_id,
+                status
+            );
+            return Ok(None);
+        }
+        self.take_next(session_id).await
+    }
+
+    /// Check if a session has any queued messages
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(QueuedMessage::exists_for_session(&self.pool, session_id).await?)
+    }
+
+    /// Get queue status for frontend display
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        Ok(QueuedMessage::find_by_session_id(&self.pool, session_id)
+            .await?
+            .into())
     }
 }
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use executors::{
+        executors::BaseCodingAgent,
+        profile::{ExecutorConfig, ExecutorProfileId},
+    };
+
+    use super::*;
+
+    async fn make_service() -> (QueuedMessageService, Uuid) {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite");
+        sqlx::migrate!("../db/migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        let workspace_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO workspaces (id, branch) VALUES ($1, 'vk/test')")
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (id, workspace_id) VALUES ($1, $2)")
+            .bind(session_id)
+            .bind(workspace_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        (
+            QueuedMessageService::new(pool, Arc::new(MsgStore::new())),
+            session_id,
+        )
+    }
+
+    fn message(text: &str) -> DraftFollowUpData {
+        DraftFollowUpData {
+            message: text.to_string(),
+            executor_config: ExecutorConfig::from(ExecutorProfileId::new(
+                BaseCodingAgent::ClaudeCode,
+            )),
+        }
+    }
+
+    fn queued_texts(status: QueueStatus) -> Vec<String> {
+        match status {
+            QueueStatus::Empty => Vec::new(),
+            QueueStatus::Queued { messages } => {
+                messages.into_iter().map(|m| m.data.0.message).collect()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_or_killed_turn_keeps_the_queue() {
+        let (service, session_id) = make_service().await;
+        service
+            .queue_message(session_id, message("first"))
+            .await
+            .unwrap();
+        service
+            .queue_message(session_id, message("second"))
+            .await
+            .unwrap();
+
+        for status in [
+            ExecutionProcessStatus::Failed,
+            ExecutionProcessStatus::Killed,
+        ] {
+            assert!(
+                service
+                    .take_next_after_turn(session_id, &status)
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+        assert_eq!(
+            queued_texts(service.get_status(session_id).await.unwrap()),
+            ["first", "second"]
+        );
+
+        let next = service
+            .take_next_after_turn(session_id, &ExecutionProcessStatus::Completed)
+            .await
+            .unwrap();
+        assert_eq!(next.unwrap().data.message, "first");
+        assert_eq!(
+            queued_texts(service.get_status(session_id).await.unwrap()),
+            ["second"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fix_up_loop_sends_the_queue_kept_after_a_failed_verification_step() {
+        let (service, session_id) = make_service().await;
+        service
+            .queue_message(session_id, message("first"))
+            .await
+            .unwrap();
+
+        // The exit monitor keeps the queue after the failed step, and the
+        // fix-up loop then sends the message in place of a fix-up
+        assert!(
+            service
+                .take_next_after_turn(session_id, &ExecutionProcessStatus::Failed)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(service.has_queued(session_id).await.unwrap());
+        let next = service.take_next(session_id).await.unwrap();
+        assert_eq!(next.unwrap().data.message, "first");
+        assert!(!service.has_queued(session_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn reorder_changes_which_message_runs_next() {
+        let (service, session_id) = make_service().await;
+        service
+            .queue_message(session_id, message("first"))
+            .await
+            .unwrap();
+        let status = service
+            .queue_message(session_id, message("second"))
+            .await
+            .unwrap();
+        let QueueStatus::Queued { messages } = status else {
+            panic!("queue should not be empty");
+        };
+
+        let reordered = service
+            .reorder(session_id, &[messages[1].id, messages[0].id])
+            .await
+            .unwrap();
+        assert_eq!(queued_texts(reordered), ["second", "first"]);
+        let next = service.take_next(session_id).await.unwrap();
+        assert_eq!(next.unwrap().data.message, "second");
     }
 }
@@ -82,6 +82,29 @@ pub async fn sync_workspace_to_remote(
     update_workspace_on_remote(client, workspace_id, name, archived, stats).await;
 }
 
+/// Identifier put in direct-merge commit messages: the simple id of the
+/// remote issue the workspace is linked to, or the local workspace id.
+pub async fn vibe_kanban_identifier(client: Option<&RemoteClient>, workspace_id: Uuid) -> String {
+    match client {
+        Some(client) => linked_issue_identifier(client, workspace_id).await,
+        None => None,
+    }
+    .unwrap_or_else(|| workspace_id.to_string())
+}
+
+/// The simple id, or failing that the id, of the remote issue the workspace
+/// is linked to
+pub async fn linked_issue_identifier(client: &RemoteClient, workspace_id: Uuid) -> Option<String> {
+    let remote_ws = client.get_workspace_by_local_id(workspace_id).await.ok()?;
+    let issue_id = remote_ws.issue_id?;
+    let issue = client.get_issue(issue_id).await.ok()?;
+    if issue.simple_id.is_empty() {
+        Some(issue_id.to_string())
+    } else {
+        Some(issue.simple_id)
+    }
+}
+
 /// Syncs issue status to remote for a workspace merged locally without a PR.
 pub async fn sync_local_workspace_merge_to_remote(client: &RemoteClient, workspace_id: Uuid) {
     match client
@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 
-use db::models::repo::{Repo as RepoModel, SearchMatchType, SearchResult};
-use git::{GitService, GitServiceError};
+use db::models::repo::{Repo as RepoModel, SearchMatchType, SearchResult, SigningMode};
+use git::{CommitSigning, GitService, GitServiceError, SigningFormat};
 use sqlx::SqlitePool;
 use thiserror::Error;
 use utils::path::expand_tilde;
@@ -33,6 +33,23 @@ pub enum RepoError {
 
 pub type Result<T> = std::result::Result<T, RepoError>;
 
+/// How the commits made on behalf of `repo` are signed, if at all
+pub fn commit_signing(repo: &RepoModel) -> Option<CommitSigning> {
+    let format = match repo.commit_signing {
+        SigningMode::Off => return None,
+        SigningMode::GitConfig => None,
+        SigningMode::Gpg => Some(SigningFormat::OpenPgp),
+        SigningMode::Ssh => Some(SigningFormat::Ssh),
+    };
+    Some(CommitSigning {
+        format,
+        key: repo
+            .signing_key
+            .as_deref()
+            .map(|key| expand_tilde(key).to_string_lossy().into_owned()),
+    })
+}
+
 #[derive(Clone, Default)]
 pub struct RepoService;
 
@@ -0,0 +1,205 @@
+//! Cron evaluation and prompt rendering for scheduled agent runs. The runner
+//! that actually creates workspaces lives in the deployment, which owns the
+//! container and workspace manager.
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use croner::Cron;
+use db::models::schedule::{Schedule, ScheduleError, UpdateSchedule};
+
+pub fn parse_cron(expression: &str) -> Result<Cron, ScheduleError> {
+    Cron::new(expression.trim())
+        .with_seconds_optional()
+        .parse()
+        .map_err(|e| ScheduleError::InvalidCron(format!("'{expression}': {e}")))
+}
+
+fn next_occurrence<Tz: TimeZone>(
+    cron: &Cron,
+    after: &DateTime<Tz>,
+) -> Result<DateTime<Tz>, ScheduleError> {
+    cron.find_next_occurrence(after, false)
+        .map_err(|e| ScheduleError::InvalidCron(e.to_string()))
+}
+
+/// The first tick of `expression` strictly after `after`, evaluated in the
+/// server's local timezone so "0 3 * * *" means 3am where VK runs.
+pub fn next_run_after(
+    expression: &str,
+    after: DateTime<Utc>,
+) -> Result<DateTime<Utc>, ScheduleError> {
+    let cron = parse_cron(expression)?;
+    next_occurrence(&cron, &after.with_timezone(&Local)).map(|next| next.with_timezone(&Utc))
+}
+
+/// Reject obviously unusable schedules before they're stored.
+pub fn validate_schedule(
+    name: &str,
+    cron_expression: &str,
+    target_branch: &str,
+    prompt_template: &str,
+) -> Result<(), ScheduleError> {
+    if name.trim().is_empty() {
+        return Err(ScheduleError::Invalid("name must not be empty".to_string()));
+    }
+    if target_branch.trim().is_empty() {
+        return Err(ScheduleError::Invalid(
+            "target_branch must not be empty".to_string(),
+        ));
+    }
+    if prompt_template.trim().is_empty() {
+        return Err(ScheduleError::Invalid(
+            "prompt_template must not be empty".to_string(),
+        ));
+    }
+    parse_cron(cron_expression)?;
+    Ok(())
+}
+
+/// `next_run_at` after applying `update` to `existing`. Paused schedules have
+/// none; resuming or changing the cron expression restarts from `now`, so
+/// ticks missed while paused are not replayed.
+pub fn next_run_for_update(
+    existing: &Schedule,
+    update: &UpdateSchedule,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, ScheduleError> {
+    if update.paused.unwrap_or(existing.paused) {
+        return Ok(None);
+    }
+    match (&update.cron_expression, existing.next_run_at) {
+        (None, Some(next)) if !existing.paused => Ok(Some(next)),
+        (expression, _) => {
+            let expression = expression.as_deref().unwrap_or(&existing.cron_expression);
+            next_run_after(expression, now).map(Some)
+        }
+    }
+}
+
+/// Fill the `{{date}}`, `{{datetime}}` and `{{schedule_name}}` placeholders
+/// of a prompt template for a run due at `scheduled_for`.
+pub fn render_prompt(template: &str, schedule_name: &str, scheduled_for: DateTime<Utc>) -> String {
+    let local = scheduled_for.with_timezone(&Local);
+    template
+        .replace("{{date}}", &local.format("%Y-%m-%d").to_string())
+        .replace("{{datetime}}", &local.to_rfc3339())
+        .replace("{{schedule_name}}", schedule_name)
+}
+
+/// Name given to the workspace created by a run.
+pub fn workspace_name(schedule_name: &str, scheduled_for: DateTime<Utc>) -> String {
+    let local = scheduled_for.with_timezone(&Local);
+    format!("{} {}", schedule_name, local.format("%Y-%m-%d %H:%M"))
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn schedule(
+        cron_expression: &str,
+        paused: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Schedule {
+        Schedule {
+            id: Uuid::new_v4(),
+            name: "nightly".to_string(),
+            cron_expression: cron_expression.to_string(),
+            repo_id: Uuid::new_v4(),
+            target_branch: "main".to_string(),
+            prompt_template: "update dependencies".to_string(),
+            executor_profile_id: sqlx::types::Json(ExecutorProfileId::new(
+                BaseCodingAgent::ClaudeCode,
+            )),
+            paused,
+            next_run_at,
+            last_run_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn next_occurrence_is_strictly_after_start() {
+        let cron = parse_cron("0 3 * * *").unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 5, 4, 3, 0, 0).unwrap();
+        assert_eq!(
+            next_occurrence(&cron, &start).unwrap(),
+            Utc.with_ymd_and_hms(2026, 5, 5, 3, 0, 0).unwrap()
+        );
+
+        let cron = parse_cron("*/15 * * * *").unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 5, 4, 10, 7, 30).unwrap();
+        assert_eq!(
+            next_occurrence(&cron, &start).unwrap(),
+            Utc.with_ymd_and_hms(2026, 5, 4, 10, 15, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        assert!(validate_schedule("nightly", "0 3 * * *", "main", "run tests").is_ok());
+        assert!(matches!(
+            validate_schedule("nightly", "every night", "main", "run tests"),
+            Err(ScheduleError::InvalidCron(_))
+        ));
+        assert!(matches!(
+            validate_schedule("nightly", "0 3 * * *", "main", "  "),
+            Err(ScheduleError::Invalid(_))
+        ));
+        assert!(matches!(
+            validate_schedule(" ", "0 3 * * *", "main", "run tests"),
+            Err(ScheduleError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn pausing_clears_and_resuming_recomputes_next_run() {
+        let now = Utc::now();
+        let next = now + chrono::Duration::hours(1);
+        let active = schedule("0 3 * * *", false, Some(next));
+
+        let pause = UpdateSchedule {
+            paused: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(next_run_for_update(&active, &pause, now).unwrap(), None);
+
+        let rename = UpdateSchedule {
+            name: Some("renamed".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_run_for_update(&active, &rename, now).unwrap(),
+            Some(next)
+        );
+
+        let paused = schedule("0 3 * * *", true, None);
+        let resume = UpdateSchedule {
+            paused: Some(false),
+            ..Default::default()
+        };
+        let resumed = next_run_for_update(&paused, &resume, now).unwrap().unwrap();
+        assert!(resumed > now);
+    }
+
+    #[test]
+    fn prompt_placeholders_are_filled() {
+        let at = Utc::now();
+        let local = at.with_timezone(&Local);
+        let rendered = render_prompt(
+            "{{schedule_name}}: update dependencies ({{date}})",
+            "nightly",
+            at,
+        );
+        assert_eq!(
+            rendered,
+            format!(
+                "nightly: update dependencies ({})",
+                local.format("%Y-%m-%d")
+            )
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Which queued coding agents may start under the concurrency limits. The
+//! container claims them here under its start queue lock and spawns them
+//! after releasing it.
+
+use db::models::{
+    execution_process::{
+        ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+    },
+    execution_start_queue::QueuedExecution,
+};
+use executors::{actions::ExecutorAction, executors::BaseCodingAgent};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::services::config::ConcurrencyConfig;
+
+/// A queued coding agent that now counts as running and has to be spawned
+pub struct ClaimedExecution {
+    pub ctx: ExecutionContext,
+    pub executor_action: ExecutorAction,
+}
+
+/// Executors of the running coding agents, leaving out `session_id`'s.
+/// A session runs one agent turn at a time, so a follow-up takes over the
+/// slot of the turn it follows.
+pub async fn running_coding_agents(
+    pool: &SqlitePool,
+    session_id: Uuid,
+) -> Result<Vec<BaseCodingAgent>, sqlx::Error> {
+    Ok(ExecutionProcess::find_running(pool)
+        .await?
+        .into_iter()
+        .filter(|process| {
+            process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && process.session_id != session_id
+        })
+        .filter_map(|process| process.executor_action().ok()?.base_executor())
+        .collect())
+}
+
+/// Take the first queued coding agent the limits allow out of the queue and
+/// mark it running. An agent whose executor is at its limit doesn't hold up
+/// the agents of other executors queued behind it. Entries that can't start
+/// at all are marked failed and dropped rather than blocking the queue.
+pub async fn claim_next(
+    pool: &SqlitePool,
+    concurrency: &ConcurrencyConfig,
+) -> Result<Option<ClaimedExecution>, sqlx::Error> {
+    for queued in QueuedExecution::find_all(pool).await? {
+        let id = queued.execution_process_id;
+        let (ctx, executor_action, executor) = match load_queued(pool, id).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::error!("Dropping queued execution {} that can't start: {}", id, e);
+                QueuedExecution::delete(pool, id).await?;
+                ExecutionProcess::update_completion(pool, id, ExecutionProcessStatus::Failed, None)
+                    .await?;
+                continue;
+            }
+        };
+        let running = running_coding_agents(pool, ctx.session.id).await?;
+        if !concurrency.has_capacity(&running, executor) {
+            continue;
+        }
+
+        QueuedExecution::delete(pool, id).await?;
+        if !ExecutionProcess::start_queued(pool, id).await? {
+            continue;
+        }
+        let mut ctx = ctx;
+        ctx.execution_process.status = ExecutionProcessStatus::Running;
+        return Ok(Some(ClaimedExecution {
+            ctx,
+            executor_action,
+        }));
+    }
+    Ok(None)
+}
+
+async fn load_queued(
+    pool: &SqlitePool,
+    id: Uuid,
+) -> anyhow::Result<(ExecutionContext, ExecutorAction, BaseCodingAgent)> {
+    let ctx = ExecutionProcess::load_context(pool, id).await?;
+    let executor_action = ctx.execution_process.executor_action()?.clone();
+    let executor = executor_action
+        .base_executor()
+        .ok_or_else(|| anyhow::anyhow!("not a coding agent action"))?;
+    Ok((ctx, executor_action, executor))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use db::models::execution_process::CreateExecutionProcess;
+    use executors::{
+        actions::{ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest},
+        profile::{ExecutorConfig, ExecutorProfileId},
+    };
+
+    use super::*;
+
+    async fn make_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite");
+        sqlx::migrate!("../db/migrations")
+            .run(&pool)
+            .await
+            .expect("migrations");
+        pool
+    }
+
+    async fn make_session(pool: &SqlitePool) -> Uuid {
+        let workspace_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO workspaces (id, branch) VALUES ($1, 'vk/test')")
+            .bind(workspace_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (id, workspace_id) VALUES ($1, $2)")
+            .bind(session_id)
+            .bind(workspace_id)
+            .execute(pool)
+            .await
+            .unwrap();
+        session_id
+    }
+
+    async fn make_agent(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        executor: BaseCodingAgent,
+        status: ExecutionProcessStatus,
+    ) -> Uuid {
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt: "Fix the bug".to_string(),
+                executor_config: ExecutorConfig::from(ExecutorProfileId::new(executor)),
+                working_dir: None,
+            }),
+            None,
+        );
+        let queued = status == ExecutionProcessStatus::Queued;
+        let process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                session_id,
+                executor_action: action,
+                run_reason: ExecutionProcessRunReason::CodingAgent,
+                status,
+            },
+            Uuid::new_v4(),
+            &[],
+        )
+        .await
+        .unwrap();
+        if queued {
+            QueuedExecution::create(pool, process.id).await.unwrap();
+        }
+        process.id
+    }
+
+    #[tokio::test]
+    async fn claims_queued_agents_in_order_as_slots_free_up() {
+        let pool = make_pool().await;
+        let concurrency = ConcurrencyConfig {
+            max_coding_agents: Some(1),
+            max_per_executor: HashMap::new(),
+        };
+        let running = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+        let first = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+        let second = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::Codex,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+
+        assert!(claim_next(&pool, &concurrency).await.unwrap().is_none());
+
+        ExecutionProcess::update_completion(
+            &pool,
+            running,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        let claimed = claim_next(&pool, &concurrency).await.unwrap().unwrap();
+        assert_eq!(claimed.ctx.execution_process.id, first);
+        let first_row = ExecutionProcess::find_by_id(&pool, first)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first_row.status, ExecutionProcessStatus::Running);
+
+        // The claimed agent holds the only slot
+        assert!(claim_next(&pool, &concurrency).await.unwrap().is_none());
+        let queue = QueuedExecution::find_all(&pool).await.unwrap();
+        assert_eq!(
+            queue
+                .iter()
+                .map(|q| q.execution_process_id)
+                .collect::<Vec<_>>(),
+            vec![second]
+        );
+    }
+
+    #[tokio::test]
+    async fn executor_at_its_limit_does_not_block_other_executors() {
+        let pool = make_pool().await;
+        let concurrency = ConcurrencyConfig {
+            max_coding_agents: None,
+            max_per_executor: HashMap::from([(BaseCodingAgent::ClaudeCode, 1)]),
+        };
+        make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Running,
+        )
+        .await;
+        make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+        let codex = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::Codex,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+
+        let claimed = claim_next(&pool, &concurrency).await.unwrap().unwrap();
+        assert_eq!(claimed.ctx.execution_process.id, codex);
+    }
+
+    #[tokio::test]
+    async fn broken_entry_is_failed_and_does_not_block_the_queue() {
+        let pool = make_pool().await;
+        let broken = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+        let next = make_agent(
+            &pool,
+            make_session(&pool).await,
+            BaseCodingAgent::ClaudeCode,
+            ExecutionProcessStatus::Queued,
+        )
+        .await;
+        sqlx::query("UPDATE execution_processes SET executor_action = '{}' WHERE id = $1")
+            .bind(broken)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let claimed = claim_next(&pool, &ConcurrencyConfig::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(claimed.ctx.execution_process.id, next);
+        let broken_row = ExecutionProcess::find_by_id(&pool, broken)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(broken_row.status, ExecutionProcessStatus::Failed);
+        assert!(QueuedExecution::find_all(&pool).await.unwrap().is_empty());
+    }
+}
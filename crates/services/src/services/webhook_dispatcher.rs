@@ -1,17 +1,36 @@
 use std::{
+    collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use db::models::webhook::Webhook;
+use chrono::Utc;
+use dashmap::DashSet;
+use db::models::{
+    execution_process::ExecutionProcess,
+    session::Session,
+    webhook::Webhook,
+    webhook_delivery::{WebhookDelivery, WebhookDeliveryStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
 use futures::StreamExt;
 use reqwest::Client;
-use serde_json::Value;
 use sqlx::SqlitePool;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{Notify, RwLock, Semaphore};
 use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 use tokio_util::sync::CancellationToken;
 use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::{
+    approvals::Approvals,
+    webhook_event::{
+        ActiveProcesses, SIGNATURE_HEADER, TIMESTAMP_HEADER, WebhookEntityType, WebhookEvent,
+        WebhookEventType, sign_payload,
+    },
+    webhook_filter::{WebhookEventScope, WebhookFilter},
+};
 
 /// How long to cache the enabled-webhooks list before re-querying the DB.
 /// Short enough that newly-added webhooks start receiving events promptly,
@@ -23,49 +42,124 @@ const WEBHOOK_CACHE_TTL: Duration = Duration::from_secs(30);
 /// tasks and exhaust file descriptors / memory.
 const MAX_CONCURRENT_DELIVERIES: usize = 128;
 
-/// Subscribes to the events MsgStore and POSTs JSON-patch payloads to all
-/// registered and enabled webhook URLs.
-type WebhookCache = Arc<RwLock<Option<(Instant, Vec<Webhook>)>>>;
+/// How often the delivery worker polls for due retries when it hasn't been
+/// woken up by a freshly queued delivery.
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum number of due deliveries picked up per worker pass.
+const DUE_BATCH_SIZE: i64 = 256;
+
+/// A delivery is dead-lettered after this many failed attempts.
+pub const MAX_DELIVERY_ATTEMPTS: i64 = 10;
+
+/// Delay before the first retry; doubled on every further failure.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(10);
+
+/// Upper bound for the exponential retry delay.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Successfully delivered rows older than this are pruned from the log.
+const DELIVERED_RETENTION: chrono::Duration = chrono::Duration::days(7);
+
+/// How often the delivered-row pruning runs.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Delay before retrying after the `attempts`-th failed attempt (1-based).
+fn retry_delay(attempts: i64) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// An enabled webhook together with its compiled subscription filter.
+/// `filter` is `None` if the stored filter no longer compiles, in which case
+/// no new events are queued for the webhook.
+#[derive(Clone)]
+struct CachedWebhook {
+    hook: Webhook,
+    filter: Option<WebhookFilter>,
+}
 
+/// Shared handle to the dispatcher's enabled-webhooks cache. Webhook CRUD
+/// invalidates it so the next event sees the change instead of waiting out
+/// `WEBHOOK_CACHE_TTL`.
+#[derive(Clone, Default)]
+pub struct WebhookCache(Arc<RwLock<Option<(Instant, Vec<CachedWebhook>)>>>);
+
+impl WebhookCache {
+    pub async fn invalidate(&self) {
+        *self.0.write().await = None;
+    }
+}
+
+/// Subscribes to the events MsgStore and the approvals stream, turns patches
+/// into typed `WebhookEvent`s, persists one `WebhookDelivery` per event and
+/// enabled webhook whose filter matches, and POSTs them with
+/// exponential-backoff retries. Because deliveries live in the database,
+/// pending retries are picked up again after a server restart.
 pub struct WebhookDispatcher {
     pool: SqlitePool,
     msg_store: Arc<MsgStore>,
+    approvals: Approvals,
     http: Client,
     /// Cached list of enabled webhooks plus the timestamp when the cache was
-    /// last refreshed. Refreshed on a TTL so we don't hit the DB on every event.
+    /// last refreshed. Refreshed on a TTL so we don't hit the DB on every
+    /// event, and dropped whenever a webhook is changed.
     cache: WebhookCache,
     /// Caps concurrent in-flight delivery tasks so a slow webhook can't
     /// cause unbounded task growth under bursty event traffic.
     semaphore: Arc<Semaphore>,
+    /// Wakes the delivery worker when new deliveries are queued (by the
+    /// fan-out loop or a manual redeliver) so it doesn't wait for the next
+    /// poll tick.
+    delivery_notify: Arc<Notify>,
+    /// Deliveries with an attempt currently in flight. Prevents the worker
+    /// from picking the same row twice when a request outlives a poll tick.
+    in_flight: Arc<DashSet<Uuid>>,
 }
 
 impl WebhookDispatcher {
-    pub fn new(pool: SqlitePool, msg_store: Arc<MsgStore>) -> Self {
+    pub fn new(
+        pool: SqlitePool,
+        msg_store: Arc<MsgStore>,
+        approvals: Approvals,
+        delivery_notify: Arc<Notify>,
+        cache: WebhookCache,
+    ) -> Self {
         Self {
             pool,
             msg_store,
+            approvals,
             http: Client::builder()
                 .timeout(std::time::Duration::from_secs(10))
                 .build()
                 .expect("reqwest client"),
-            cache: Arc::new(RwLock::new(None)),
+            cache,
             semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_DELIVERIES)),
+            delivery_notify,
+            in_flight: Arc::new(DashSet::new()),
         }
     }
 
-    /// Spawn a background task that fans out events to all enabled webhooks.
-    /// The returned JoinHandle should be retained by the caller so the task is
-    /// not detached. The dispatcher terminates when `shutdown` is cancelled.
+    /// Spawn a background task that fans out events to all enabled webhooks
+    /// and drives delivery attempts. The returned JoinHandle should be
+    /// retained by the caller so the task is not detached. The dispatcher
+    /// terminates when `shutdown` is cancelled.
     pub fn spawn(self, shutdown: CancellationToken) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
-            self.run(shutdown).await;
+            let dispatcher = Arc::new(self);
+            tokio::join!(
+                dispatcher.clone().run(shutdown.clone()),
+                dispatcher.run_deliveries(shutdown),
+            );
         })
     }
 
-    async fn enabled_webhooks(&self) -> Result<Vec<Webhook>, sqlx::Error> {
+    async fn enabled_webhooks(&self) -> Result<Vec<CachedWebhook>, sqlx::Error> {
         // Fast path: cache is fresh
         {
-            let cache = self.cache.read().await;
+            let cache = self.cache.0.read().await;
             if let Some((fetched_at, hooks)) = cache.as_ref()
                 && fetched_at.elapsed() < WEBHOOK_CACHE_TTL
             {
@@ -78,23 +172,89 @@ impl WebhookDispatcher {
         // refreshed it while we were queued, and without this guard a burst
         // of events after cache expiry would all fall through and hit the
         // DB (thundering herd).
-        let mut cache = self.cache.write().await;
+        let mut cache = self.cache.0.write().await;
         if let Some((fetched_at, hooks)) = cache.as_ref()
             && fetched_at.elapsed() < WEBHOOK_CACHE_TTL
         {
             return Ok(hooks.clone());
         }
-        let hooks = Webhook::find_enabled(&self.pool).await?;
+        let hooks: Vec<CachedWebhook> = Webhook::find_enabled(&self.pool)
+            .await?
+            .into_iter()
+            .map(|hook| {
+                let filter = WebhookFilter::from_webhook(&hook)
+                    .inspect_err(|e| {
+                        tracing::warn!(url = %hook.url, "webhook_dispatcher: skipping webhook: {e}")
+                    })
+                    .ok();
+                CachedWebhook { hook, filter }
+            })
+            .collect();
         *cache = Some((Instant::now(), hooks.clone()));
         Ok(hooks)
     }
 
-    async fn run(self, shutdown: CancellationToken) {
+    /// Work out which workspace an event belongs to. Returns `None` when the
+    /// entity no longer exists (deletions, resolved approvals we never saw
+    /// requested).
+    async fn resolve_scope(&self, event: &WebhookEvent) -> Option<WebhookEventScope> {
+        let workspace = match event.entity_type {
+            WebhookEntityType::Workspace => {
+                let id = event.entity_id.parse::<Uuid>().ok()?;
+                Workspace::find_by_id(&self.pool, id).await.ok()??
+            }
+            WebhookEntityType::ExecutionProcess => {
+                let session_id = event.data.get("session_id")?.as_str()?.parse().ok()?;
+                let session = Session::find_by_id(&self.pool, session_id).await.ok()??;
+                Workspace::find_by_id(&self.pool, session.workspace_id)
+                    .await
+                    .ok()??
+            }
+            WebhookEntityType::Approval => {
+                let process_id = event
+                    .data
+                    .get("execution_process_id")?
+                    .as_str()?
+                    .parse()
+                    .ok()?;
+                let process = ExecutionProcess::find_by_id(&self.pool, process_id)
+                    .await
+                    .ok()??;
+                let (workspace, _) = process
+                    .parent_workspace_and_session(&self.pool)
+                    .await
+                    .ok()??;
+                workspace
+            }
+        };
+        let repo_ids = WorkspaceRepo::find_by_workspace_id(&self.pool, workspace.id)
+            .await
+            .ok()?
+            .into_iter()
+            .map(|wr| wr.repo_id)
+            .collect();
+        Some(WebhookEventScope {
+            workspace_id: workspace.id,
+            workspace_name: workspace.name,
+            branch: workspace.branch,
+            repo_ids,
+        })
+    }
+
+    /// Fan-out loop: turns every broadcast event into one persisted delivery
+    /// per enabled webhook whose filter matches. Sending happens in
+    /// `run_deliveries`.
+    async fn run(self: Arc<Self>, shutdown: CancellationToken) {
         let rx = self.msg_store.get_receiver();
         let mut stream = BroadcastStream::new(rx);
+        let mut approvals = self.approvals.patch_stream();
+        // Resolved approvals carry no data, so remember the scope of each
+        // pending approval to filter its `approval.resolved` event.
+        let mut approval_scopes: HashMap<String, WebhookEventScope> = HashMap::new();
+        let mut processes = ActiveProcesses::default();
 
         loop {
-            tokio::select! {
+            let events = tokio::select! {
                 biased;
                 _ = shutdown.cancelled() => {
                     tracing::info!("webhook_dispatcher: shutdown signalled, exiting");
@@ -106,8 +266,9 @@ impl WebhookDispatcher {
                         return;
                     };
 
-                    let msg = match item {
-                        Ok(m) => m,
+                    match item {
+                        Ok(LogMsg::JsonPatch(patch)) => WebhookEvent::from_events_patch(&patch, &mut processes),
+                        Ok(_) => continue,
                         Err(BroadcastStreamRecvError::Lagged(count)) => {
                             tracing::warn!(
                                 "webhook_dispatcher: broadcast lagged, {} events dropped",
@@ -115,89 +276,305 @@ impl WebhookDispatcher {
                             );
                             continue;
                         }
-                    };
+                    }
+                }
+                Some(patch) = approvals.next() => WebhookEvent::from_approvals_patch(&patch),
+            };
 
-                    let payload: Option<Value> = match &msg {
-                        LogMsg::JsonPatch(patch) => serde_json::to_value(patch).ok(),
-                        _ => None,
-                    };
+            if events.is_empty() {
+                continue;
+            }
 
-                    let Some(payload) = payload else { continue };
+            let hooks = match self.enabled_webhooks().await {
+                Ok(h) => h,
+                Err(e) => {
+                    tracing::warn!("webhook_dispatcher: db error loading webhooks: {e}");
+                    continue;
+                }
+            };
 
-                    let hooks = match self.enabled_webhooks().await {
-                        Ok(h) => h,
-                        Err(e) => {
-                            tracing::warn!("webhook_dispatcher: db error loading webhooks: {e}");
-                            continue;
-                        }
-                    };
+            if hooks.is_empty() {
+                continue;
+            }
 
-                    if hooks.is_empty() {
+            let needs_scope = hooks
+                .iter()
+                .any(|h| h.filter.as_ref().is_some_and(WebhookFilter::is_scoped));
+
+            let mut queued = false;
+            for event in &events {
+                let scope = match (event.entity_type, event.event_type) {
+                    (WebhookEntityType::Approval, WebhookEventType::ApprovalResolved) => {
+                        approval_scopes.remove(&event.entity_id)
+                    }
+                    _ if needs_scope => self.resolve_scope(event).await,
+                    _ => None,
+                };
+                if event.event_type == WebhookEventType::ApprovalRequested
+                    && let Some(scope) = &scope
+                {
+                    approval_scopes.insert(event.entity_id.clone(), scope.clone());
+                }
+
+                let matching: Vec<&Webhook> = hooks
+                    .iter()
+                    .filter(|h| {
+                        h.filter
+                            .as_ref()
+                            .is_some_and(|f| f.matches(event, scope.as_ref()))
+                    })
+                    .map(|h| &h.hook)
+                    .collect();
+                if matching.is_empty() {
+                    continue;
+                }
+
+                let payload = match serde_json::to_value(event) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        tracing::warn!("webhook_dispatcher: failed to serialize event: {e}");
                         continue;
                     }
+                };
+                let event_type = event.event_type.to_string();
+                for hook in matching {
+                    match WebhookDelivery::create(&self.pool, hook.id, &event_type, &payload).await
+                    {
+                        Ok(_) => queued = true,
+                        Err(e) => {
+                            tracing::warn!(
+                                url = %hook.url,
+                                "webhook_dispatcher: failed to queue delivery: {e}"
+                            );
+                        }
+                    }
+                }
+            }
+
+            if queued {
+                self.delivery_notify.notify_one();
+            }
+        }
+    }
+
+    /// Delivery worker: attempts every due pending delivery, either when
+    /// woken by `delivery_notify` or on a fixed poll interval (which is what
+    /// picks up backed-off retries and anything left over from a previous
+    /// run of the server).
+    async fn run_deliveries(self: Arc<Self>, shutdown: CancellationToken) {
+        let mut poll = tokio::time::interval(DELIVERY_POLL_INTERVAL);
+        let mut last_prune: Option<Instant> = None;
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    tracing::info!("webhook_dispatcher: delivery worker shutting down");
+                    return;
+                }
+                _ = self.delivery_notify.notified() => {}
+                _ = poll.tick() => {}
+            }
+
+            if !self.dispatch_due(&shutdown).await {
+                return;
+            }
 
-                    // Fire-and-forget each delivery so a slow webhook can't
-                    // block the dispatcher loop (head-of-line blocking).
-                    // A semaphore caps total in-flight deliveries so slow
-                    // downstreams can't balloon task counts unbounded.
-                    //
-                    // IMPORTANT: semaphore acquisition is raced against the
-                    // shutdown token so that a saturated permit pool (128
-                    // in-flight deliveries against slow targets) cannot
-                    // block graceful shutdown indefinitely.
-                    for hook in hooks {
-                        let permit = tokio::select! {
-                            biased;
-                            _ = shutdown.cancelled() => {
-                                tracing::info!(
-                                    "webhook_dispatcher: shutdown while waiting for delivery \
-                                     permit, stopping fan-out"
-                                );
-                                return;
-                            }
-                            result = Arc::clone(&self.semaphore).acquire_owned() => {
-                                match result {
-                                    Ok(p) => p,
-                                    Err(_) => {
-                                        // Semaphore explicitly closed — shutting down.
-                                        tracing::info!(
-                                            "webhook_dispatcher: semaphore closed, stopping fan-out"
-                                        );
-                                        return;
-                                    }
-                                }
-                            }
-                        };
-                        let client = self.http.clone();
-                        let body = payload.clone();
-                        tokio::spawn(async move {
-                            let _permit = permit; // released on drop
-                            let mut req = client
-                                .post(&hook.url)
-                                .header("Content-Type", "application/json")
-                                .header("X-VK-Event", "patch");
-
-                            if let Some(secret) = &hook.secret {
-                                req = req.header("X-VK-Secret", secret.as_str());
-                            }
-
-                            match req.json(&body).send().await {
-                                Ok(resp) if resp.status().is_success() => {}
-                                Ok(resp) => {
-                                    tracing::warn!(
-                                        url = %hook.url,
-                                        status = %resp.status(),
-                                        "webhook delivery non-2xx"
-                                    );
-                                }
-                                Err(e) => {
-                                    tracing::warn!(url = %hook.url, "webhook delivery error: {e}");
-                                }
-                            }
-                        });
+            if last_prune.is_none_or(|t| t.elapsed() >= PRUNE_INTERVAL) {
+                last_prune = Some(Instant::now());
+                match WebhookDelivery::prune_delivered(&self.pool, Utc::now() - DELIVERED_RETENTION)
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => tracing::debug!("webhook_dispatcher: pruned {n} delivered rows"),
+                    Err(e) => tracing::warn!("webhook_dispatcher: failed to prune deliveries: {e}"),
+                }
+            }
+        }
+    }
+
+    /// Spawns an attempt for every due delivery that isn't already in
+    /// flight. Returns `false` if shutdown was signalled while waiting for a
+    /// delivery permit.
+    async fn dispatch_due(self: &Arc<Self>, shutdown: &CancellationToken) -> bool {
+        let due = match WebhookDelivery::find_due(&self.pool, Utc::now(), DUE_BATCH_SIZE).await {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::warn!("webhook_dispatcher: db error loading due deliveries: {e}");
+                return true;
+            }
+        };
+
+        for delivery in due {
+            if !self.in_flight.insert(delivery.id) {
+                continue;
+            }
+
+            // Fire-and-forget each delivery so a slow webhook can't block
+            // the worker loop (head-of-line blocking). A semaphore caps total
+            // in-flight deliveries so slow downstreams can't balloon task
+            // counts unbounded.
+            //
+            // IMPORTANT: semaphore acquisition is raced against the shutdown
+            // token so that a saturated permit pool (128 in-flight deliveries
+            // against slow targets) cannot block graceful shutdown
+            // indefinitely. Anything not attempted stays pending in the DB
+            // and is retried on the next start.
+            let permit = tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => {
+                    tracing::info!(
+                        "webhook_dispatcher: shutdown while waiting for delivery permit, \
+                         stopping dispatch"
+                    );
+                    self.in_flight.remove(&delivery.id);
+                    return false;
+                }
+                result = Arc::clone(&self.semaphore).acquire_owned() => {
+                    match result {
+                        Ok(p) => p,
+                        Err(_) => {
+                            // Semaphore explicitly closed — shutting down.
+                            tracing::info!(
+                                "webhook_dispatcher: semaphore closed, stopping dispatch"
+                            );
+                            self.in_flight.remove(&delivery.id);
+                            return false;
+                        }
                     }
                 }
+            };
+
+            let dispatcher = Arc::clone(self);
+            tokio::spawn(async move {
+                let _permit = permit; // released on drop
+                let delivery_id = delivery.id;
+                dispatcher.attempt(delivery).await;
+                dispatcher.in_flight.remove(&delivery_id);
+            });
+        }
+
+        true
+    }
+
+    /// Perform a single delivery attempt and persist its outcome.
+    async fn attempt(&self, delivery: WebhookDelivery) {
+        // Read the webhook itself rather than the cache so a changed URL or
+        // secret applies to the very next attempt.
+        let hook = match Webhook::find_by_id(&self.pool, delivery.webhook_id).await {
+            Ok(hook) => hook,
+            Err(e) => {
+                tracing::warn!("webhook_dispatcher: db error loading webhook: {e}");
+                return;
             }
+        };
+        // Disabled or deleted since the delivery was queued; leave the row
+        // alone (a deleted webhook's deliveries are removed by cascade).
+        let Some(hook) = hook.filter(|h| h.enabled) else {
+            return;
+        };
+
+        // Serialize once so the signature covers exactly the bytes we send.
+        let body = match serde_json::to_vec(&delivery.payload.0) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("webhook_dispatcher: failed to serialize payload: {e}");
+                return;
+            }
+        };
+        // Signed per attempt, so retries carry a fresh timestamp.
+        let timestamp = Utc::now().timestamp();
+
+        let mut req = self
+            .http
+            .post(&hook.url)
+            .header("Content-Type", "application/json")
+            .header("X-VK-Event", delivery.event_type.as_str())
+            .header("X-VK-Delivery", delivery.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string());
+
+        if let Some(secret) = &hook.secret {
+            req = req.header(
+                SIGNATURE_HEADER,
+                sign_payload(secret.as_bytes(), timestamp, &body),
+            );
         }
+
+        let (response_status, error) = match req.body(body).send().await {
+            Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16() as i64), None),
+            Ok(resp) => {
+                tracing::warn!(
+                    url = %hook.url,
+                    status = %resp.status(),
+                    attempt = delivery.attempts + 1,
+                    "webhook delivery non-2xx"
+                );
+                (
+                    Some(resp.status().as_u16() as i64),
+                    Some(format!("Receiver responded with {}", resp.status())),
+                )
+            }
+            Err(e) => {
+                tracing::warn!(
+                    url = %hook.url,
+                    attempt = delivery.attempts + 1,
+                    "webhook delivery error: {e}"
+                );
+                (None, Some(e.to_string()))
+            }
+        };
+
+        let attempts = delivery.attempts + 1;
+        let now = Utc::now();
+        let (status, next_attempt_at) = match &error {
+            None => (WebhookDeliveryStatus::Delivered, now),
+            Some(_) if attempts >= MAX_DELIVERY_ATTEMPTS => {
+                tracing::warn!(
+                    url = %hook.url,
+                    delivery_id = %delivery.id,
+                    "webhook delivery dead-lettered after {attempts} attempts"
+                );
+                (WebhookDeliveryStatus::Dead, now)
+            }
+            Some(_) => (
+                WebhookDeliveryStatus::Pending,
+                now + chrono::Duration::from_std(retry_delay(attempts))
+                    .unwrap_or(chrono::Duration::MAX),
+            ),
+        };
+
+        if let Err(e) = WebhookDelivery::record_attempt(
+            &self.pool,
+            delivery.id,
+            status,
+            response_status,
+            error.as_deref(),
+            next_attempt_at,
+        )
+        .await
+        {
+            tracing::warn!(
+                delivery_id = %delivery.id,
+                "webhook_dispatcher: failed to record delivery attempt: {e}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_from_base() {
+        assert_eq!(retry_delay(1), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(4), RETRY_BASE_DELAY * 8);
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        assert_eq!(retry_delay(MAX_DELIVERY_ATTEMPTS), RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(i64::MAX), RETRY_MAX_DELAY);
     }
 }
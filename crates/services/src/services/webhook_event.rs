@@ -0,0 +1,407 @@
+//! Typed, versioned envelopes for outbound webhook deliveries and the HMAC
+//! signature attached to each request.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use json_patch::{Patch, PatchOperation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Bumped whenever the envelope shape changes incompatibly.
+pub const WEBHOOK_EVENT_VERSION: u32 = 1;
+
+/// Header carrying `sha256=<hex hmac>` of `"{timestamp}.{body}"`.
+pub const SIGNATURE_HEADER: &str = "X-VK-Signature";
+
+/// Header carrying the unix timestamp (seconds) that was signed. Receivers
+/// should reject requests whose timestamp is too far from their own clock.
+pub const TIMESTAMP_HEADER: &str = "X-VK-Timestamp";
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    TS,
+    Display,
+    EnumString,
+    EnumIter,
+    AsRefStr,
+)]
+pub enum WebhookEventType {
+    #[serde(rename = "workspace.created")]
+    #[strum(serialize = "workspace.created")]
+    WorkspaceCreated,
+    #[serde(rename = "workspace.updated")]
+    #[strum(serialize = "workspace.updated")]
+    WorkspaceUpdated,
+    #[serde(rename = "workspace.deleted")]
+    #[strum(serialize = "workspace.deleted")]
+    WorkspaceDeleted,
+    #[serde(rename = "execution_process.started")]
+    #[strum(serialize = "execution_process.started")]
+    ExecutionProcessStarted,
+    #[serde(rename = "execution_process.updated")]
+    #[strum(serialize = "execution_process.updated")]
+    ExecutionProcessUpdated,
+    #[serde(rename = "execution_process.completed")]
+    #[strum(serialize = "execution_process.completed")]
+    ExecutionProcessCompleted,
+    #[serde(rename = "execution_process.failed")]
+    #[strum(serialize = "execution_process.failed")]
+    ExecutionProcessFailed,
+    #[serde(rename = "execution_process.killed")]
+    #[strum(serialize = "execution_process.killed")]
+    ExecutionProcessKilled,
+    #[serde(rename = "execution_process.deleted")]
+    #[strum(serialize = "execution_process.deleted")]
+    ExecutionProcessDeleted,
+    #[serde(rename = "approval.requested")]
+    #[strum(serialize = "approval.requested")]
+    ApprovalRequested,
+    #[serde(rename = "approval.resolved")]
+    #[strum(serialize = "approval.resolved")]
+    ApprovalResolved,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEntityType {
+    Workspace,
+    ExecutionProcess,
+    Approval,
+}
+
+/// The JSON body POSTed to a webhook.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WebhookEvent {
+    pub version: u32,
+    /// Unique per event; shared by every webhook the event is fanned out to.
+    pub id: Uuid,
+    pub event_type: WebhookEventType,
+    pub entity_type: WebhookEntityType,
+    /// Workspace / execution process UUID, or the approval request id.
+    pub entity_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Full snapshot of the entity after the change; `null` for deletions
+    /// and resolved approvals.
+    #[ts(type = "JsonValue")]
+    pub data: Value,
+}
+
+impl WebhookEvent {
+    fn new(
+        event_type: WebhookEventType,
+        entity_type: WebhookEntityType,
+        entity_id: String,
+        data: Value,
+    ) -> Self {
+        Self {
+            version: WEBHOOK_EVENT_VERSION,
+            id: Uuid::new_v4(),
+            event_type,
+            entity_type,
+            entity_id,
+            timestamp: Utc::now(),
+            data,
+        }
+    }
+
+    /// Translate a patch from the global events stream (`/workspaces/{id}`,
+    /// `/execution_processes/{id}`) into typed events. Operations on other
+    /// paths (e.g. scratch drafts) are not exposed to webhooks.
+    ///
+    /// An execution process row keeps being replaced after it finishes
+    /// (resource and token usage, dropped flag), so `processes` tracks which
+    /// ones are still active and a terminal event is only emitted on the
+    /// transition out of that state.
+    pub fn from_events_patch(patch: &Patch, processes: &mut ActiveProcesses) -> Vec<Self> {
+        patch
+            .0
+            .iter()
+            .filter_map(|op| {
+                let (collection, id) = split_entity_path(op.path().as_str())?;
+                match collection {
+                    "workspaces" => {
+                        let (event_type, data) = match op {
+                            PatchOperation::Add(add) => {
+                                (WebhookEventType::WorkspaceCreated, add.value.clone())
+                            }
+                            PatchOperation::Replace(replace) => {
+                                (WebhookEventType::WorkspaceUpdated, replace.value.clone())
+                            }
+                            PatchOperation::Remove(_) => {
+                                (WebhookEventType::WorkspaceDeleted, Value::Null)
+                            }
+                            _ => return None,
+                        };
+                        Some(Self::new(
+                            event_type,
+                            WebhookEntityType::Workspace,
+                            id.to_string(),
+                            data,
+                        ))
+                    }
+                    "execution_processes" => {
+                        let (event_type, data) = match op {
+                            PatchOperation::Add(add) => {
+                                processes.observe(id, &add.value);
+                                (WebhookEventType::ExecutionProcessStarted, add.value.clone())
+                            }
+                            PatchOperation::Replace(replace) => {
+                                (processes.observe(id, &replace.value), replace.value.clone())
+                            }
+                            PatchOperation::Remove(_) => {
+                                processes.0.remove(id);
+                                (WebhookEventType::ExecutionProcessDeleted, Value::Null)
+                            }
+                            _ => return None,
+                        };
+                        Some(Self::new(
+                            event_type,
+                            WebhookEntityType::ExecutionProcess,
+                            id.to_string(),
+                            data,
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Translate a patch from `Approvals::patch_stream` (`/pending/{id}`).
+    /// Full `/pending` snapshots and in-place updates of a pending approval
+    /// are ignored; only its creation and resolution are events.
+    pub fn from_approvals_patch(patch: &Patch) -> Vec<Self> {
+        patch
+            .0
+            .iter()
+            .filter_map(|op| {
+                let (collection, id) = split_entity_path(op.path().as_str())?;
+                if collection != "pending" {
+                    return None;
+                }
+                let (event_type, data) = match op {
+                    PatchOperation::Add(add) => {
+                        (WebhookEventType::ApprovalRequested, add.value.clone())
+                    }
+                    PatchOperation::Remove(_) => (WebhookEventType::ApprovalResolved, Value::Null),
+                    _ => return None,
+                };
+                Some(Self::new(
+                    event_type,
+                    WebhookEntityType::Approval,
+                    id.to_string(),
+                    data,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Split `/collection/id` into its two segments. Deeper paths are rejected.
+fn split_entity_path(path: &str) -> Option<(&str, &str)> {
+    let mut segments = path.strip_prefix('/')?.split('/');
+    let collection = segments.next()?;
+    let id = segments.next()?;
+    if segments.next().is_some() || id.is_empty() {
+        return None;
+    }
+    Some((collection, id))
+}
+
+/// Ids of the execution processes last seen in a non-terminal status.
+/// Processes that finished before the dispatcher saw them running only
+/// produce `execution_process.updated` events.
+#[derive(Debug, Default)]
+pub struct ActiveProcesses(HashSet<String>);
+
+impl ActiveProcesses {
+    /// Record the status in `value` and return the event type it implies.
+    fn observe(&mut self, id: &str, value: &Value) -> WebhookEventType {
+        let terminal = match value.get("status").and_then(Value::as_str) {
+            Some("completed") => WebhookEventType::ExecutionProcessCompleted,
+            Some("failed") => WebhookEventType::ExecutionProcessFailed,
+            Some("killed") => WebhookEventType::ExecutionProcessKilled,
+            _ => {
+                self.0.insert(id.to_string());
+                return WebhookEventType::ExecutionProcessUpdated;
+            }
+        };
+        if self.0.remove(id) {
+            terminal
+        } else {
+            WebhookEventType::ExecutionProcessUpdated
+        }
+    }
+}
+
+/// Compute the `X-VK-Signature` header value for `body` sent at `timestamp`.
+///
+/// The signed message is `"{timestamp}.{body}"` so a captured request can't
+/// be replayed with a fresh timestamp.
+pub fn sign_payload(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use json_patch::{AddOperation, RemoveOperation, ReplaceOperation};
+    use serde_json::json;
+
+    use super::*;
+
+    fn replace(path: &str, value: Value) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: path.try_into().unwrap(),
+            value,
+        })])
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let secret = b"test-secret";
+        let body = br#"{"hello":"world"}"#;
+
+        let mut mac = HmacSha256::new_from_slice(secret).unwrap();
+        mac.update(b"1700000000.");
+        mac.update(body);
+        let expected = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert_eq!(sign_payload(secret, 1_700_000_000, body), expected);
+        assert_ne!(sign_payload(secret, 1_700_000_001, body), expected);
+    }
+
+    #[test]
+    fn workspace_patches_map_to_workspace_events() {
+        let id = Uuid::new_v4();
+        let add = Patch(vec![PatchOperation::Add(AddOperation {
+            path: format!("/workspaces/{id}").as_str().try_into().unwrap(),
+            value: json!({ "id": id }),
+        })]);
+        let events = WebhookEvent::from_events_patch(&add, &mut ActiveProcesses::default());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, WebhookEventType::WorkspaceCreated);
+        assert_eq!(events[0].entity_id, id.to_string());
+        assert_eq!(events[0].data, json!({ "id": id }));
+
+        let remove = Patch(vec![PatchOperation::Remove(RemoveOperation {
+            path: format!("/workspaces/{id}").as_str().try_into().unwrap(),
+        })]);
+        let events = WebhookEvent::from_events_patch(&remove, &mut ActiveProcesses::default());
+        assert_eq!(events[0].event_type, WebhookEventType::WorkspaceDeleted);
+        assert!(events[0].data.is_null());
+    }
+
+    #[test]
+    fn execution_process_status_selects_event_type() {
+        let cases = [
+            ("running", WebhookEventType::ExecutionProcessUpdated),
+            ("completed", WebhookEventType::ExecutionProcessCompleted),
+            ("failed", WebhookEventType::ExecutionProcessFailed),
+            ("killed", WebhookEventType::ExecutionProcessKilled),
+        ];
+        for (status, expected) in cases {
+            let path = format!("/execution_processes/{}", Uuid::new_v4());
+            let mut processes = ActiveProcesses::default();
+            WebhookEvent::from_events_patch(
+                &replace(&path, json!({ "status": "running" })),
+                &mut processes,
+            );
+            let events = WebhookEvent::from_events_patch(
+                &replace(&path, json!({ "status": status })),
+                &mut processes,
+            );
+            assert_eq!(events[0].event_type, expected, "status {status}");
+        }
+    }
+
+    #[test]
+    fn terminal_event_is_emitted_once_per_transition() {
+        let path = format!("/execution_processes/{}", Uuid::new_v4());
+        let mut processes = ActiveProcesses::default();
+        let mut event_type = |value: Value| {
+            WebhookEvent::from_events_patch(&replace(&path, value), &mut processes)[0].event_type
+        };
+
+        assert_eq!(
+            event_type(json!({ "status": "running" })),
+            WebhookEventType::ExecutionProcessUpdated
+        );
+        assert_eq!(
+            event_type(json!({ "status": "completed" })),
+            WebhookEventType::ExecutionProcessCompleted
+        );
+        // Later row updates (e.g. token usage) keep the completed status
+        assert_eq!(
+            event_type(json!({ "status": "completed", "dropped": true })),
+            WebhookEventType::ExecutionProcessUpdated
+        );
+    }
+
+    #[test]
+    fn unseen_finished_process_is_an_update() {
+        let path = format!("/execution_processes/{}", Uuid::new_v4());
+        let events = WebhookEvent::from_events_patch(
+            &replace(&path, json!({ "status": "failed" })),
+            &mut ActiveProcesses::default(),
+        );
+        assert_eq!(
+            events[0].event_type,
+            WebhookEventType::ExecutionProcessUpdated
+        );
+    }
+
+    #[test]
+    fn approval_replace_is_not_a_new_request() {
+        let path = format!("/pending/{}", Uuid::new_v4());
+        assert!(WebhookEvent::from_approvals_patch(&replace(&path, json!({}))).is_empty());
+    }
+
+    #[test]
+    fn unrelated_paths_are_ignored() {
+        assert!(
+            WebhookEvent::from_events_patch(
+                &replace("/scratch", json!({})),
+                &mut ActiveProcesses::default()
+            )
+            .is_empty()
+        );
+        assert!(WebhookEvent::from_approvals_patch(&replace("/pending", json!({}))).is_empty());
+    }
+
+    #[test]
+    fn event_type_round_trips_through_strings() {
+        assert_eq!(
+            WebhookEventType::ApprovalRequested.to_string(),
+            "approval.requested"
+        );
+        assert_eq!(
+            "execution_process.completed"
+                .parse::<WebhookEventType>()
+                .unwrap(),
+            WebhookEventType::ExecutionProcessCompleted
+        );
+        assert_eq!(
+            serde_json::to_value(WebhookEventType::WorkspaceUpdated).unwrap(),
+            json!("workspace.updated")
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Per-webhook subscription filters: which event types a webhook receives and
+//! which workspaces (by repo, name or branch) those events may come from.
+
+use std::collections::HashSet;
+
+use db::models::webhook::{Webhook, WebhookError};
+use globset::{Glob, GlobMatcher};
+use uuid::Uuid;
+
+use crate::services::webhook_event::{WebhookEvent, WebhookEventType};
+
+/// The workspace an event belongs to, resolved once per event and only when
+/// at least one webhook has a repo or glob filter.
+#[derive(Debug, Clone)]
+pub struct WebhookEventScope {
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    pub branch: String,
+    pub repo_ids: Vec<Uuid>,
+}
+
+/// Compiled form of the filter columns on a `Webhook`.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookFilter {
+    event_types: Option<HashSet<WebhookEventType>>,
+    repo_ids: Option<HashSet<Uuid>>,
+    workspace_glob: Option<GlobMatcher>,
+    branch_glob: Option<GlobMatcher>,
+}
+
+impl WebhookFilter {
+    pub fn from_webhook(hook: &Webhook) -> Result<Self, WebhookError> {
+        Ok(Self {
+            event_types: hook
+                .event_types
+                .as_ref()
+                .map(|types| parse_event_types(types))
+                .transpose()?,
+            repo_ids: hook
+                .repo_ids
+                .as_ref()
+                .map(|ids| ids.iter().copied().collect()),
+            workspace_glob: hook
+                .workspace_glob
+                .as_deref()
+                .map(compile_glob)
+                .transpose()?,
+            branch_glob: hook.branch_glob.as_deref().map(compile_glob).transpose()?,
+        })
+    }
+
+    /// Whether matching this filter requires knowing the event's workspace.
+    pub fn is_scoped(&self) -> bool {
+        self.repo_ids.is_some() || self.workspace_glob.is_some() || self.branch_glob.is_some()
+    }
+
+    /// Events whose workspace could not be resolved (e.g. deletions) only
+    /// match filters that aren't scoped.
+    pub fn matches(&self, event: &WebhookEvent, scope: Option<&WebhookEventScope>) -> bool {
+        if let Some(types) = &self.event_types
+            && !types.contains(&event.event_type)
+        {
+            return false;
+        }
+        if !self.is_scoped() {
+            return true;
+        }
+        let Some(scope) = scope else {
+            return false;
+        };
+        if let Some(repo_ids) = &self.repo_ids
+            && !scope.repo_ids.iter().any(|id| repo_ids.contains(id))
+        {
+            return false;
+        }
+        if let Some(glob) = &self.workspace_glob
+            && !scope
+                .workspace_name
+                .as_deref()
+                .is_some_and(|name| glob.is_match(name))
+        {
+            return false;
+        }
+        if let Some(glob) = &self.branch_glob
+            && !glob.is_match(&scope.branch)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Check user-supplied filter values before they're stored. Empty lists are
+/// rejected because they would silently mute the webhook; `null` is the way
+/// to remove a restriction.
+pub fn validate_filters(
+    event_types: Option<&[String]>,
+    repo_ids: Option<&[Uuid]>,
+    workspace_glob: Option<&str>,
+    branch_glob: Option<&str>,
+) -> Result<(), WebhookError> {
+    if let Some(types) = event_types {
+        if types.is_empty() {
+            return Err(WebhookError::InvalidFilter(
+                "event_types must not be empty".to_string(),
+            ));
+        }
+        parse_event_types(types)?;
+    }
+    if repo_ids.is_some_and(|ids| ids.is_empty()) {
+        return Err(WebhookError::InvalidFilter(
+            "repo_ids must not be empty".to_string(),
+        ));
+    }
+    if let Some(glob) = workspace_glob {
+        compile_glob(glob)?;
+    }
+    if let Some(glob) = branch_glob {
+        compile_glob(glob)?;
+    }
+    Ok(())
+}
+
+fn parse_event_types(types: &[String]) -> Result<HashSet<WebhookEventType>, WebhookError> {
+    types
+        .iter()
+        .map(|t| {
+            t.parse::<WebhookEventType>()
+                .map_err(|_| WebhookError::InvalidFilter(format!("unknown event type '{t}'")))
+        })
+        .collect()
+}
+
+fn compile_glob(pattern: &str) -> Result<GlobMatcher, WebhookError> {
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| WebhookError::InvalidFilter(format!("invalid glob '{pattern}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::services::webhook_event::{WEBHOOK_EVENT_VERSION, WebhookEntityType};
+
+    fn event(event_type: WebhookEventType) -> WebhookEvent {
+        WebhookEvent {
+            version: WEBHOOK_EVENT_VERSION,
+            id: Uuid::new_v4(),
+            event_type,
+            entity_type: WebhookEntityType::Workspace,
+            entity_id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            data: Value::Null,
+        }
+    }
+
+    fn scope(name: Option<&str>, branch: &str, repo_ids: Vec<Uuid>) -> WebhookEventScope {
+        WebhookEventScope {
+            workspace_id: Uuid::new_v4(),
+            workspace_name: name.map(str::to_string),
+            branch: branch.to_string(),
+            repo_ids,
+        }
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = WebhookFilter::default();
+        assert!(!filter.is_scoped());
+        assert!(filter.matches(&event(WebhookEventType::WorkspaceDeleted), None));
+    }
+
+    #[test]
+    fn event_types_restrict_delivery() {
+        let filter = WebhookFilter {
+            event_types: Some(
+                parse_event_types(&["execution_process.failed".to_string()]).unwrap(),
+            ),
+            ..Default::default()
+        };
+        assert!(filter.matches(&event(WebhookEventType::ExecutionProcessFailed), None));
+        assert!(!filter.matches(&event(WebhookEventType::WorkspaceUpdated), None));
+    }
+
+    #[test]
+    fn scoped_filters_require_matching_workspace() {
+        let repo = Uuid::new_v4();
+        let filter = WebhookFilter {
+            repo_ids: Some(HashSet::from([repo])),
+            branch_glob: Some(compile_glob("vk/*").unwrap()),
+            ..Default::default()
+        };
+        let ev = event(WebhookEventType::WorkspaceUpdated);
+
+        assert!(filter.is_scoped());
+        assert!(!filter.matches(&ev, None));
+        assert!(filter.matches(&ev, Some(&scope(None, "vk/1234-fix", vec![repo]))));
+        assert!(!filter.matches(&ev, Some(&scope(None, "main", vec![repo]))));
+        assert!(!filter.matches(&ev, Some(&scope(None, "vk/1234-fix", vec![Uuid::new_v4()]))));
+    }
+
+    #[test]
+    fn workspace_glob_matches_name() {
+        let filter = WebhookFilter {
+            workspace_glob: Some(compile_glob("release*").unwrap()),
+            ..Default::default()
+        };
+        let ev = event(WebhookEventType::WorkspaceUpdated);
+        assert!(filter.matches(&ev, Some(&scope(Some("release 1.2"), "b", vec![]))));
+        assert!(!filter.matches(&ev, Some(&scope(Some("hotfix"), "b", vec![]))));
+        assert!(!filter.matches(&ev, Some(&scope(None, "b", vec![]))));
+    }
+
+    #[test]
+    fn validation_rejects_unknown_types_and_bad_globs() {
+        assert!(
+            validate_filters(Some(&["workspace.created".to_string()]), None, None, None).is_ok()
+        );
+        assert!(matches!(
+            validate_filters(Some(&["patch".to_string()]), None, None, None),
+            Err(WebhookError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            validate_filters(Some(&[]), None, None, None),
+            Err(WebhookError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            validate_filters(None, Some(&[]), None, None),
+            Err(WebhookError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            validate_filters(None, None, None, Some("feature/[")),
+            Err(WebhookError::InvalidFilter(_))
+        ));
+    }
+}
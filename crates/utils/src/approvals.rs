@@ -5,6 +5,11 @@ use uuid::Uuid;
 
 pub const APPROVAL_TIMEOUT_SECONDS: i64 = 36000; // 10 hours
 
+/// Header a local client sets when answering an approval to identify itself in
+/// the approval history. The only recognised value is `mcp`; relay clients are
+/// identified by their signed request instead.
+pub const APPROVAL_CLIENT_HEADER: &str = "x-vk-approval-client";
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ApprovalRequest {
     pub id: String,
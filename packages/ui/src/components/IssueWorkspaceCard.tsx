@@ -43,7 +43,12 @@ export interface WorkspaceWithStats {
   hasRunningDevServer?: boolean;
   hasUnseenActivity?: boolean;
   latestProcessCompletedAt?: string;
-  latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
+  latestProcessStatus?:
+    | 'queued'
+    | 'running'
+    | 'completed'
+    | 'failed'
+    | 'killed';
   hostName?: string;
 }
 
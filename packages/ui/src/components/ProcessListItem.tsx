@@ -26,6 +26,8 @@ function getRunReasonLabel(runReason: string): string {
       return 'Cleanup Script';
     case 'archivescript':
       return 'Archive Script';
+    case 'testscript':
+      return 'Test Script';
     case 'devserver':
       return 'Dev Server';
     default:
@@ -40,6 +42,7 @@ function getRunReasonIcon(runReason: string): typeof TerminalIcon {
     case 'setupscript':
     case 'cleanupscript':
     case 'archivescript':
+    case 'testscript':
       return GearIcon;
     case 'devserver':
       return GlobeIcon;
@@ -50,6 +53,8 @@ function getRunReasonIcon(runReason: string): typeof TerminalIcon {
 
 function getStatusColor(status: string): string {
   switch (status) {
+    case 'queued':
+      return 'bg-warning';
     case 'running':
       return 'bg-info';
     case 'completed':
@@ -31,7 +31,12 @@ export interface WorkspacesSidebarWorkspace {
   hasRunningDevServer?: boolean;
   hasUnseenActivity?: boolean;
   latestProcessCompletedAt?: string;
-  latestProcessStatus?: 'running' | 'completed' | 'failed' | 'killed';
+  latestProcessStatus?:
+    | 'queued'
+    | 'running'
+    | 'completed'
+    | 'failed'
+    | 'killed';
   prStatus?: 'open' | 'merged' | 'closed' | 'unknown';
   hostName?: string;
 }
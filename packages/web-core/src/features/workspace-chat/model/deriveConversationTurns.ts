@@ -13,7 +13,9 @@ type ScriptTurnKind =
   | 'setup_script'
   | 'cleanup_script'
   | 'archive_script'
-  | 'tool_install_script';
+  | 'tool_install_script'
+  | 'test_script'
+  | 'verification_script';
 
 export interface ConversationAgentTurn {
   readonly key: string;
@@ -206,6 +208,10 @@ function toScriptTurnKind(
       return 'archive_script';
     case 'ToolInstallScript':
       return 'tool_install_script';
+    case 'TestScript':
+      return 'test_script';
+    case 'VerificationScript':
+      return 'verification_script';
     default:
       return null;
   }
@@ -221,6 +227,10 @@ function toScriptToolName(kind: ScriptTurnKind): string {
       return 'Archive Script';
     case 'tool_install_script':
       return 'Tool Install Script';
+    case 'test_script':
+      return 'Test Script';
+    case 'verification_script':
+      return 'Verification Script';
   }
 }
 
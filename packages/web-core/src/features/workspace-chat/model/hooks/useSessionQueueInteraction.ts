@@ -1,7 +1,11 @@
 import { useCallback } from 'react';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { queueApi } from '@/shared/lib/api';
-import type { ExecutorConfig, QueueStatus } from 'shared/types';
+import type {
+  ExecutorConfig,
+  QueuedMessage,
+  QueueStatus,
+} from 'shared/types';
 
 interface UseSessionQueueInteractionOptions {
   /** Session ID for queue operations */
@@ -11,9 +15,11 @@ interface UseSessionQueueInteractionOptions {
 interface UseSessionQueueInteractionResult {
   /** Whether a message is currently queued */
   isQueued: boolean;
-  /** The queued message content, if any */
+  /** All queued messages, in execution order */
+  queuedMessages: QueuedMessage[];
+  /** The content of the most recently queued message, if any */
   queuedMessage: string | null;
-  /** The executor config from the queued message, if any */
+  /** The executor config from the most recently queued message, if any */
   queuedConfig: ExecutorConfig | null;
   /** Whether a queue operation is in progress */
   isQueueLoading: boolean;
@@ -22,7 +28,7 @@ interface UseSessionQueueInteractionResult {
     message: string,
     executorConfig: ExecutorConfig
   ) => Promise<void>;
-  /** Cancel the queued message */
+  /** Cancel all queued messages */
   cancelQueue: () => Promise<void>;
   /** Refresh queue status from server */
   refreshQueueStatus: () => Promise<void>;
@@ -48,9 +54,10 @@ export function useSessionQueueInteraction({
     });
 
   const isQueued = queueStatus.status === 'queued';
-  const queuedMessageData = isQueued
-    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).message
-    : null;
+  const queuedMessages = isQueued
+    ? (queueStatus as Extract<QueueStatus, { status: 'queued' }>).messages
+    : [];
+  const queuedMessageData = queuedMessages[queuedMessages.length - 1] ?? null;
   const queuedMessage = queuedMessageData?.data.message ?? null;
   const queuedConfig: ExecutorConfig | null =
     queuedMessageData?.data.executor_config ?? null;
@@ -104,6 +111,7 @@ export function useSessionQueueInteraction({
 
   return {
     isQueued,
+    queuedMessages,
     queuedMessage,
     queuedConfig,
     isQueueLoading: queueMutation.isPending || cancelMutation.isPending,
@@ -366,6 +366,7 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
       targetBranch: repoWithConflicts.target_branch_name,
       conflictedFiles: repoWithConflicts.conflicted_files ?? [],
       repoName: repoWithConflicts.repo_name,
+      repoId: repoWithConflicts.repo_id,
     });
   }, [workspaceId, repoWithConflicts, attemptBranch]);
 
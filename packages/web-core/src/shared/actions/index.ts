@@ -207,6 +207,7 @@ async function performGuardedMerge(
       targetBranch: repoStatus.target_branch_name,
       conflictedFiles: repoStatus.conflicted_files ?? [],
       repoName: repoStatus.repo_name,
+      repoId: repoStatus.repo_id,
     });
 
     if (result.action === 'resolved') {
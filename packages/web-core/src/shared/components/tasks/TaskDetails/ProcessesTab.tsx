@@ -85,6 +85,8 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
 
   const getStatusColor = (status: ExecutionProcessStatus) => {
     switch (status) {
+      case 'queued':
+        return 'bg-yellow-50 border-yellow-200 text-yellow-800';
       case 'running':
         return 'bg-blue-50 border-blue-200 text-blue-800';
       case 'completed':
@@ -103,6 +105,19 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
     return date.toLocaleString();
   };
 
+  const formatUsage = (process: ExecutionProcess) => {
+    const parts: string[] = [];
+    if (process.peak_memory_bytes !== null) {
+      const mib = Number(process.peak_memory_bytes) / (1024 * 1024);
+      parts.push(t('processes.peakMemory', { mib: mib.toFixed(0) }));
+    }
+    if (process.cpu_time_ms !== null) {
+      const seconds = Number(process.cpu_time_ms) / 1000;
+      parts.push(t('processes.cpuTime', { seconds: seconds.toFixed(1) }));
+    }
+    return parts.join(' · ');
+  };
+
   const fetchProcessDetails = useCallback(async (processId: string) => {
     try {
       setLoadingProcessId(processId);
@@ -255,6 +270,16 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.status === 'queued' && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('processes.queued')}
+                        </p>
+                      )}
+                      {process.failure_reason === 'oom_killed' && (
+                        <p className="text-xs text-destructive mt-1">
+                          {t('processes.oomKilled')}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
@@ -272,6 +297,9 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                         </span>
                       )}
                     </div>
+                    {formatUsage(process) && (
+                      <div className="mt-1">{formatUsage(process)}</div>
+                    )}
                   </div>
                 </div>
               ))}
@@ -7,6 +7,7 @@ export const PROCESS_RUN_REASONS = {
   ARCHIVE_SCRIPT: 'archivescript' as ExecutionProcessRunReason,
   CODING_AGENT: 'codingagent' as ExecutionProcessRunReason,
   DEV_SERVER: 'devserver' as ExecutionProcessRunReason,
+  TEST_SCRIPT: 'testscript' as ExecutionProcessRunReason,
 } as const;
 
 export const isCodingAgent = (
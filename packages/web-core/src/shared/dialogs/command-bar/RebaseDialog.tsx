@@ -129,6 +129,7 @@ function RebaseDialogContent({
           targetBranch: repoStatus.target_branch_name,
           conflictedFiles: repoStatus.conflicted_files ?? [],
           repoName: repoStatus.repo_name,
+          repoId,
         });
       } else {
         // Rebase in progress WITHOUT conflicts -> show simpler dialog
@@ -197,6 +198,7 @@ function RebaseDialogContent({
             targetBranch: errorData.target_branch,
             conflictedFiles: errorData.conflicted_files,
             repoName: undefined,
+            repoId,
           });
         }
         return;
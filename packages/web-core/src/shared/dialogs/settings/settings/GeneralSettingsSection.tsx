@@ -9,11 +9,14 @@ import {
 import { FolderPickerDialog } from '@/shared/dialogs/shared/FolderPickerDialog';
 import {
   type BaseCodingAgent,
+  type ConcurrencyConfig,
   DEFAULT_COMMIT_REMINDER_PROMPT,
   DEFAULT_PR_DESCRIPTION_PROMPT,
   EditorType,
   type ExecutorProfileId,
   type InputEditorMode,
+  type SandboxConfig,
+  type SandboxNetwork,
   type SendMessageShortcut,
   SoundFile,
   ThemeMode,
@@ -253,6 +256,39 @@ export function GeneralSettingsSection() {
     label: toPrettyCase(sound),
   }));
 
+  const sandboxNetworkOptions: { value: SandboxNetwork; label: string }[] = [
+    { value: 'allow', label: t('settings.general.sandbox.network.allow') },
+    { value: 'deny', label: t('settings.general.sandbox.network.deny') },
+  ];
+
+  const sandbox: SandboxConfig = draft?.sandbox ?? {
+    enabled: false,
+    network: 'allow',
+    writable_paths: [],
+  };
+
+  const concurrency: ConcurrencyConfig = draft?.concurrency ?? {
+    max_coding_agents: null,
+    max_per_executor: {},
+  };
+
+  // Blank or zero means unlimited
+  const parseAgentLimit = (value: string): number | null =>
+    Number(value.replace(/\D/g, '')) || null;
+
+  const updateExecutorLimit = (executor: BaseCodingAgent, value: string) => {
+    const limit = parseAgentLimit(value);
+    const maxPerExecutor = { ...concurrency.max_per_executor };
+    if (limit === null) {
+      delete maxPerExecutor[executor];
+    } else {
+      maxPerExecutor[executor] = limit;
+    }
+    updateDraft({
+      concurrency: { ...concurrency, max_per_executor: maxPerExecutor },
+    });
+  };
+
   return (
     <>
       {/* Status messages */}
@@ -530,6 +566,56 @@ export function GeneralSettingsSection() {
         </SettingsField>
       </SettingsCard>
 
+      {/* Concurrency */}
+      <SettingsCard
+        title={t('settings.general.concurrency.title')}
+        description={t('settings.general.concurrency.description')}
+      >
+        <SettingsField
+          label={t('settings.general.concurrency.maxAgents.label')}
+          description={t('settings.general.concurrency.maxAgents.helper')}
+        >
+          <SettingsInput
+            value={concurrency.max_coding_agents?.toString() ?? ''}
+            onChange={(value) =>
+              updateDraft({
+                concurrency: {
+                  ...concurrency,
+                  max_coding_agents: parseAgentLimit(value),
+                },
+              })
+            }
+            placeholder={t('settings.general.concurrency.unlimited')}
+          />
+        </SettingsField>
+
+        <SettingsField
+          label={t('settings.general.concurrency.perExecutor.label')}
+          description={t('settings.general.concurrency.perExecutor.helper')}
+        >
+          <div className="grid grid-cols-2 gap-2">
+            {executorOptions.map((option) => {
+              const executor = option.value as BaseCodingAgent;
+              return (
+                <label
+                  key={executor}
+                  className="flex items-center gap-2 text-sm text-normal"
+                >
+                  <span className="w-1/2 truncate">{option.label}</span>
+                  <SettingsInput
+                    value={
+                      concurrency.max_per_executor[executor]?.toString() ?? ''
+                    }
+                    onChange={(value) => updateExecutorLimit(executor, value)}
+                    placeholder={t('settings.general.concurrency.unlimited')}
+                  />
+                </label>
+              );
+            })}
+          </div>
+        </SettingsField>
+      </SettingsCard>
+
       {/* Git */}
       <SettingsCard
         title={t('settings.general.git.title')}
@@ -697,6 +783,62 @@ export function GeneralSettingsSection() {
         )}
       </SettingsCard>
 
+      {/* Sandbox */}
+      <SettingsCard
+        title={t('settings.general.sandbox.title')}
+        description={t('settings.general.sandbox.description')}
+      >
+        <SettingsCheckbox
+          id="sandbox-enabled"
+          label={t('settings.general.sandbox.enabled.label')}
+          description={t('settings.general.sandbox.enabled.helper')}
+          checked={sandbox.enabled}
+          onChange={(checked) =>
+            updateDraft({ sandbox: { ...sandbox, enabled: checked } })
+          }
+        />
+
+        {sandbox.enabled && (
+          <>
+            <SettingsField
+              label={t('settings.general.sandbox.network.label')}
+              description={t('settings.general.sandbox.network.helper')}
+            >
+              <SettingsSelect
+                value={sandbox.network}
+                options={sandboxNetworkOptions}
+                onChange={(value: SandboxNetwork) =>
+                  updateDraft({ sandbox: { ...sandbox, network: value } })
+                }
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.general.sandbox.writablePaths.label')}
+              description={t('settings.general.sandbox.writablePaths.helper')}
+            >
+              <SettingsTextarea
+                value={sandbox.writable_paths.join('\n')}
+                onChange={(value) =>
+                  updateDraft({
+                    sandbox: {
+                      ...sandbox,
+                      writable_paths: value
+                        .split('\n')
+                        .map((path) => path.trim())
+                        .filter(Boolean),
+                    },
+                  })
+                }
+                placeholder={t(
+                  'settings.general.sandbox.writablePaths.placeholder'
+                )}
+              />
+            </SettingsField>
+          </>
+        )}
+      </SettingsCard>
+
       {/* Notifications */}
       <SettingsCard
         title={t('settings.general.notifications.title')}
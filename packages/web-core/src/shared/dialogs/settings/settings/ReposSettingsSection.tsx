@@ -2,7 +2,12 @@ import { useCallback, useEffect, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useQuery, useQueryClient } from '@tanstack/react-query';
 import { isEqual } from 'lodash';
-import { GitBranchIcon, PlusIcon, SpinnerIcon } from '@phosphor-icons/react';
+import {
+  GitBranchIcon,
+  PlusIcon,
+  SpinnerIcon,
+  TrashIcon,
+} from '@phosphor-icons/react';
 import { Loader2 } from 'lucide-react';
 import { create, useModal } from '@ebay/nice-modal-react';
 import { useMachineRepoBranches } from '@/shared/hooks/useRepoBranches';
@@ -11,7 +16,14 @@ import { useAllOrganizationProjects } from '@/shared/hooks/useAllOrganizationPro
 import { getProjectRepoDefaults } from '@/shared/hooks/useProjectRepoDefaults';
 import { ApiError } from '@/shared/lib/api';
 import { defineModal } from '@/shared/lib/modals';
-import type { Repo, UpdateRepo } from 'shared/types';
+import type {
+  GitHubAccess,
+  Repo,
+  SigningMode,
+  UpdateRepo,
+  VerificationStep,
+  VerificationStepInput,
+} from 'shared/types';
 import { SearchableDropdownContainer } from '@/shared/components/ui-new/containers/SearchableDropdownContainer';
 import { FolderPickerDialog } from '@/shared/dialogs/shared/FolderPickerDialog';
 import { Button } from '@vibe/ui/components/Button';
@@ -37,6 +49,7 @@ import {
   SettingsInput,
   SettingsTextarea,
   SettingsCheckbox,
+  SettingsSelect,
   SettingsSaveBar,
 } from './SettingsComponents';
 import { useSettingsMachineClient } from './SettingsHostContext';
@@ -49,11 +62,26 @@ interface RepoScriptsFormState {
   parallel_setup_script: boolean;
   cleanup_script: string;
   archive_script: string;
+  verification_steps: VerificationStepInput[];
+  verify_after_agent: boolean;
+  require_verification: boolean;
+  commit_message_template: string;
+  conventional_commits: boolean;
+  agent_commit_trailers: boolean;
+  commit_signing: SigningMode;
+  signing_key: string;
+  cpu_limit: string;
+  memory_limit_mb: string;
+  pids_limit: string;
   copy_files: string;
   dev_server_script: string;
+  github_access: GitHubAccess;
 }
 
-function repoToFormState(repo: Repo): RepoScriptsFormState {
+function repoToFormState(
+  repo: Repo,
+  steps: VerificationStep[]
+): RepoScriptsFormState {
   return {
     display_name: repo.display_name,
     default_working_dir: repo.default_working_dir ?? '',
@@ -62,11 +90,32 @@ function repoToFormState(repo: Repo): RepoScriptsFormState {
     parallel_setup_script: repo.parallel_setup_script,
     cleanup_script: repo.cleanup_script ?? '',
     archive_script: repo.archive_script ?? '',
+    verification_steps: steps.map(({ name, script }) => ({ name, script })),
+    verify_after_agent: repo.verify_after_agent,
+    require_verification: repo.require_verification,
+    commit_message_template: repo.commit_message_template ?? '',
+    conventional_commits: repo.conventional_commits,
+    agent_commit_trailers: repo.agent_commit_trailers,
+    commit_signing: repo.commit_signing,
+    signing_key: repo.signing_key ?? '',
+    cpu_limit: repo.cpu_limit?.toString() ?? '',
+    memory_limit_mb: repo.memory_limit_mb?.toString() ?? '',
+    pids_limit: repo.pids_limit?.toString() ?? '',
     copy_files: repo.copy_files ?? '',
     dev_server_script: repo.dev_server_script ?? '',
+    github_access: repo.github_access,
   };
 }
 
+/** A resource limit input as a number; `null` when empty, `NaN` if invalid */
+function parseLimit(value: string, integer: boolean): number | null {
+  const trimmed = value.trim();
+  if (!trimmed) return null;
+  const limit = Number(trimmed);
+  if (limit <= 0 || (integer && !Number.isInteger(limit))) return NaN;
+  return limit;
+}
+
 // ── Remove Repo confirmation dialog ──────────────────────────────────
 interface RemoveRepoDialogProps {
   repoName: string;
@@ -179,6 +228,24 @@ export function ReposSettingsSection({
 
   const [selectedRepo, setSelectedRepo] = useState<Repo | null>(null);
 
+  // Fetch the verification steps of the selected repo
+  const verificationStepsQueryKey = [
+    ...reposQueryKey,
+    selectedRepoId,
+    'verification-steps',
+  ] as const;
+  const { data: verificationSteps } = useQuery({
+    queryKey: verificationStepsQueryKey,
+    queryFn: () => {
+      if (!machineClient) {
+        throw new Error('Machine client is required');
+      }
+
+      return machineClient.getVerificationSteps(selectedRepoId);
+    },
+    enabled: machineClient != null && !!selectedRepoId,
+  });
+
   // Form state
   const [draft, setDraft] = useState<RepoScriptsFormState | null>(null);
   const [saving, setSaving] = useState(false);
@@ -225,9 +292,9 @@ export function ReposSettingsSection({
 
   // Check for unsaved changes
   const hasUnsavedChanges = useMemo(() => {
-    if (!draft || !selectedRepo) return false;
-    return !isEqual(draft, repoToFormState(selectedRepo));
-  }, [draft, selectedRepo]);
+    if (!draft || !selectedRepo || !verificationSteps) return false;
+    return !isEqual(draft, repoToFormState(selectedRepo, verificationSteps));
+  }, [draft, selectedRepo, verificationSteps]);
 
   // Handle repo selection
   const handleRepoSelect = useCallback(
@@ -326,14 +393,22 @@ export function ReposSettingsSection({
       return;
     }
 
-    if (hasUnsavedChanges) return;
+    if (hasUnsavedChanges || !verificationSteps) return;
 
-    setDraft(repoToFormState(nextRepo));
-  }, [repos, selectedRepoId, hasUnsavedChanges]);
+    setDraft(repoToFormState(nextRepo, verificationSteps));
+  }, [repos, selectedRepoId, hasUnsavedChanges, verificationSteps]);
 
   const handleSave = async () => {
     if (!draft || !selectedRepo) return;
 
+    const cpuLimit = parseLimit(draft.cpu_limit, false);
+    const memoryLimit = parseLimit(draft.memory_limit_mb, true);
+    const pidsLimit = parseLimit(draft.pids_limit, true);
+    if ([cpuLimit, memoryLimit, pidsLimit].some(Number.isNaN)) {
+      setError(t('settings.repos.scripts.resourceLimits.invalid'));
+      return;
+    }
+
     setSaving(true);
     setError(null);
     setSuccess(false);
@@ -346,21 +421,40 @@ export function ReposSettingsSection({
         setup_script: draft.setup_script.trim() || null,
         cleanup_script: draft.cleanup_script.trim() || null,
         archive_script: draft.archive_script.trim() || null,
+        verify_after_agent: draft.verify_after_agent,
+        require_verification: draft.require_verification,
+        commit_message_template: draft.commit_message_template.trim() || null,
+        conventional_commits: draft.conventional_commits,
+        agent_commit_trailers: draft.agent_commit_trailers,
+        commit_signing: draft.commit_signing,
+        signing_key: draft.signing_key.trim() || null,
+        cpu_limit: cpuLimit,
+        memory_limit_mb: memoryLimit,
+        pids_limit: pidsLimit,
         copy_files: draft.copy_files.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
+        github_access: draft.github_access,
       };
 
       if (!machineClient) {
         return;
       }
 
+      const updatedSteps = await machineClient.replaceVerificationSteps(
+        selectedRepo.id,
+        draft.verification_steps.map((step) => ({
+          name: step.name.trim(),
+          script: step.script.trim(),
+        }))
+      );
       const updatedRepo = await machineClient.updateRepo(
         selectedRepo.id,
         updateData
       );
+      queryClient.setQueryData(verificationStepsQueryKey, updatedSteps);
       setSelectedRepo(updatedRepo);
-      setDraft(repoToFormState(updatedRepo));
+      setDraft(repoToFormState(updatedRepo, updatedSteps));
       queryClient.setQueryData(reposQueryKey, (old: Repo[] | undefined) =>
         old?.map((r) => (r.id === updatedRepo.id ? updatedRepo : r))
       );
@@ -376,8 +470,8 @@ export function ReposSettingsSection({
   };
 
   const handleDiscard = () => {
-    if (!selectedRepo) return;
-    setDraft(repoToFormState(selectedRepo));
+    if (!selectedRepo || !verificationSteps) return;
+    setDraft(repoToFormState(selectedRepo, verificationSteps));
   };
 
   const updateDraft = (updates: Partial<RepoScriptsFormState>) => {
@@ -387,6 +481,21 @@ export function ReposSettingsSection({
     });
   };
 
+  const updateVerificationStep = (
+    index: number,
+    updates: Partial<VerificationStepInput>
+  ) => {
+    setDraft((prev) => {
+      if (!prev) return prev;
+      return {
+        ...prev,
+        verification_steps: prev.verification_steps.map((step, i) =>
+          i === index ? { ...step, ...updates } : step
+        ),
+      };
+    });
+  };
+
   if (reposLoading) {
     return (
       <div className="flex items-center justify-center py-8 gap-2">
@@ -566,6 +675,41 @@ export function ReposSettingsSection({
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.general.githubAccess.label', {
+                defaultValue: 'GitHub Access',
+              })}
+              description={t('settings.repos.general.githubAccess.helper', {
+                defaultValue:
+                  'How pull requests are created and tracked for GitHub remotes. Auto uses the REST API when a GitHub token is configured and the gh CLI otherwise.',
+              })}
+            >
+              <SettingsSelect<GitHubAccess>
+                value={draft.github_access}
+                options={[
+                  {
+                    value: 'auto',
+                    label: t('settings.repos.general.githubAccess.auto', {
+                      defaultValue: 'Auto',
+                    }),
+                  },
+                  {
+                    value: 'api',
+                    label: t('settings.repos.general.githubAccess.api', {
+                      defaultValue: 'REST API',
+                    }),
+                  },
+                  {
+                    value: 'cli',
+                    label: t('settings.repos.general.githubAccess.cli', {
+                      defaultValue: 'gh CLI',
+                    }),
+                  },
+                ]}
+                onChange={(value) => updateDraft({ github_access: value })}
+              />
+            </SettingsField>
+
             <div className="border-t border-primary pt-base mt-base">
               <div className="flex items-center justify-between">
                 <div>
@@ -687,6 +831,180 @@ export function ReposSettingsSection({
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.scripts.verification.label')}
+              description={t('settings.repos.scripts.verification.helper')}
+            >
+              <div className="space-y-base">
+                {draft.verification_steps.map((step, index) => (
+                  <div key={index} className="space-y-half">
+                    <div className="flex items-center gap-base">
+                      <SettingsInput
+                        value={step.name}
+                        onChange={(value) =>
+                          updateVerificationStep(index, { name: value })
+                        }
+                        placeholder={t(
+                          'settings.repos.scripts.verification.namePlaceholder'
+                        )}
+                      />
+                      <PrimaryButton
+                        variant="tertiary"
+                        onClick={() =>
+                          updateDraft({
+                            verification_steps: draft.verification_steps.filter(
+                              (_, i) => i !== index
+                            ),
+                          })
+                        }
+                      >
+                        <TrashIcon className="size-icon-sm" weight="bold" />
+                      </PrimaryButton>
+                    </div>
+                    <SettingsTextarea
+                      value={step.script}
+                      onChange={(value) =>
+                        updateVerificationStep(index, { script: value })
+                      }
+                      placeholder="npm test"
+                      rows={2}
+                      monospace
+                    />
+                  </div>
+                ))}
+                <PrimaryButton
+                  variant="secondary"
+                  onClick={() =>
+                    updateDraft({
+                      verification_steps: [
+                        ...draft.verification_steps,
+                        { name: '', script: '' },
+                      ],
+                    })
+                  }
+                >
+                  <PlusIcon className="size-icon-sm" weight="bold" />
+                  {t('settings.repos.scripts.verification.addStep')}
+                </PrimaryButton>
+              </div>
+            </SettingsField>
+
+            <SettingsCheckbox
+              id="verify-after-agent"
+              label={t('settings.repos.scripts.verification.afterAgentLabel')}
+              description={t(
+                'settings.repos.scripts.verification.afterAgentHelper'
+              )}
+              checked={draft.verify_after_agent}
+              onChange={(checked) =>
+                updateDraft({ verify_after_agent: checked })
+              }
+              disabled={draft.verification_steps.length === 0}
+            />
+
+            <SettingsCheckbox
+              id="require-verification"
+              label={t('settings.repos.scripts.verification.requiredLabel')}
+              description={t(
+                'settings.repos.scripts.verification.requiredHelper'
+              )}
+              checked={draft.require_verification}
+              onChange={(checked) =>
+                updateDraft({ require_verification: checked })
+              }
+              disabled={draft.verification_steps.length === 0}
+            />
+
+            <SettingsField
+              label={t('settings.repos.scripts.commitMessages.templateLabel')}
+              description={t(
+                'settings.repos.scripts.commitMessages.templateHelper'
+              )}
+            >
+              <SettingsTextarea
+                value={draft.commit_message_template}
+                onChange={(value) =>
+                  updateDraft({ commit_message_template: value })
+                }
+                placeholder={t(
+                  'settings.repos.scripts.commitMessages.templatePlaceholder'
+                )}
+                rows={3}
+                monospace
+              />
+            </SettingsField>
+
+            <SettingsCheckbox
+              id="conventional-commits"
+              label={t(
+                'settings.repos.scripts.commitMessages.conventionalLabel'
+              )}
+              description={t(
+                'settings.repos.scripts.commitMessages.conventionalHelper'
+              )}
+              checked={draft.conventional_commits}
+              onChange={(checked) =>
+                updateDraft({ conventional_commits: checked })
+              }
+            />
+
+            <SettingsCheckbox
+              id="agent-commit-trailers"
+              label={t('settings.repos.scripts.commitMessages.trailersLabel')}
+              description={t(
+                'settings.repos.scripts.commitMessages.trailersHelper'
+              )}
+              checked={draft.agent_commit_trailers}
+              onChange={(checked) =>
+                updateDraft({ agent_commit_trailers: checked })
+              }
+            />
+
+            <SettingsField
+              label={t('settings.repos.scripts.commitSigning.label')}
+              description={t('settings.repos.scripts.commitSigning.helper')}
+            >
+              <SettingsSelect<SigningMode>
+                value={draft.commit_signing}
+                options={[
+                  {
+                    value: 'off',
+                    label: t('settings.repos.scripts.commitSigning.off'),
+                  },
+                  {
+                    value: 'git_config',
+                    label: t('settings.repos.scripts.commitSigning.gitConfig'),
+                  },
+                  {
+                    value: 'gpg',
+                    label: t('settings.repos.scripts.commitSigning.gpg'),
+                  },
+                  {
+                    value: 'ssh',
+                    label: t('settings.repos.scripts.commitSigning.ssh'),
+                  },
+                ]}
+                onChange={(value) => updateDraft({ commit_signing: value })}
+              />
+            </SettingsField>
+
+            {draft.commit_signing !== 'off' && (
+              <SettingsField
+                label={t('settings.repos.scripts.commitSigning.keyLabel')}
+                description={t(
+                  'settings.repos.scripts.commitSigning.keyHelper'
+                )}
+              >
+                <SettingsInput
+                  value={draft.signing_key}
+                  onChange={(value) => updateDraft({ signing_key: value })}
+                  placeholder={t(
+                    'settings.repos.scripts.commitSigning.keyPlaceholder'
+                  )}
+                />
+              </SettingsField>
+            )}
+
             <SettingsField
               label={t('settings.repos.scripts.copyFiles.label')}
               description={t('settings.repos.scripts.copyFiles.helper')}
@@ -698,6 +1016,52 @@ export function ReposSettingsSection({
                 rows={3}
               />
             </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.scripts.resourceLimits.cpuLabel')}
+              description={t('settings.repos.scripts.resourceLimits.cpuHelper')}
+            >
+              <SettingsInput
+                value={draft.cpu_limit}
+                onChange={(value) => updateDraft({ cpu_limit: value })}
+                placeholder={t(
+                  'settings.repos.scripts.resourceLimits.cpuPlaceholder'
+                )}
+                error={Number.isNaN(parseLimit(draft.cpu_limit, false))}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.scripts.resourceLimits.memoryLabel')}
+              description={t(
+                'settings.repos.scripts.resourceLimits.memoryHelper'
+              )}
+            >
+              <SettingsInput
+                value={draft.memory_limit_mb}
+                onChange={(value) => updateDraft({ memory_limit_mb: value })}
+                placeholder={t(
+                  'settings.repos.scripts.resourceLimits.memoryPlaceholder'
+                )}
+                error={Number.isNaN(parseLimit(draft.memory_limit_mb, true))}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.scripts.resourceLimits.pidsLabel')}
+              description={t(
+                'settings.repos.scripts.resourceLimits.pidsHelper'
+              )}
+            >
+              <SettingsInput
+                value={draft.pids_limit}
+                onChange={(value) => updateDraft({ pids_limit: value })}
+                placeholder={t(
+                  'settings.repos.scripts.resourceLimits.pidsPlaceholder'
+                )}
+                error={Number.isNaN(parseLimit(draft.pids_limit, true))}
+              />
+            </SettingsField>
           </SettingsCard>
 
           <SettingsSaveBar
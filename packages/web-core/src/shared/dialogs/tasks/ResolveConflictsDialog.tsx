@@ -17,7 +17,7 @@ import { useUserSystem } from '@/shared/hooks/useUserSystem';
 import { useWorkspaceContext } from '@/shared/hooks/useWorkspaceContext';
 import { useHostId } from '@/shared/providers/HostIdProvider';
 import { workspaceSessionKeys } from '@/shared/hooks/workspaceSessionKeys';
-import { sessionsApi } from '@/shared/lib/api';
+import { sessionsApi, workspacesApi } from '@/shared/lib/api';
 import { useQueryClient } from '@tanstack/react-query';
 import { create, useModal } from '@ebay/nice-modal-react';
 import { defineModal } from '@/shared/lib/modals';
@@ -37,6 +37,9 @@ export interface ResolveConflictsDialogProps {
   targetBranch: string;
   conflictedFiles: string[];
   repoName?: string;
+  // When set, the server builds the prompt and continues the operation once
+  // the agent has resolved every conflict
+  repoId?: string;
 }
 
 export type ResolveConflictsDialogResult =
@@ -51,6 +54,7 @@ const ResolveConflictsDialogImpl = create<ResolveConflictsDialogProps>(
     targetBranch,
     conflictedFiles,
     repoName,
+    repoId,
   }) => {
     const modal = useModal();
     const queryClient = useQueryClient();
@@ -164,18 +168,27 @@ const ResolveConflictsDialogImpl = create<ResolveConflictsDialogProps>(
           return;
         }
 
-        // Send follow-up with conflict resolution instructions
-        await sessionsApi.followUp(targetSessionId, {
-          prompt: conflictInstructions,
-          executor_config: {
-            executor: effectiveProfile.executor,
-            variant: effectiveProfile.variant,
-          },
-          retry_process_id: null,
-          force_when_dirty: null,
-          perform_git_reset: null,
-          override_session_id: null,
-        });
+        const executorConfig = {
+          executor: effectiveProfile.executor,
+          variant: effectiveProfile.variant,
+        };
+        if (repoId) {
+          await workspacesApi.resolveConflicts(workspaceId, {
+            repo_id: repoId,
+            session_id: targetSessionId,
+            executor_config: executorConfig,
+          });
+        } else {
+          // Send follow-up with conflict resolution instructions
+          await sessionsApi.followUp(targetSessionId, {
+            prompt: conflictInstructions,
+            executor_config: executorConfig,
+            retry_process_id: null,
+            force_when_dirty: null,
+            perform_git_reset: null,
+            override_session_id: null,
+          });
+        }
 
         // Invalidate queries and wait for them to complete
         await Promise.all([
@@ -213,6 +226,7 @@ const ResolveConflictsDialogImpl = create<ResolveConflictsDialogProps>(
       createNewSession,
       workspaceId,
       hostId,
+      repoId,
       conflictInstructions,
       queryClient,
       selectSession,
@@ -1,15 +1,18 @@
 import { useCallback } from 'react';
 import { useJsonPatchWsStream } from '@/shared/hooks/useJsonPatchWsStream';
 import { useHostId } from '@/shared/providers/HostIdProvider';
-import type { ExecutionProcess } from 'shared/types';
+import type { ExecutionProcess, QueuedMessage } from 'shared/types';
 
 type ExecutionProcessState = {
   execution_processes: Record<string, ExecutionProcess>;
+  queued_messages?: QueuedMessage[];
 };
 
 interface UseExecutionProcessesResult {
   executionProcesses: ExecutionProcess[];
   executionProcessesById: Record<string, ExecutionProcess>;
+  /** Follow-up messages queued for the session, in execution order */
+  queuedMessages: QueuedMessage[];
   isAttemptRunning: boolean;
   isLoading: boolean;
   isConnected: boolean;
@@ -20,6 +23,7 @@ interface UseExecutionProcessesResult {
  * Stream execution processes for a session via WebSocket (JSON Patch) and expose as array + map.
  * Server sends initial snapshot: replace /execution_processes with an object keyed by id.
  * Live updates arrive at /execution_processes/<id> via add/replace/remove operations.
+ * The session's follow-up queue is replaced wholesale at /queued_messages.
  */
 export const useExecutionProcesses = (
   sessionId: string | undefined,
@@ -39,7 +43,10 @@ export const useExecutionProcesses = (
   }
 
   const initialData = useCallback(
-    (): ExecutionProcessState => ({ execution_processes: {} }),
+    (): ExecutionProcessState => ({
+      execution_processes: {},
+      queued_messages: [],
+    }),
     []
   );
 
@@ -77,14 +84,20 @@ export const useExecutionProcesses = (
       (process.run_reason === 'codingagent' ||
         process.run_reason === 'setupscript' ||
         process.run_reason === 'cleanupscript' ||
-        process.run_reason === 'archivescript') &&
-      process.status === 'running'
+        process.run_reason === 'archivescript' ||
+        process.run_reason === 'testscript') &&
+      (process.status === 'running' || process.status === 'queued')
   );
   const isLoading = !!sessionId && !isInitialized && !error; // until first snapshot
 
+  const queuedMessages = (data?.queued_messages ?? []).filter(
+    (message) => message.session_id === sessionId
+  );
+
   return {
     executionProcesses,
     executionProcessesById,
+    queuedMessages,
     isAttemptRunning,
     isLoading,
     isConnected,
@@ -39,6 +39,10 @@ import {
   BaseCodingAgent,
   ExecutorConfig,
   DraftFollowUpData,
+  FixUpLoop,
+  CheckpointTurn,
+  RestoreCheckpointRequest,
+  BranchFromCheckpointRequest,
   AgentPresetOptionsQuery,
   RunAgentSetupRequest,
   RunAgentSetupResponse,
@@ -68,12 +72,24 @@ import {
   TokenResponse,
   CurrentUserResponse,
   QueueStatus,
+  QueuedExecution,
   PrCommentsResponse,
+  AddressPrCommentsRequest,
+  PrCommentFix,
   MergeWorkspaceRequest,
   PushWorkspaceRequest,
   RepoBranchStatus,
   AbortConflictsRequest,
   ContinueRebaseRequest,
+  ResolveConflictsRequest,
+  ConflictResolution,
+  DiffHunk,
+  RevertChangesRequest,
+  StageChangesRequest,
+  CommitChangesRequest,
+  CommitChangesResponse,
+  RunVerificationRequest,
+  VerificationRunWithResults,
   Session,
   Workspace,
   StartReviewRequest,
@@ -662,6 +678,112 @@ export const workspacesApi = {
     return handleApiResponse<void>(response);
   },
 
+  resolveConflicts: async (
+    workspaceId: string,
+    data: ResolveConflictsRequest
+  ): Promise<ConflictResolution> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ConflictResolution>(response);
+  },
+
+  getConflictResolutions: async (
+    workspaceId: string
+  ): Promise<ConflictResolution[]> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/conflicts/resolutions`
+    );
+    return handleApiResponse<ConflictResolution[]>(response);
+  },
+
+  getFileHunks: async (
+    workspaceId: string,
+    repoId: string,
+    path: string,
+    unstaged = false
+  ): Promise<DiffHunk[]> => {
+    const params = new URLSearchParams({
+      repo_id: repoId,
+      path,
+      unstaged: String(unstaged),
+    });
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/changes/hunks?${params.toString()}`
+    );
+    return handleApiResponse<DiffHunk[]>(response);
+  },
+
+  /** Undo files or hunks of the diff against the target branch */
+  revertChanges: async (
+    workspaceId: string,
+    data: RevertChangesRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/changes/revert`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  stageChanges: async (
+    workspaceId: string,
+    data: StageChangesRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/changes/stage`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  commitChanges: async (
+    workspaceId: string,
+    data: CommitChangesRequest
+  ): Promise<CommitChangesResponse> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/changes/commit`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CommitChangesResponse>(response);
+  },
+
+  getVerificationRuns: async (
+    workspaceId: string
+  ): Promise<VerificationRunWithResults[]> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/verification`
+    );
+    return handleApiResponse<VerificationRunWithResults[]>(response);
+  },
+
+  runVerification: async (
+    workspaceId: string,
+    data: RunVerificationRequest
+  ): Promise<VerificationRunWithResults> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/verification`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<VerificationRunWithResults>(response);
+  },
+
   createPR: async (
     workspaceId: string,
     data: CreatePrApiRequest
@@ -762,6 +884,28 @@ export const workspacesApi = {
     return handleApiResponse<PrCommentsResponse>(response);
   },
 
+  /** Send review comments of the repo's pull request to the coding agent */
+  addressPrComments: async (
+    workspaceId: string,
+    data: AddressPrCommentsRequest
+  ): Promise<PrCommentFix> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/pull-requests/comments/address`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<PrCommentFix>(response);
+  },
+
+  getPrCommentFixes: async (workspaceId: string): Promise<PrCommentFix[]> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/pull-requests/comments/fixes`
+    );
+    return handleApiResponse<PrCommentFix[]>(response);
+  },
+
   /** Mark all coding agent turns for a workspace as seen */
   markSeen: async (workspaceId: string): Promise<void> => {
     const response = await makeRequest(`/api/workspaces/${workspaceId}/seen`, {
@@ -829,6 +973,30 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<{ injected: boolean }>(response);
   },
+
+  /** Coding agents waiting for a free concurrency slot, in start order */
+  getStartQueue: async (): Promise<QueuedExecution[]> => {
+    const response = await makeRequest('/api/execution-processes/queue');
+    return handleApiResponse<QueuedExecution[]>(response);
+  },
+
+  /**
+   * Change the start order of queued coding agents. `processIds` must list
+   * every queued execution. Queued ones are cancelled through
+   * `stopExecutionProcess`.
+   */
+  reorderStartQueue: async (
+    processIds: string[]
+  ): Promise<QueuedExecution[]> => {
+    const response = await makeRequest(
+      '/api/execution-processes/queue/reorder',
+      {
+        method: 'POST',
+        body: JSON.stringify({ execution_process_ids: processIds }),
+      }
+    );
+    return handleApiResponse<QueuedExecution[]>(response);
+  },
 };
 
 // File System APIs
@@ -1592,7 +1760,7 @@ export const queueApi = {
   },
 
   /**
-   * Cancel a queued follow-up message
+   * Cancel all queued follow-up messages
    */
   cancel: async (sessionId: string): Promise<QueueStatus> => {
     const response = await makeRequest(`/api/sessions/${sessionId}/queue`, {
@@ -1601,6 +1769,55 @@ export const queueApi = {
     return handleApiResponse<QueueStatus>(response);
   },
 
+  /**
+   * Edit a single queued message
+   */
+  update: async (
+    sessionId: string,
+    messageId: string,
+    data: DraftFollowUpData
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Remove a single queued message
+   */
+  remove: async (
+    sessionId: string,
+    messageId: string
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/${messageId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
+  /**
+   * Set the execution order of the queue (must list every queued message id)
+   */
+  reorder: async (
+    sessionId: string,
+    messageIds: string[]
+  ): Promise<QueueStatus> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/queue/reorder`,
+      {
+        method: 'POST',
+        body: JSON.stringify({ message_ids: messageIds }),
+      }
+    );
+    return handleApiResponse<QueueStatus>(response);
+  },
+
   /**
    * Get the current queue status for a session
    */
@@ -1610,6 +1827,90 @@ export const queueApi = {
   },
 };
 
+// Fix-up loops: send failing verification output back to a session's agent
+export const fixUpApi = {
+  /**
+   * List the session's fix-up loops, newest first
+   */
+  list: async (sessionId: string): Promise<FixUpLoop[]> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/fix-up`);
+    return handleApiResponse<FixUpLoop[]>(response);
+  },
+
+  /**
+   * Verify now and send up to `maxIterations` fix-up follow-ups while the
+   * verification steps fail
+   */
+  start: async (
+    sessionId: string,
+    maxIterations?: number
+  ): Promise<FixUpLoop> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/fix-up`, {
+      method: 'POST',
+      body: JSON.stringify({ max_iterations: maxIterations ?? null }),
+    });
+    return handleApiResponse<FixUpLoop>(response);
+  },
+
+  /**
+   * Stop the running fix-up loop once its current step finishes
+   */
+  stop: async (sessionId: string): Promise<void> => {
+    const response = await makeRequest(`/api/sessions/${sessionId}/fix-up`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Checkpoints: snapshots taken after each tool-using agent turn
+export const checkpointsApi = {
+  /**
+   * List the session's turns that have checkpoints, oldest first
+   */
+  list: async (sessionId: string): Promise<CheckpointTurn[]> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints`
+    );
+    return handleApiResponse<CheckpointTurn[]>(response);
+  },
+
+  /**
+   * Put the workspace back to a checkpoint and drop the later turns. Returns
+   * the follow-up started when a prompt is given.
+   */
+  restore: async (
+    sessionId: string,
+    data: RestoreCheckpointRequest
+  ): Promise<ExecutionProcess | null> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints/restore`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ExecutionProcess | null>(response);
+  },
+
+  /**
+   * Start a new workspace from a checkpoint
+   */
+  branch: async (
+    sessionId: string,
+    data: BranchFromCheckpointRequest
+  ): Promise<CreateAndStartWorkspaceResponse> => {
+    const response = await makeRequest(
+      `/api/sessions/${sessionId}/checkpoints/branch`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CreateAndStartWorkspaceResponse>(response);
+  },
+};
+
 // Relay API
 export const relayApi = {
   getEnrollmentCode: async (): Promise<{ enrollment_code: string }> => {
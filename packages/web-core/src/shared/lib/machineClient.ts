@@ -7,6 +7,8 @@ import type {
   UpdateMcpServersBody,
   UpdateRepo,
   UserSystemInfo,
+  VerificationStep,
+  VerificationStepInput,
 } from 'shared/types';
 import type { AppRuntime } from '@/shared/hooks/useAppRuntime';
 import { handleApiResponse } from './api';
@@ -42,6 +44,11 @@ export interface MachineClient {
     display_name?: string;
   }) => Promise<Repo>;
   getRepoBranches: (repoId: string) => Promise<GitBranch[]>;
+  getVerificationSteps: (repoId: string) => Promise<VerificationStep[]>;
+  replaceVerificationSteps: (
+    repoId: string,
+    steps: VerificationStepInput[]
+  ) => Promise<VerificationStep[]>;
   loadProfiles: () => Promise<{ content: string; path: string }>;
   saveProfiles: (content: string) => Promise<string>;
   loadMcpServers: (query: McpServerQuery) => Promise<GetMcpServerResponse>;
@@ -146,6 +153,26 @@ export function createMachineClient(
           `/api/repos/${repoId}/branches`
         )
       ),
+    getVerificationSteps: async (repoId) =>
+      handleApiResponse<VerificationStep[]>(
+        await makeMachineRequest(
+          runtime,
+          target,
+          `/api/repos/${repoId}/verification-steps`
+        )
+      ),
+    replaceVerificationSteps: async (repoId, steps) =>
+      handleApiResponse<VerificationStep[]>(
+        await makeMachineRequest(
+          runtime,
+          target,
+          `/api/repos/${repoId}/verification-steps`,
+          {
+            method: 'PUT',
+            body: JSON.stringify({ steps }),
+          }
+        )
+      ),
     loadProfiles: async () =>
       handleApiResponse<{ content: string; path: string }>(
         await makeMachineRequest(runtime, target, '/api/profiles')
@@ -35,8 +35,9 @@ export const ExecutionProcessesProvider: React.FC<{
         (process) =>
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'cleanupscript' ||
-            process.run_reason === 'archivescript') &&
-          process.status === 'running'
+            process.run_reason === 'archivescript' ||
+            process.run_reason === 'testscript') &&
+          (process.status === 'running' || process.status === 'queued')
       ),
     [visible]
   );
@@ -4,11 +4,64 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Run the verification steps after every coding agent turn
+ */
+verify_after_agent: boolean, 
+/**
+ * Refuse to merge or open a pull request until the verification steps
+ * pass on the current commit
+ */
+require_verification: boolean, 
+/**
+ * Template for messages of commits made after a coding agent turn. The
+ * agent's summary when unset.
+ */
+commit_message_template: string | null, 
+/**
+ * Prefix commit messages with an inferred conventional-commit type
+ */
+conventional_commits: boolean, 
+/**
+ * Add `Co-authored-by` and `X-Agent` trailers naming the coding agent
+ */
+agent_commit_trailers: boolean, 
+/**
+ * Sign agent commits and merge commits
+ */
+commit_signing: SigningMode, 
+/**
+ * GPG key id or SSH key path. The repository's `user.signingkey` when
+ * unset.
+ */
+signing_key: string | null, 
+/**
+ * CPUs an execution's processes may use together, e.g. `1.5`
+ */
+cpu_limit: number | null, 
+/**
+ * Memory an execution's processes may use together, in MiB
+ */
+memory_limit_mb: bigint | null, 
+/**
+ * Number of processes and threads an execution may run at once
+ */
+pids_limit: bigint | null, default_target_branch: string | null, default_working_dir: string | null, github_access: GitHubAccess, created_at: Date, updated_at: Date, };
+
+/**
+ * How pull requests of a GitHub repository are reached
+ */
+export type GitHubAccess = "auto" | "api" | "cli";
+
+/**
+ * How commits made on behalf of a repository are signed
+ */
+export type SigningMode = "off" | "git_config" | "gpg" | "ssh";
 
 export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, verify_after_agent?: boolean | null, require_verification?: boolean | null, commit_message_template?: string | null, conventional_commits?: boolean | null, agent_commit_trailers?: boolean | null, commit_signing?: SigningMode | null, signing_key?: string | null, cpu_limit?: number | null, memory_limit_mb?: number | null, pids_limit?: number | null, default_target_branch?: string | null, default_working_dir?: string | null, github_access?: GitHubAccess | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -22,7 +75,55 @@ export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string,
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Run the verification steps after every coding agent turn
+ */
+verify_after_agent: boolean, 
+/**
+ * Refuse to merge or open a pull request until the verification steps
+ * pass on the current commit
+ */
+require_verification: boolean, 
+/**
+ * Template for messages of commits made after a coding agent turn. The
+ * agent's summary when unset.
+ */
+commit_message_template: string | null, 
+/**
+ * Prefix commit messages with an inferred conventional-commit type
+ */
+conventional_commits: boolean, 
+/**
+ * Add `Co-authored-by` and `X-Agent` trailers naming the coding agent
+ */
+agent_commit_trailers: boolean, 
+/**
+ * Sign agent commits and merge commits
+ */
+commit_signing: SigningMode, 
+/**
+ * GPG key id or SSH key path. The repository's `user.signingkey` when
+ * unset.
+ */
+signing_key: string | null, 
+/**
+ * CPUs an execution's processes may use together, e.g. `1.5`
+ */
+cpu_limit: number | null, 
+/**
+ * Memory an execution's processes may use together, in MiB
+ */
+memory_limit_mb: bigint | null, 
+/**
+ * Number of processes and threads an execution may run at once
+ */
+pids_limit: bigint | null, default_target_branch: string | null, default_working_dir: string | null, github_access: GitHubAccess, created_at: Date, updated_at: Date, };
+
+/**
+ * Link from a stacked workspace to the workspace whose branch it builds on
+ */
+export type WorkspaceStack = { workspace_id: string, parent_workspace_id: string, created_at: string, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -239,6 +340,20 @@ export type DiskUsageResponse = { workspaces: Array<WorkspaceDiskUsage>, total_b
 export type Session = { id: string, workspace_id: string, name: string | null, executor: string | null, agent_working_dir: string | null, host_id: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * Set when the exit code alone doesn't explain a failure
+ */
+failure_reason: ExecutionFailureReason | null, 
+/**
+ * Peak memory of the process and its children, recorded when resource
+ * limits applied to it
+ */
+peak_memory_bytes: bigint | null, 
+/**
+ * CPU time of the process and its children, recorded when resource
+ * limits applied to it
+ */
+cpu_time_ms: bigint | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -246,9 +361,14 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver";
+/**
+ * Why an execution failed, beyond its exit code
+ */
+export type ExecutionFailureReason = "oom_killed";
+
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver" | "testscript";
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
@@ -262,6 +382,198 @@ export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+export type MergeQueueStatus = "queued" | "running" | "merged" | "conflict" | "failed" | "cancelled";
+
+/**
+ * A workspace waiting in the merge queue of one repo and target branch
+ */
+export type MergeQueueEntry = { id: string, workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * `MergeStrategy` the workspace is merged with
+ */
+strategy: string, status: MergeQueueStatus, error: string | null, 
+/**
+ * The latest verification step run for this entry
+ */
+verification_execution_process_id: string | null, merge_commit: string | null, created_at: string, updated_at: string, };
+
+/**
+ * The pending entries of one repo and target branch, in merge order
+ */
+export type MergeQueue = { repo_id: string, target_branch: string, 
+/**
+ * Set while an entry has a conflict or failed; nothing behind it merges
+ * until it is retried or cancelled
+ */
+paused: boolean, entries: Array<MergeQueueEntry>, };
+
+export type ConflictResolutionStatus = "running" | "continued" | "unresolved" | "failed";
+
+/**
+ * A coding agent run resolving the conflicts of one repo in a workspace
+ */
+export type ConflictResolution = { id: string, workspace_id: string, repo_id: string, execution_process_id: string, 
+/**
+ * `ConflictOp` of the operation being resolved
+ */
+op: string, conflicted_files: Array<string>, status: ConflictResolutionStatus, error: string | null, created_at: string, updated_at: string, };
+
+/**
+ * A named check of a repository, such as lint, test or typecheck. The steps
+ * of a repository run in order and the run stops at the first failure.
+ */
+export type VerificationStep = { id: string, repo_id: string, name: string, script: string, 
+/**
+ * Run order within the repository; lowest first
+ */
+position: bigint, created_at: string, updated_at: string, };
+
+export type VerificationStepInput = { name: string, script: string, };
+
+export type VerificationRunStatus = "running" | "passed" | "failed" | "cancelled";
+
+export type VerificationResultStatus = "pending" | "running" | "passed" | "failed" | "cancelled" | "skipped";
+
+export type VerificationTrigger = "agent" | "manual" | "merge_queue";
+
+/**
+ * One pass over the verification steps of a workspace's repos
+ */
+export type VerificationRun = { id: string, workspace_id: string, session_id: string, trigger: VerificationTrigger, status: VerificationRunStatus, created_at: string, updated_at: string, };
+
+/**
+ * The outcome of one step in a verification run
+ */
+export type VerificationResult = { id: string, run_id: string, repo_id: string, 
+/**
+ * The step this result was created from, unless it has since been removed
+ */
+step_id: string | null, name: string, 
+/**
+ * Run order within the run; lowest first
+ */
+position: bigint, 
+/**
+ * The script run of this step, set once the step starts
+ */
+execution_process_id: string | null, status: VerificationResultStatus, exit_code: bigint | null, created_at: string, updated_at: string, };
+
+export type VerificationRunWithResults = { results: Array<VerificationResult>, id: string, workspace_id: string, session_id: string, trigger: VerificationTrigger, status: VerificationRunStatus, created_at: string, updated_at: string, };
+
+export type ReplaceVerificationStepsRequest = { 
+/**
+ * The repository's complete step list, in run order
+ */
+steps: Array<VerificationStepInput>, };
+
+export type RunVerificationRequest = { 
+/**
+ * Verify only these repos of the workspace; all of them when omitted
+ */
+repo_ids: Array<string> | null, 
+/**
+ * Session to run the steps in; the latest session when omitted
+ */
+session_id: string | null, };
+
+export type FixUpLoopStatus = "running" | "passed" | "exhausted" | "failed" | "cancelled";
+
+/**
+ * A loop sending failing verification output back to a session's coding
+ * agent until the verification steps pass
+ */
+export type FixUpLoop = { id: string, workspace_id: string, session_id: string, 
+/**
+ * Most follow-ups the loop sends before giving up
+ */
+max_iterations: bigint, 
+/**
+ * Follow-ups sent so far
+ */
+iterations: bigint, 
+/**
+ * Coding agent run of the latest follow-up
+ */
+execution_process_id: string | null, 
+/**
+ * Verification run chained after the latest follow-up
+ */
+verification_run_id: string | null, status: FixUpLoopStatus, error: string | null, created_at: string, updated_at: string, };
+
+/**
+ * Request body for starting a fix-up loop on a session
+ */
+export type StartFixUpLoopRequest = { 
+/**
+ * Most follow-ups to send before giving up; 3 when omitted
+ */
+max_iterations: bigint | null, };
+
+/**
+ * A snapshot of one repo's worktree taken after a coding agent turn,
+ * uncommitted changes included
+ */
+export type Checkpoint = { id: string, workspace_id: string, session_id: string, 
+/**
+ * The coding agent turn the snapshot was taken after
+ */
+execution_process_id: string, repo_id: string, 
+/**
+ * Hidden ref keeping the snapshot commit reachable
+ */
+ref_name: string, 
+/**
+ * The snapshot commit, whose parent is `head_commit`
+ */
+commit_sha: string, 
+/**
+ * HEAD when the snapshot was taken
+ */
+head_commit: string, 
+/**
+ * Changes of the turn, counted from the previous checkpoint
+ */
+files_changed: bigint, lines_added: bigint, lines_removed: bigint, created_at: string, };
+
+/**
+ * A coding agent turn of the session with the checkpoints taken after it
+ */
+export type CheckpointTurn = { execution_process_id: string, prompt: string | null, summary: string | null, 
+/**
+ * Totals over the repos of the turn
+ */
+files_changed: bigint, lines_added: bigint, lines_removed: bigint, created_at: string, 
+/**
+ * One checkpoint per repo
+ */
+checkpoints: Array<Checkpoint>, };
+
+/**
+ * Request body for restoring the session to a checkpoint
+ */
+export type RestoreCheckpointRequest = { execution_process_id: string, 
+/**
+ * Discard changes made after the latest checkpoint
+ */
+force_when_dirty: boolean | null, 
+/**
+ * Send this prompt as a follow-up from the restored turn
+ */
+prompt: string | null, 
+/**
+ * Defaults to the session's latest executor
+ */
+executor_config: ExecutorConfig | null, };
+
+/**
+ * Request body for starting a new workspace from a checkpoint
+ */
+export type BranchFromCheckpointRequest = { execution_process_id: string, prompt: string, name: string | null, 
+/**
+ * Defaults to the session's latest executor
+ */
+executor_config: ExecutorConfig | null, };
+
 export type ApprovalInfo = { approval_id: string, tool_name: string, execution_process_id: string, is_question: boolean, created_at: string, timeout_at: string, questions: Array<AskUserQuestionItem> | null, };
 
 export type ApprovalStatus = { "status": "pending" } | { "status": "approved" } | { "status": "denied", reason?: string, } | { "status": "timed_out" };
@@ -456,12 +768,50 @@ export type RebaseWorkspaceRequest = { repo_id: string, old_base_branch: string
 
 export type ContinueRebaseRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, 
+/**
+ * Session to run the agent in. Defaults to the workspace's latest session.
+ */
+session_id: string | null, 
+/**
+ * Defaults to the executor the session last ran with
+ */
+executor_config: ExecutorConfig | null, };
+
 export type AbortConflictsRequest = { repo_id: string, };
 
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
+export type FileHunksQuery = { repo_id: string, path: string, 
+/**
+ * Hunks of the unstaged changes (worktree against the index) instead of
+ * the changes against the target branch shown in the diff stream
+ */
+unstaged: boolean, };
+
+export type RevertChangesRequest = { repo_id: string, 
+/**
+ * Files and hunks of the diff against the target branch to undo
+ */
+files: Array<DiffSelection>, };
+
+export type StageChangesRequest = { repo_id: string, 
+/**
+ * Files and hunks of the unstaged changes to stage
+ */
+files: Array<DiffSelection>, };
+
+export type CommitChangesRequest = { repo_id: string, message: string, 
+/**
+ * Files and hunks of the unstaged changes to stage before committing.
+ * Only what is already staged is committed when omitted.
+ */
+files: Array<DiffSelection> | null, };
+
+export type CommitChangesResponse = { commit_sha: string, };
+
 export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
 
 export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
@@ -482,13 +832,67 @@ export type GetPrCommentsError = { "type": "cli_not_installed", provider: Provid
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
+export type AddressPrCommentsRequest = { repo_id: string, 
+/**
+ * Inline review comments to address; when omitted, the first comment of
+ * every unresolved thread that no fix addressed yet
+ */
+comment_ids: Array<bigint> | null, 
+/**
+ * Session to send the follow-up in; the latest session when omitted
+ */
+session_id: string | null, 
+/**
+ * Also mark each replied thread resolved, where the host supports it
+ */
+resolve_threads: boolean, };
+
+export type PrCommentFixStatus = "running" | "addressed" | "failed" | "cancelled";
+
+/**
+ * A coding agent follow-up addressing inline review comments on the pull
+ * request of one repo in a workspace
+ */
+export type PrCommentFix = { id: string, workspace_id: string, repo_id: string, session_id: string, execution_process_id: string, pr_number: bigint, 
+/**
+ * The host's ids of the review comments being addressed
+ */
+comment_ids: Array<bigint>, 
+/**
+ * Mark each replied thread resolved
+ */
+resolve_threads: boolean, 
+/**
+ * How many of the comments were replied to
+ */
+replied: bigint, status: PrCommentFixStatus, error: string | null, created_at: string, updated_at: string, };
+
 export type CreateAndStartWorkspaceRequest = { name: string | null, repos: Array<WorkspaceRepoInput>, linked_issue: LinkedIssueInfo | null, executor_config: ExecutorConfig, prompt: string, attachment_ids: Array<string> | null, };
 
 export type CreateAndStartWorkspaceResponse = { workspace: Workspace, execution_process: ExecutionProcess, link_warning: string | null, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "gitea" | "unknown";
+
+/**
+ * A self-hosted git host that cannot be recognised from its URL alone
+ */
+export type GitHostConfig = { 
+/**
+ * Host name as it appears in remote URLs, e.g. `git.example.com`. Include
+ * the port if the remotes do, e.g. `git.example.com:8443`.
+ */
+host: string, provider: ProviderKind, 
+/**
+ * Base URL of the REST API. Defaults to `https://{host}/api/v4` for GitLab
+ * and `https://{host}/api/v1` for Gitea.
+ */
+api_url: string | null, 
+/**
+ * Access token. Falls back to `GITLAB_TOKEN` or `GITEA_TOKEN`.
+ */
+token: string | null, };
 
 export type PullRequestDetail = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, title: string, base_branch: string, head_branch: string, };
 
@@ -496,6 +900,24 @@ export type GitRemote = { name: string, url: string, };
 
 export type MergeStrategy = "squash" | "rebase" | "merge";
 
+/**
+ * A commit's signature as the local `git` verifies it
+ */
+export type CommitSignature = { status: SignatureStatus, 
+/**
+ * Who signed, e.g. `Jane Doe <jane@example.com>`
+ */
+signer: string | null, 
+/**
+ * Fingerprint or id of the signing key
+ */
+key: string | null, };
+
+/**
+ * How a commit's signature verifies, from `git log --format=%G?`
+ */
+export type SignatureStatus = "good" | "untrusted" | "expired" | "revoked" | "bad" | "unknown_key" | "unsigned";
+
 export type ListPrsError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
 export type LinkPrToIssueRequest = { pr_url: string, pr_number: number, base_branch: string, };
@@ -506,7 +928,38 @@ export type CreateWorkspaceFromPrResponse = { workspace: Workspace, };
 
 export type CreateFromPrError = { "type": "pr_not_found" } | { "type": "branch_fetch_failed", message: string, } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
-export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, is_rebase_in_progress: boolean, conflict_op: ConflictOp | null, conflicted_files: Array<string>, is_target_remote: boolean, };
+export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, is_rebase_in_progress: boolean, conflict_op: ConflictOp | null, conflicted_files: Array<string>, is_target_remote: boolean, 
+/**
+ * How the signature of the HEAD commit verifies locally
+ */
+head_signature: CommitSignature | null, 
+/**
+ * Commits ahead of the target branch that carry no signature
+ */
+unsigned_commits: number | null, };
+
+/**
+ * Start a workspace on top of this workspace's branch
+ */
+export type CreateStackedWorkspaceRequest = { name: string | null, executor_config: ExecutorConfig, prompt: string, 
+/**
+ * Repositories of the parent to include. Defaults to all of them.
+ */
+repo_ids: Array<string> | null, };
+
+export type StackedWorkspace = { workspace: Workspace, parent_workspace_id: string | null, 
+/**
+ * 0 for the bottom of the stack, which branches off a repository branch
+ */
+depth: number, };
+
+export type RestackResponse = { 
+/**
+ * Workspaces that were rebased, bottom of the stack first
+ */
+restacked_workspace_ids: Array<string>, };
+
+export type RestackError = { "type": "merge_conflicts", workspace_id: string, repo_id: string, message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress", workspace_id: string, repo_id: string, };
 
 export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
 
@@ -566,7 +1019,11 @@ pr_url: string | null,
 /**
  * Host ID of the session that ran the latest execution process
  */
-latest_host_id: string | null, };
+latest_host_id: string | null, 
+/**
+ * Status of the latest verification run (if the workspace was verified)
+ */
+verification_status: VerificationRunStatus | null, };
 
 export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
@@ -578,7 +1035,19 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, appearance: AppearanceConfig, input_editor_mode: InputEditorMode, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, appearance: AppearanceConfig, input_editor_mode: InputEditorMode, 
+/**
+ * Self-hosted GitLab and Gitea instances, keyed by the host in remote URLs
+ */
+git_hosts: Array<GitHostConfig>, 
+/**
+ * Bubblewrap jail for coding agents and repository scripts (Linux only)
+ */
+sandbox: SandboxConfig, 
+/**
+ * Caps on concurrently running coding agents
+ */
+concurrency: ConcurrencyConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -616,26 +1085,89 @@ export type LinksConfig = { discord_enabled: boolean, discord_url: string | null
 
 export type InputEditorMode = "WYSIWYG" | "RAW";
 
+/**
+ * Limits on coding agents running at once. Starts beyond them wait in a
+ * queue until a running agent finishes.
+ */
+export type ConcurrencyConfig = { 
+/**
+ * Coding agents running at once across all executors; unlimited when
+ * unset
+ */
+max_coding_agents: number | null, 
+/**
+ * Coding agents of one executor running at once
+ */
+max_per_executor: { [key in BaseCodingAgent]?: number }, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, 
 /**
  * The session this message is queued for
  */
 session_id: string, 
 /**
- * The follow-up data (message + variant)
+ * Ordering within the session's queue; lowest runs first
+ */
+position: bigint, 
+/**
+ * The follow-up data (message + executor config)
  */
 data: DraftFollowUpData, 
 /**
  * Timestamp when the message was queued
  */
-queued_at: string, };
+queued_at: string, updated_at: string, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type QueueStatus = { "status": "empty" } | { "status": "queued", messages: Array<QueuedMessage>, };
+
+/**
+ * A coding agent execution waiting for a free concurrency slot
+ */
+export type QueuedExecution = { execution_process_id: string, session_id: string, workspace_id: string, workspace_name: string | null, workspace_branch: string, 
+/**
+ * The coding agent of the session, e.g. `CLAUDE_CODE`
+ */
+executor: string | null, 
+/**
+ * Ordering of the queue; lowest starts first
+ */
+position: bigint, queued_at: string, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+/**
+ * One hunk of a file's changes, as `git diff` prints it
+ */
+export type DiffHunk = { old_start: number, old_lines: number, new_start: number, new_lines: number, 
+/**
+ * The `@@ -a,b +c,d @@` line
+ */
+header: string, 
+/**
+ * Each line prefixed with ` `, `+` or `-`, without its line ending
+ */
+lines: Array<string>, };
+
+/**
+ * Identifies a hunk by the start lines of its header
+ */
+export type DiffHunkId = { old_start: number, new_start: number, };
+
+/**
+ * The changes of one file to act on
+ */
+export type DiffSelection = { 
+/**
+ * Path relative to the repository root
+ */
+path: string, 
+/**
+ * Hunks of the file to act on. The whole file when omitted.
+ */
+hunks: Array<DiffHunkId> | null, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
@@ -668,7 +1200,7 @@ reasoning_id?: string | null,
  */
 permission_policy?: PermissionPolicy | null, };
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "DevServer" | "ToolInstallScript" | "TestScript" | "VerificationScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -679,9 +1211,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM_CLI = "CUSTOM_CLI" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_CLI": CustomCli };
 
 export type SlashCommandDescription = { 
 /**
@@ -701,6 +1233,26 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type SandboxNetwork = "allow" | "deny";
+
+export type SandboxConfig = { 
+/**
+ * Run coding agents and repository scripts in the sandbox
+ */
+enabled: boolean, 
+/**
+ * Network access of sandboxed processes, unless their profile overrides it
+ */
+network: SandboxNetwork, 
+/**
+ * Paths besides the workspace that sandboxed processes may write to
+ */
+writable_paths: Array<string>, };
+
+export type SandboxOverrides = { network?: SandboxNetwork | null, writable_paths?: Array<string> | null, };
+
+export type ResourceLimits = { cpus?: number | null, memory_mb?: bigint | null, pids?: bigint | null, };
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -721,7 +1273,7 @@ models?: Array<string>,
  */
 reasoning_by_model?: { [key in string]?: string }, };
 
-export type ExecutorProfile = { recently_used_models?: ExecutorRecentModels | null, } & ({ [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } });
+export type ExecutorProfile = { recently_used_models?: ExecutorRecentModels | null, } & ({ [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM_CLI": CustomCli } });
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorProfile }, };
 
@@ -729,13 +1281,13 @@ export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER =
 
 export type ClaudeEffort = "low" | "medium" | "high" | "xhigh" | "max";
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, effort?: ClaudeEffort | null, agent?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, effort?: ClaudeEffort | null, agent?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, plan: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, plan: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -747,9 +1299,9 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, reasoning?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, reasoning?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, agent?: string | null, 
 /**
@@ -759,16 +1311,32 @@ auto_approve: boolean,
 /**
  * Enable auto-compaction when the context length approaches the model's context window limit
  */
-auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+/**
+ * An agent CLI described entirely by its profile, so new agents can be used
+ * without adding an executor.
+ */
+export type CustomCli = { append_prompt: AppendPrompt, command: string, prompt_input: CustomCliPromptInput, output_format: CustomCliOutputFormat, follow_up_params?: Array<string> | null, session_id_regex?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, process_sandbox?: SandboxOverrides | null, resource_limits?: ResourceLimits | null, };
+
+/**
+ * How the agent's stdout is turned into conversation entries
+ */
+export type CustomCliOutputFormat = "plain_text" | "acp" | "stream_json";
+
+/**
+ * How the prompt is handed to the agent
+ */
+export type CustomCliPromptInput = "stdin" | "argument";
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -819,7 +1387,13 @@ export type NormalizedEntry = { timestamp: string | null, entry_type: Normalized
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo | { "type": "user_answered_questions", answers: Array<AnsweredQuestion>, };
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens used by the execution so far, for agents that report a breakdown
+ */
+usage: TokenUsage | null, };
+
+export type TokenUsage = { model: string | null, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**